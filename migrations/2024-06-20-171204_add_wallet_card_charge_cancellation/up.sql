@@ -0,0 +1,13 @@
+-- intermediate state auths that we attempted to cancel, failed ones need follow up
+CREATE TABLE IF NOT EXISTS wallet_card_charge_cancellation(
+    id SERIAL PRIMARY KEY,
+    registered_transaction_id INT NOT NULL REFERENCES registered_transaction(id),
+    wallet_card_charge_id INT NOT NULL REFERENCES wallet_card_charge(id),
+    psp_reference VARCHAR(255),
+    cancel_psp_reference VARCHAR(255) UNIQUE,
+    cancel_status VARCHAR(30) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+CREATE UNIQUE INDEX IF NOT EXISTS wallet_card_charge_cancellation_charge_unique ON wallet_card_charge_cancellation(wallet_card_charge_id);
+CREATE INDEX IF NOT EXISTS wallet_card_charge_cancellation_status ON wallet_card_charge_cancellation(cancel_status);
@@ -0,0 +1,2 @@
+ALTER TABLE registered_transaction DROP COLUMN asa_response_result;
+ALTER TABLE registered_transaction DROP COLUMN lithic_transaction_token;
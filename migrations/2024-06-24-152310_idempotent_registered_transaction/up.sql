@@ -0,0 +1,3 @@
+-- lithic transaction token, used to dedupe asa retries
+ALTER TABLE registered_transaction ADD COLUMN lithic_transaction_token VARCHAR(255) UNIQUE;
+ALTER TABLE registered_transaction ADD COLUMN asa_response_result VARCHAR(30);
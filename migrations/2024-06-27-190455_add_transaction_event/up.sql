@@ -0,0 +1,11 @@
+-- lifecycle events lithic sends after the auth, event token dedupes webhook retries
+CREATE TABLE IF NOT EXISTS transaction_event(
+    id SERIAL PRIMARY KEY,
+    registered_transaction_id INT NOT NULL REFERENCES registered_transaction(id),
+    event_token VARCHAR(255) UNIQUE NOT NULL,
+    event_type VARCHAR(40) NOT NULL,
+    amount_cents INT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+CREATE INDEX IF NOT EXISTS transaction_event_registered_transaction ON transaction_event(registered_transaction_id);
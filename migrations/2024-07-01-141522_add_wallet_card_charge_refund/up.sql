@@ -0,0 +1,16 @@
+-- refunds issued against a successful wallet charge, a charge can be partially refunded many times
+CREATE TABLE IF NOT EXISTS wallet_card_charge_refund(
+    id SERIAL PRIMARY KEY,
+    registered_transaction_id INT NOT NULL REFERENCES registered_transaction(id),
+    successful_end_to_end_charge_id INT NOT NULL REFERENCES successful_end_to_end_charge(id),
+    wallet_card_charge_id INT NOT NULL REFERENCES wallet_card_charge(id),
+    psp_reference VARCHAR(255) NOT NULL,
+    refund_psp_reference VARCHAR(255) UNIQUE,
+    reference VARCHAR(255) NOT NULL,
+    amount_cents INT NOT NULL,
+    refund_status VARCHAR(30) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+CREATE INDEX IF NOT EXISTS wallet_card_charge_refund_charge ON wallet_card_charge_refund(wallet_card_charge_id);
+CREATE INDEX IF NOT EXISTS wallet_card_charge_refund_status ON wallet_card_charge_refund(refund_status);
@@ -0,0 +1,14 @@
+-- incremental auths and amount decreases against an already approved transaction, charged on the same wallet card
+CREATE TABLE IF NOT EXISTS authorization_adjustment(
+    id SERIAL PRIMARY KEY,
+    registered_transaction_id INT NOT NULL REFERENCES registered_transaction(id),
+    wallet_card_charge_id INT NOT NULL REFERENCES wallet_card_charge(id),
+    reference UUID UNIQUE NOT NULL,
+    previous_amount_cents INT NOT NULL,
+    amount_cents INT NOT NULL,
+    psp_reference VARCHAR(255),
+    status VARCHAR(30) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+CREATE INDEX IF NOT EXISTS authorization_adjustment_registered_transaction ON authorization_adjustment(registered_transaction_id);
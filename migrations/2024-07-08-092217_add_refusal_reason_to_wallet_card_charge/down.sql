@@ -0,0 +1,2 @@
+ALTER TABLE wallet_card_charge DROP COLUMN refusal_reason;
+ALTER TABLE wallet_card_charge DROP COLUMN refusal_reason_code;
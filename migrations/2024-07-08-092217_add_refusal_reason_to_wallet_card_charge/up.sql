@@ -0,0 +1,3 @@
+-- adyen refusal details per card so support can see why each card in the wallet declined
+ALTER TABLE wallet_card_charge ADD COLUMN refusal_reason VARCHAR(255);
+ALTER TABLE wallet_card_charge ADD COLUMN refusal_reason_code VARCHAR(10);
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS end_to_end_charge_wallet_card_charge;
+DROP INDEX IF EXISTS wallet_card_charge_success_txn_card;
+CREATE UNIQUE INDEX IF NOT EXISTS wallet_card_charge_success_txn ON wallet_card_charge(registered_transaction_id, is_success);
+ALTER TABLE users DROP COLUMN split_tender_enabled;
@@ -0,0 +1,16 @@
+-- split tender is opt in, users with it on can have one purchase covered by several cards
+ALTER TABLE users ADD COLUMN split_tender_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- a split purchase has one successful inner charge per card instead of one per transaction
+DROP INDEX IF EXISTS wallet_card_charge_success_txn;
+CREATE UNIQUE INDEX IF NOT EXISTS wallet_card_charge_success_txn_card ON wallet_card_charge(registered_transaction_id, wallet_card_id, is_success);
+
+-- every inner charge that makes up an end to end charge, the end to end row keeps pointing at the first
+CREATE TABLE IF NOT EXISTS end_to_end_charge_wallet_card_charge(
+    id SERIAL PRIMARY KEY,
+    successful_end_to_end_charge_id INT NOT NULL REFERENCES successful_end_to_end_charge(id),
+    wallet_card_charge_id INT UNIQUE NOT NULL REFERENCES wallet_card_charge(id),
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+CREATE INDEX IF NOT EXISTS end_to_end_charge_wallet_card_charge_e2e ON end_to_end_charge_wallet_card_charge(successful_end_to_end_charge_id);
@@ -0,0 +1 @@
+ALTER TABLE registered_transaction_metadata DROP COLUMN IF EXISTS routing_trace;
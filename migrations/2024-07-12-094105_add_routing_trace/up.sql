@@ -0,0 +1,2 @@
+-- the raw asa lives in body, the routing trace records why each card was tried and how it went
+ALTER TABLE registered_transaction_metadata ADD COLUMN IF NOT EXISTS routing_trace TEXT;
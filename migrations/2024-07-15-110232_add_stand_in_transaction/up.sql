@@ -0,0 +1,16 @@
+-- decisions made without the full pipeline, kept until the wallet charge and ledger catch up
+CREATE TABLE IF NOT EXISTS stand_in_transaction(
+    id SERIAL PRIMARY KEY,
+    public_id UUID UNIQUE NOT NULL,
+    card_token VARCHAR(255) NOT NULL,
+    lithic_transaction_token VARCHAR(255),
+    amount_cents INT NOT NULL,
+    asa_response_result VARCHAR(255) NOT NULL,
+    reason TEXT NOT NULL,
+    body TEXT NOT NULL,
+    status VARCHAR(255) NOT NULL,
+    reconciled_result VARCHAR(255),
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+CREATE INDEX IF NOT EXISTS stand_in_transaction_status ON stand_in_transaction(status);
@@ -0,0 +1,14 @@
+ALTER TABLE users DROP COLUMN charge_in_merchant_currency;
+ALTER TABLE settled_wallet_transaction_ledger DROP COLUMN conversion_rate;
+ALTER TABLE settled_wallet_transaction_ledger DROP COLUMN currency;
+ALTER TABLE pending_wallet_transaction_ledger DROP COLUMN conversion_rate;
+ALTER TABLE pending_wallet_transaction_ledger DROP COLUMN currency;
+ALTER TABLE settled_passthrough_card_transaction_ledger DROP COLUMN conversion_rate;
+ALTER TABLE settled_passthrough_card_transaction_ledger DROP COLUMN currency;
+ALTER TABLE pending_passthrough_card_transaction_ledger DROP COLUMN conversion_rate;
+ALTER TABLE pending_passthrough_card_transaction_ledger DROP COLUMN currency;
+ALTER TABLE registered_transaction DROP COLUMN charge_currency;
+ALTER TABLE registered_transaction DROP COLUMN conversion_rate;
+ALTER TABLE registered_transaction DROP COLUMN merchant_currency;
+ALTER TABLE registered_transaction DROP COLUMN merchant_amount_cents;
+ALTER TABLE registered_transaction DROP COLUMN currency;
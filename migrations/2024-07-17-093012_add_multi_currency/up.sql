@@ -0,0 +1,19 @@
+-- amounts stay in the cardholder currency, the merchant side and the rate it was converted at are kept alongside
+ALTER TABLE registered_transaction ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'USD';
+ALTER TABLE registered_transaction ADD COLUMN IF NOT EXISTS merchant_amount_cents INT;
+ALTER TABLE registered_transaction ADD COLUMN IF NOT EXISTS merchant_currency VARCHAR(3);
+ALTER TABLE registered_transaction ADD COLUMN IF NOT EXISTS conversion_rate DOUBLE PRECISION;
+-- the currency the backing card was asked to pay in
+ALTER TABLE registered_transaction ADD COLUMN IF NOT EXISTS charge_currency VARCHAR(3) NOT NULL DEFAULT 'USD';
+
+ALTER TABLE pending_passthrough_card_transaction_ledger ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'USD';
+ALTER TABLE pending_passthrough_card_transaction_ledger ADD COLUMN IF NOT EXISTS conversion_rate DOUBLE PRECISION;
+ALTER TABLE settled_passthrough_card_transaction_ledger ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'USD';
+ALTER TABLE settled_passthrough_card_transaction_ledger ADD COLUMN IF NOT EXISTS conversion_rate DOUBLE PRECISION;
+ALTER TABLE pending_wallet_transaction_ledger ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'USD';
+ALTER TABLE pending_wallet_transaction_ledger ADD COLUMN IF NOT EXISTS conversion_rate DOUBLE PRECISION;
+ALTER TABLE settled_wallet_transaction_ledger ADD COLUMN IF NOT EXISTS currency VARCHAR(3) NOT NULL DEFAULT 'USD';
+ALTER TABLE settled_wallet_transaction_ledger ADD COLUMN IF NOT EXISTS conversion_rate DOUBLE PRECISION;
+
+-- opt in, users with it on have foreign purchases charged to their card in the merchant currency
+ALTER TABLE users ADD COLUMN IF NOT EXISTS charge_in_merchant_currency BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1 @@
+ALTER TABLE registered_transaction DROP COLUMN requested_amount_cents;
@@ -0,0 +1,2 @@
+-- set when less than the asa asked for was approved, amount_cents then holds what was approved
+ALTER TABLE registered_transaction ADD COLUMN IF NOT EXISTS requested_amount_cents INT;
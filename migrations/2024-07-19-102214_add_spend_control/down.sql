@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS spend_control_usage;
+DROP TABLE IF EXISTS spend_control;
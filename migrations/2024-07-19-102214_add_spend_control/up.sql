@@ -0,0 +1,29 @@
+-- limits a user puts on their own spend, either across all their cards or on one passthrough card
+CREATE TABLE IF NOT EXISTS spend_control(
+    id SERIAL PRIMARY KEY,
+    public_id UUID UNIQUE NOT NULL,
+    user_id INT NOT NULL REFERENCES users(id),
+    passthrough_card_id INT REFERENCES passthrough_card(id),
+    per_transaction_max_cents INT,
+    daily_max_cents INT,
+    weekly_max_cents INT,
+    monthly_max_cents INT,
+    hourly_transaction_limit INT,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+-- passthrough_card_id is null on the user wide control, so there is one of those and one per card
+CREATE UNIQUE INDEX IF NOT EXISTS spend_control_user_card ON spend_control(user_id, COALESCE(passthrough_card_id, 0));
+
+-- spend counted against the controls. the redis counters are rebuilt from here when they expire or redis is down
+CREATE TABLE IF NOT EXISTS spend_control_usage(
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users(id),
+    passthrough_card_id INT NOT NULL REFERENCES passthrough_card(id),
+    transaction_token VARCHAR(255) UNIQUE NOT NULL,
+    amount_cents INT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+CREATE INDEX IF NOT EXISTS spend_control_usage_user_created ON spend_control_usage(user_id, created_at);
+CREATE INDEX IF NOT EXISTS spend_control_usage_card_created ON spend_control_usage(passthrough_card_id, created_at);
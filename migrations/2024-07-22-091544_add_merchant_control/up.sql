@@ -0,0 +1,29 @@
+-- merchants a user allows or blocks on their passthrough card
+CREATE TABLE IF NOT EXISTS merchant_control(
+    id SERIAL PRIMARY KEY,
+    public_id UUID UNIQUE NOT NULL,
+    user_id INT NOT NULL REFERENCES users(id),
+    list_type VARCHAR(30) NOT NULL,
+    match_type VARCHAR(30) NOT NULL,
+    -- the mcc, start of an mcc range, descriptor pattern, acceptor id or country depending on match_type
+    match_value VARCHAR(255),
+    mcc_range_end VARCHAR(4),
+    category_id INT REFERENCES category(id),
+    reason VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+CREATE INDEX IF NOT EXISTS merchant_control_user ON merchant_control(user_id);
+
+-- asas declined by a merchant control and why, the control is kept nullable so deleting one keeps its history
+CREATE TABLE IF NOT EXISTS merchant_control_decline(
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users(id),
+    passthrough_card_id INT NOT NULL REFERENCES passthrough_card(id),
+    merchant_control_id INT REFERENCES merchant_control(id) ON DELETE SET NULL,
+    transaction_token VARCHAR(255),
+    reason VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+CREATE INDEX IF NOT EXISTS merchant_control_decline_user ON merchant_control_decline(user_id);
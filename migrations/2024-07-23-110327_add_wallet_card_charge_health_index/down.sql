@@ -0,0 +1 @@
+DROP INDEX IF EXISTS wallet_card_charge_wallet_card_recent;
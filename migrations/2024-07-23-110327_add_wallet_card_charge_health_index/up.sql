@@ -0,0 +1,2 @@
+-- recent charges per wallet card, newest first, for the card health check on the asa path
+CREATE INDEX IF NOT EXISTS wallet_card_charge_wallet_card_recent ON wallet_card_charge(wallet_card_id, id DESC);
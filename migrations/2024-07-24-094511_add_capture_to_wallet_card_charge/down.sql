@@ -0,0 +1,2 @@
+ALTER TABLE wallet_card_charge DROP COLUMN capture_status;
+ALTER TABLE wallet_card_charge DROP COLUMN captured_amount_cents;
@@ -0,0 +1,3 @@
+-- charges are authorized at asa time and captured when lithic clears, null for charges adyen captured on auth
+ALTER TABLE wallet_card_charge ADD COLUMN capture_status VARCHAR(255);
+ALTER TABLE wallet_card_charge ADD COLUMN captured_amount_cents INT4;
@@ -0,0 +1,4 @@
+ALTER TABLE settled_passthrough_card_transaction_ledger DROP COLUMN cash_amount_cents;
+ALTER TABLE pending_passthrough_card_transaction_ledger DROP COLUMN cash_amount_cents;
+ALTER TABLE registered_transaction DROP COLUMN cash_amount_cents;
+ALTER TABLE wallet DROP COLUMN cash_advance_enabled;
@@ -0,0 +1,6 @@
+-- opt in per card, only cards the user marked as fine for cash advances take cash withdrawals and cashback
+ALTER TABLE wallet ADD COLUMN IF NOT EXISTS cash_advance_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+-- the part of the amount handed out as cash, included in amount_cents
+ALTER TABLE registered_transaction ADD COLUMN IF NOT EXISTS cash_amount_cents INT;
+ALTER TABLE pending_passthrough_card_transaction_ledger ADD COLUMN IF NOT EXISTS cash_amount_cents INT;
+ALTER TABLE settled_passthrough_card_transaction_ledger ADD COLUMN IF NOT EXISTS cash_amount_cents INT;
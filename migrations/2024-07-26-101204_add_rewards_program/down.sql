@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS user_rewards_program_valuation;
+ALTER TABLE credit_card DROP COLUMN rewards_program_id;
+DROP TABLE IF EXISTS rewards_program;
@@ -0,0 +1,32 @@
+-- what a point in each program is worth, in hundredths of a cent so 1.5 cents a point is 150
+CREATE TABLE IF NOT EXISTS rewards_program(
+    id SERIAL PRIMARY KEY,
+    public_id UUID UNIQUE NOT NULL DEFAULT uuid_generate_v4(),
+    "name" VARCHAR(255) UNIQUE NOT NULL,
+    cents_per_point_hundredths INT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+
+INSERT INTO rewards_program(id, "name", cents_per_point_hundredths) VALUES
+    (1, 'Ultimate Rewards', 150),
+    (2, 'Membership Rewards', 150),
+    (3, 'Bilt Rewards', 150),
+    (4, 'Cashback', 100);
+SELECT setval(pg_get_serial_sequence('rewards_program', 'id'), max(id)) FROM rewards_program;
+
+-- cards without a program are valued at a cent a point
+ALTER TABLE credit_card ADD COLUMN rewards_program_id INT REFERENCES rewards_program(id);
+UPDATE credit_card SET rewards_program_id = 1 WHERE id IN (1, 2);
+UPDATE credit_card SET rewards_program_id = 3 WHERE id = 3;
+
+-- a user's own valuation of a program, used in place of the program's
+CREATE TABLE IF NOT EXISTS user_rewards_program_valuation(
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users(id),
+    rewards_program_id INT NOT NULL REFERENCES rewards_program(id),
+    cents_per_point_hundredths INT NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE(user_id, rewards_program_id)
+);
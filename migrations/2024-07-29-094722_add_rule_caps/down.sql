@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS wallet_card_charge_rule_usage;
+ALTER TABLE rule DROP COLUMN fallback_cashback_percentage_bips;
+ALTER TABLE rule DROP COLUMN fallback_points_multiplier;
+ALTER TABLE rule DROP COLUMN cap_period;
+ALTER TABLE rule DROP COLUMN cap_amount_cents;
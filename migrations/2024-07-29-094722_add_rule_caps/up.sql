@@ -0,0 +1,8 @@
+-- bonus rates that only apply up to a spend cap each period, and what the rule earns once the cap is used up
+ALTER TABLE rule ADD COLUMN cap_amount_cents INT;
+ALTER TABLE rule ADD COLUMN cap_period VARCHAR(30);
+ALTER TABLE rule ADD COLUMN fallback_points_multiplier INT;
+ALTER TABLE rule ADD COLUMN fallback_cashback_percentage_bips INT;
+
+-- spend against a capped rule per wallet card, summed on the asa path
+CREATE INDEX IF NOT EXISTS wallet_card_charge_rule_usage ON wallet_card_charge(wallet_card_id, rule_id, created_at);
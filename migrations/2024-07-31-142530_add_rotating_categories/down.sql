@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS rotating_category_activation;
+ALTER TABLE rule DROP COLUMN rotating_category_schedule_id;
+DROP TABLE IF EXISTS rotating_category_schedule;
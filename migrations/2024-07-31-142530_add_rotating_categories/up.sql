@@ -0,0 +1,25 @@
+-- cards like freedom flex and discover it earn on different categories each quarter, the categories are the rules on the schedule
+CREATE TABLE IF NOT EXISTS rotating_category_schedule(
+    id SERIAL PRIMARY KEY,
+    public_id UUID UNIQUE NOT NULL DEFAULT uuid_generate_v4(),
+    credit_card_id INT NOT NULL REFERENCES credit_card(id),
+    "year" INT NOT NULL,
+    quarter INT NOT NULL CHECK (quarter BETWEEN 1 AND 4),
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE(credit_card_id, "year", quarter)
+);
+
+ALTER TABLE rule ADD COLUMN rotating_category_schedule_id INT REFERENCES rotating_category_schedule(id);
+
+-- the issuer only pays the rotating rate once the user activates the quarter on that card
+CREATE TABLE IF NOT EXISTS rotating_category_activation(
+    id SERIAL PRIMARY KEY,
+    public_id UUID UNIQUE NOT NULL DEFAULT uuid_generate_v4(),
+    user_id INT NOT NULL REFERENCES users(id),
+    wallet_card_id INT NOT NULL REFERENCES wallet(id),
+    rotating_category_schedule_id INT NOT NULL REFERENCES rotating_category_schedule(id),
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE(wallet_card_id, rotating_category_schedule_id)
+);
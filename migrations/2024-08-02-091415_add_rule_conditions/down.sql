@@ -0,0 +1,5 @@
+ALTER TABLE rule DROP COLUMN mccs;
+ALTER TABLE rule DROP COLUMN card_presence;
+ALTER TABLE rule DROP COLUMN merchant_country;
+ALTER TABLE rule DROP COLUMN max_amount_cents;
+ALTER TABLE rule DROP COLUMN min_amount_cents;
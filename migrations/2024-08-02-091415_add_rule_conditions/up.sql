@@ -0,0 +1,8 @@
+-- conditions a rule only pays on, every one that is set has to hold for the request
+ALTER TABLE rule ADD COLUMN min_amount_cents INT;
+ALTER TABLE rule ADD COLUMN max_amount_cents INT;
+-- iso 3166 alpha-3, as lithic sends it
+ALTER TABLE rule ADD COLUMN merchant_country VARCHAR(3);
+ALTER TABLE rule ADD COLUMN card_presence VARCHAR(30);
+-- matches any of them, can stand in for a category
+ALTER TABLE rule ADD COLUMN mccs TEXT[];
@@ -0,0 +1,5 @@
+ALTER TABLE rule ADD COLUMN merchant_name VARCHAR(255);
+UPDATE rule SET merchant_name = lower(merchant."name") FROM merchant WHERE rule.merchant_id = merchant.id;
+ALTER TABLE rule DROP COLUMN merchant_id;
+DROP TABLE IF EXISTS merchant_alias;
+DROP TABLE IF EXISTS merchant;
@@ -0,0 +1,53 @@
+-- canonical merchants that merchant rules point at, descriptors are resolved to one through merchant_alias
+CREATE TABLE IF NOT EXISTS merchant(
+    id SERIAL PRIMARY KEY,
+    public_id UUID UNIQUE NOT NULL DEFAULT uuid_generate_v4(),
+    "name" VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp
+);
+CREATE UNIQUE INDEX IF NOT EXISTS merchant_name_lower ON merchant(lower("name"));
+
+-- a descriptor pattern matched against the cleaned descriptor, * stands in for any run of characters, or an exact acceptor id
+CREATE TABLE IF NOT EXISTS merchant_alias(
+    id SERIAL PRIMARY KEY,
+    merchant_id INT NOT NULL REFERENCES merchant(id),
+    alias_type VARCHAR(30) NOT NULL,
+    pattern VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    UNIQUE(alias_type, pattern)
+);
+
+INSERT INTO merchant(id, "name") VALUES
+    (1, 'Amazon'),
+    (2, 'Whole Foods'),
+    (3, 'Uber'),
+    (4, 'Lyft'),
+    (5, 'Starbucks'),
+    (6, 'Netflix');
+SELECT setval(pg_get_serial_sequence('merchant', 'id'), max(id)) FROM merchant;
+
+INSERT INTO merchant_alias(merchant_id, alias_type, pattern) VALUES
+    (1, 'DESCRIPTOR', 'amazon*'),
+    (1, 'DESCRIPTOR', 'amzn*'),
+    (2, 'DESCRIPTOR', 'whole foods*'),
+    (2, 'DESCRIPTOR', 'wfm*'),
+    (3, 'DESCRIPTOR', 'uber*'),
+    (4, 'DESCRIPTOR', 'lyft*'),
+    (5, 'DESCRIPTOR', 'starbucks*'),
+    (6, 'DESCRIPTOR', 'netflix*');
+
+-- existing merchant rules become a merchant each, matching the cleaned descriptor exactly as before
+INSERT INTO merchant("name")
+    SELECT DISTINCT lower(rule.merchant_name) FROM rule
+    WHERE rule.merchant_name IS NOT NULL
+    AND NOT EXISTS (SELECT 1 FROM merchant WHERE lower(merchant."name") = lower(rule.merchant_name));
+INSERT INTO merchant_alias(merchant_id, alias_type, pattern)
+    SELECT merchant.id, 'DESCRIPTOR', trim(regexp_replace(lower(merchant."name"), '[^a-z0-9]+', ' ', 'g')) FROM merchant
+    WHERE EXISTS (SELECT 1 FROM rule WHERE lower(rule.merchant_name) = lower(merchant."name"))
+ON CONFLICT DO NOTHING;
+
+ALTER TABLE rule ADD COLUMN merchant_id INT REFERENCES merchant(id);
+UPDATE rule SET merchant_id = merchant.id FROM merchant WHERE lower(rule.merchant_name) = lower(merchant."name");
+ALTER TABLE rule DROP COLUMN merchant_name;
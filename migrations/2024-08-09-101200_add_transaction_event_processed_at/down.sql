@@ -0,0 +1 @@
+ALTER TABLE transaction_event DROP COLUMN processed_at;
@@ -0,0 +1,3 @@
+-- an event row is claimed before its side effects run and stamped processed once they've landed
+ALTER TABLE transaction_event ADD COLUMN processed_at TIMESTAMP;
+UPDATE transaction_event SET processed_at = created_at;
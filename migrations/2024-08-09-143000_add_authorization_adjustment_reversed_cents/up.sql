@@ -0,0 +1,2 @@
+-- increments are captured as their own payments, this is how much of one has been refunded by a void, expiry or reversal
+ALTER TABLE authorization_adjustment ADD COLUMN reversed_cents INT NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+DROP INDEX IF EXISTS spend_control_usage_token_reference;
+DELETE FROM spend_control_usage later USING spend_control_usage earlier
+    WHERE later.transaction_token = earlier.transaction_token AND later.id > earlier.id;
+ALTER TABLE spend_control_usage ADD CONSTRAINT spend_control_usage_transaction_token_key UNIQUE (transaction_token);
+ALTER TABLE spend_control_usage DROP COLUMN reference;
@@ -0,0 +1,7 @@
+-- a follow up asa reuses the transaction token, so usage is keyed by token and reference instead.
+-- the reference is the amount lithic asked for on an asa and the event token on a release
+ALTER TABLE spend_control_usage ADD COLUMN reference VARCHAR(255);
+UPDATE spend_control_usage SET reference = amount_cents::text;
+ALTER TABLE spend_control_usage ALTER COLUMN reference SET NOT NULL;
+ALTER TABLE spend_control_usage DROP CONSTRAINT IF EXISTS spend_control_usage_transaction_token_key;
+CREATE UNIQUE INDEX IF NOT EXISTS spend_control_usage_token_reference ON spend_control_usage(transaction_token, reference);
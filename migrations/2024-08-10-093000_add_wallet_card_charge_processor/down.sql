@@ -0,0 +1 @@
+ALTER TABLE wallet_card_charge DROP COLUMN processor;
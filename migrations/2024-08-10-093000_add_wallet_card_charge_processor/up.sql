@@ -0,0 +1,2 @@
+-- every charge so far went through adyen
+ALTER TABLE wallet_card_charge ADD COLUMN processor VARCHAR(255) NOT NULL DEFAULT 'ADYEN';
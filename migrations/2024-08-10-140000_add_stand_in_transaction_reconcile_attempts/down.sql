@@ -0,0 +1,4 @@
+ALTER TABLE stand_in_transaction DROP COLUMN claimed_until;
+ALTER TABLE stand_in_transaction DROP COLUMN next_attempt_at;
+ALTER TABLE stand_in_transaction DROP COLUMN attempts;
+ALTER TABLE stand_in_transaction DROP COLUMN user_id;
@@ -0,0 +1,5 @@
+-- exposure is held per user, and the reconciler leases rows and brings failed ones back around with backoff
+ALTER TABLE stand_in_transaction ADD COLUMN user_id INT REFERENCES users(id);
+ALTER TABLE stand_in_transaction ADD COLUMN attempts INT NOT NULL DEFAULT 0;
+ALTER TABLE stand_in_transaction ADD COLUMN next_attempt_at TIMESTAMP NOT NULL DEFAULT current_timestamp;
+ALTER TABLE stand_in_transaction ADD COLUMN claimed_until TIMESTAMP;
@@ -0,0 +1,9 @@
+-- where the processor left the charge in our own terms, so nothing downstream has to read a processor's result codes
+ALTER TABLE wallet_card_charge ADD COLUMN processor_charge_status VARCHAR(255);
+UPDATE wallet_card_charge SET processor_charge_status = CASE
+    WHEN TRIM(BOTH '"' FROM returned_charge_status) IN ('Authorised', 'Pending', 'Received', 'Success', 'APPROVED') THEN 'APPROVED'
+    WHEN TRIM(BOTH '"' FROM returned_charge_status) IN ('PartiallyAuthorised', 'PARTIALLY_APPROVED') THEN 'PARTIALLY_APPROVED'
+    WHEN TRIM(BOTH '"' FROM returned_charge_status) = 'PENDING' THEN 'PENDING'
+    ELSE 'DECLINED'
+END
+WHERE returned_charge_status IS NOT NULL;
@@ -0,0 +1 @@
+ALTER TABLE authorization_adjustment DROP COLUMN asa_request_key;
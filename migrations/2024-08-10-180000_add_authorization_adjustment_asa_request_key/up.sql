@@ -0,0 +1,2 @@
+-- lithic retries an asa as is, the key of its payload tells a retry of this adjustment from a later one at the same amount
+ALTER TABLE authorization_adjustment ADD COLUMN asa_request_key UUID;
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 //TODO: placeholder until lithic gets back about generating openapi
 
 //TODO: make this all optional
@@ -27,6 +28,17 @@ pub struct AsaRequest {
     pub token_info: Option<TokenInfo>,
 }
 
+impl AsaRequest {
+    // lithic retries an asa with the same payload, so its key tells a retry apart from a new asa on the same token
+    pub fn replay_key(&self) -> Result<Uuid, serde_json::Error> {
+        Ok(replay_key_for_body(&serde_json::to_string(self)?))
+    }
+}
+
+pub fn replay_key_for_body(body: &str) -> Uuid {
+    Uuid::new_v5(&Uuid::NAMESPACE_OID, body.as_bytes())
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Card {
     pub token: Option<String>,
@@ -61,7 +73,12 @@ pub struct Avs {
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Event {
-
+    pub amount: Option<i32>,
+    pub created: Option<String>,
+    pub result: Option<String>,
+    pub token: Option<String>,
+    #[serde(rename = "type")]
+    pub type_: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -123,7 +140,7 @@ pub fn create_example_asa(amount_cents: i32, mcc_code: String) -> AsaRequest {
         }),
         settled_amount: Some(0),
         status: Some("new".to_string()),
-        token: Some("test_token".to_string()),
+        token: Some(uuid::Uuid::new_v4().to_string()),
         token_info: Some(TokenInfo {})
     }
 
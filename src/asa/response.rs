@@ -27,6 +27,8 @@ pub struct AsaResponse {
     pub result: AsaResponseResult,
     pub avs_result: Option<String>,
     pub balance: Option<Balance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approved_amount: Option<i32>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -103,7 +105,7 @@ impl From<ChargeEngineResult> for AsaResponseResult {
             ChargeEngineResult::Denied => AsaResponseResult::UnauthorizedMerchant,
             ChargeEngineResult::InsufficientFunds => AsaResponseResult::InsufficientFunds,
             ChargeEngineResult::CardPaused => AsaResponseResult::CardPaused,
-            _ => AsaResponseResult::AccountInactive
+            ChargeEngineResult::AvsInvalid => AsaResponseResult::AvsInvalid,
         }
     }
 }
@@ -49,6 +49,7 @@ mod tests {
         assert_eq!(AsaResponseResult::UnauthorizedMerchant, AsaResponseResult::from(ChargeEngineResult::Denied));
         assert_eq!(AsaResponseResult::InsufficientFunds, AsaResponseResult::from(ChargeEngineResult::InsufficientFunds));
         assert_eq!(AsaResponseResult::Approved, AsaResponseResult::from(ChargeEngineResult::Approved));
+        assert_eq!(AsaResponseResult::AvsInvalid, AsaResponseResult::from(ChargeEngineResult::AvsInvalid));
 
     }
 
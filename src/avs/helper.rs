@@ -0,0 +1,98 @@
+use crate::asa::response::AvsResponseResult;
+use crate::footprint::response::VaultAddress;
+
+pub fn normalize_zipcode(zipcode: &str) -> String {
+    // us zips are checked on the first five digits, so 10017-1234 and 10017 are the same
+    let digits: String = zipcode.chars().filter(|c| c.is_ascii_digit()).collect();
+    match digits.len() >= 5 {
+        true => digits[..5].to_string(),
+        false => zipcode.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_uppercase()
+    }
+}
+
+pub fn normalize_address(address: &str) -> String {
+    // networks only compare the house number, "123 Main St" and "123 Main Street Apt 4" are the same
+    let address = address.trim().to_uppercase();
+    match address.split_whitespace().next() {
+        Some(number) if number.chars().any(|c| c.is_ascii_digit()) => number.chars().filter(|c| c.is_ascii_alphanumeric()).collect(),
+        _ => address.chars().filter(|c| c.is_ascii_alphanumeric()).collect()
+    }
+}
+
+fn matches(given: Option<&str>, on_file: Option<&str>, normalize: fn(&str) -> String) -> bool {
+    match (given.map(normalize), on_file.map(normalize)) {
+        (Some(given), Some(on_file)) => !given.is_empty() && given == on_file,
+        _ => false
+    }
+}
+
+pub fn compare_address(address: Option<&str>, zipcode: Option<&str>, on_file: &VaultAddress) -> Option<AvsResponseResult> {
+    if address.is_none() && zipcode.is_none() {
+        return None;
+    }
+    let address_match = matches(address, on_file.address_line1.as_deref(), normalize_address);
+    let zipcode_match = matches(zipcode, on_file.zip.as_deref(), normalize_zipcode);
+    Some(
+        match (address_match, zipcode_match) {
+            (true, true) => AvsResponseResult::Match,
+            (false, true) => AvsResponseResult::MatchZipOnly,
+            (true, false) => AvsResponseResult::MatchAddressOnly,
+            (false, false) => AvsResponseResult::Fail
+        }
+    )
+}
+
+#[cfg(test)]
+mod helper_tests {
+    use crate::asa::response::AvsResponseResult;
+    use crate::avs::helper::{compare_address, normalize_address, normalize_zipcode};
+    use crate::footprint::response::VaultAddress;
+
+    fn on_file() -> VaultAddress {
+        VaultAddress {
+            address_line1: Some("123 Main Street".to_string()),
+            zip: Some("10017".to_string())
+        }
+    }
+
+    #[test]
+    fn test_normalize_zipcode() {
+        assert_eq!("10017", normalize_zipcode("10017"));
+        assert_eq!("10017", normalize_zipcode("10017-1234"));
+        assert_eq!("SW1A1AA", normalize_zipcode("sw1a 1aa"));
+    }
+
+    #[test]
+    fn test_normalize_address() {
+        assert_eq!("123", normalize_address("123 Main St"));
+        assert_eq!("12B", normalize_address(" 12b Main St Apt 4"));
+        assert_eq!("MAINST", normalize_address("Main St"));
+    }
+
+    #[test]
+    fn test_compare_address_match() {
+        assert_eq!(Some(AvsResponseResult::Match), compare_address(Some("123 Main St"), Some("10017-1234"), &on_file()));
+    }
+
+    #[test]
+    fn test_compare_address_zip_only() {
+        assert_eq!(Some(AvsResponseResult::MatchZipOnly), compare_address(Some("99 Main St"), Some("10017"), &on_file()));
+        assert_eq!(Some(AvsResponseResult::MatchZipOnly), compare_address(None, Some("10017"), &on_file()));
+    }
+
+    #[test]
+    fn test_compare_address_address_only() {
+        assert_eq!(Some(AvsResponseResult::MatchAddressOnly), compare_address(Some("123 Main St"), Some("94107"), &on_file()));
+    }
+
+    #[test]
+    fn test_compare_address_fail() {
+        assert_eq!(Some(AvsResponseResult::Fail), compare_address(Some("99 Main St"), Some("94107"), &on_file()));
+        assert_eq!(Some(AvsResponseResult::Fail), compare_address(Some("123 Main St"), Some("10017"), &VaultAddress { address_line1: None, zip: None }));
+    }
+
+    #[test]
+    fn test_compare_address_nothing_given() {
+        assert_eq!(None, compare_address(None, None, &on_file()));
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use tokio::time::timeout;
+use crate::asa::request::AsaRequest;
+use crate::asa::response::AvsResponseResult;
+use crate::avs::helper::compare_address;
+use crate::charge::model::ChargeBudget;
+use crate::configuration::avs::{AvsConfiguration, AvsPolicy};
+use crate::footprint::service::FootprintServiceTrait;
+use crate::user::model::UserModel as User;
+
+#[async_trait(?Send)]
+pub trait AvsServiceTrait {
+    async fn verify(self: Arc<Self>, request: &AsaRequest, user: &User, budget: &ChargeBudget) -> Option<AvsResponseResult>;
+    fn should_decline(self: Arc<Self>, result: &AvsResponseResult) -> bool;
+}
+
+pub struct AvsService {
+    footprint_service: Arc<dyn FootprintServiceTrait>,
+    configuration: AvsConfiguration,
+}
+
+impl AvsService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
+    pub fn new_with_services(
+        footprint_service: Arc<dyn FootprintServiceTrait>,
+        configuration: &AvsConfiguration
+    ) -> Self {
+        Self {
+            footprint_service,
+            configuration: configuration.clone(),
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl AvsServiceTrait for AvsService {
+    #[tracing::instrument(skip(self))]
+    async fn verify(self: Arc<Self>, request: &AsaRequest, user: &User, budget: &ChargeBudget) -> Option<AvsResponseResult> {
+        if !self.configuration.enabled {
+            return None;
+        }
+        let avs = request.avs.as_ref()?;
+        if avs.address.is_none() && avs.zipcode.is_none() {
+            return None;
+        }
+        // not knowing the address on file isn't a reason to decline, so any failure here leaves avs unchecked
+        let on_file = match timeout(budget.remaining(), self.footprint_service.clone().get_vault_address(&user.footprint_vault_id)).await {
+            Ok(Ok(address)) => address,
+            Ok(Err(e)) => {
+                tracing::warn!("Unable to get address on file for user={}, skipping avs error={:?}", user.id, &e);
+                return None;
+            },
+            Err(_) => {
+                tracing::warn!("Address lookup for user={} ran past the asa budget, skipping avs", user.id);
+                return None;
+            }
+        };
+        let result = compare_address(avs.address.as_deref(), avs.zipcode.as_deref(), &on_file);
+        tracing::info!("Avs result={:?} for user={}", &result, user.id);
+        result
+    }
+
+    fn should_decline(self: Arc<Self>, result: &AvsResponseResult) -> bool {
+        match self.configuration.policy {
+            AvsPolicy::Ignore => false,
+            AvsPolicy::DeclineOnFail => *result == AvsResponseResult::Fail,
+            AvsPolicy::RequireMatch => *result != AvsResponseResult::Match
+        }
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::test;
+    use crate::asa::request::{create_example_asa, Avs};
+    use crate::asa::response::AvsResponseResult;
+    use crate::avs::service::{AvsService, AvsServiceTrait};
+    use crate::configuration::avs::{AvsConfiguration, AvsPolicy};
+    use crate::footprint::error::FootprintError;
+    use crate::footprint::response::VaultAddress;
+    use crate::footprint::service::MockFootprintServiceTrait;
+    use crate::test_helper::charge::default_charge_budget;
+    use crate::test_helper::user::create_mock_user;
+
+    fn avs_configuration(enabled: bool, policy: AvsPolicy) -> AvsConfiguration {
+        AvsConfiguration {
+            enabled,
+            policy,
+        }
+    }
+
+    fn footprint_with_address(address_line1: &str, zip: &str) -> MockFootprintServiceTrait {
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let address = VaultAddress {
+            address_line1: Some(address_line1.to_string()),
+            zip: Some(zip.to_string())
+        };
+        footprint_mock.expect_get_vault_address()
+            .times(1)
+            .return_once(move |_| Ok(address));
+        footprint_mock
+    }
+
+    #[test]
+    async fn test_verify_match() {
+        crate::test_helper::general::init();
+        let service = Arc::new(AvsService::new_with_services(
+            Arc::new(footprint_with_address("1 Test Address", "10017")),
+            &avs_configuration(true, AvsPolicy::DeclineOnFail)
+        ));
+        let mut asa = create_example_asa(100, "7184".to_string());
+        asa.avs = Some(Avs { address: Some("1 Test Address".to_string()), zipcode: Some("10017".to_string()) });
+        let result = service.clone().verify(&asa, &create_mock_user(), &default_charge_budget()).await;
+        assert_eq!(Some(AvsResponseResult::Match), result);
+    }
+
+    #[test]
+    async fn test_verify_zip_only() {
+        crate::test_helper::general::init();
+        let service = Arc::new(AvsService::new_with_services(
+            Arc::new(footprint_with_address("2 Other Street", "10017")),
+            &avs_configuration(true, AvsPolicy::DeclineOnFail)
+        ));
+        let mut asa = create_example_asa(100, "7184".to_string());
+        asa.avs = Some(Avs { address: Some("1 Test Address".to_string()), zipcode: Some("10017".to_string()) });
+        let result = service.clone().verify(&asa, &create_mock_user(), &default_charge_budget()).await;
+        assert_eq!(Some(AvsResponseResult::MatchZipOnly), result);
+    }
+
+    #[test]
+    async fn test_verify_fail() {
+        crate::test_helper::general::init();
+        let service = Arc::new(AvsService::new_with_services(
+            Arc::new(footprint_with_address("2 Other Street", "94107")),
+            &avs_configuration(true, AvsPolicy::DeclineOnFail)
+        ));
+        let mut asa = create_example_asa(100, "7184".to_string());
+        asa.avs = Some(Avs { address: Some("1 Test Address".to_string()), zipcode: Some("10017".to_string()) });
+        let result = service.clone().verify(&asa, &create_mock_user(), &default_charge_budget()).await;
+        assert_eq!(Some(AvsResponseResult::Fail), result);
+    }
+
+    #[test]
+    async fn test_verify_skipped_when_vault_unavailable() {
+        crate::test_helper::general::init();
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        footprint_mock.expect_get_vault_address()
+            .times(1)
+            .return_once(|_| Err(FootprintError::Unexpected("test".into())));
+        let service = Arc::new(AvsService::new_with_services(
+            Arc::new(footprint_mock),
+            &avs_configuration(true, AvsPolicy::DeclineOnFail)
+        ));
+        let asa = create_example_asa(100, "7184".to_string());
+        let result = service.clone().verify(&asa, &create_mock_user(), &default_charge_budget()).await;
+        assert_eq!(None, result);
+    }
+
+    #[test]
+    async fn test_verify_skipped_without_avs_or_when_disabled() {
+        crate::test_helper::general::init();
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        footprint_mock.expect_get_vault_address().times(0);
+        let footprint_service = Arc::new(footprint_mock);
+
+        let service = Arc::new(AvsService::new_with_services(
+            footprint_service.clone(),
+            &avs_configuration(true, AvsPolicy::DeclineOnFail)
+        ));
+        let mut asa = create_example_asa(100, "7184".to_string());
+        asa.avs = None;
+        assert_eq!(None, service.clone().verify(&asa, &create_mock_user(), &default_charge_budget()).await);
+
+        let disabled = Arc::new(AvsService::new_with_services(
+            footprint_service.clone(),
+            &avs_configuration(false, AvsPolicy::DeclineOnFail)
+        ));
+        let asa = create_example_asa(100, "7184".to_string());
+        assert_eq!(None, disabled.clone().verify(&asa, &create_mock_user(), &default_charge_budget()).await);
+    }
+
+    #[test]
+    async fn test_should_decline_by_policy() {
+        crate::test_helper::general::init();
+        let footprint_service = Arc::new(MockFootprintServiceTrait::new());
+        let ignore = Arc::new(AvsService::new_with_services(footprint_service.clone(), &avs_configuration(true, AvsPolicy::Ignore)));
+        assert!(!ignore.clone().should_decline(&AvsResponseResult::Fail));
+
+        let decline_on_fail = Arc::new(AvsService::new_with_services(footprint_service.clone(), &avs_configuration(true, AvsPolicy::DeclineOnFail)));
+        assert!(decline_on_fail.clone().should_decline(&AvsResponseResult::Fail));
+        assert!(!decline_on_fail.clone().should_decline(&AvsResponseResult::MatchZipOnly));
+
+        let require_match = Arc::new(AvsService::new_with_services(footprint_service.clone(), &avs_configuration(true, AvsPolicy::RequireMatch)));
+        assert!(require_match.clone().should_decline(&AvsResponseResult::MatchZipOnly));
+        assert!(!require_match.clone().should_decline(&AvsResponseResult::Match));
+    }
+}
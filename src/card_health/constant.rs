@@ -0,0 +1,34 @@
+use std::fmt;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum CardHealthStatus {
+    Healthy,
+    Degraded,
+    Open,
+    HalfOpen
+}
+
+impl CardHealthStatus {
+    // lower goes first when the wallet is reordered
+    pub fn routing_rank(&self) -> u8 {
+        match self {
+            CardHealthStatus::Healthy => 0,
+            CardHealthStatus::Degraded => 1,
+            CardHealthStatus::HalfOpen => 2,
+            CardHealthStatus::Open => 3
+        }
+    }
+}
+
+impl fmt::Display for CardHealthStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            CardHealthStatus::Healthy => "HEALTHY",
+            CardHealthStatus::Degraded => "DEGRADED",
+            CardHealthStatus::Open => "OPEN",
+            CardHealthStatus::HalfOpen => "HALF_OPEN"
+        })
+    }
+}
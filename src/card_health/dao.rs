@@ -0,0 +1,91 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::Duration;
+use crate::card_health::entity::WalletCardChargeOutcome;
+use crate::error::data_error::DataError;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::services::{
+    RedisService,
+    RedisServiceTrait
+};
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::helper::try_redis_fallback_db;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::key::Key;
+
+#[cfg(test)]
+use mockall::{automock, predicate::*};
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait CardHealthDaoTrait {
+    async fn get_recent_outcomes(self: Arc<Self>, wallet_card_id: i32, limit: i64) -> Result<Vec<WalletCardChargeOutcome>, DataError>;
+    async fn get_latest_outcomes(self: Arc<Self>, wallet_card_ids: &Vec<i32>) -> Result<Vec<WalletCardChargeOutcome>, DataError>;
+    async fn expire_recent_outcomes(self: Arc<Self>, wallet_card_id: i32);
+    async fn claim_trial(self: Arc<Self>, wallet_card_id: i32, ttl: Duration) -> Result<bool, DataError>;
+}
+
+pub struct CardHealthDao {
+    #[cfg(not(feature = "no-redis"))]
+    redis: Arc<RedisService>
+}
+
+impl CardHealthDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub fn new() -> Self {
+        #[cfg(not(feature = "no-redis"))] {
+            Self {
+                redis: Arc::new(RedisService::new())
+            }
+        }
+        #[cfg(feature = "no-redis")] {
+            Self {}
+        }
+    }
+}
+
+#[async_trait]
+impl CardHealthDaoTrait for CardHealthDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_recent_outcomes(self: Arc<Self>, wallet_card_id: i32, limit: i64) -> Result<Vec<WalletCardChargeOutcome>, DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            Ok(try_redis_fallback_db(
+                self.redis.clone(),
+                Key::CardHealth(wallet_card_id),
+                || async {WalletCardChargeOutcome::get_recent_for_wallet_card(wallet_card_id, limit).await},
+                false
+            ).await?)
+        }
+        #[cfg(feature = "no-redis")] {
+            WalletCardChargeOutcome::get_recent_for_wallet_card(wallet_card_id, limit).await
+        }
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_latest_outcomes(self: Arc<Self>, wallet_card_ids: &Vec<i32>) -> Result<Vec<WalletCardChargeOutcome>, DataError> {
+        // always from the db, this is what the cached history gets checked against
+        WalletCardChargeOutcome::get_latest_for_wallet_cards(wallet_card_ids).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn expire_recent_outcomes(self: Arc<Self>, wallet_card_id: i32) {
+        #[cfg(not(feature = "no-redis"))] {
+            tracing::info!("Expiring card health in redis for wallet_card_id={}", wallet_card_id);
+            self.redis.clone().expire_now::<_>(&Key::CardHealth(wallet_card_id)).await;
+        }
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn claim_trial(self: Arc<Self>, wallet_card_id: i32, ttl: Duration) -> Result<bool, DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            // only the first asa to set the key gets to try the card, the rest keep skipping it until it expires
+            Ok(
+                self.redis.clone().set_primitive_if_absent(&Key::CardHealthTrial(wallet_card_id), 1, ttl).await
+                    .map_err(|e| DataError::Unexpected(e.into()))?
+            )
+        }
+        #[cfg(feature = "no-redis")] {
+            Ok(true)
+        }
+    }
+}
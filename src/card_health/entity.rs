@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use serde::{Deserialize, Serialize};
+use crate::error::data_error::DataError;
+use crate::payment_processor::constant::ProcessorChargeStatus;
+use crate::schema::wallet_card_charge;
+use crate::util::db;
+
+/// Just the columns of a wallet card charge that say how the card answered.
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, Selectable, PartialEq)]
+#[diesel(table_name = wallet_card_charge)]
+pub struct WalletCardChargeOutcome {
+    pub id: i32,
+    pub wallet_card_id: i32,
+    pub is_success: Option<bool>,
+    pub processor_charge_status: Option<ProcessorChargeStatus>,
+    pub created_at: NaiveDateTime,
+}
+
+impl WalletCardChargeOutcome {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_recent_for_wallet_card(wallet_card_id: i32, limit: i64) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let outcomes = wallet_card_charge::table
+            .filter(wallet_card_charge::wallet_card_id.eq(wallet_card_id))
+            .order(wallet_card_charge::id.desc())
+            .limit(limit)
+            .select(WalletCardChargeOutcome::as_select())
+            .load::<WalletCardChargeOutcome>(&mut conn).await?;
+        Ok(outcomes)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_latest_for_wallet_cards(wallet_card_ids: &Vec<i32>) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let outcomes = wallet_card_charge::table
+            .filter(wallet_card_charge::wallet_card_id.eq_any(wallet_card_ids))
+            .distinct_on(wallet_card_charge::wallet_card_id)
+            .order((wallet_card_charge::wallet_card_id, wallet_card_charge::id.desc()))
+            .select(WalletCardChargeOutcome::as_select())
+            .load::<WalletCardChargeOutcome>(&mut conn).await?;
+        Ok(outcomes)
+    }
+}
@@ -0,0 +1,182 @@
+use chrono::{Duration, NaiveDateTime};
+use crate::card_health::constant::CardHealthStatus;
+use crate::card_health::entity::WalletCardChargeOutcome;
+use crate::card_health::model::{CardHealthModel, CardHealthSummary};
+use crate::configuration::card_health::CardHealthConfiguration;
+use crate::payment_processor::constant::ProcessorChargeStatus;
+
+/// Whether the card itself turned the charge down. A charge that was authorised and then rolled back
+/// (partial released, split tender unwound) still proves the card works, so only the processor status counts.
+/// Abandoned and errored charges have no approving status and count against the card.
+pub fn is_failure(outcome: &WalletCardChargeOutcome) -> bool {
+    if outcome.is_success == Some(true) {
+        return false;
+    }
+    !matches!(
+        outcome.processor_charge_status,
+        Some(ProcessorChargeStatus::Approved) | Some(ProcessorChargeStatus::PartiallyApproved)
+    )
+}
+
+/// Outcomes come in newest first.
+pub fn summarize(outcomes: &Vec<WalletCardChargeOutcome>) -> CardHealthSummary {
+    let mut summary = CardHealthSummary::default();
+    let mut in_streak = true;
+    for outcome in outcomes {
+        summary.attempts += 1;
+        if is_failure(outcome) {
+            summary.failures += 1;
+            if in_streak {
+                summary.consecutive_failures += 1;
+            }
+            if summary.last_failure_at.is_none() {
+                summary.last_failure_at = Some(outcome.created_at);
+            }
+        } else {
+            in_streak = false;
+            if summary.last_success_at.is_none() {
+                summary.last_success_at = Some(outcome.created_at);
+            }
+        }
+    }
+    summary
+}
+
+/// Open cards are skipped until `open_seconds` after their last failure, then go half open and
+/// are skipped again unless the caller hands them a trial charge.
+pub fn evaluate(
+    wallet_card_id: i32,
+    summary: &CardHealthSummary,
+    configuration: &CardHealthConfiguration,
+    now: NaiveDateTime
+) -> CardHealthModel {
+    let (status, retry_at) = if summary.consecutive_failures >= configuration.open_after_failures {
+        let retry_at = summary.last_failure_at
+            .map(|last_failure| last_failure + Duration::seconds(configuration.open_seconds));
+        match retry_at {
+            Some(retry_at) if now < retry_at => (CardHealthStatus::Open, Some(retry_at)),
+            _ => (CardHealthStatus::HalfOpen, retry_at)
+        }
+    } else if summary.consecutive_failures >= configuration.demote_after_failures {
+        (CardHealthStatus::Degraded, None)
+    } else {
+        (CardHealthStatus::Healthy, None)
+    };
+    CardHealthModel {
+        wallet_card_id,
+        status,
+        attempts: summary.attempts,
+        failures: summary.failures,
+        consecutive_failures: summary.consecutive_failures,
+        last_failure_at: summary.last_failure_at,
+        retry_at,
+        skipped: status == CardHealthStatus::Open || status == CardHealthStatus::HalfOpen,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::{Duration, NaiveDateTime};
+    use crate::card_health::constant::CardHealthStatus;
+    use crate::card_health::entity::WalletCardChargeOutcome;
+    use crate::card_health::helper::{evaluate, is_failure, summarize};
+    use crate::card_health::model::CardHealthSummary;
+    use crate::configuration::card_health::CardHealthConfiguration;
+    use crate::payment_processor::constant::ProcessorChargeStatus;
+
+    fn configuration() -> CardHealthConfiguration {
+        CardHealthConfiguration {
+            enabled: true,
+            lookback: 20,
+            demote_after_failures: 2,
+            open_after_failures: 5,
+            open_seconds: 300,
+            trial_seconds: 30
+        }
+    }
+
+    fn outcome(is_success: Option<bool>, status: Option<ProcessorChargeStatus>, created_at: NaiveDateTime) -> WalletCardChargeOutcome {
+        WalletCardChargeOutcome {
+            id: 1,
+            wallet_card_id: 1,
+            is_success,
+            processor_charge_status: status,
+            created_at,
+        }
+    }
+
+    fn failing_summary(consecutive_failures: i32, last_failure_at: NaiveDateTime) -> CardHealthSummary {
+        CardHealthSummary {
+            attempts: consecutive_failures,
+            failures: consecutive_failures,
+            consecutive_failures,
+            last_failure_at: Some(last_failure_at),
+            last_success_at: None,
+        }
+    }
+
+    #[test]
+    fn test_is_failure() {
+        let now = chrono::Utc::now().naive_utc();
+        assert!(!is_failure(&outcome(Some(true), Some(ProcessorChargeStatus::Approved), now)));
+        // rolled back after approving
+        assert!(!is_failure(&outcome(None, Some(ProcessorChargeStatus::Approved), now)));
+        assert!(!is_failure(&outcome(None, Some(ProcessorChargeStatus::PartiallyApproved), now)));
+        assert!(is_failure(&outcome(Some(false), Some(ProcessorChargeStatus::Declined), now)));
+        // cancelled before moving on
+        assert!(is_failure(&outcome(None, Some(ProcessorChargeStatus::Pending), now)));
+        // abandoned
+        assert!(is_failure(&outcome(None, None, now)));
+    }
+
+    #[test]
+    fn test_summarize_counts_streak_from_newest() {
+        let now = chrono::Utc::now().naive_utc();
+        let outcomes = vec![
+            outcome(Some(false), Some(ProcessorChargeStatus::Declined), now),
+            outcome(Some(false), Some(ProcessorChargeStatus::Declined), now - Duration::seconds(10)),
+            outcome(Some(true), Some(ProcessorChargeStatus::Approved), now - Duration::seconds(20)),
+            outcome(Some(false), Some(ProcessorChargeStatus::Declined), now - Duration::seconds(30)),
+        ];
+        let summary = summarize(&outcomes);
+        assert_eq!(4, summary.attempts);
+        assert_eq!(3, summary.failures);
+        assert_eq!(2, summary.consecutive_failures);
+        assert_eq!(Some(now), summary.last_failure_at);
+        assert_eq!(Some(now - Duration::seconds(20)), summary.last_success_at);
+    }
+
+    #[test]
+    fn test_summarize_empty() {
+        assert_eq!(CardHealthSummary::default(), summarize(&vec![]));
+    }
+
+    #[test]
+    fn test_evaluate_healthy_and_degraded() {
+        let now = chrono::Utc::now().naive_utc();
+        let healthy = evaluate(1, &failing_summary(1, now), &configuration(), now);
+        assert_eq!(CardHealthStatus::Healthy, healthy.status);
+        assert!(!healthy.skipped);
+        let degraded = evaluate(1, &failing_summary(2, now), &configuration(), now);
+        assert_eq!(CardHealthStatus::Degraded, degraded.status);
+        assert!(!degraded.skipped);
+        assert_eq!(None, degraded.retry_at);
+    }
+
+    #[test]
+    fn test_evaluate_open_within_window() {
+        let now = chrono::Utc::now().naive_utc();
+        let health = evaluate(1, &failing_summary(5, now - Duration::seconds(60)), &configuration(), now);
+        assert_eq!(CardHealthStatus::Open, health.status);
+        assert!(health.skipped);
+        assert_eq!(Some(now + Duration::seconds(240)), health.retry_at);
+    }
+
+    #[test]
+    fn test_evaluate_half_open_after_window() {
+        let now = chrono::Utc::now().naive_utc();
+        let health = evaluate(1, &failing_summary(7, now - Duration::seconds(301)), &configuration(), now);
+        assert_eq!(CardHealthStatus::HalfOpen, health.status);
+        assert!(health.skipped);
+    }
+}
@@ -0,0 +1,8 @@
+pub mod constant;
+pub mod helper;
+pub mod model;
+pub mod service;
+
+mod dao;
+mod entity;
+mod tests;
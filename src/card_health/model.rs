@@ -0,0 +1,25 @@
+use chrono::NaiveDateTime;
+use crate::card_health::constant::CardHealthStatus;
+
+/// What the recent charge history says about one wallet card, newest attempt first.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CardHealthSummary {
+    pub attempts: i32,
+    pub failures: i32,
+    pub consecutive_failures: i32,
+    pub last_failure_at: Option<NaiveDateTime>,
+    pub last_success_at: Option<NaiveDateTime>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CardHealthModel {
+    pub wallet_card_id: i32,
+    pub status: CardHealthStatus,
+    pub attempts: i32,
+    pub failures: i32,
+    pub consecutive_failures: i32,
+    pub last_failure_at: Option<NaiveDateTime>,
+    // when an open card is next given a trial charge
+    pub retry_at: Option<NaiveDateTime>,
+    pub skipped: bool,
+}
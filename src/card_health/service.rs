@@ -0,0 +1,149 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::Duration;
+use futures::future::join_all;
+use crate::card_health::constant::CardHealthStatus;
+use crate::card_health::dao::{CardHealthDao, CardHealthDaoTrait};
+use crate::card_health::entity::WalletCardChargeOutcome;
+use crate::card_health::helper::{evaluate, is_failure, summarize};
+use crate::card_health::model::CardHealthModel;
+use crate::configuration::card_health::CardHealthConfiguration;
+use crate::error::data_error::DataError;
+use crate::wallet::model::WalletModelWithRule as Wallet;
+
+#[async_trait(?Send)]
+pub trait CardHealthServiceTrait {
+    async fn route(self: Arc<Self>, cards: Vec<Wallet>) -> Vec<Wallet>;
+    async fn get_health(self: Arc<Self>, wallet_card_ids: &Vec<i32>) -> Result<Vec<CardHealthModel>, DataError>;
+    async fn invalidate(self: Arc<Self>, cards: &Vec<Wallet>);
+}
+
+pub struct CardHealthService {
+    dao: Arc<dyn CardHealthDaoTrait>,
+    configuration: CardHealthConfiguration,
+}
+
+impl CardHealthService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
+    pub fn new(configuration: &CardHealthConfiguration) -> Self {
+        Self {
+            dao: Arc::new(CardHealthDao::new()),
+            configuration: configuration.clone(),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn new_with_mocks(
+        dao: Arc<dyn CardHealthDaoTrait>,
+        configuration: &CardHealthConfiguration
+    ) -> Self {
+        Self {
+            dao,
+            configuration: configuration.clone(),
+        }
+    }
+
+    async fn health_for(self: Arc<Self>, wallet_card_id: i32) -> Result<CardHealthModel, DataError> {
+        let outcomes = self.dao.clone().get_recent_outcomes(wallet_card_id, self.configuration.lookback).await?;
+        Ok(evaluate(wallet_card_id, &summarize(&outcomes), &self.configuration, chrono::Utc::now().naive_utc()))
+    }
+}
+
+/// Whether the newest charge on a card moves its health away from what routing saw. Another success
+/// on a card without a failure streak changes nothing, so its cached history can stay.
+fn outcome_changed(card: &Wallet, latest: Option<&WalletCardChargeOutcome>) -> bool {
+    let (Some(health), Some(latest)) = (card.health.as_ref(), latest) else {
+        return true;
+    };
+    if is_failure(latest) {
+        health.last_failure_at.map_or(true, |last_failure| latest.created_at > last_failure)
+    } else {
+        health.consecutive_failures > 0
+    }
+}
+
+#[async_trait(?Send)]
+impl CardHealthServiceTrait for CardHealthService {
+    /// Moves failing cards behind healthy ones and marks open ones as skipped. Ties keep the rule order
+    /// they came in with. A half open card only gets tried by the asa that wins its trial.
+    #[tracing::instrument(skip(self))]
+    async fn route(self: Arc<Self>, cards: Vec<Wallet>) -> Vec<Wallet> {
+        if !self.configuration.enabled {
+            return cards;
+        }
+        let mut routed: Vec<Wallet> = vec![];
+        for mut card in cards {
+            // not knowing a card's history is no reason to stop trying it
+            let mut health = match self.clone().health_for(card.id).await {
+                Ok(health) => health,
+                Err(e) => {
+                    tracing::warn!("Unable to get health for wallet_card={}, treating as healthy error={:?}", card.id, &e);
+                    routed.push(card);
+                    continue;
+                }
+            };
+            if health.status == CardHealthStatus::HalfOpen {
+                let ttl = Duration::seconds(self.configuration.trial_seconds);
+                health.skipped = match self.dao.clone().claim_trial(card.id, ttl).await {
+                    Ok(claimed) => !claimed,
+                    Err(e) => {
+                        tracing::warn!("Unable to claim trial for wallet_card={}, skipping error={:?}", card.id, &e);
+                        true
+                    }
+                };
+            }
+            if health.status != CardHealthStatus::Healthy {
+                tracing::info!("Wallet_card={} is {} after {} consecutive failures, skipped={}", card.id, &health.status, health.consecutive_failures, health.skipped);
+            }
+            card.health = Some(health);
+            routed.push(card);
+        }
+        // a wallet where every card is skipped would decline everything, so try them all, least broken first
+        let all_skipped = routed.iter().all(|card| card.is_skipped());
+        if all_skipped {
+            tracing::warn!("Every card in the wallet is skipped, trying all of them");
+            for card in routed.iter_mut() {
+                if let Some(health) = card.health.as_mut() {
+                    health.skipped = false;
+                }
+            }
+        }
+        routed.sort_by_key(|card| card.health.as_ref().map_or(
+            (false, 0),
+            |health| (health.skipped, health.status.routing_rank())
+        ));
+        routed
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_health(self: Arc<Self>, wallet_card_ids: &Vec<i32>) -> Result<Vec<CardHealthModel>, DataError> {
+        let mut health = vec![];
+        for wallet_card_id in wallet_card_ids {
+            health.push(self.clone().health_for(*wallet_card_id).await?);
+        }
+        Ok(health)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn invalidate(self: Arc<Self>, cards: &Vec<Wallet>) {
+        if !self.configuration.enabled {
+            return;
+        }
+        let tried: Vec<&Wallet> = cards.iter().filter(|card| !card.is_skipped()).collect();
+        if tried.is_empty() {
+            return;
+        }
+        let tried_ids = tried.iter().map(|card| card.id).collect();
+        let changed: Vec<i32> = match self.dao.clone().get_latest_outcomes(&tried_ids).await {
+            Ok(latest) => tried.iter()
+                .filter(|card| outcome_changed(card, latest.iter().find(|outcome| outcome.wallet_card_id == card.id)))
+                .map(|card| card.id)
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Unable to get latest outcomes, expiring every tried card error={:?}", &e);
+                tried_ids
+            }
+        };
+        join_all(changed.into_iter().map(|wallet_card_id| self.dao.clone().expire_recent_outcomes(wallet_card_id))).await;
+    }
+}
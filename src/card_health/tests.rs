@@ -0,0 +1,233 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::test;
+    use chrono::Duration;
+    use crate::card_health::constant::CardHealthStatus;
+    use crate::card_health::dao::MockCardHealthDaoTrait;
+    use crate::card_health::entity::WalletCardChargeOutcome;
+    use crate::card_health::service::{CardHealthService, CardHealthServiceTrait};
+    use crate::configuration::card_health::CardHealthConfiguration;
+    use crate::error::data_error::DataError;
+    use crate::payment_processor::constant::ProcessorChargeStatus;
+    use crate::test_helper::user::create_user;
+    use crate::test_helper::wallet::{create_mock_wallet_with_args, create_wallet_with_rule};
+    use crate::wallet::model::WalletModelWithRule as Wallet;
+
+    const OPEN_CARD: i32 = 1;
+    const DEGRADED_CARD: i32 = 2;
+    const HEALTHY_CARD: i32 = 3;
+    const HALF_OPEN_CARD: i32 = 4;
+
+    fn configuration() -> CardHealthConfiguration {
+        CardHealthConfiguration {
+            enabled: true,
+            lookback: 20,
+            demote_after_failures: 2,
+            open_after_failures: 5,
+            open_seconds: 300,
+            trial_seconds: 30
+        }
+    }
+
+    fn card(id: i32) -> Wallet {
+        create_mock_wallet_with_args(id, 1, 1).into()
+    }
+
+    fn declines(wallet_card_id: i32, count: usize, seconds_ago: i64) -> Vec<WalletCardChargeOutcome> {
+        let now = chrono::Utc::now().naive_utc();
+        (0..count).map(|i| WalletCardChargeOutcome {
+            id: (count - i) as i32,
+            wallet_card_id,
+            is_success: Some(false),
+            processor_charge_status: Some(ProcessorChargeStatus::Declined),
+            created_at: now - Duration::seconds(seconds_ago + i as i64),
+        }).collect()
+    }
+
+    fn outcomes_for(wallet_card_id: i32) -> Vec<WalletCardChargeOutcome> {
+        match wallet_card_id {
+            OPEN_CARD => declines(wallet_card_id, 5, 10),
+            DEGRADED_CARD => declines(wallet_card_id, 2, 10),
+            HALF_OPEN_CARD => declines(wallet_card_id, 6, 600),
+            _ => vec![]
+        }
+    }
+
+    fn ids(cards: &Vec<Wallet>) -> Vec<i32> {
+        cards.iter().map(|card| card.id).collect()
+    }
+
+    #[test]
+    async fn test_route_demotes_and_skips_failing_cards() {
+        crate::test_helper::general::init();
+        let mut dao = MockCardHealthDaoTrait::new();
+        dao.expect_get_recent_outcomes()
+            .times(3)
+            .returning(|wallet_card_id, _| Ok(outcomes_for(wallet_card_id)));
+        dao.expect_claim_trial()
+            .times(0);
+        let service = Arc::new(CardHealthService::new_with_mocks(Arc::new(dao), &configuration()));
+        let routed = service.clone().route(vec![card(OPEN_CARD), card(DEGRADED_CARD), card(HEALTHY_CARD)]).await;
+        assert_eq!(vec![HEALTHY_CARD, DEGRADED_CARD, OPEN_CARD], ids(&routed));
+        assert_eq!(
+            vec![Some(CardHealthStatus::Healthy), Some(CardHealthStatus::Degraded), Some(CardHealthStatus::Open)],
+            routed.iter().map(|card| card.health.as_ref().map(|health| health.status)).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![false, false, true], routed.iter().map(|card| card.is_skipped()).collect::<Vec<_>>());
+        assert!(routed[2].health.as_ref().unwrap().retry_at.is_some());
+    }
+
+    #[test]
+    async fn test_route_half_open_trial() {
+        crate::test_helper::general::init();
+        let mut dao = MockCardHealthDaoTrait::new();
+        dao.expect_get_recent_outcomes()
+            .returning(|wallet_card_id, _| Ok(outcomes_for(wallet_card_id)));
+        dao.expect_claim_trial()
+            .times(1)
+            .return_once(|_, _| Ok(true));
+        let service = Arc::new(CardHealthService::new_with_mocks(Arc::new(dao), &configuration()));
+        let routed = service.clone().route(vec![card(HALF_OPEN_CARD), card(DEGRADED_CARD), card(HEALTHY_CARD)]).await;
+        // the trial goes after every card that is still trusted
+        assert_eq!(vec![HEALTHY_CARD, DEGRADED_CARD, HALF_OPEN_CARD], ids(&routed));
+        assert_eq!(Some(CardHealthStatus::HalfOpen), routed[2].health.as_ref().map(|health| health.status));
+        assert!(!routed[2].is_skipped());
+    }
+
+    #[test]
+    async fn test_route_half_open_trial_already_claimed() {
+        crate::test_helper::general::init();
+        let mut dao = MockCardHealthDaoTrait::new();
+        dao.expect_get_recent_outcomes()
+            .returning(|wallet_card_id, _| Ok(outcomes_for(wallet_card_id)));
+        dao.expect_claim_trial()
+            .times(1)
+            .return_once(|_, _| Ok(false));
+        let service = Arc::new(CardHealthService::new_with_mocks(Arc::new(dao), &configuration()));
+        let routed = service.clone().route(vec![card(HALF_OPEN_CARD), card(HEALTHY_CARD)]).await;
+        assert_eq!(vec![HEALTHY_CARD, HALF_OPEN_CARD], ids(&routed));
+        assert!(routed[1].is_skipped());
+    }
+
+    #[test]
+    async fn test_route_never_skips_whole_wallet() {
+        crate::test_helper::general::init();
+        let mut dao = MockCardHealthDaoTrait::new();
+        dao.expect_get_recent_outcomes()
+            .returning(|wallet_card_id, _| Ok(outcomes_for(wallet_card_id)));
+        dao.expect_claim_trial()
+            .times(1)
+            .return_once(|_, _| Ok(false));
+        let service = Arc::new(CardHealthService::new_with_mocks(Arc::new(dao), &configuration()));
+        let routed = service.clone().route(vec![card(HALF_OPEN_CARD), card(OPEN_CARD)]).await;
+        assert_eq!(vec![HALF_OPEN_CARD, OPEN_CARD], ids(&routed));
+        assert!(routed.iter().all(|card| !card.is_skipped()));
+    }
+
+    #[test]
+    async fn test_route_treats_unknown_history_as_healthy() {
+        crate::test_helper::general::init();
+        let mut dao = MockCardHealthDaoTrait::new();
+        dao.expect_get_recent_outcomes()
+            .returning(|wallet_card_id, _| match wallet_card_id {
+                HEALTHY_CARD => Err(DataError::Unexpected("database unavailable".into())),
+                _ => Ok(outcomes_for(wallet_card_id))
+            });
+        let service = Arc::new(CardHealthService::new_with_mocks(Arc::new(dao), &configuration()));
+        let routed = service.clone().route(vec![card(DEGRADED_CARD), card(HEALTHY_CARD)]).await;
+        assert_eq!(vec![HEALTHY_CARD, DEGRADED_CARD], ids(&routed));
+        assert_eq!(None, routed[0].health);
+    }
+
+    #[test]
+    async fn test_route_disabled() {
+        crate::test_helper::general::init();
+        let mut dao = MockCardHealthDaoTrait::new();
+        dao.expect_get_recent_outcomes()
+            .times(0);
+        let mut configuration = configuration();
+        configuration.enabled = false;
+        let service = Arc::new(CardHealthService::new_with_mocks(Arc::new(dao), &configuration));
+        let cards = vec![card(OPEN_CARD), card(HEALTHY_CARD)];
+        assert_eq!(cards.clone(), service.clone().route(cards).await);
+    }
+
+    #[test]
+    async fn test_invalidate_skips_untried_cards() {
+        crate::test_helper::general::init();
+        let mut dao = MockCardHealthDaoTrait::new();
+        dao.expect_get_recent_outcomes()
+            .returning(|wallet_card_id, _| Ok(outcomes_for(wallet_card_id)));
+        dao.expect_get_latest_outcomes()
+            .withf(|wallet_card_ids| *wallet_card_ids == vec![HEALTHY_CARD])
+            .times(1)
+            .return_once(|_| Ok(declines(HEALTHY_CARD, 1, 0)));
+        dao.expect_expire_recent_outcomes()
+            .withf(|wallet_card_id| *wallet_card_id == HEALTHY_CARD)
+            .times(1)
+            .return_const(());
+        let service = Arc::new(CardHealthService::new_with_mocks(Arc::new(dao), &configuration()));
+        let routed = service.clone().route(vec![card(OPEN_CARD), card(HEALTHY_CARD)]).await;
+        service.clone().invalidate(&routed).await;
+    }
+
+    #[test]
+    async fn test_invalidate_only_changed_cards() {
+        crate::test_helper::general::init();
+        let mut dao = MockCardHealthDaoTrait::new();
+        dao.expect_get_recent_outcomes()
+            .returning(|wallet_card_id, _| Ok(outcomes_for(wallet_card_id)));
+        dao.expect_get_latest_outcomes()
+            .times(1)
+            .return_once(|_| {
+                let now = chrono::Utc::now().naive_utc();
+                let success = |wallet_card_id| WalletCardChargeOutcome {
+                    id: 100,
+                    wallet_card_id,
+                    is_success: Some(true),
+                    processor_charge_status: Some(ProcessorChargeStatus::Approved),
+                    created_at: now,
+                };
+                // the degraded card recovered, the healthy card stayed healthy
+                Ok(vec![success(DEGRADED_CARD), success(HEALTHY_CARD)])
+            });
+        dao.expect_expire_recent_outcomes()
+            .withf(|wallet_card_id| *wallet_card_id == DEGRADED_CARD)
+            .times(1)
+            .return_const(());
+        let service = Arc::new(CardHealthService::new_with_mocks(Arc::new(dao), &configuration()));
+        let routed = service.clone().route(vec![card(DEGRADED_CARD), card(HEALTHY_CARD)]).await;
+        service.clone().invalidate(&routed).await;
+    }
+
+    #[test]
+    async fn test_invalidate_expires_tried_cards_when_lookup_fails() {
+        crate::test_helper::general::init();
+        let mut dao = MockCardHealthDaoTrait::new();
+        dao.expect_get_recent_outcomes()
+            .returning(|wallet_card_id, _| Ok(outcomes_for(wallet_card_id)));
+        dao.expect_get_latest_outcomes()
+            .times(1)
+            .return_once(|_| Err(DataError::Unexpected("database unavailable".into())));
+        dao.expect_expire_recent_outcomes()
+            .times(2)
+            .return_const(());
+        let service = Arc::new(CardHealthService::new_with_mocks(Arc::new(dao), &configuration()));
+        let routed = service.clone().route(vec![card(DEGRADED_CARD), card(HEALTHY_CARD)]).await;
+        service.clone().invalidate(&routed).await;
+    }
+
+    #[test]
+    async fn test_get_health_for_new_card() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let wallet_card = create_wallet_with_rule(&user).await;
+        let service = Arc::new(CardHealthService::new(&configuration()));
+        let health = service.clone().get_health(&vec![wallet_card.id]).await.expect("gets health");
+        assert_eq!(1, health.len());
+        assert_eq!(CardHealthStatus::Healthy, health[0].status);
+        assert_eq!(0, health[0].attempts);
+        assert!(!health[0].skipped);
+    }
+}
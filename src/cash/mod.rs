@@ -0,0 +1,3 @@
+pub mod service;
+
+mod tests;
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+use crate::asa::request::AsaRequest;
+use crate::configuration::cash::{CashConfiguration, CashPolicy};
+use crate::wallet::model::WalletModelWithRule as Wallet;
+
+pub trait CashServiceTrait {
+    fn route(self: Arc<Self>, request: &AsaRequest, cards: Vec<Wallet>) -> Option<Vec<Wallet>>;
+}
+
+pub struct CashService {
+    configuration: CashConfiguration,
+}
+
+impl CashService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
+    pub fn new(configuration: &CashConfiguration) -> Self {
+        Self {
+            configuration: configuration.clone(),
+        }
+    }
+}
+
+impl CashServiceTrait for CashService {
+    /// Cards that can take an asa with a cash portion, in the order they came in. None means the asa
+    /// has to be declined, charging cash to a card as a purchase leaves the user with cash advance fees.
+    #[tracing::instrument(skip(self))]
+    fn route(self: Arc<Self>, request: &AsaRequest, cards: Vec<Wallet>) -> Option<Vec<Wallet>> {
+        let cash_amount_cents = match request.cash_amount.filter(|cash| *cash > 0) {
+            Some(cash) => cash,
+            None => return Some(cards)
+        };
+        match self.configuration.policy {
+            CashPolicy::Decline => {
+                tracing::info!("Declining asa with {} cents of cash", cash_amount_cents);
+                None
+            },
+            CashPolicy::CashAdvanceCardsOnly => {
+                let cards: Vec<Wallet> = cards.into_iter()
+                    .filter(|card| card.cash_advance_enabled)
+                    .collect();
+                tracing::info!("Routing {} cents of cash to {} cash advance cards", cash_amount_cents, cards.len());
+                (!cards.is_empty()).then_some(cards)
+            }
+        }
+    }
+}
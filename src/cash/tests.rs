@@ -0,0 +1,47 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::test;
+    use crate::asa::request::create_example_asa;
+    use crate::cash::service::{CashService, CashServiceTrait};
+    use crate::configuration::cash::{CashConfiguration, CashPolicy};
+    use crate::test_helper::wallet::create_mock_wallet_with_args;
+    use crate::wallet::model::WalletModelWithRule as Wallet;
+
+    fn card(id: i32, cash_advance_enabled: bool) -> Wallet {
+        let mut card: Wallet = create_mock_wallet_with_args(id, 1, 1).into();
+        card.cash_advance_enabled = cash_advance_enabled;
+        card
+    }
+
+    fn service(policy: CashPolicy) -> Arc<CashService> {
+        Arc::new(CashService::new(&CashConfiguration { policy }))
+    }
+
+    #[test]
+    async fn test_route_without_cash_keeps_every_card() {
+        let request = create_example_asa(1000, "5411".to_string());
+        let cards = vec![card(1, false), card(2, true)];
+        assert_eq!(Some(cards.clone()), service(CashPolicy::Decline).route(&request, cards.clone()));
+        assert_eq!(Some(cards.clone()), service(CashPolicy::CashAdvanceCardsOnly).route(&request, cards));
+    }
+
+    #[test]
+    async fn test_route_declines_cash() {
+        let mut request = create_example_asa(1000, "5411".to_string());
+        request.cash_amount = Some(400);
+        assert_eq!(None, service(CashPolicy::Decline).route(&request, vec![card(1, true)]));
+    }
+
+    #[test]
+    async fn test_route_cash_to_cash_advance_cards_only() {
+        let mut request = create_example_asa(1000, "6011".to_string());
+        request.cash_amount = Some(1000);
+        let routed = service(CashPolicy::CashAdvanceCardsOnly)
+            .route(&request, vec![card(1, false), card(2, true), card(3, true)])
+            .expect("routes to cash advance cards");
+        assert_eq!(vec![2, 3], routed.iter().map(|card| card.id).collect::<Vec<i32>>());
+
+        assert_eq!(None, service(CashPolicy::CashAdvanceCardsOnly).route(&request, vec![card(1, false)]));
+    }
+}
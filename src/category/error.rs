@@ -2,6 +2,8 @@ use crate::error::data_error::DataError;
 
 #[derive(thiserror::Error, Debug)]
 pub enum CategoryError {
+    #[error("Not found")]
+    NotFound(#[source] Box<dyn std::error::Error + Send + Sync>),
     #[error("Unexpected")]
     Unexpected(#[source] Box<dyn std::error::Error + Send + Sync>),
 }
@@ -10,7 +12,7 @@ impl From<DataError> for CategoryError {
     fn from(value: DataError) -> Self {
         match value {
             DataError::Conflict(e) => CategoryError::Unexpected(e),
-            DataError::NotFound(e) => CategoryError::Unexpected(e),
+            DataError::NotFound(e) => CategoryError::NotFound(e),
             DataError::Format(e) => CategoryError::Unexpected(e),
             DataError::Unexpected(e) => CategoryError::Unexpected(e),
         }
@@ -21,7 +23,8 @@ impl From<DataError> for CategoryError {
 impl PartialEq for CategoryError {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            (CategoryError::Unexpected(_), CategoryError::Unexpected(_)) => true,
+            (CategoryError::NotFound(_), CategoryError::NotFound(_))
+            | (CategoryError::Unexpected(_), CategoryError::Unexpected(_)) => true,
             _ => false
         }
     }
@@ -35,7 +38,7 @@ mod test {
     pub fn test_data_error_mappings() {
         let base_test = "test";
         assert_eq!(CategoryError::Unexpected(base_test.clone().into()), CategoryError::from(DataError::Conflict(base_test.clone().into())));
-        assert_eq!(CategoryError::Unexpected(base_test.clone().into()), CategoryError::from(DataError::NotFound(base_test.clone().into())));
+        assert_eq!(CategoryError::NotFound(base_test.clone().into()), CategoryError::from(DataError::NotFound(base_test.clone().into())));
         assert_eq!(CategoryError::Unexpected(base_test.clone().into()), CategoryError::from(DataError::Format(base_test.clone().into())));
         assert_eq!(CategoryError::Unexpected(base_test.clone().into()), CategoryError::from(DataError::Unexpected(base_test.clone().into())));
     }
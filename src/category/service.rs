@@ -1,5 +1,7 @@
 use async_trait::async_trait;
 use std::sync::Arc;
+#[cfg(test)]
+use mockall::automock;
 use super::entity::{Category, MccMapping};
 use super::error::CategoryError;
 use crate::category::dao::{CategoryDao, CategoryDaoTrait, MccMappingDao, MccMappingDaoTrait};
@@ -7,6 +9,7 @@ use crate::category::model::{CategoryModel, MccMappingModel};
 
 
 // TODO: all future services should return only objects exposed in request / response
+#[cfg_attr(test, automock)]
 #[async_trait(?Send)]
 pub trait CategoryServiceTrait {
     async fn get_category_by_name(self: Arc<Self>, name: &str) -> Result<CategoryModel, CategoryError>;
@@ -79,7 +82,7 @@ mod test {
     async fn test_get_category_by_name_not_found() {
         let svc = Arc::new(CategoryService::new());
         let error = svc.clone().get_category_by_name("restaurants are not category named").await.expect_err("Ok");
-        assert_eq!(CategoryError::Unexpected("test".into()), error);
+        assert_eq!(CategoryError::NotFound("test".into()), error);
     }
 
     #[test]
@@ -94,7 +97,7 @@ mod test {
     async fn test_get_mcc_mapping_by_mcc_not_found() {
         let svc = Arc::new(CategoryService::new());
         let error = svc.clone().get_mcc_mapping_by_mcc("this is not an mcc").await.expect_err("Ok");
-        assert_eq!(CategoryError::Unexpected("test".into()), error);
+        assert_eq!(CategoryError::NotFound("test".into()), error);
 
     }
 
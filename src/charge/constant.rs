@@ -11,8 +11,12 @@ use diesel::serialize::{ToSql, Output, IsNull};
 use diesel::sql_types::*;
 use std::fmt::{Display, Formatter};
 use adyen_checkout::models::payment_response::ResultCode;
+use crate::payment_processor::constant::ProcessorChargeStatus;
 use serde::{Serializer};
 
+// how far past its authorization a clearing may capture on a card, tips land on top of the authorized amount
+pub const MAX_TIP_PERCENT: i32 = 20;
+
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, AsExpression, FromSqlRow)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 #[diesel(sql_type = Text)]
@@ -64,9 +68,170 @@ impl ChargeStatus {
     }
 }
 
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, AsExpression, FromSqlRow)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[diesel(sql_type = Text)]
+pub enum CancelStatus {
+    Received,
+    Failed
+}
+
+impl ToSql<Text, Pg> for CancelStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.to_string().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for CancelStatus {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"RECEIVED" => Ok(CancelStatus::Received),
+            b"FAILED" => Ok(CancelStatus::Failed),
+            v => Err(format!("Unknown value for CancelStatus found").into()),
+        }
+    }
+}
 
+impl fmt::Display for CancelStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            CancelStatus::Received => "RECEIVED",
+            CancelStatus::Failed => "FAILED"
+        })
+    }
+}
 
 
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, AsExpression, FromSqlRow)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[diesel(sql_type = Text)]
+pub enum RefundStatus {
+    Received,
+    Failed
+}
+
+impl ToSql<Text, Pg> for RefundStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.to_string().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for RefundStatus {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"RECEIVED" => Ok(RefundStatus::Received),
+            b"FAILED" => Ok(RefundStatus::Failed),
+            v => Err(format!("Unknown value for RefundStatus found").into()),
+        }
+    }
+}
+
+impl fmt::Display for RefundStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            RefundStatus::Received => "RECEIVED",
+            RefundStatus::Failed => "FAILED"
+        })
+    }
+}
+
+// where a successful wallet charge is in its auth and capture, charges made before delayed capture have none
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, AsExpression, FromSqlRow)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[diesel(sql_type = Text)]
+pub enum CaptureStatus {
+    Authorized,
+    Captured,
+    Failed,
+    Cancelled
+}
+
+impl ToSql<Text, Pg> for CaptureStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.to_string().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for CaptureStatus {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"AUTHORIZED" => Ok(CaptureStatus::Authorized),
+            b"CAPTURED" => Ok(CaptureStatus::Captured),
+            b"FAILED" => Ok(CaptureStatus::Failed),
+            b"CANCELLED" => Ok(CaptureStatus::Cancelled),
+            v => Err(format!("Unknown value for CaptureStatus found").into()),
+        }
+    }
+}
+
+impl fmt::Display for CaptureStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            CaptureStatus::Authorized => "AUTHORIZED",
+            CaptureStatus::Captured => "CAPTURED",
+            CaptureStatus::Failed => "FAILED",
+            CaptureStatus::Cancelled => "CANCELLED"
+        })
+    }
+}
+
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, AsExpression, FromSqlRow)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[diesel(sql_type = Text)]
+pub enum TransactionEventType {
+    Clearing,
+    Void,
+    AuthorizationReversal,
+    AuthorizationExpiry,
+    Return
+}
+
+impl ToSql<Text, Pg> for TransactionEventType {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.to_string().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for TransactionEventType {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match TransactionEventType::from_lithic_type(std::str::from_utf8(bytes.as_bytes())?) {
+            Some(event_type) => Ok(event_type),
+            None => Err(format!("Unknown value for TransactionEventType found").into()),
+        }
+    }
+}
+
+impl fmt::Display for TransactionEventType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            TransactionEventType::Clearing => "CLEARING",
+            TransactionEventType::Void => "VOID",
+            TransactionEventType::AuthorizationReversal => "AUTHORIZATION_REVERSAL",
+            TransactionEventType::AuthorizationExpiry => "AUTHORIZATION_EXPIRY",
+            TransactionEventType::Return => "RETURN"
+        })
+    }
+}
+
+impl TransactionEventType {
+    // lithic sends many more event types, we only act on the ones that move money after the auth
+    pub fn from_lithic_type(value: &str) -> Option<Self> {
+        match value {
+            "CLEARING" => Some(TransactionEventType::Clearing),
+            "VOID" => Some(TransactionEventType::Void),
+            "AUTHORIZATION_REVERSAL" => Some(TransactionEventType::AuthorizationReversal),
+            "AUTHORIZATION_EXPIRY" => Some(TransactionEventType::AuthorizationExpiry),
+            "RETURN" => Some(TransactionEventType::Return),
+            _ => None
+        }
+    }
+}
+
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum ChargeEngineResult {
@@ -74,7 +239,8 @@ pub enum ChargeEngineResult {
     Denied,
     InsufficientFunds,
     CardClosed,
-    CardPaused
+    CardPaused,
+    AvsInvalid
 }
 
 impl ChargeEngineResult {
@@ -84,6 +250,57 @@ impl ChargeEngineResult {
             _ => None
         }
     }
+
+    // when every card declines, the most specific reason wins so the merchant sees something actionable
+    pub fn decline_priority(self: &Self) -> u8 {
+        match *self {
+            ChargeEngineResult::InsufficientFunds => 3,
+            ChargeEngineResult::AvsInvalid => 2,
+            ChargeEngineResult::CardClosed => 1,
+            _ => 0
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum RefusalReason {
+    InsufficientFunds,
+    ExpiredCard,
+    BlockedCard,
+    Fraud,
+    CvcDeclined,
+    AvsDeclined,
+    Other
+}
+
+impl RefusalReason {
+    // adyen refusalReasonCode values, see https://docs.adyen.com/development-resources/refusal-reasons
+    pub fn from_refusal_reason_code(code: &str) -> Self {
+        match code {
+            "12" | "28" | "29" => RefusalReason::InsufficientFunds,
+            "6" => RefusalReason::ExpiredCard,
+            "5" | "25" => RefusalReason::BlockedCard,
+            "14" | "20" | "22" | "31" => RefusalReason::Fraud,
+            "24" => RefusalReason::CvcDeclined,
+            "32" => RefusalReason::AvsDeclined,
+            _ => RefusalReason::Other
+        }
+    }
+}
+
+impl From<&RefusalReason> for ChargeEngineResult {
+    fn from(value: &RefusalReason) -> Self {
+        match *value {
+            RefusalReason::InsufficientFunds => ChargeEngineResult::InsufficientFunds,
+            RefusalReason::AvsDeclined => ChargeEngineResult::AvsInvalid,
+            // these describe the backing card, not the passthrough card the merchant sees, so they stay on the wallet charge
+            RefusalReason::ExpiredCard => ChargeEngineResult::Denied,
+            RefusalReason::BlockedCard => ChargeEngineResult::Denied,
+            RefusalReason::Fraud => ChargeEngineResult::Denied,
+            RefusalReason::CvcDeclined => ChargeEngineResult::Denied,
+            RefusalReason::Other => ChargeEngineResult::Denied
+        }
+    }
 }
 
 
@@ -91,6 +308,7 @@ impl ChargeEngineResult {
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum ChargeCardAttemptResult {
     Approved,
+    PartiallyApproved,
     Denied,
     PartialCancelSucceeded,
     PartialCancelFailed
@@ -108,22 +326,17 @@ impl From<&ChargeCardAttemptResult> for bool {
 
 impl From<ResultCode> for ChargeCardAttemptResult {
     fn from(value: ResultCode) -> Self {
+        ChargeCardAttemptResult::from(ProcessorChargeStatus::from(value))
+    }
+}
+
+impl From<ProcessorChargeStatus> for ChargeCardAttemptResult {
+    fn from(value: ProcessorChargeStatus) -> Self {
         match value {
-            ResultCode::Authorised => ChargeCardAttemptResult::Approved,
-            ResultCode::Pending => ChargeCardAttemptResult::Approved,
-            ResultCode::Received => ChargeCardAttemptResult::Approved,
-            ResultCode::Success => ChargeCardAttemptResult::Approved,
-
-            ResultCode::AuthenticationFinished => ChargeCardAttemptResult::Denied,
-            ResultCode::AuthenticationNotRequired => ChargeCardAttemptResult::Denied,
-            ResultCode::Cancelled => ChargeCardAttemptResult::Denied,
-            ResultCode::ChallengeShopper => ChargeCardAttemptResult::Denied,
-            ResultCode::Error => ChargeCardAttemptResult::Denied,
-            ResultCode::IdentifyShopper => ChargeCardAttemptResult::Denied,
-            ResultCode::PartiallyAuthorised => ChargeCardAttemptResult::Denied,
-            ResultCode::PresentToShopper => ChargeCardAttemptResult::Denied,
-            ResultCode::RedirectShopper => ChargeCardAttemptResult::Denied,
-            ResultCode::Refused => ChargeCardAttemptResult::Denied,
+            ProcessorChargeStatus::Approved => ChargeCardAttemptResult::Approved,
+            ProcessorChargeStatus::PartiallyApproved => ChargeCardAttemptResult::PartiallyApproved,
+            ProcessorChargeStatus::Declined => ChargeCardAttemptResult::Denied,
+            ProcessorChargeStatus::Pending => ChargeCardAttemptResult::Denied,
         }
     }
 }
\ No newline at end of file
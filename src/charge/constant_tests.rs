@@ -2,9 +2,15 @@
 mod tests {
     use adyen_checkout::models::payment_response::ResultCode;
     use crate::charge::constant::{
+            CancelStatus,
+            CaptureStatus,
             ChargeCardAttemptResult,
-            ChargeEngineResult
+            ChargeEngineResult,
+            RefundStatus,
+            RefusalReason,
+            TransactionEventType
     };
+    use crate::payment_processor::constant::ProcessorChargeStatus;
 
     #[actix_web::test]
     async fn test_result_code_to_charge_card_attempt_result() {
@@ -19,12 +25,20 @@ mod tests {
         assert_eq!(ChargeCardAttemptResult::from(ResultCode::ChallengeShopper), ChargeCardAttemptResult::Denied);
         assert_eq!(ChargeCardAttemptResult::from(ResultCode::Error), ChargeCardAttemptResult::Denied);
         assert_eq!(ChargeCardAttemptResult::from(ResultCode::IdentifyShopper), ChargeCardAttemptResult::Denied);
-        assert_eq!(ChargeCardAttemptResult::from(ResultCode::PartiallyAuthorised), ChargeCardAttemptResult::Denied);
+        assert_eq!(ChargeCardAttemptResult::from(ResultCode::PartiallyAuthorised), ChargeCardAttemptResult::PartiallyApproved);
         assert_eq!(ChargeCardAttemptResult::from(ResultCode::PresentToShopper), ChargeCardAttemptResult::Denied);
         assert_eq!(ChargeCardAttemptResult::from(ResultCode::RedirectShopper), ChargeCardAttemptResult::Denied);
         assert_eq!(ChargeCardAttemptResult::from(ResultCode::Refused), ChargeCardAttemptResult::Denied);
     }
 
+    #[actix_web::test]
+    async fn test_processor_charge_status_to_charge_card_attempt_result() {
+        assert_eq!(ChargeCardAttemptResult::from(ProcessorChargeStatus::Approved), ChargeCardAttemptResult::Approved);
+        assert_eq!(ChargeCardAttemptResult::from(ProcessorChargeStatus::PartiallyApproved), ChargeCardAttemptResult::PartiallyApproved);
+        assert_eq!(ChargeCardAttemptResult::from(ProcessorChargeStatus::Declined), ChargeCardAttemptResult::Denied);
+        assert_eq!(ChargeCardAttemptResult::from(ProcessorChargeStatus::Pending), ChargeCardAttemptResult::Denied);
+    }
+
     #[actix_web::test]
     async fn test_bool_from_result_code() {
         assert!(bool::from(&ChargeCardAttemptResult::Approved));
@@ -32,4 +46,67 @@ mod tests {
         assert!(!bool::from(&ChargeCardAttemptResult::PartialCancelSucceeded));
         assert!(!bool::from(&ChargeCardAttemptResult::PartialCancelFailed));
     }
-}
\ No newline at end of file
+
+    #[actix_web::test]
+    async fn test_cancel_status_display() {
+        assert_eq!("RECEIVED", CancelStatus::Received.to_string());
+        assert_eq!("FAILED", CancelStatus::Failed.to_string());
+    }
+
+    #[actix_web::test]
+    async fn test_transaction_event_type_from_lithic_type() {
+        assert_eq!(Some(TransactionEventType::Clearing), TransactionEventType::from_lithic_type("CLEARING"));
+        assert_eq!(Some(TransactionEventType::Void), TransactionEventType::from_lithic_type("VOID"));
+        assert_eq!(Some(TransactionEventType::AuthorizationReversal), TransactionEventType::from_lithic_type("AUTHORIZATION_REVERSAL"));
+        assert_eq!(Some(TransactionEventType::AuthorizationExpiry), TransactionEventType::from_lithic_type("AUTHORIZATION_EXPIRY"));
+        assert_eq!(Some(TransactionEventType::Return), TransactionEventType::from_lithic_type("RETURN"));
+        assert_eq!(None, TransactionEventType::from_lithic_type("AUTHORIZATION"));
+    }
+
+    #[actix_web::test]
+    async fn test_transaction_event_type_display() {
+        assert_eq!("CLEARING", TransactionEventType::Clearing.to_string());
+        assert_eq!("VOID", TransactionEventType::Void.to_string());
+        assert_eq!("AUTHORIZATION_REVERSAL", TransactionEventType::AuthorizationReversal.to_string());
+        assert_eq!("AUTHORIZATION_EXPIRY", TransactionEventType::AuthorizationExpiry.to_string());
+        assert_eq!("RETURN", TransactionEventType::Return.to_string());
+    }
+
+    #[actix_web::test]
+    async fn test_refund_status_display() {
+        assert_eq!("RECEIVED", RefundStatus::Received.to_string());
+        assert_eq!("FAILED", RefundStatus::Failed.to_string());
+    }
+
+    #[actix_web::test]
+    async fn test_capture_status_display() {
+        assert_eq!("AUTHORIZED", CaptureStatus::Authorized.to_string());
+        assert_eq!("CAPTURED", CaptureStatus::Captured.to_string());
+        assert_eq!("FAILED", CaptureStatus::Failed.to_string());
+        assert_eq!("CANCELLED", CaptureStatus::Cancelled.to_string());
+    }
+
+    #[actix_web::test]
+    async fn test_refusal_reason_from_code() {
+        assert_eq!(RefusalReason::InsufficientFunds, RefusalReason::from_refusal_reason_code("12"));
+        assert_eq!(RefusalReason::ExpiredCard, RefusalReason::from_refusal_reason_code("6"));
+        assert_eq!(RefusalReason::BlockedCard, RefusalReason::from_refusal_reason_code("5"));
+        assert_eq!(RefusalReason::Fraud, RefusalReason::from_refusal_reason_code("20"));
+        assert_eq!(RefusalReason::CvcDeclined, RefusalReason::from_refusal_reason_code("24"));
+        assert_eq!(RefusalReason::AvsDeclined, RefusalReason::from_refusal_reason_code("32"));
+        assert_eq!(RefusalReason::Other, RefusalReason::from_refusal_reason_code("2"));
+    }
+
+    #[actix_web::test]
+    async fn test_charge_engine_result_from_refusal_reason() {
+        assert_eq!(ChargeEngineResult::InsufficientFunds, ChargeEngineResult::from(&RefusalReason::InsufficientFunds));
+        assert_eq!(ChargeEngineResult::Denied, ChargeEngineResult::from(&RefusalReason::ExpiredCard));
+        assert_eq!(ChargeEngineResult::Denied, ChargeEngineResult::from(&RefusalReason::BlockedCard));
+        assert_eq!(ChargeEngineResult::AvsInvalid, ChargeEngineResult::from(&RefusalReason::AvsDeclined));
+        assert_eq!(ChargeEngineResult::Denied, ChargeEngineResult::from(&RefusalReason::Fraud));
+        assert_eq!(ChargeEngineResult::Denied, ChargeEngineResult::from(&RefusalReason::CvcDeclined));
+        assert_eq!(ChargeEngineResult::Denied, ChargeEngineResult::from(&RefusalReason::Other));
+        assert!(ChargeEngineResult::InsufficientFunds.decline_priority() > ChargeEngineResult::CardClosed.decline_priority());
+        assert!(ChargeEngineResult::CardClosed.decline_priority() > ChargeEngineResult::Denied.decline_priority());
+    }
+}
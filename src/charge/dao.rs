@@ -1,7 +1,9 @@
 use std::sync::Arc;
+use chrono::NaiveDateTime;
 use uuid::Uuid;
 use crate::error::data_error::DataError;
-use crate::charge::entity::{WalletCardCharge, InsertableWalletCardCharge, InsertablePassthroughCardCharge, InsertableRegisteredTransaction, InsertableSuccessfulEndToEndCharge, PassthroughCardCharge, RegisteredTransaction, SuccessfulEndToEndCharge, InsertableExpectedWalletChargeReference, ExpectedWalletChargeReference};
+use crate::charge::entity::{WalletCardCharge, InsertableWalletCardCharge, InsertablePassthroughCardCharge, InsertableRegisteredTransaction, InsertableSuccessfulEndToEndCharge, PassthroughCardCharge, RegisteredTransaction, SuccessfulEndToEndCharge, InsertableExpectedWalletChargeReference, ExpectedWalletChargeReference, InsertableWalletCardChargeCancellation, WalletCardChargeCancellation, InsertableTransactionEvent, TransactionEvent, InsertableWalletCardChargeRefund, WalletCardChargeRefund, InsertableAuthorizationAdjustment, AuthorizationAdjustment, InsertableEndToEndChargeWalletCardCharge, EndToEndChargeWalletCardCharge, InsertableRegisteredTransactionMetadata, RegisteredTransactionMetadata};
+use crate::charge::constant::{CancelStatus, CaptureStatus, ChargeStatus, RefundStatus};
 use async_trait::async_trait;
 
 #[cfg(test)]
@@ -13,6 +15,14 @@ pub trait ChargeDaoTrait {
     async fn insert_registered_transaction<'a>(self: Arc<Self>, database_transaction: &mut Transaction<'_, '_>, registered_transaction: &InsertableRegisteredTransaction<'a>) -> Result<RegisteredTransaction, DataError>;
     async fn get_registered_transaction_by_transaction_id(self: Arc<Self>, id: &Uuid) -> Result<RegisteredTransaction, DataError>;
     async fn get_registered_transaction(self: Arc<Self>, id: i32) -> Result<RegisteredTransaction, DataError>;
+    async fn get_registered_transaction_by_lithic_transaction_token(self: Arc<Self>, token: &str) -> Result<RegisteredTransaction, DataError>;
+    async fn update_registered_transaction_asa_response_result(self: Arc<Self>, id: i32, result: &str) -> Result<RegisteredTransaction, DataError>;
+    async fn update_registered_transaction_amount_cents<'a>(self: Arc<Self>, database_transaction: &mut Transaction<'_, '_>, id: i32, amount_cents: i32) -> Result<RegisteredTransaction, DataError>;
+    async fn update_registered_transaction_partial_approval<'a>(self: Arc<Self>, database_transaction: &mut Transaction<'_, '_>, id: i32, approved_amount_cents: i32, requested_amount_cents: i32) -> Result<RegisteredTransaction, DataError>;
+
+    async fn insert_registered_transaction_metadata<'a>(self: Arc<Self>, database_transaction: &mut Transaction<'_, '_>, metadata: &InsertableRegisteredTransactionMetadata<'a>) -> Result<RegisteredTransactionMetadata, DataError>;
+    async fn get_registered_transaction_metadata(self: Arc<Self>, registered_transaction_id: i32) -> Result<RegisteredTransactionMetadata, DataError>;
+    async fn update_registered_transaction_routing_trace(self: Arc<Self>, registered_transaction_id: i32, routing_trace: &str) -> Result<RegisteredTransactionMetadata, DataError>;
 
     async fn insert_expected_wallet_charge_reference<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>,reference: &InsertableExpectedWalletChargeReference) -> Result<ExpectedWalletChargeReference, DataError>;
 
@@ -20,14 +30,40 @@ pub trait ChargeDaoTrait {
     async fn get_wallet_charges_by_registered_transaction(self: Arc<Self>, registered_transaction: i32) -> Result<Vec<WalletCardCharge>, DataError>;
     async fn get_successful_wallet_charge_by_registered_transaction(self: Arc<Self>, registered_transaction: i32) -> Result<WalletCardCharge, DataError>;
     async fn get_wallet_charge_by_id(self: Arc<Self>, id: i32) -> Result<WalletCardCharge, DataError>;
+    async fn get_successful_wallet_charges_by_registered_transaction(self: Arc<Self>, registered_transaction: i32) -> Result<Vec<WalletCardCharge>, DataError>;
+    async fn update_wallet_charge_status<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, id: i32, status: &ChargeStatus, is_success: Option<bool>) -> Result<WalletCardCharge, DataError>;
+    async fn update_wallet_charge_capture<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, id: i32, status: &CaptureStatus, captured_amount_cents: Option<i32>) -> Result<WalletCardCharge, DataError>;
+    async fn update_wallet_charge_amount_cents<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, id: i32, amount_cents: i32) -> Result<WalletCardCharge, DataError>;
 
     async fn insert_passthrough_card_charge<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, charge: &InsertablePassthroughCardCharge) -> Result<PassthroughCardCharge, DataError>;
+    async fn delete_failed_passthrough_card_charge<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, registered_transaction: i32) -> Result<usize, DataError>;
     async fn get_passthrough_card_charge_by_registered_transaction(self: Arc<Self>, registered_transaction: i32) -> Result<PassthroughCardCharge, DataError>;
     async fn get_passthrough_card_charge_by_id(self: Arc<Self>, id: i32) -> Result<PassthroughCardCharge, DataError>;
 
     async fn insert_successful_end_to_end_charge<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, charge: &InsertableSuccessfulEndToEndCharge) -> Result<SuccessfulEndToEndCharge, DataError>;
     async fn get_successful_end_to_end_charge_by_registered_transaction_id(self: Arc<Self>, id: i32) -> Result<SuccessfulEndToEndCharge, DataError>;
     async fn get_successful_end_to_end_charge_by_id(self: Arc<Self>, id: i32) -> Result<SuccessfulEndToEndCharge, DataError>;
+    async fn insert_end_to_end_charge_wallet_card_charge<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, charge: &InsertableEndToEndChargeWalletCardCharge) -> Result<EndToEndChargeWalletCardCharge, DataError>;
+    async fn get_end_to_end_charge_wallet_card_charges(self: Arc<Self>, successful_end_to_end_charge_id: i32) -> Result<Vec<EndToEndChargeWalletCardCharge>, DataError>;
+
+    async fn insert_wallet_charge_cancellation<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, cancellation: &InsertableWalletCardChargeCancellation) -> Result<WalletCardChargeCancellation, DataError>;
+    async fn get_wallet_charge_cancellation_by_wallet_charge_id(self: Arc<Self>, wallet_card_charge_id: i32) -> Result<WalletCardChargeCancellation, DataError>;
+    async fn get_wallet_charge_cancellations_by_status(self: Arc<Self>, status: &CancelStatus) -> Result<Vec<WalletCardChargeCancellation>, DataError>;
+
+    async fn insert_transaction_event(self: Arc<Self>, event: &InsertableTransactionEvent) -> Result<TransactionEvent, DataError>;
+    async fn reclaim_stale_transaction_event(self: Arc<Self>, event_token: &str, claimed_before: NaiveDateTime) -> Result<TransactionEvent, DataError>;
+    async fn mark_transaction_event_processed<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, id: i32) -> Result<TransactionEvent, DataError>;
+    async fn delete_unprocessed_transaction_event(self: Arc<Self>, id: i32) -> Result<usize, DataError>;
+    async fn get_transaction_event_by_event_token(self: Arc<Self>, event_token: &str) -> Result<TransactionEvent, DataError>;
+    async fn get_transaction_events_by_registered_transaction(self: Arc<Self>, registered_transaction_id: i32) -> Result<Vec<TransactionEvent>, DataError>;
+
+    async fn insert_wallet_charge_refund<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, refund: &InsertableWalletCardChargeRefund) -> Result<WalletCardChargeRefund, DataError>;
+    async fn get_wallet_charge_refunds_by_wallet_charge_id(self: Arc<Self>, wallet_card_charge_id: i32) -> Result<Vec<WalletCardChargeRefund>, DataError>;
+    async fn get_wallet_charge_refunds_by_status(self: Arc<Self>, status: &RefundStatus) -> Result<Vec<WalletCardChargeRefund>, DataError>;
+
+    async fn insert_authorization_adjustment<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, adjustment: &InsertableAuthorizationAdjustment) -> Result<AuthorizationAdjustment, DataError>;
+    async fn get_authorization_adjustments_by_registered_transaction(self: Arc<Self>, registered_transaction_id: i32) -> Result<Vec<AuthorizationAdjustment>, DataError>;
+    async fn add_authorization_adjustment_reversed_cents<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, id: i32, reversed_cents: i32) -> Result<AuthorizationAdjustment, DataError>;
 }
 
 pub struct ChargeDao {}
@@ -55,6 +91,41 @@ impl ChargeDaoTrait for ChargeDao {
         RegisteredTransaction::get(id).await
     }
 
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_registered_transaction_by_lithic_transaction_token(self: Arc<Self>, token: &str) -> Result<RegisteredTransaction, DataError> {
+        RegisteredTransaction::get_by_lithic_transaction_token(token).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_registered_transaction_asa_response_result(self: Arc<Self>, id: i32, result: &str) -> Result<RegisteredTransaction, DataError> {
+        RegisteredTransaction::update_asa_response_result(id, result).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_registered_transaction_amount_cents<'a>(self: Arc<Self>, database_transaction: &mut Transaction<'_, '_>, id: i32, amount_cents: i32) -> Result<RegisteredTransaction, DataError> {
+        RegisteredTransaction::update_amount_cents(database_transaction, id, amount_cents).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_registered_transaction_partial_approval<'a>(self: Arc<Self>, database_transaction: &mut Transaction<'_, '_>, id: i32, approved_amount_cents: i32, requested_amount_cents: i32) -> Result<RegisteredTransaction, DataError> {
+        RegisteredTransaction::update_partial_approval(database_transaction, id, approved_amount_cents, requested_amount_cents).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert_registered_transaction_metadata<'a>(self: Arc<Self>, database_transaction: &mut Transaction<'_, '_>, metadata: &InsertableRegisteredTransactionMetadata<'a>) -> Result<RegisteredTransactionMetadata, DataError> {
+        RegisteredTransactionMetadata::insert(database_transaction, metadata).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_registered_transaction_metadata(self: Arc<Self>, registered_transaction_id: i32) -> Result<RegisteredTransactionMetadata, DataError> {
+        RegisteredTransactionMetadata::get_by_registered_transaction_id(registered_transaction_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_registered_transaction_routing_trace(self: Arc<Self>, registered_transaction_id: i32, routing_trace: &str) -> Result<RegisteredTransactionMetadata, DataError> {
+        RegisteredTransactionMetadata::update_routing_trace(registered_transaction_id, routing_trace).await
+    }
+
     #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
     async fn insert_expected_wallet_charge_reference<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>,reference: &InsertableExpectedWalletChargeReference) -> Result<ExpectedWalletChargeReference, DataError> {
         ExpectedWalletChargeReference::insert(
@@ -82,11 +153,36 @@ impl ChargeDaoTrait for ChargeDao {
         WalletCardCharge::get_by_id(id).await
     }
 
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_successful_wallet_charges_by_registered_transaction(self: Arc<Self>, registered_transaction: i32) -> Result<Vec<WalletCardCharge>, DataError> {
+        WalletCardCharge::get_all_successful_by_registered_transaction(registered_transaction).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_wallet_charge_status<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, id: i32, status: &ChargeStatus, is_success: Option<bool>) -> Result<WalletCardCharge, DataError> {
+        WalletCardCharge::update_status(transaction, id, status, is_success).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_wallet_charge_capture<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, id: i32, status: &CaptureStatus, captured_amount_cents: Option<i32>) -> Result<WalletCardCharge, DataError> {
+        WalletCardCharge::update_capture(transaction, id, status, captured_amount_cents).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_wallet_charge_amount_cents<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, id: i32, amount_cents: i32) -> Result<WalletCardCharge, DataError> {
+        WalletCardCharge::update_amount_cents(transaction, id, amount_cents).await
+    }
+
     #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
     async fn insert_passthrough_card_charge<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, charge: &InsertablePassthroughCardCharge) -> Result<PassthroughCardCharge, DataError> {
         PassthroughCardCharge::insert(transaction, charge).await
     }
 
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn delete_failed_passthrough_card_charge<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, registered_transaction: i32) -> Result<usize, DataError> {
+        PassthroughCardCharge::delete_failed(transaction, registered_transaction).await
+    }
+
     #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
     async fn get_passthrough_card_charge_by_registered_transaction(self: Arc<Self>, registered_transaction: i32) -> Result<PassthroughCardCharge, DataError> {
         PassthroughCardCharge::get_outer_charge_by_registered_transaction(registered_transaction).await
@@ -112,6 +208,88 @@ impl ChargeDaoTrait for ChargeDao {
         SuccessfulEndToEndCharge::get_by_id(id).await
     }
 
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert_end_to_end_charge_wallet_card_charge<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, charge: &InsertableEndToEndChargeWalletCardCharge) -> Result<EndToEndChargeWalletCardCharge, DataError> {
+        EndToEndChargeWalletCardCharge::insert(transaction, charge).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_end_to_end_charge_wallet_card_charges(self: Arc<Self>, successful_end_to_end_charge_id: i32) -> Result<Vec<EndToEndChargeWalletCardCharge>, DataError> {
+        EndToEndChargeWalletCardCharge::get_all_by_successful_end_to_end_charge_id(successful_end_to_end_charge_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert_wallet_charge_cancellation<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, cancellation: &InsertableWalletCardChargeCancellation) -> Result<WalletCardChargeCancellation, DataError> {
+        WalletCardChargeCancellation::insert(transaction, cancellation).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_wallet_charge_cancellation_by_wallet_charge_id(self: Arc<Self>, wallet_card_charge_id: i32) -> Result<WalletCardChargeCancellation, DataError> {
+        WalletCardChargeCancellation::get_by_wallet_card_charge_id(wallet_card_charge_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_wallet_charge_cancellations_by_status(self: Arc<Self>, status: &CancelStatus) -> Result<Vec<WalletCardChargeCancellation>, DataError> {
+        WalletCardChargeCancellation::get_all_by_status(status).await
+    }
 
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert_transaction_event(self: Arc<Self>, event: &InsertableTransactionEvent) -> Result<TransactionEvent, DataError> {
+        TransactionEvent::insert(event).await
+    }
 
-}
\ No newline at end of file
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn reclaim_stale_transaction_event(self: Arc<Self>, event_token: &str, claimed_before: NaiveDateTime) -> Result<TransactionEvent, DataError> {
+        TransactionEvent::reclaim_stale(event_token, claimed_before).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn mark_transaction_event_processed<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, id: i32) -> Result<TransactionEvent, DataError> {
+        TransactionEvent::mark_processed(transaction, id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn delete_unprocessed_transaction_event(self: Arc<Self>, id: i32) -> Result<usize, DataError> {
+        TransactionEvent::delete_unprocessed(id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_transaction_event_by_event_token(self: Arc<Self>, event_token: &str) -> Result<TransactionEvent, DataError> {
+        TransactionEvent::get_by_event_token(event_token).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_transaction_events_by_registered_transaction(self: Arc<Self>, registered_transaction_id: i32) -> Result<Vec<TransactionEvent>, DataError> {
+        TransactionEvent::get_by_registered_transaction_id(registered_transaction_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert_wallet_charge_refund<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, refund: &InsertableWalletCardChargeRefund) -> Result<WalletCardChargeRefund, DataError> {
+        WalletCardChargeRefund::insert(transaction, refund).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_wallet_charge_refunds_by_wallet_charge_id(self: Arc<Self>, wallet_card_charge_id: i32) -> Result<Vec<WalletCardChargeRefund>, DataError> {
+        WalletCardChargeRefund::get_all_by_wallet_card_charge_id(wallet_card_charge_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_wallet_charge_refunds_by_status(self: Arc<Self>, status: &RefundStatus) -> Result<Vec<WalletCardChargeRefund>, DataError> {
+        WalletCardChargeRefund::get_all_by_status(status).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert_authorization_adjustment<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, adjustment: &InsertableAuthorizationAdjustment) -> Result<AuthorizationAdjustment, DataError> {
+        AuthorizationAdjustment::insert(transaction, adjustment).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_authorization_adjustments_by_registered_transaction(self: Arc<Self>, registered_transaction_id: i32) -> Result<Vec<AuthorizationAdjustment>, DataError> {
+        AuthorizationAdjustment::get_all_by_registered_transaction_id(registered_transaction_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn add_authorization_adjustment_reversed_cents<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, id: i32, reversed_cents: i32) -> Result<AuthorizationAdjustment, DataError> {
+        AuthorizationAdjustment::add_reversed_cents(transaction, id, reversed_cents).await
+    }
+}
@@ -4,8 +4,9 @@ mod dao_tests {
     use crate::passthrough_card::model::PassthroughCardModel as PassthroughCard;
     use crate::test_helper::passthrough_card::{create_mock_lithic_card, create_passthrough_card};
     use crate::test_helper::user::create_user;
-    use crate::charge::constant::ChargeStatus;
-    use crate::charge::entity::{InsertableWalletCardCharge, WalletCardCharge, InsertablePassthroughCardCharge, PassthroughCardCharge, RegisteredTransaction, InsertableRegisteredTransaction, SuccessfulEndToEndCharge, InsertableSuccessfulEndToEndCharge, InsertableExpectedWalletChargeReference};
+    use crate::charge::constant::{CaptureStatus, ChargeStatus};
+    use crate::payment_processor::constant::PaymentProcessorName;
+    use crate::charge::entity::{InsertableWalletCardCharge, WalletCardCharge, InsertablePassthroughCardCharge, PassthroughCardCharge, RegisteredTransaction, InsertableRegisteredTransaction, SuccessfulEndToEndCharge, InsertableSuccessfulEndToEndCharge, InsertableExpectedWalletChargeReference, InsertableRegisteredTransactionMetadata};
     use crate::wallet::model::WalletModel as Wallet;
     use crate::test_helper::wallet::create_wallet;
     use actix_web::test;
@@ -32,7 +33,14 @@ mod dao_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await;
@@ -48,6 +56,46 @@ mod dao_tests {
         assert_eq!(txn.id, get_by_txn.id);
     }
 
+    #[test]
+    async fn test_registered_txn_metadata_create_and_trace() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let dao = Arc::new(ChargeDao::new());
+        let dc = dao.clone();
+        let metadata = transactional::<_, DataError, _>(move |conn| Box::pin(async move {
+            let txn = dc.clone().insert_registered_transaction(
+                conn,
+                &InsertableRegisteredTransaction {
+                    user_id: user.id,
+                    memo: TEST_MEMO,
+                    amount_cents: TEST_AMOUNT,
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
+                }
+            ).await?;
+            dc.clone().insert_registered_transaction_metadata(
+                conn,
+                &InsertableRegisteredTransactionMetadata {
+                    registered_transaction_id: txn.id,
+                    body: "{}"
+                }
+            ).await
+        })).await.expect("metadata should be ok");
+        assert_eq!(Some("{}".to_string()), metadata.body);
+        assert_eq!(None, metadata.routing_trace);
+
+        dao.clone().update_registered_transaction_routing_trace(metadata.registered_transaction_id, "[]").await.expect("updates");
+        let get_by_txn = dao.clone().get_registered_transaction_metadata(metadata.registered_transaction_id).await.expect("finds");
+        assert_eq!(Some("{}".to_string()), get_by_txn.body);
+        assert_eq!(Some("[]".to_string()), get_by_txn.routing_trace);
+    }
+
     #[test]
     async fn test_inner_charge_creates() {
         crate::test_helper::general::init();
@@ -61,7 +109,14 @@ mod dao_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("ledger should be ok");
@@ -97,6 +152,11 @@ mod dao_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect("should create");
@@ -114,6 +174,81 @@ mod dao_tests {
         assert_eq!(inner_charge.id, get_by_txn[0].id);
     }
 
+    #[test]
+    async fn test_inner_charge_capture_updates() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let dao = Arc::new(ChargeDao::new());
+        let mut dc = dao.clone();
+        let rtx = transactional::<_, DataError, _>(move |conn| Box::pin(async move {
+            dc.clone().insert_registered_transaction(
+                conn,
+                &InsertableRegisteredTransaction {
+                    user_id: user.id,
+                    memo: TEST_MEMO,
+                    amount_cents: TEST_AMOUNT,
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
+                }
+            ).await
+        })).await.expect("ledger should be ok");
+
+        let card = create_wallet(
+            &user
+        ).await;
+
+        dc = dao.clone();
+        let inner_charge = transactional::<_, DataError, _>(move |conn| Box::pin(async move {
+            let expected = dc.clone().insert_expected_wallet_charge_reference(
+                conn,
+                &InsertableExpectedWalletChargeReference {
+                    registered_transaction_id: rtx.id,
+                    user_id: rtx.user_id,
+                    wallet_card_id: card.id,
+                    amount_cents: TEST_AMOUNT,
+                }
+            ).await.expect("creates expect");
+            dc.clone().insert_wallet_charge(
+                conn,
+                &InsertableWalletCardCharge {
+                    registered_transaction_id: rtx.id,
+                    user_id: rtx.user_id,
+                    wallet_card_id: card.id,
+                    amount_cents: TEST_AMOUNT,
+                    is_success: Some(true),
+                    rule_id: None,
+                    expected_wallet_charge_reference_id: expected.id,
+                    resolved_charge_status: ChargeStatus::Success,
+                    psp_reference: None,
+                    returned_reference: None,
+                    returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: Some(CaptureStatus::Authorized),
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
+                }
+            ).await
+        })).await.expect("should create");
+        assert_eq!(Some(CaptureStatus::Authorized), inner_charge.capture_status);
+        assert_eq!(None, inner_charge.captured_amount_cents);
+
+        dc = dao.clone();
+        let id = inner_charge.id;
+        let captured = transactional::<_, DataError, _>(move |conn| Box::pin(async move {
+            dc.clone().update_wallet_charge_capture(conn, id, &CaptureStatus::Captured, Some(TEST_AMOUNT + 100)).await
+        })).await.expect("should update");
+        assert_eq!(Some(CaptureStatus::Captured), captured.capture_status);
+        assert_eq!(Some(TEST_AMOUNT + 100), captured.captured_amount_cents);
+        assert_eq!(TEST_AMOUNT, captured.amount_cents);
+    }
+
     #[test]
     async fn test_inner_charge_creates_several() {
         crate::test_helper::general::init();
@@ -127,7 +262,14 @@ mod dao_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("creates");
@@ -160,6 +302,11 @@ mod dao_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect("should create");
@@ -197,6 +344,11 @@ mod dao_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect("should create");
@@ -229,6 +381,13 @@ mod dao_tests {
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
                     mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("ledger should be ok");
@@ -261,6 +420,11 @@ mod dao_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect("should create");
@@ -298,6 +462,11 @@ mod dao_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect_err("should create error");
@@ -329,6 +498,11 @@ mod dao_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect_err("should create error");
@@ -348,7 +522,14 @@ mod dao_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("ledger should be ok");
@@ -424,7 +605,14 @@ mod dao_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("ledger should be ok");
@@ -488,7 +676,14 @@ mod dao_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("ledger should be ok");
@@ -519,6 +714,11 @@ mod dao_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect("should create");
@@ -592,7 +792,14 @@ mod dao_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("creates");
@@ -623,6 +830,11 @@ mod dao_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect("should create");
@@ -710,7 +922,14 @@ mod dao_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await.expect("ledger should be ok");
 
@@ -720,7 +939,14 @@ mod dao_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await.expect("ok");
             let expected = dc.clone().insert_expected_wallet_charge_reference(
@@ -747,6 +973,11 @@ mod dao_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await.expect("should create");
 
@@ -812,7 +1043,14 @@ mod dao_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await.expect("ledger should be ok");
 
@@ -822,7 +1060,14 @@ mod dao_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await.expect("ledger should be ok");
 
@@ -850,6 +1095,11 @@ mod dao_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await.expect("should create");
 
@@ -877,6 +1127,11 @@ mod dao_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await.expect("should create");
 
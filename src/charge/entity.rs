@@ -1,6 +1,6 @@
 use std::sync::Arc;
 use chrono::NaiveDateTime;
-use crate::schema::{passthrough_card_charge, wallet_card_charge, registered_transaction, registered_transaction_metadata, successful_end_to_end_charge, expected_wallet_charge_reference};
+use crate::schema::{passthrough_card_charge, wallet_card_charge, registered_transaction, registered_transaction_metadata, successful_end_to_end_charge, expected_wallet_charge_reference, wallet_card_charge_cancellation, wallet_card_charge_refund, transaction_event, authorization_adjustment, end_to_end_charge_wallet_card_charge};
 use diesel::{BoolExpressionMethods, Identifiable, Insertable, Queryable, Selectable};
 use diesel::associations::HasTable;
 use diesel_async::{AsyncConnection, RunQueryDsl};
@@ -11,7 +11,8 @@ use crate::wallet::model::WalletModel as Wallet;
 use diesel::prelude::*;
 use crate::category::constant::Category;
 use crate::error::data_error::DataError;
-use crate::charge::constant::ChargeStatus;
+use crate::charge::constant::{CancelStatus, CaptureStatus, ChargeStatus, RefundStatus, TransactionEventType};
+use crate::payment_processor::constant::{PaymentProcessorName, ProcessorChargeStatus};
 use crate::util::transaction::Transaction;
 
 #[derive(Debug, Insertable, Serialize, Deserialize)]
@@ -21,7 +22,14 @@ pub struct InsertableRegisteredTransaction<'a> {
     pub user_id: i32,
     pub memo: &'a str,
     pub amount_cents: i32,
-    pub mcc: &'a str
+    pub mcc: &'a str,
+    pub lithic_transaction_token: Option<&'a str>,
+    pub currency: &'a str,
+    pub merchant_amount_cents: Option<i32>,
+    pub merchant_currency: Option<&'a str>,
+    pub conversion_rate: Option<f64>,
+    pub charge_currency: &'a str,
+    pub cash_amount_cents: Option<i32>
 }
 
 #[derive(Debug, Identifiable, Serialize, Deserialize, Queryable, Selectable)]
@@ -36,6 +44,15 @@ pub struct RegisteredTransaction {
     pub mcc: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub lithic_transaction_token: Option<String>,
+    pub asa_response_result: Option<String>,
+    pub currency: String,
+    pub merchant_amount_cents: Option<i32>,
+    pub merchant_currency: Option<String>,
+    pub conversion_rate: Option<f64>,
+    pub charge_currency: String,
+    pub requested_amount_cents: Option<i32>,
+    pub cash_amount_cents: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Queryable, Selectable)]
@@ -43,7 +60,10 @@ pub struct RegisteredTransaction {
 #[diesel(table_name = registered_transaction_metadata)]
 pub struct RegisteredTransactionMetadata {
     pub registered_transaction_id: i32,
-    pub body: String
+    pub body: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub routing_trace: Option<String>,
 }
 
 #[derive(Debug, Insertable, Serialize, Deserialize)]
@@ -134,7 +154,13 @@ pub struct WalletCardCharge {
     pub is_success: Option<bool>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
-    pub public_id: Uuid
+    pub public_id: Uuid,
+    pub refusal_reason: Option<String>,
+    pub refusal_reason_code: Option<String>,
+    pub capture_status: Option<CaptureStatus>,
+    pub captured_amount_cents: Option<i32>,
+    pub processor: PaymentProcessorName,
+    pub processor_charge_status: Option<ProcessorChargeStatus>,
 }
 
 #[derive(Debug, Insertable, Serialize, Deserialize)]
@@ -154,6 +180,11 @@ pub struct InsertableWalletCardCharge {
     pub returned_reference: Option<String>,
     pub returned_charge_status: Option<String>,
     pub is_success: Option<bool>,
+    pub refusal_reason: Option<String>,
+    pub refusal_reason_code: Option<String>,
+    pub capture_status: Option<CaptureStatus>,
+    pub processor: PaymentProcessorName,
+    pub processor_charge_status: Option<ProcessorChargeStatus>,
 }
 
 
@@ -182,6 +213,147 @@ pub struct InsertableSuccessfulEndToEndCharge {
 }
 
 
+#[derive(Debug, Identifiable, Serialize, Deserialize, Queryable, Selectable, Clone)]
+#[diesel(belongs_to(RegisteredTransaction))]
+#[diesel(belongs_to(WalletCardCharge))]
+#[diesel(table_name = wallet_card_charge_cancellation)]
+pub struct WalletCardChargeCancellation {
+    pub id: i32,
+    pub registered_transaction_id: i32,
+    pub wallet_card_charge_id: i32,
+    pub psp_reference: Option<String>,
+    pub cancel_psp_reference: Option<String>,
+    pub cancel_status: CancelStatus,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime
+}
+
+#[derive(Debug, Insertable, Serialize, Deserialize)]
+#[diesel(belongs_to(RegisteredTransaction))]
+#[diesel(belongs_to(WalletCardCharge))]
+#[diesel(table_name = wallet_card_charge_cancellation)]
+pub struct InsertableWalletCardChargeCancellation {
+    pub registered_transaction_id: i32,
+    pub wallet_card_charge_id: i32,
+    pub psp_reference: Option<String>,
+    pub cancel_psp_reference: Option<String>,
+    pub cancel_status: CancelStatus,
+}
+
+#[derive(Debug, Identifiable, Serialize, Deserialize, Queryable, Selectable, Clone)]
+#[diesel(belongs_to(RegisteredTransaction))]
+#[diesel(table_name = transaction_event)]
+pub struct TransactionEvent {
+    pub id: i32,
+    pub registered_transaction_id: i32,
+    pub event_token: String,
+    pub event_type: TransactionEventType,
+    pub amount_cents: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub processed_at: Option<NaiveDateTime>
+}
+
+#[derive(Debug, Insertable, Serialize, Deserialize)]
+#[diesel(belongs_to(RegisteredTransaction))]
+#[diesel(table_name = transaction_event)]
+pub struct InsertableTransactionEvent {
+    pub registered_transaction_id: i32,
+    pub event_token: String,
+    pub event_type: TransactionEventType,
+    pub amount_cents: i32
+}
+
+#[derive(Debug, Identifiable, Serialize, Deserialize, Queryable, Selectable, Clone)]
+#[diesel(belongs_to(RegisteredTransaction))]
+#[diesel(belongs_to(SuccessfulEndToEndCharge))]
+#[diesel(belongs_to(WalletCardCharge))]
+#[diesel(table_name = wallet_card_charge_refund)]
+pub struct WalletCardChargeRefund {
+    pub id: i32,
+    pub registered_transaction_id: i32,
+    pub successful_end_to_end_charge_id: i32,
+    pub wallet_card_charge_id: i32,
+    pub psp_reference: String,
+    pub refund_psp_reference: Option<String>,
+    pub reference: String,
+    pub amount_cents: i32,
+    pub refund_status: RefundStatus,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime
+}
+
+#[derive(Debug, Insertable, Serialize, Deserialize)]
+#[diesel(belongs_to(RegisteredTransaction))]
+#[diesel(belongs_to(SuccessfulEndToEndCharge))]
+#[diesel(belongs_to(WalletCardCharge))]
+#[diesel(table_name = wallet_card_charge_refund)]
+pub struct InsertableWalletCardChargeRefund {
+    pub registered_transaction_id: i32,
+    pub successful_end_to_end_charge_id: i32,
+    pub wallet_card_charge_id: i32,
+    pub psp_reference: String,
+    pub refund_psp_reference: Option<String>,
+    pub reference: String,
+    pub amount_cents: i32,
+    pub refund_status: RefundStatus,
+}
+
+#[derive(Debug, Identifiable, Serialize, Deserialize, Queryable, Selectable, Clone)]
+#[diesel(belongs_to(RegisteredTransaction))]
+#[diesel(belongs_to(WalletCardCharge))]
+#[diesel(table_name = authorization_adjustment)]
+pub struct AuthorizationAdjustment {
+    pub id: i32,
+    pub registered_transaction_id: i32,
+    pub wallet_card_charge_id: i32,
+    pub reference: Uuid,
+    pub previous_amount_cents: i32,
+    pub amount_cents: i32,
+    pub psp_reference: Option<String>,
+    pub status: ChargeStatus,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub reversed_cents: i32,
+    pub asa_request_key: Option<Uuid>
+}
+
+#[derive(Debug, Insertable, Serialize, Deserialize)]
+#[diesel(belongs_to(RegisteredTransaction))]
+#[diesel(belongs_to(WalletCardCharge))]
+#[diesel(table_name = authorization_adjustment)]
+pub struct InsertableAuthorizationAdjustment {
+    pub registered_transaction_id: i32,
+    pub wallet_card_charge_id: i32,
+    pub reference: Uuid,
+    pub previous_amount_cents: i32,
+    pub amount_cents: i32,
+    pub psp_reference: Option<String>,
+    pub status: ChargeStatus,
+    pub asa_request_key: Option<Uuid>,
+}
+
+#[derive(Debug, Identifiable, Serialize, Deserialize, Queryable, Selectable, Clone)]
+#[diesel(belongs_to(SuccessfulEndToEndCharge))]
+#[diesel(belongs_to(WalletCardCharge))]
+#[diesel(table_name = end_to_end_charge_wallet_card_charge)]
+pub struct EndToEndChargeWalletCardCharge {
+    pub id: i32,
+    pub successful_end_to_end_charge_id: i32,
+    pub wallet_card_charge_id: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime
+}
+
+#[derive(Debug, Insertable, Serialize, Deserialize)]
+#[diesel(belongs_to(SuccessfulEndToEndCharge))]
+#[diesel(belongs_to(WalletCardCharge))]
+#[diesel(table_name = end_to_end_charge_wallet_card_charge)]
+pub struct InsertableEndToEndChargeWalletCardCharge {
+    pub successful_end_to_end_charge_id: i32,
+    pub wallet_card_charge_id: i32,
+}
+
 
 impl RegisteredTransaction {
     #[cfg_attr(feature="trace-detail", tracing::instrument)]
@@ -209,6 +381,74 @@ impl RegisteredTransaction {
         ).first::<RegisteredTransaction>(&mut conn).await?;
         Ok(txn)
     }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_by_lithic_transaction_token(token: &str) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let txn = registered_transaction::table.filter(
+            registered_transaction::lithic_transaction_token.eq(token)
+        ).first::<RegisteredTransaction>(&mut conn).await?;
+        Ok(txn)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_asa_response_result(id: i32, result: &str) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let txn = diesel::update(registered_transaction::table)
+            .filter(registered_transaction::id.eq(id))
+            .set(registered_transaction::asa_response_result.eq(result))
+            .get_result::<RegisteredTransaction>(&mut conn).await?;
+        Ok(txn)
+    }
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_amount_cents(database_transaction: &mut Transaction<'_, '_>, id: i32, amount_cents: i32) -> Result<Self, DataError> {
+        let txn = diesel::update(registered_transaction::table)
+            .filter(registered_transaction::id.eq(id))
+            .set(registered_transaction::amount_cents.eq(amount_cents))
+            .get_result::<RegisteredTransaction>(database_transaction).await?;
+        Ok(txn)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_partial_approval(database_transaction: &mut Transaction<'_, '_>, id: i32, approved_amount_cents: i32, requested_amount_cents: i32) -> Result<Self, DataError> {
+        let txn = diesel::update(registered_transaction::table)
+            .filter(registered_transaction::id.eq(id))
+            .set((
+                registered_transaction::amount_cents.eq(approved_amount_cents),
+                registered_transaction::requested_amount_cents.eq(requested_amount_cents)
+            ))
+            .get_result::<RegisteredTransaction>(database_transaction).await?;
+        Ok(txn)
+    }
+}
+
+impl RegisteredTransactionMetadata {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert<'a>(database_transaction: &mut Transaction<'_, '_>, metadata: &InsertableRegisteredTransactionMetadata<'a>) -> Result<Self, DataError> {
+        let metadata = diesel::insert_into(registered_transaction_metadata::table)
+            .values(metadata)
+            .get_result(database_transaction).await?;
+        Ok(metadata)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_by_registered_transaction_id(registered_transaction_id: i32) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let metadata = registered_transaction_metadata::table.filter(
+            registered_transaction_metadata::registered_transaction_id.eq(registered_transaction_id)
+        ).first::<RegisteredTransactionMetadata>(&mut conn).await?;
+        Ok(metadata)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_routing_trace(registered_transaction_id: i32, routing_trace: &str) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let metadata = diesel::update(registered_transaction_metadata::table)
+            .filter(registered_transaction_metadata::registered_transaction_id.eq(registered_transaction_id))
+            .set(registered_transaction_metadata::routing_trace.eq(routing_trace))
+            .get_result::<RegisteredTransactionMetadata>(&mut conn).await?;
+        Ok(metadata)
+    }
 }
 
 impl ExpectedWalletChargeReference {
@@ -238,6 +478,7 @@ impl WalletCardCharge {
             .filter(
                 wallet_card_charge::registered_transaction_id.eq(registered_transaction)
             )
+            .order(wallet_card_charge::id.asc())
             .load::<WalletCardCharge>(&mut conn).await?;
         Ok(txns)
     }
@@ -257,6 +498,54 @@ impl WalletCardCharge {
 
     }
 
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_all_successful_by_registered_transaction(registered_transaction: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let txns = wallet_card_charge::table
+            .filter(
+                wallet_card_charge::registered_transaction_id.eq(registered_transaction)
+                    .and(
+                        wallet_card_charge::is_success.eq(Some(true))
+                    )
+            )
+            .order(wallet_card_charge::id.asc())
+            .load::<WalletCardCharge>(&mut conn).await?;
+        Ok(txns)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_status(transaction: &mut Transaction<'_, '_>, id: i32, status: &ChargeStatus, is_success: Option<bool>) -> Result<Self, DataError> {
+        let txn = diesel::update(wallet_card_charge::table)
+            .filter(wallet_card_charge::id.eq(id))
+            .set((
+                wallet_card_charge::resolved_charge_status.eq(status),
+                wallet_card_charge::is_success.eq(is_success)
+            ))
+            .get_result::<Self>(transaction).await?;
+        Ok(txn)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_capture(transaction: &mut Transaction<'_, '_>, id: i32, status: &CaptureStatus, captured_amount_cents: Option<i32>) -> Result<Self, DataError> {
+        let txn = diesel::update(wallet_card_charge::table)
+            .filter(wallet_card_charge::id.eq(id))
+            .set((
+                wallet_card_charge::capture_status.eq(status),
+                wallet_card_charge::captured_amount_cents.eq(captured_amount_cents)
+            ))
+            .get_result::<Self>(transaction).await?;
+        Ok(txn)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_amount_cents(transaction: &mut Transaction<'_, '_>, id: i32, amount_cents: i32) -> Result<Self, DataError> {
+        let txn = diesel::update(wallet_card_charge::table)
+            .filter(wallet_card_charge::id.eq(id))
+            .set(wallet_card_charge::amount_cents.eq(amount_cents))
+            .get_result::<Self>(transaction).await?;
+        Ok(txn)
+    }
+
     #[cfg_attr(feature="trace-detail", tracing::instrument)]
     pub async fn get_by_id(id: i32) -> Result<Self, DataError> {
         let mut conn = db::connection().await?;
@@ -279,6 +568,17 @@ impl PassthroughCardCharge {
         Ok(txn)
     }
 
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn delete_failed<'a>(transaction: &mut Transaction<'_, '_>, registered_transaction: i32) -> Result<usize, DataError> {
+        let deleted = diesel::delete(passthrough_card_charge::table)
+            .filter(
+                passthrough_card_charge::registered_transaction_id.eq(registered_transaction)
+                    .and(passthrough_card_charge::status.eq(ChargeStatus::Fail))
+            )
+            .execute(transaction).await?;
+        Ok(deleted)
+    }
+
     #[cfg_attr(feature="trace-detail", tracing::instrument)]
     pub async fn get_outer_charge_by_registered_transaction(registered_transaction: i32) -> Result<Self, DataError> {
         let mut conn = db::connection().await?;
@@ -334,3 +634,199 @@ impl SuccessfulEndToEndCharge {
     }
 }
 
+impl WalletCardChargeCancellation {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert<'a>(transaction: &mut Transaction<'_, '_>, cancellation: &InsertableWalletCardChargeCancellation) -> Result<Self, DataError> {
+        let cancellation = diesel::insert_into(wallet_card_charge_cancellation::table)
+            .values(cancellation)
+            .get_result::<Self>(transaction).await?;
+        Ok(cancellation)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_by_wallet_card_charge_id(wallet_card_charge_id: i32) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let cancellation = wallet_card_charge_cancellation::table
+            .filter(
+                wallet_card_charge_cancellation::wallet_card_charge_id.eq(wallet_card_charge_id)
+            )
+            .first(&mut conn).await?;
+        Ok(cancellation)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_all_by_status(status: &CancelStatus) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let cancellations = wallet_card_charge_cancellation::table
+            .filter(
+                wallet_card_charge_cancellation::cancel_status.eq(status)
+            )
+            .order(wallet_card_charge_cancellation::id.asc())
+            .load::<WalletCardChargeCancellation>(&mut conn).await?;
+        Ok(cancellations)
+    }
+}
+
+impl TransactionEvent {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert(event: &InsertableTransactionEvent) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let event = diesel::insert_into(transaction_event::table)
+            .values(event)
+            .get_result::<Self>(&mut conn).await?;
+        Ok(event)
+    }
+
+    // only an unprocessed claim last touched before claimed_before can be taken over
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn reclaim_stale(event_token: &str, claimed_before: NaiveDateTime) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let event = diesel::update(transaction_event::table)
+            .filter(
+                transaction_event::event_token.eq(event_token)
+                    .and(transaction_event::processed_at.is_null())
+                    .and(transaction_event::updated_at.lt(claimed_before))
+            )
+            .set(transaction_event::updated_at.eq(chrono::Utc::now().naive_utc()))
+            .get_result::<Self>(&mut conn).await?;
+        Ok(event)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn mark_processed<'a>(transaction: &mut Transaction<'_, '_>, id: i32) -> Result<Self, DataError> {
+        let now = chrono::Utc::now().naive_utc();
+        let event = diesel::update(transaction_event::table)
+            .filter(transaction_event::id.eq(id))
+            .set((
+                transaction_event::processed_at.eq(now),
+                transaction_event::updated_at.eq(now)
+            ))
+            .get_result::<Self>(transaction).await?;
+        Ok(event)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn delete_unprocessed(id: i32) -> Result<usize, DataError> {
+        let mut conn = db::connection().await?;
+        let deleted = diesel::delete(transaction_event::table)
+            .filter(
+                transaction_event::id.eq(id)
+                    .and(transaction_event::processed_at.is_null())
+            )
+            .execute(&mut conn).await?;
+        Ok(deleted)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_by_event_token(event_token: &str) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let event = transaction_event::table
+            .filter(
+                transaction_event::event_token.eq(event_token)
+            )
+            .first(&mut conn).await?;
+        Ok(event)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_by_registered_transaction_id(registered_transaction_id: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let events = transaction_event::table
+            .filter(
+                transaction_event::registered_transaction_id.eq(registered_transaction_id)
+            )
+            .order(transaction_event::id.asc())
+            .load::<TransactionEvent>(&mut conn).await?;
+        Ok(events)
+    }
+}
+
+impl WalletCardChargeRefund {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert<'a>(transaction: &mut Transaction<'_, '_>, refund: &InsertableWalletCardChargeRefund) -> Result<Self, DataError> {
+        let refund = diesel::insert_into(wallet_card_charge_refund::table)
+            .values(refund)
+            .get_result::<Self>(transaction).await?;
+        Ok(refund)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_all_by_wallet_card_charge_id(wallet_card_charge_id: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let refunds = wallet_card_charge_refund::table
+            .filter(
+                wallet_card_charge_refund::wallet_card_charge_id.eq(wallet_card_charge_id)
+            )
+            .order(wallet_card_charge_refund::id.asc())
+            .load::<WalletCardChargeRefund>(&mut conn).await?;
+        Ok(refunds)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_all_by_status(status: &RefundStatus) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let refunds = wallet_card_charge_refund::table
+            .filter(
+                wallet_card_charge_refund::refund_status.eq(status)
+            )
+            .order(wallet_card_charge_refund::id.asc())
+            .load::<WalletCardChargeRefund>(&mut conn).await?;
+        Ok(refunds)
+    }
+}
+
+impl AuthorizationAdjustment {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert<'a>(transaction: &mut Transaction<'_, '_>, adjustment: &InsertableAuthorizationAdjustment) -> Result<Self, DataError> {
+        let adjustment = diesel::insert_into(authorization_adjustment::table)
+            .values(adjustment)
+            .get_result::<Self>(transaction).await?;
+        Ok(adjustment)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_all_by_registered_transaction_id(registered_transaction_id: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let adjustments = authorization_adjustment::table
+            .filter(
+                authorization_adjustment::registered_transaction_id.eq(registered_transaction_id)
+            )
+            .order(authorization_adjustment::id.asc())
+            .load::<AuthorizationAdjustment>(&mut conn).await?;
+        Ok(adjustments)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn add_reversed_cents<'a>(transaction: &mut Transaction<'_, '_>, id: i32, reversed_cents: i32) -> Result<Self, DataError> {
+        let adjustment = diesel::update(authorization_adjustment::table)
+            .filter(authorization_adjustment::id.eq(id))
+            .set((
+                authorization_adjustment::reversed_cents.eq(authorization_adjustment::reversed_cents + reversed_cents),
+                authorization_adjustment::updated_at.eq(chrono::Utc::now().naive_utc())
+            ))
+            .get_result::<Self>(transaction).await?;
+        Ok(adjustment)
+    }
+}
+
+impl EndToEndChargeWalletCardCharge {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert<'a>(transaction: &mut Transaction<'_, '_>, charge: &InsertableEndToEndChargeWalletCardCharge) -> Result<Self, DataError> {
+        let charge = diesel::insert_into(end_to_end_charge_wallet_card_charge::table)
+            .values(charge)
+            .get_result::<Self>(transaction).await?;
+        Ok(charge)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_all_by_successful_end_to_end_charge_id(successful_end_to_end_charge_id: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let charges = end_to_end_charge_wallet_card_charge::table
+            .filter(
+                end_to_end_charge_wallet_card_charge::successful_end_to_end_charge_id.eq(successful_end_to_end_charge_id)
+            )
+            .order(end_to_end_charge_wallet_card_charge::id.asc())
+            .load::<EndToEndChargeWalletCardCharge>(&mut conn).await?;
+        Ok(charges)
+    }
+}
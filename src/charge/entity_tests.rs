@@ -4,6 +4,7 @@ mod entity_tests {
     use crate::test_helper::passthrough_card::{create_mock_lithic_card, create_passthrough_card};
     use crate::test_helper::user::create_user;
     use crate::charge::constant::ChargeStatus;
+    use crate::payment_processor::constant::PaymentProcessorName;
     use crate::charge::entity::{InsertableWalletCardCharge, WalletCardCharge, InsertablePassthroughCardCharge, PassthroughCardCharge, RegisteredTransaction, InsertableRegisteredTransaction, SuccessfulEndToEndCharge, InsertableSuccessfulEndToEndCharge, ExpectedWalletChargeReference, InsertableExpectedWalletChargeReference};
     use crate::wallet::model::WalletModel as Wallet;
     use crate::test_helper::wallet::create_wallet;
@@ -27,7 +28,14 @@ mod entity_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await;
@@ -43,6 +51,61 @@ mod entity_tests {
         assert_eq!(txn.id, get_by_txn.id);
     }
 
+    #[test]
+    async fn test_registered_txn_create_with_token_fails_dupe() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let user_id = user.id;
+        let token = create().to_string();
+        let token_clone = token.clone();
+        let txn = transactional::<_, DataError, _>(move |conn| Box::pin(async move {
+            RegisteredTransaction::insert(
+                conn,
+                &InsertableRegisteredTransaction {
+                    user_id: user_id,
+                    memo: TEST_MEMO,
+                    amount_cents: TEST_AMOUNT,
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: Some(&token_clone),
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
+                }
+            ).await
+        })).await.expect("ledger should be ok");
+        assert_eq!(Some(token.clone()), txn.lithic_transaction_token);
+        assert_eq!(None, txn.asa_response_result);
+
+        let token_clone = token.clone();
+        let err = transactional::<_, DataError, _>(move |conn| Box::pin(async move {
+            RegisteredTransaction::insert(
+                conn,
+                &InsertableRegisteredTransaction {
+                    user_id: user_id,
+                    memo: TEST_MEMO,
+                    amount_cents: TEST_AMOUNT,
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: Some(&token_clone),
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
+                }
+            ).await
+        })).await.expect_err("duplicate token");
+        assert_eq!(DataError::Conflict("test".into()), err);
+
+        let get_by_token = RegisteredTransaction::get_by_lithic_transaction_token(&token).await.expect("finds");
+        assert_eq!(txn.id, get_by_token.id);
+        let updated = RegisteredTransaction::update_asa_response_result(txn.id, "APPROVED").await.expect("updates");
+        assert_eq!(Some("APPROVED".to_string()), updated.asa_response_result);
+    }
+
     // no longer  possible
     async fn test_registered_txn_create_fails_dupe() {
         crate::test_helper::general::init();
@@ -54,7 +117,14 @@ mod entity_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await;
@@ -71,7 +141,14 @@ mod entity_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect_err("Expect data error");
@@ -89,7 +166,14 @@ mod entity_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("ledger should be ok");
@@ -123,6 +207,11 @@ mod entity_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect("should create");
@@ -151,7 +240,14 @@ mod entity_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("ledger should be ok");
@@ -184,6 +280,11 @@ mod entity_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect("should create");
@@ -220,6 +321,11 @@ mod entity_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect("should create");
@@ -250,6 +356,13 @@ mod entity_tests {
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
                     mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("ledger should be ok");
@@ -282,6 +395,11 @@ mod entity_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect("should create");
@@ -318,6 +436,11 @@ mod entity_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect_err("should be an error");
@@ -346,9 +469,14 @@ mod entity_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
                     is_success: Some(true),
                     rule_id: None,
-                    expected_wallet_charge_reference_id: 0
+                    expected_wallet_charge_reference_id: 0,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect_err("should create error");
@@ -366,7 +494,14 @@ mod entity_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("ledger should be ok");
@@ -438,7 +573,14 @@ mod entity_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("ledger should be ok");
@@ -496,7 +638,14 @@ mod entity_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("ledger should be ok");
@@ -526,6 +675,11 @@ mod entity_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect("should create");
@@ -596,7 +750,14 @@ mod entity_tests {
                     user_id: user.id,
                     memo: TEST_MEMO,
                     amount_cents: TEST_AMOUNT,
-                    mcc: TEST_MCC
+                    mcc: TEST_MCC,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }
             ).await
         })).await.expect("ledger should be ok");
@@ -626,6 +787,11 @@ mod entity_tests {
                     psp_reference: None,
                     returned_reference: None,
                     returned_charge_status: None,
+                    refusal_reason: None,
+                    refusal_reason_code: None,
+                    capture_status: None,
+                    processor: PaymentProcessorName::Adyen,
+                    processor_charge_status: None,
                 }
             ).await
         })).await.expect("should create");
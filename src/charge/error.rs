@@ -5,6 +5,10 @@ use crate::ledger::error::LedgerError;
 pub enum ChargeError {
     #[error("No card present to charge in the request")]
     NoCardInRequest,
+    #[error("Transaction has already been registered and is still processing")]
+    DuplicateTransaction(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Refund is not valid for the charge")]
+    InvalidRefund(#[source] Box<dyn std::error::Error + Send + Sync>),
     #[error("Unexpected charge error")]
     Unexpected(#[source] Box<dyn std::error::Error + Send + Sync>)
 }
@@ -21,6 +25,8 @@ impl PartialEq for ChargeError {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (ChargeError::NoCardInRequest, ChargeError::NoCardInRequest)
+                | (ChargeError::DuplicateTransaction(_), ChargeError::DuplicateTransaction(_))
+                | (ChargeError::InvalidRefund(_), ChargeError::InvalidRefund(_))
                 | (ChargeError::Unexpected(_), ChargeError::Unexpected(_)) => true,
             _ => false
         }
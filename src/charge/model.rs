@@ -1,7 +1,15 @@
+use std::time::Duration;
 use chrono::NaiveDateTime;
+use tokio::time::Instant;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::charge::constant::ChargeStatus;
+use crate::asa::response::AsaResponseResult;
+use crate::card_health::constant::CardHealthStatus;
+use crate::charge::constant::{CaptureStatus, ChargeEngineResult, ChargeStatus};
+use crate::common::currency::{cardholder_to_merchant_amount, merchant_to_cardholder_amount};
 use crate::charge::entity::{WalletCardCharge, PassthroughCardCharge, RegisteredTransaction, SuccessfulEndToEndCharge};
+use crate::configuration::asa::AsaConfiguration;
+use crate::wallet::model::WalletModelWithRule as Wallet;
 
 #[derive(Clone, Debug)]
 pub struct RegisteredTransactionModel {
@@ -10,7 +18,18 @@ pub struct RegisteredTransactionModel {
     pub transaction_id: Uuid,
     pub memo: String,
     pub amount_cents: i32,
-    pub mcc: String
+    pub mcc: String,
+    pub lithic_transaction_token: Option<String>,
+    pub asa_response_result: Option<AsaResponseResult>,
+    pub currency: String,
+    pub merchant_amount_cents: Option<i32>,
+    pub merchant_currency: Option<String>,
+    pub conversion_rate: Option<f64>,
+    pub charge_currency: String,
+    pub requested_amount_cents: Option<i32>,
+    pub cash_amount_cents: Option<i32>,
+    // not stored, set while collecting a stand in so it doesn't replay what the errored asa already cancelled
+    pub collection_key: Option<Uuid>
 }
 
 #[derive(Clone, Debug)]
@@ -37,6 +56,70 @@ pub struct WalletCardChargeModel {
     pub is_success: Option<bool>,
     pub created_at: NaiveDateTime,
     pub rule_id: Option<i32>,
+    pub refusal_reason: Option<String>,
+    pub refusal_reason_code: Option<String>,
+    pub capture_status: Option<CaptureStatus>,
+    pub captured_amount_cents: Option<i32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AsaChargeResult {
+    pub result: AsaResponseResult,
+    // only set when less than the asa asked for was approved
+    pub approved_amount_cents: Option<i32>,
+}
+
+impl From<AsaResponseResult> for AsaChargeResult {
+    fn from(value: AsaResponseResult) -> Self {
+        AsaChargeResult {
+            result: value,
+            approved_amount_cents: None
+        }
+    }
+}
+
+/// Time left to answer an asa. Started when the request comes in, and every proxy call
+/// made while charging is bounded by whatever remains of it, one card's attempt by less.
+#[derive(Clone, Copy, Debug)]
+pub struct ChargeBudget {
+    deadline: Instant,
+    minimum_attempt: Duration,
+    maximum_attempt: Duration,
+    cleanup_timeout: Duration,
+}
+
+/// Why the wallet was tried in the order it was and what each attempt came back with.
+/// Stored next to the raw asa so a dispute can be walked back card by card.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoutingTrace {
+    pub candidates: Vec<RoutingCandidate>,
+    pub attempts: Vec<RoutingAttempt>,
+    pub result: Option<ChargeEngineResult>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoutingCandidate {
+    pub position: usize,
+    pub wallet_card_id: i32,
+    pub credit_card_id: i32,
+    pub matched_rule_ids: Vec<i32>,
+    pub rule_id: Option<i32>,
+    pub reward_amount: i32,
+    #[serde(default)]
+    pub health: Option<CardHealthStatus>,
+    #[serde(default)]
+    pub skipped: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RoutingAttempt {
+    pub wallet_card_charge_id: i32,
+    pub wallet_card_id: i32,
+    pub amount_cents: i32,
+    pub resolved_charge_status: ChargeStatus,
+    pub returned_charge_status: Option<String>,
+    pub refusal_reason_code: Option<String>,
+    pub is_success: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -57,9 +140,139 @@ impl From<RegisteredTransaction> for RegisteredTransactionModel {
             transaction_id: value.transaction_id,
             memo: value.memo,
             amount_cents: value.amount_cents,
-            mcc: value.mcc
+            mcc: value.mcc,
+            lithic_transaction_token: value.lithic_transaction_token,
+            asa_response_result: value.asa_response_result.map(AsaResponseResult::from),
+            currency: value.currency,
+            merchant_amount_cents: value.merchant_amount_cents,
+            merchant_currency: value.merchant_currency,
+            conversion_rate: value.conversion_rate,
+            charge_currency: value.charge_currency,
+            requested_amount_cents: value.requested_amount_cents,
+            cash_amount_cents: value.cash_amount_cents,
+            collection_key: None
+        }
+    }
+}
+
+impl RegisteredTransactionModel {
+    pub fn idempotency_key_for_wallet_card(&self, wallet_card_public_id: &Uuid) -> Uuid {
+        // deterministic per (transaction, card), so a retried asa never double charges a card
+        let transaction_key = match &self.lithic_transaction_token {
+            Some(token) => token.clone(),
+            None => self.transaction_id.to_string()
+        };
+        let transaction_key = match &self.collection_key {
+            Some(collection_key) => format!("{}:{}", transaction_key, collection_key),
+            None => transaction_key
+        };
+        Uuid::new_v5(
+            &Uuid::NAMESPACE_OID,
+            format!("{}:{}", transaction_key, wallet_card_public_id).as_bytes()
+        )
+    }
+
+    pub fn idempotency_key_for_adjustment(&self, wallet_card_public_id: &Uuid, amount_cents: i32) -> Uuid {
+        // incremental auths reuse the transaction token, so the new total keeps each adjustment distinct
+        Uuid::new_v5(
+            &Uuid::NAMESPACE_OID,
+            format!("{}:{}", self.idempotency_key_for_wallet_card(wallet_card_public_id), amount_cents).as_bytes()
+        )
+    }
+
+    // everything we keep is in the cardholder currency, only what goes to the backing card is converted
+    pub fn charge_amount(&self, amount_cents: i32) -> i32 {
+        match self.conversion_rate {
+            Some(rate) if self.charge_currency != self.currency => cardholder_to_merchant_amount(amount_cents, &self.currency, &self.charge_currency, rate),
+            _ => amount_cents
         }
     }
+
+    // cash is handed out first, so it's the part of any movement up to the cash amount
+    pub fn cash_portion(&self, amount_cents: i32) -> Option<i32> {
+        self.cash_amount_cents.map(|cash| cash.min(amount_cents))
+    }
+
+    pub fn cardholder_amount(&self, charge_amount: i32) -> i32 {
+        match self.conversion_rate {
+            Some(rate) if self.charge_currency != self.currency => merchant_to_cardholder_amount(charge_amount, &self.currency, &self.charge_currency, rate),
+            _ => charge_amount
+        }
+    }
+}
+
+impl ChargeBudget {
+    pub fn new(budget: Duration, minimum_attempt: Duration, maximum_attempt: Duration, cleanup_timeout: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + budget,
+            minimum_attempt,
+            maximum_attempt,
+            cleanup_timeout,
+        }
+    }
+
+    pub fn from_configuration(configuration: &AsaConfiguration) -> Self {
+        Self::new(
+            Duration::from_millis(configuration.budget_ms),
+            Duration::from_millis(configuration.minimum_attempt_ms),
+            Duration::from_millis(configuration.maximum_attempt_ms),
+            Duration::from_millis(configuration.cleanup_timeout_ms),
+        )
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    pub fn can_attempt(&self) -> bool {
+        // an attempt that can't reasonably finish in time is only going to leave an auth to clean up
+        let remaining = self.remaining();
+        !remaining.is_zero() && remaining >= self.minimum_attempt
+    }
+
+    pub fn attempt_timeout(&self) -> Duration {
+        // one slow card can't take the whole budget, the cards after it still get a chance
+        self.remaining().min(self.maximum_attempt)
+    }
+
+    pub fn cleanup_timeout(&self) -> Duration {
+        // cleanup still has to fit in the window lithic gives us
+        self.remaining().min(self.cleanup_timeout)
+    }
+}
+
+impl RoutingTrace {
+    pub fn from_wallet(wallet: &Vec<Wallet>) -> Self {
+        Self {
+            candidates: wallet.iter().enumerate().map(|(position, card)| RoutingCandidate {
+                position,
+                wallet_card_id: card.id,
+                credit_card_id: card.credit_card_id,
+                matched_rule_ids: card.matched_rule_ids.clone(),
+                rule_id: card.rule_id,
+                reward_amount: card.reward_amount,
+                health: card.health.as_ref().map(|health| health.status),
+                skipped: card.is_skipped(),
+            }).collect(),
+            attempts: vec![],
+            result: None,
+        }
+    }
+
+    pub fn with_attempts(mut self, wallet_card_charges: &Vec<WalletCardCharge>, result: &ChargeEngineResult) -> Self {
+        // charges come back in insert order, which is the order the cards were tried
+        self.attempts = wallet_card_charges.iter().map(|charge| RoutingAttempt {
+            wallet_card_charge_id: charge.id,
+            wallet_card_id: charge.wallet_card_id,
+            amount_cents: charge.amount_cents,
+            resolved_charge_status: charge.resolved_charge_status.clone(),
+            returned_charge_status: charge.returned_charge_status.clone(),
+            refusal_reason_code: charge.refusal_reason_code.clone(),
+            is_success: charge.is_success.unwrap_or(false),
+        }).collect();
+        self.result = Some(result.clone());
+        self
+    }
 }
 
 impl From<PassthroughCardCharge> for PassthroughCardChargeModel {
@@ -89,6 +302,10 @@ impl From<WalletCardCharge> for WalletCardChargeModel {
             is_success: value.is_success,
             created_at: value.created_at,
             rule_id: value.rule_id,
+            refusal_reason: value.refusal_reason,
+            refusal_reason_code: value.refusal_reason_code,
+            capture_status: value.capture_status,
+            captured_amount_cents: value.captured_amount_cents,
         }
     }
 }
@@ -1,25 +1,24 @@
-use std::collections::HashSet;
-use adyen_checkout::models::{Amount, PaymentCancelResponse, PaymentRequest, PaymentRequestPaymentMethod, PaymentResponse};
 use std::sync::Arc;
-use adyen_checkout::models::payment_response::ResultCode;
 use async_trait::async_trait;
-use lazy_static::lazy_static;
+use tokio::time::timeout;
 use uuid::Uuid;
-use crate::asa::request::AsaRequest;
+use crate::asa::request::{replay_key_for_body, AsaRequest};
 use crate::asa::response::AsaResponseResult;
-use crate::charge::constant::{ChargeCardAttemptResult, ChargeEngineResult, ChargeStatus};
+use crate::charge::constant::{MAX_TIP_PERCENT, CancelStatus, CaptureStatus, ChargeCardAttemptResult, ChargeEngineResult, ChargeStatus, RefundStatus, RefusalReason, TransactionEventType};
 use crate::charge::dao::{ChargeDao, ChargeDaoTrait};
-use crate::charge::entity::{ExpectedWalletChargeReference, InsertableExpectedWalletChargeReference, InsertablePassthroughCardCharge, InsertableRegisteredTransaction, InsertableSuccessfulEndToEndCharge, InsertableWalletCardCharge, PassthroughCardCharge, RegisteredTransaction, WalletCardCharge};
+use crate::charge::entity::{ExpectedWalletChargeReference, InsertableExpectedWalletChargeReference, InsertablePassthroughCardCharge, InsertableRegisteredTransaction, InsertableSuccessfulEndToEndCharge, InsertableWalletCardCharge, InsertableWalletCardChargeCancellation, InsertableTransactionEvent, InsertableWalletCardChargeRefund, InsertableAuthorizationAdjustment, InsertableEndToEndChargeWalletCardCharge, InsertableRegisteredTransactionMetadata, AuthorizationAdjustment, PassthroughCardCharge, RegisteredTransaction, SuccessfulEndToEndCharge, TransactionEvent, WalletCardCharge, WalletCardChargeRefund};
 use crate::charge::error::ChargeError;
-use crate::charge::model::{RegisteredTransactionModel, SuccessfulEndToEndChargeModel};
+use crate::charge::model::{AsaChargeResult, ChargeBudget, RegisteredTransactionModel, RoutingTrace, SuccessfulEndToEndChargeModel};
 use crate::common::model::TransactionMetadata;
 use crate::error::data_error::DataError;
-use crate::footprint::error::FootprintError;
-use crate::footprint::request::ChargeThroughProxyRequest;
 use crate::footprint::service::FootprintServiceTrait;
 use crate::ledger::error::LedgerError;
 use crate::ledger::model::PendingPassthroughCardTransactionLedgerModel;
 use crate::passthrough_card::model::PassthroughCardModel as PassthroughCard;
+use crate::payment_processor::adyen::AdyenFootprintProcessor;
+use crate::payment_processor::constant::ProcessorChargeStatus;
+use crate::payment_processor::model::{ProcessorChargeRequest, ProcessorChargeResult, ProcessorModificationRequest, ProcessorModificationResult};
+use crate::payment_processor::service::PaymentProcessorTrait;
 use crate::user::model::UserModel as User;
 use crate::wallet::model::WalletModelWithRule as Wallet;
 use crate::ledger::service::LedgerServiceTrait;
@@ -27,6 +26,9 @@ use crate::user::service::UserServiceTrait;
 use crate::util::error::UtilityError::DateError;
 use crate::util::transaction::{Transaction, transactional};
 
+// an unprocessed claim older than this belongs to a delivery that died, the next one takes it over
+const EVENT_CLAIM_LEASE_SECONDS: i64 = 300;
+
 #[async_trait(?Send)]
 // TODO: do not group ledger calls in with payment calls in txn. need ledger data to go in always?
 pub trait ChargeServiceTrait {
@@ -36,29 +38,92 @@ pub trait ChargeServiceTrait {
         wallet: &Vec<Wallet>,
         passthrough_card: &PassthroughCard,
         user: &User,
-    ) -> Result<AsaResponseResult, ChargeError>;
+        budget: &ChargeBudget,
+    ) -> Result<AsaChargeResult, ChargeError>;
+
+    async fn get_previous_result_for_request(
+        self: Arc<Self>,
+        request: &AsaRequest,
+    ) -> Result<Option<AsaChargeResult>, ChargeError>;
+
+    async fn collect_stand_in(
+        self: Arc<Self>,
+        request: &AsaRequest,
+        wallet: &Vec<Wallet>,
+        passthrough_card: &PassthroughCard,
+        user: &User,
+        collection_key: &Uuid,
+        budget: &ChargeBudget,
+    ) -> Result<AsaChargeResult, ChargeError>;
+
+    async fn process_transaction_event(
+        self: Arc<Self>,
+        lithic_transaction_token: &str,
+        event_type: &TransactionEventType,
+        event_token: &str,
+        amount_cents: i32,
+        passthrough_card: &PassthroughCard,
+    ) -> Result<(), ChargeError>;
+
+    async fn refund_registered_transaction(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        amount_cents: i32,
+        reference: &str,
+    ) -> Result<Vec<WalletCardChargeRefund>, ChargeError>;
 }
 
 pub struct ChargeService {
     user_service: Arc<dyn UserServiceTrait>,
     ledger_service: Arc<dyn LedgerServiceTrait + Send + Sync>,
-    footprint_service: Arc<dyn FootprintServiceTrait>,
+    // new charges go through this one
+    payment_processor: Arc<dyn PaymentProcessorTrait>,
+    // every processor a wallet card charge may have gone through, to send its modifications back to
+    payment_processors: Vec<Arc<dyn PaymentProcessorTrait>>,
     dao: Arc<dyn ChargeDaoTrait + Send + Sync>
 }
 
-lazy_static! {
-    static ref ACCEPTABLE_STATUSES: HashSet<ResultCode> = HashSet::from(
-        [
-            ResultCode::Authorised
-        ]
-    );
-    static ref FINAL_STATE_ERROR_CODES: HashSet<ResultCode> = HashSet::from(
-        [
-            ResultCode::Cancelled,
-            ResultCode::Error,
-            ResultCode::Refused
-        ]
-    );
+/// Spreads an amount over charges in order, no charge takes more than `limit_cents` allows, returns whatever didn't fit.
+fn allocate_across_charges(
+    wallet_card_charges: &[WalletCardCharge],
+    amount_cents: i32,
+    limit_cents: fn(&WalletCardCharge) -> i32
+) -> (Vec<(&WalletCardCharge, i32)>, i32) {
+    let mut remaining_cents = amount_cents;
+    let mut allocations: Vec<(&WalletCardCharge, i32)> = vec![];
+    for wallet_card_charge in wallet_card_charges {
+        if remaining_cents <= 0 { break; }
+        let allocated_cents = remaining_cents.min(limit_cents(wallet_card_charge).max(0));
+        if allocated_cents <= 0 { continue; }
+        allocations.push((wallet_card_charge, allocated_cents));
+        remaining_cents -= allocated_cents;
+    }
+    (allocations, remaining_cents.max(0))
+}
+
+/// The most a clearing can still capture on a wallet charge, never past what the card authorized.
+fn capturable_cents(wallet_card_charge: &WalletCardCharge) -> i32 {
+    match wallet_card_charge.capture_status {
+        Some(CaptureStatus::Captured) => wallet_card_charge.amount_cents - wallet_card_charge.captured_amount_cents.unwrap_or(0),
+        // captured on auth still takes its share of the clearing so the rest lands on the right charge
+        None | Some(CaptureStatus::Authorized) => wallet_card_charge.amount_cents,
+        Some(CaptureStatus::Failed | CaptureStatus::Cancelled) => 0
+    }
+}
+
+/// How much a first capture may take past the authorization for a tip.
+fn tip_allowance_cents(wallet_card_charge: &WalletCardCharge) -> i32 {
+    wallet_card_charge.amount_cents * MAX_TIP_PERCENT / 100
+}
+
+/// The most a wallet charge can give back, what was captured once it's captured, the authorization while it's still open.
+fn refundable_cents(wallet_card_charge: &WalletCardCharge) -> i32 {
+    match wallet_card_charge.capture_status {
+        Some(CaptureStatus::Captured) => wallet_card_charge.captured_amount_cents.unwrap_or(0),
+        // captured on auth before manual capture, or only authorized and refunded off the authorization
+        None | Some(CaptureStatus::Authorized) => wallet_card_charge.amount_cents,
+        Some(CaptureStatus::Failed | CaptureStatus::Cancelled) => 0
+    }
 }
 
 
@@ -72,7 +137,8 @@ impl ChargeServiceTrait for ChargeService {
         wallet: &Vec<Wallet>,
         passthrough_card: &PassthroughCard,
         user: &User,
-    ) -> Result<AsaResponseResult, ChargeError> {
+        budget: &ChargeBudget,
+    ) -> Result<AsaChargeResult, ChargeError> {
         tracing::info!("Starting charge");
         let metadata = TransactionMetadata::convert(&request)
             .map_err(|e| {
@@ -88,45 +154,278 @@ impl ChargeServiceTrait for ChargeService {
             ChargeError::NoCardInRequest
         })?;
 
+        let request_body = serde_json::to_string(request).map_err(|e| {
+            tracing::error!("Error serializing asa request error={:?}", &e);
+            ChargeError::Unexpected(e.into())
+        })?;
+
+        if let Some(lithic_transaction_token) = &request.token {
+            match self.dao.clone().get_registered_transaction_by_lithic_transaction_token(lithic_transaction_token).await {
+                Ok(registered_transaction) => {
+                    tracing::info!("Follow up authorization for transaction={}", &registered_transaction.transaction_id);
+                    return self.clone().adjust_authorization(
+                        &registered_transaction.into(),
+                        wallet,
+                        &metadata,
+                        &passthrough_card,
+                        &user,
+                        budget,
+                        &replay_key_for_body(&request_body)
+                    ).await.map(AsaChargeResult::from)
+                },
+                Err(DataError::NotFound(_)) => {},
+                Err(e) => return Err(ChargeError::Unexpected(e.into()))
+            }
+        }
+
         tracing::info!("Registering transaction");
 
         tracing::info!("Placing hold on passthrough funds");
         let registered_transaction = self.clone().register_transaction_and_pending_passthrough_card_charge(
             &user,
             &metadata,
-            &passthrough_card
+            &passthrough_card,
+            request.token.as_deref(),
+            &request_body
         ).await?;
 
         tracing::info!("Registered transaction with public_id={}", &registered_transaction.transaction_id);
 
-        tracing::info!("Charging wallet");
-        let (charge_result, ledger) = self.clone().charge_wallet(&user, wallet, &metadata, &registered_transaction).await?;
+        let charged = self.clone().charge_registered_transaction(wallet, &metadata, &registered_transaction, &passthrough_card, &user, budget).await;
+        if let Err(e) = &charged {
+            tracing::error!("Error charging transaction={}, closing it out as declined error={:?}", &registered_transaction.transaction_id, e);
+            self.clone().register_errored_transaction(&registered_transaction, &passthrough_card, budget).await;
+        }
+        charged
+    }
 
-        tracing::info!("Charged wallet with result={:?}", &charge_result);
-        return match charge_result {
-            ChargeEngineResult::Approved => {
-                return match ledger {
-                    Some(ledger) => {
-                        // TODO: should verify that this is success
-                        tracing::info!("Charge success, registering in ledger for transaction={}", &registered_transaction.transaction_id);
-                        self.clone().register_successful_passthrough_card_charge(&registered_transaction, &ledger, &passthrough_card).await?;
-                        Ok(AsaResponseResult::from(charge_result))
+    #[tracing::instrument(skip(self))]
+    async fn get_previous_result_for_request(
+        self: Arc<Self>,
+        request: &AsaRequest,
+    ) -> Result<Option<AsaChargeResult>, ChargeError> {
+        let token = match &request.token {
+            Some(token) => token,
+            None => {
+                tracing::warn!("No transaction token on request, unable to check for replay");
+                return Ok(None)
+            }
+        };
+        return match self.dao.clone().get_registered_transaction_by_lithic_transaction_token(token).await {
+            Ok(registered_transaction) => {
+                let registered_transaction: RegisteredTransactionModel = registered_transaction.into();
+                let result = match registered_transaction.asa_response_result.clone() {
+                    Some(result) => result,
+                    None => {
+                        tracing::warn!("Transaction={} already registered and still processing", &registered_transaction.transaction_id);
+                        return Err(ChargeError::DuplicateTransaction("Transaction already registered and still processing".into()))
+                    }
+                };
+                // the amount on the token moves with every adjustment, so which asa this is comes from its payload
+                let asa_request_key = request.replay_key().map_err(|e| {
+                    tracing::error!("Error serializing asa request error={:?}", &e);
+                    ChargeError::Unexpected(e.into())
+                })?;
+                let adjustments = self.dao.clone().get_authorization_adjustments_by_registered_transaction(registered_transaction.id).await
+                    .map_err(|e| ChargeError::Unexpected(e.into()))?;
+                if let Some(adjustment) = adjustments.iter().find(|adjustment| adjustment.asa_request_key == Some(asa_request_key)) {
+                    tracing::info!("Found previous adjustment={} for transaction={}", adjustment.id, &registered_transaction.transaction_id);
+                    return Ok(Some(AsaChargeResult::from(match adjustment.status {
+                        ChargeStatus::Success => AsaResponseResult::from(ChargeEngineResult::Approved),
+                        ChargeStatus::Fail => AsaResponseResult::from(ChargeEngineResult::Denied)
+                    })))
+                }
+
+                // what the original asa was answered with, before any adjustment moved the amount
+                let original_cents = adjustments.first().map_or(registered_transaction.amount_cents, |adjustment| adjustment.previous_amount_cents);
+                let original_body = match self.dao.clone().get_registered_transaction_metadata(registered_transaction.id).await {
+                    Ok(metadata) => metadata.body,
+                    Err(DataError::NotFound(_)) => None,
+                    Err(e) => return Err(ChargeError::Unexpected(e.into()))
+                };
+                let is_original = match original_body {
+                    Some(body) => replay_key_for_body(&body) == asa_request_key,
+                    // registered before the payload was kept, the asked amount is all there is to go on
+                    None => request.amount.is_some() && request.amount == Some(registered_transaction.requested_amount_cents.unwrap_or(original_cents))
+                };
+                if !is_original {
+                    // a new asa on a known token is an incremental auth
+                    return Ok(None)
+                }
+                match registered_transaction.requested_amount_cents {
+                    Some(_) => {
+                        // replay of an asa we only partially approved, answer with the same partial amount
+                        tracing::info!("Found previous partial approval of {} cents for transaction={}", original_cents, &registered_transaction.transaction_id);
+                        Ok(Some(AsaChargeResult { result, approved_amount_cents: Some(original_cents) }))
                     },
                     None => {
-                        tracing::warn!("Outer transaction came in with no registered inner transaction ledgers");
-                        tracing::warn!("Registering failed outer charge for transaction={}", &registered_transaction.transaction_id);
-                        self.clone().register_failed_passthrough_card_charge(&registered_transaction, &passthrough_card).await?;
-                        // TODO: this might actually just mean user has no cards
-                        Err(ChargeError::Unexpected("Approved inner charge with no ledger entry, should not be possible".into()))
+                        tracing::info!("Found previous result={:?} for transaction={}", &result, &registered_transaction.transaction_id);
+                        Ok(Some(AsaChargeResult::from(result)))
                     }
                 }
             },
+            Err(DataError::NotFound(_)) => Ok(None),
+            Err(e) => Err(ChargeError::Unexpected(e.into()))
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn collect_stand_in(
+        self: Arc<Self>,
+        request: &AsaRequest,
+        wallet: &Vec<Wallet>,
+        passthrough_card: &PassthroughCard,
+        user: &User,
+        collection_key: &Uuid,
+        budget: &ChargeBudget,
+    ) -> Result<AsaChargeResult, ChargeError> {
+        let registered_transaction = match &request.token {
+            Some(token) => match self.dao.clone().get_registered_transaction_by_lithic_transaction_token(token).await {
+                Ok(registered_transaction) => Some(RegisteredTransactionModel::from(registered_transaction)),
+                Err(DataError::NotFound(_)) => None,
+                Err(e) => return Err(ChargeError::Unexpected(e.into()))
+            },
+            None => None
+        };
+        let mut registered_transaction = match registered_transaction {
+            // the asa ran out of budget mid charge and was dropped without a result, unwind it like an errored one first
+            Some(registered_transaction) if registered_transaction.asa_response_result.is_none() => {
+                tracing::warn!("Closing out transaction={} left without a result", &registered_transaction.transaction_id);
+                self.clone().register_errored_transaction(&registered_transaction, passthrough_card, budget).await;
+                registered_transaction
+            },
+            // the asa errored and was closed out as declined, but stand in approved it so the charge still has to happen
+            Some(registered_transaction) if registered_transaction.asa_response_result.as_ref().is_some_and(|result| *result != AsaResponseResult::Approved) => registered_transaction,
             _ => {
-                tracing::warn!("Registering failed outer charge for transaction={}", &registered_transaction.transaction_id);
-                self.clone().register_failed_passthrough_card_charge(&registered_transaction, &passthrough_card).await?;
-                Ok(AsaResponseResult::from(charge_result))
+                // the charge may have gone through before whatever made us stand in
+                if let Some(result) = self.clone().get_previous_result_for_request(request).await? {
+                    return Ok(result)
+                }
+                return self.clone().charge_from_asa_request(request, wallet, passthrough_card, user, budget).await
+            }
+        };
+        tracing::info!("Reopening closed out transaction={} to collect its stand in", &registered_transaction.transaction_id);
+        registered_transaction.collection_key = Some(*collection_key);
+        let metadata = TransactionMetadata::convert(&request)
+            .map_err(|e| {
+                tracing::error!("Error converting to required metadata");
+                ChargeError::Unexpected(e.into())
+            })?;
+        self.clone().reopen_closed_out_transaction(&registered_transaction, passthrough_card).await?;
+        let charged = self.clone().charge_registered_transaction(wallet, &metadata, &registered_transaction, passthrough_card, user, budget).await;
+        if let Err(e) = &charged {
+            tracing::error!("Error charging reopened transaction={}, closing it out as declined error={:?}", &registered_transaction.transaction_id, e);
+            self.clone().register_errored_transaction(&registered_transaction, passthrough_card, budget).await;
+        }
+        charged
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn process_transaction_event(
+        self: Arc<Self>,
+        lithic_transaction_token: &str,
+        event_type: &TransactionEventType,
+        event_token: &str,
+        amount_cents: i32,
+        passthrough_card: &PassthroughCard,
+    ) -> Result<(), ChargeError> {
+        let registered_transaction: RegisteredTransactionModel = match self.dao.clone().get_registered_transaction_by_lithic_transaction_token(lithic_transaction_token).await {
+            Ok(registered_transaction) => registered_transaction.into(),
+            Err(DataError::NotFound(e)) => {
+                // erroring gets the event redelivered, a clearing can land before its stand-in is reconciled
+                tracing::error!("No registered transaction for lithic token={}, failing event={} so lithic redelivers it", lithic_transaction_token, event_token);
+                return Err(ChargeError::Unexpected(e))
+            },
+            Err(e) => return Err(ChargeError::Unexpected(e.into()))
+        };
+
+        // the event row is claimed before anything is captured or refunded, a concurrent delivery of the same event stops here
+        let event = match self.clone().claim_transaction_event(&registered_transaction, event_type, event_token, amount_cents).await? {
+            Some(event) => event,
+            None => return Ok(())
+        };
+
+        let processed = self.clone().apply_transaction_event(&registered_transaction, event_type, &event, amount_cents, passthrough_card).await;
+        if let Err(e) = &processed {
+            // dropping the claim lets lithic's redelivery run the event again
+            tracing::error!("Error processing event={} for transaction={}, releasing claim error={:?}", event_token, &registered_transaction.transaction_id, e);
+            if let Err(e) = self.dao.clone().delete_unprocessed_transaction_event(event.id).await {
+                tracing::error!("Unable to release claim on event={} error={:?}", event_token, e);
             }
         }
+        processed
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn refund_registered_transaction(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        amount_cents: i32,
+        reference: &str,
+    ) -> Result<Vec<WalletCardChargeRefund>, ChargeError> {
+        let end_to_end_charge = self.clone().get_end_to_end_charge_to_refund(registered_transaction).await?;
+        let wallet_card_charges = self.clone().get_end_to_end_wallet_card_charges(&end_to_end_charge).await?;
+        // increments were captured as payments of their own, a return of the full cleared amount has to reach them too
+        let increments: Vec<AuthorizationAdjustment> = self.dao.clone().get_authorization_adjustments_by_registered_transaction(registered_transaction.id).await
+            .map_err(|e| ChargeError::Unexpected(e.into()))?
+            .into_iter()
+            .filter(|adjustment| adjustment.status == ChargeStatus::Success && adjustment.amount_cents > adjustment.previous_amount_cents)
+            .collect();
+
+        // increments were the last money taken so they're the first given back, newest first
+        let mut refundable: Vec<(WalletCardCharge, Option<AuthorizationAdjustment>, i32)> = vec![];
+        for adjustment in increments.iter().rev() {
+            let wallet_card_charge = match wallet_card_charges.iter().find(|charge| charge.id == adjustment.wallet_card_charge_id) {
+                Some(wallet_card_charge) => wallet_card_charge.clone(),
+                None => self.dao.clone().get_wallet_charge_by_id(adjustment.wallet_card_charge_id).await
+                    .map_err(|e| ChargeError::Unexpected(e.into()))?
+            };
+            let reversible_cents = adjustment.amount_cents - adjustment.previous_amount_cents - adjustment.reversed_cents;
+            refundable.push((wallet_card_charge, Some(adjustment.clone()), reversible_cents));
+        }
+        let mut replayed: Vec<WalletCardChargeRefund> = vec![];
+        for wallet_card_charge in wallet_card_charges {
+            let previous_refunds: Vec<WalletCardChargeRefund> = self.dao.clone().get_wallet_charge_refunds_by_wallet_charge_id(wallet_card_charge.id).await
+                .map_err(|e| ChargeError::Unexpected(e.into()))?
+                .into_iter()
+                .filter(|refund| refund.refund_status == RefundStatus::Received)
+                .collect();
+            // refunds of an increment hang off the charge it increased, only those against the charge's own psp count against it
+            let refunded_cents: i32 = previous_refunds.iter()
+                .filter(|refund| Some(&refund.psp_reference) == wallet_card_charge.psp_reference.as_ref())
+                .map(|refund| refund.amount_cents)
+                .sum();
+            replayed.extend(previous_refunds.into_iter().filter(|refund| refund.reference == reference));
+            let remaining_cents = refundable_cents(&wallet_card_charge) - refunded_cents;
+            refundable.push((wallet_card_charge, None, remaining_cents));
+        }
+        // a replayed reference gets back whatever it refunded the first time
+        if !replayed.is_empty() {
+            tracing::info!("Refund with reference={} already issued for transaction={}", reference, &registered_transaction.transaction_id);
+            return Ok(replayed)
+        }
+
+        let refundable_cents: i32 = refundable.iter().map(|(_, _, remaining_cents)| *remaining_cents).sum();
+        if amount_cents <= 0 || amount_cents > refundable_cents {
+            tracing::error!("Refund of {} cents invalid for transaction={} with {} cents left to refund", amount_cents, &registered_transaction.transaction_id, refundable_cents);
+            return Err(ChargeError::InvalidRefund("Refund exceeds the remaining charge amount".into()))
+        }
+
+        let mut refunds = vec![];
+        let mut remaining_cents = amount_cents;
+        for (wallet_card_charge, increment, refundable_cents) in refundable {
+            if remaining_cents <= 0 { break; }
+            let refund_cents = remaining_cents.min(refundable_cents);
+            if refund_cents <= 0 { continue; }
+            refunds.push(
+                match &increment {
+                    Some(increment) => self.clone().refund_authorization_increment(registered_transaction, &end_to_end_charge, &wallet_card_charge, increment, refund_cents, reference).await?,
+                    None => self.clone().refund_wallet_card_charge(registered_transaction, &end_to_end_charge, &wallet_card_charge, refund_cents, reference).await?
+                }
+            );
+            remaining_cents -= refund_cents;
+        }
+        Ok(refunds)
     }
 }
 
@@ -138,51 +437,376 @@ impl ChargeService {
         user_service: Arc<dyn UserServiceTrait>,
         ledger_service: Arc<dyn LedgerServiceTrait + Send + Sync>,
         footprint_service: Arc<dyn FootprintServiceTrait>
+    ) -> Self {
+        Self::new_with_processor(
+            user_service,
+            ledger_service,
+            Arc::new(AdyenFootprintProcessor::new_with_services(footprint_service))
+        )
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
+    pub fn new_with_processor(
+        user_service: Arc<dyn UserServiceTrait>,
+        ledger_service: Arc<dyn LedgerServiceTrait + Send + Sync>,
+        payment_processor: Arc<dyn PaymentProcessorTrait>
     ) -> Self {
         Self {
             user_service,
             ledger_service,
-            footprint_service,
+            payment_processors: vec![payment_processor.clone()],
+            payment_processor,
             dao: Arc::new(ChargeDao::new()),
         }
     }
 
+    fn processor_for(&self, wallet_card_charge: &WalletCardCharge) -> Result<Arc<dyn PaymentProcessorTrait>, ChargeError> {
+        self.payment_processors.iter()
+            .find(|processor| processor.name() == wallet_card_charge.processor)
+            .cloned()
+            .ok_or_else(|| {
+                tracing::error!("No {} processor for wallet charge={}", &wallet_card_charge.processor, wallet_card_charge.id);
+                ChargeError::Unexpected(format!("No {} processor configured", &wallet_card_charge.processor).into())
+            })
+    }
+
+    // puts back the hold that closing the transaction out released, so it can be charged like it was just registered
+    pub async fn reopen_closed_out_transaction(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        passthrough_card: &PassthroughCard,
+    ) -> Result<(), ChargeError> {
+        let ledger_service = self.ledger_service.clone();
+        let dao = self.dao.clone();
+        let registered_transaction = registered_transaction.clone();
+        let passthrough_card = passthrough_card.clone();
+        transactional(move |conn| {
+            Box::pin(async move {
+                let deleted = dao.clone().delete_failed_passthrough_card_charge(conn, registered_transaction.id).await?;
+                if deleted > 0 {
+                    ledger_service.clone().reserve_passthrough_card_amount(
+                        conn,
+                        &registered_transaction,
+                        &passthrough_card,
+                        registered_transaction.amount_cents
+                    ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                }
+                Ok(())
+            })
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn charge_registered_transaction(
+        self: Arc<Self>,
+        wallet: &Vec<Wallet>,
+        metadata: &TransactionMetadata,
+        registered_transaction: &RegisteredTransactionModel,
+        passthrough_card: &PassthroughCard,
+        user: &User,
+        budget: &ChargeBudget,
+    ) -> Result<AsaChargeResult, ChargeError> {
+        tracing::info!("Charging wallet");
+        let (charge_result, wallet_card_charges) = self.clone().charge_wallet(user, wallet, metadata, registered_transaction, budget).await?;
+        tracing::info!("Charged wallet with result={:?}", &charge_result);
+        self.clone().register_routing_trace(registered_transaction, RoutingTrace::from_wallet(wallet), &charge_result).await;
+        return match charge_result {
+            ChargeEngineResult::Approved => {
+                return match wallet_card_charges.is_empty() {
+                    false => {
+                        // TODO: should verify that this is success
+                        tracing::info!("Charge success across {} cards, registering in ledger for transaction={}", wallet_card_charges.len(), &registered_transaction.transaction_id);
+                        let approved_cents = self.clone().register_successful_passthrough_card_charge(registered_transaction, &wallet_card_charges, passthrough_card).await?;
+                        let result = AsaResponseResult::from(charge_result);
+                        self.clone().register_asa_response_result(registered_transaction, &result).await?;
+                        Ok(
+                            AsaChargeResult {
+                                result,
+                                approved_amount_cents: (approved_cents < registered_transaction.amount_cents).then_some(approved_cents)
+                            }
+                        )
+                    },
+                    true => {
+                        tracing::warn!("Outer transaction came in with no registered inner transaction ledgers");
+                        tracing::warn!("Registering failed outer charge for transaction={}", &registered_transaction.transaction_id);
+                        self.clone().register_failed_passthrough_card_charge(registered_transaction, passthrough_card).await?;
+                        // TODO: this might actually just mean user has no cards
+                        Err(ChargeError::Unexpected("Approved inner charge with no ledger entry, should not be possible".into()))
+                    }
+                }
+            },
+            _ => {
+                tracing::warn!("Registering failed outer charge for transaction={}", &registered_transaction.transaction_id);
+                self.clone().register_failed_passthrough_card_charge(registered_transaction, passthrough_card).await?;
+                let result = AsaResponseResult::from(charge_result);
+                self.clone().register_asa_response_result(registered_transaction, &result).await?;
+                Ok(AsaChargeResult::from(result))
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn register_errored_transaction(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        passthrough_card: &PassthroughCard,
+        budget: &ChargeBudget,
+    ) {
+        /*
+        A registered transaction without a result reads as still processing, so every lithic retry of the token would conflict
+        Unwind whatever the failed attempt left behind and answer any retry with a decline instead
+         */
+        match self.dao.clone().get_successful_wallet_charges_by_registered_transaction(registered_transaction.id).await {
+            Ok(wallet_card_charges) => for wallet_card_charge in &wallet_card_charges {
+                if let Err(e) = self.clone().roll_back_wallet_card_charge(registered_transaction, wallet_card_charge, budget).await {
+                    tracing::error!("Unable to roll back wallet charge={} for errored transaction={} error={:?}", wallet_card_charge.id, &registered_transaction.transaction_id, &e);
+                }
+            },
+            Err(e) => tracing::error!("Error getting wallet charges for errored transaction={} error={:?}", &registered_transaction.transaction_id, &e)
+        }
+        match self.dao.clone().get_passthrough_card_charge_by_registered_transaction(registered_transaction.id).await {
+            // the outer charge is already settled one way or the other, so the hold was already dealt with
+            Ok(_) => {},
+            Err(DataError::NotFound(_)) => {
+                if let Err(e) = self.clone().register_failed_passthrough_card_charge(registered_transaction, passthrough_card).await {
+                    tracing::error!("Unable to release hold for errored transaction={} error={:?}", &registered_transaction.transaction_id, &e);
+                }
+            },
+            Err(e) => tracing::error!("Error getting outer charge for errored transaction={} error={:?}", &registered_transaction.transaction_id, &e)
+        }
+        if let Err(e) = self.clone().register_asa_response_result(registered_transaction, &AsaResponseResult::from(ChargeEngineResult::Denied)).await {
+            tracing::error!("Unable to close out errored transaction={} error={:?}", &registered_transaction.transaction_id, &e);
+        }
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn charge_wallet(
         self: Arc<Self>,
         user: &User,
         wallet: &Vec<Wallet>,
         transaction_metadata: &TransactionMetadata,
-        registered_transaction: &RegisteredTransactionModel
-    ) -> Result<(ChargeEngineResult, Option<WalletCardCharge>), ChargeError> {
+        registered_transaction: &RegisteredTransactionModel,
+        budget: &ChargeBudget
+    ) -> Result<(ChargeEngineResult, Vec<WalletCardCharge>), ChargeError> {
+        if user.split_tender_enabled && wallet.len() > 1 {
+            return self.charge_wallet_split_tender(user, wallet, transaction_metadata, registered_transaction, budget).await
+        }
         // iterate through the users wallet, charging one and ONLY ONE card
         tracing::info!("Charging {} cards for user={}", wallet.len(), user.id);
-        let idempotency_key = Uuid::new_v4();
         let mut success_charge = false;
         let mut codes : Vec<ChargeCardAttemptResult> = vec![];
         let mut ledger_res: Option<WalletCardCharge> = None;
+        // best partial approval so far, only kept if no card approves the full amount
+        let mut partial_res: Option<WalletCardCharge> = None;
+        let mut declines: Vec<ChargeEngineResult> = vec![];
         for card in wallet {
             if success_charge { break; }
-            if let Ok((charge_attempt, ledger)) = self.clone().charge_card_with_cleanup(
+            if card.is_skipped() {
+                tracing::info!("Skipping unhealthy card={} for user={}", card.id, &user.id);
+                continue;
+            }
+            if !budget.can_attempt() {
+                tracing::warn!("Asa budget spent with {:?} left, not trying card={} for user={}", budget.remaining(), card.id, &user.id);
+                break;
+            }
+            let idempotency_key = registered_transaction.idempotency_key_for_wallet_card(&card.public_id);
+            if let Ok((charge_attempt, ledger)) = self.clone().charge_card_amount_with_cleanup(
                 idempotency_key,
                 card,
                 user,
                 transaction_metadata,
-                registered_transaction
+                registered_transaction,
+                transaction_metadata.amount_cents,
+                transaction_metadata.partial_approval_capable,
+                budget
             ).await {
                 tracing::info!("Charged card={} for user={} with result={:?}", card.id, &user.id, &charge_attempt);
                 success_charge = bool::from(&charge_attempt);
-                ledger_res = ledger;
+                if let Some(code) = ledger.as_ref().and_then(|charge| charge.refusal_reason_code.as_deref()) {
+                    let reason = RefusalReason::from_refusal_reason_code(code);
+                    tracing::info!("Card={} refused with code={} reason={:?}", card.id, code, &reason);
+                    declines.push(ChargeEngineResult::from(&reason));
+                }
+                if let (ChargeCardAttemptResult::PartiallyApproved, Some(partial)) = (&charge_attempt, &ledger) {
+                    let (keep, release) = match partial_res.take() {
+                        Some(held) if held.amount_cents >= partial.amount_cents => (held, Some(partial.clone())),
+                        held => (partial.clone(), held)
+                    };
+                    if let Some(release) = release {
+                        self.clone().roll_back_wallet_card_charge(registered_transaction, &release, budget).await?;
+                    }
+                    partial_res = Some(keep);
+                } else {
+                    ledger_res = ledger;
+                }
                 codes.push(charge_attempt)
             }
         }
         if success_charge {
+            if let Some(partial) = partial_res {
+                tracing::info!("Releasing partial charge={} after full approval for user={}", partial.id, &user.id);
+                self.clone().roll_back_wallet_card_charge(registered_transaction, &partial, budget).await?;
+            }
             tracing::info!("Successfully charged a card for user={}", &user.id);
-            Ok((ChargeEngineResult::Approved, ledger_res))
+            Ok((ChargeEngineResult::Approved, ledger_res.into_iter().collect()))
+        } else if let Some(partial) = partial_res {
+            tracing::info!("Partially approved {} of {} cents for user={}", partial.amount_cents, transaction_metadata.amount_cents, &user.id);
+            Ok((ChargeEngineResult::Approved, vec![partial]))
         } else {
-            tracing::warn!("Unable to charge a card for user={}", &user.id);
-            Ok((ChargeEngineResult::Denied, ledger_res))
+            let result = declines.into_iter()
+                .max_by_key(|decline| decline.decline_priority())
+                .unwrap_or(ChargeEngineResult::Denied);
+            tracing::warn!("Unable to charge a card for user={} result={:?}", &user.id, &result);
+            Ok((result, vec![]))
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn charge_wallet_split_tender(
+        self: Arc<Self>,
+        user: &User,
+        wallet: &Vec<Wallet>,
+        transaction_metadata: &TransactionMetadata,
+        registered_transaction: &RegisteredTransactionModel,
+        budget: &ChargeBudget
+    ) -> Result<(ChargeEngineResult, Vec<WalletCardCharge>), ChargeError> {
+        // walk the wallet in rule order, letting each card approve as much of what's left as it can
+        tracing::info!("Split tender charging {} cards for user={}", wallet.len(), user.id);
+        let mut remaining_cents = transaction_metadata.amount_cents;
+        let mut wallet_card_charges: Vec<WalletCardCharge> = vec![];
+        let mut declines: Vec<ChargeEngineResult> = vec![];
+        for card in wallet {
+            if remaining_cents <= 0 { break; }
+            if card.is_skipped() {
+                tracing::info!("Skipping unhealthy card={} for user={}", card.id, &user.id);
+                continue;
+            }
+            if !budget.can_attempt() {
+                tracing::warn!("Asa budget spent with {:?} left, not trying card={} for user={}", budget.remaining(), card.id, &user.id);
+                break;
+            }
+            let idempotency_key = registered_transaction.idempotency_key_for_wallet_card(&card.public_id);
+            if let Ok((charge_attempt, ledger)) = self.clone().charge_card_amount_with_cleanup(
+                idempotency_key,
+                card,
+                user,
+                transaction_metadata,
+                registered_transaction,
+                remaining_cents,
+                true,
+                budget
+            ).await {
+                tracing::info!("Charged card={} for user={} with result={:?}", card.id, &user.id, &charge_attempt);
+                match (charge_attempt, ledger) {
+                    (ChargeCardAttemptResult::Approved | ChargeCardAttemptResult::PartiallyApproved, Some(wallet_card_charge)) => {
+                        remaining_cents -= wallet_card_charge.amount_cents;
+                        wallet_card_charges.push(wallet_card_charge);
+                    },
+                    (_, Some(wallet_card_charge)) => {
+                        if let Some(code) = wallet_card_charge.refusal_reason_code.as_deref() {
+                            declines.push(ChargeEngineResult::from(&RefusalReason::from_refusal_reason_code(code)));
+                        }
+                    },
+                    _ => {}
+                }
+            }
+        }
+        if remaining_cents <= 0 {
+            tracing::info!("Covered transaction={} across {} cards for user={}", &registered_transaction.transaction_id, wallet_card_charges.len(), &user.id);
+            return Ok((ChargeEngineResult::Approved, wallet_card_charges))
+        }
+        if transaction_metadata.partial_approval_capable && !wallet_card_charges.is_empty() {
+            // the terminal can take the rest another way, so approve what the wallet covered
+            tracing::info!("Partially covered transaction={} leaving {} cents for user={}", &registered_transaction.transaction_id, remaining_cents, &user.id);
+            return Ok((ChargeEngineResult::Approved, wallet_card_charges))
         }
+
+        // all or nothing, anything we did get approved goes back before declining
+        tracing::warn!("Unable to cover {} cents of transaction={} for user={}, rolling back {} charges", remaining_cents, &registered_transaction.transaction_id, &user.id, wallet_card_charges.len());
+        for wallet_card_charge in &wallet_card_charges {
+            self.clone().roll_back_wallet_card_charge(registered_transaction, wallet_card_charge, budget).await?;
+        }
+        let result = declines.into_iter()
+            .max_by_key(|decline| decline.decline_priority())
+            .unwrap_or(ChargeEngineResult::Denied);
+        Ok((result, vec![]))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn roll_back_wallet_card_charge(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        wallet_card_charge: &WalletCardCharge,
+        budget: &ChargeBudget,
+    ) -> Result<WalletCardCharge, ChargeError> {
+        let payment_processor = self.processor_for(wallet_card_charge)?;
+        let cancel = match &wallet_card_charge.psp_reference {
+            Some(psp) => self.clone().cancel_within_budget(payment_processor, psp, budget).await,
+            None => {
+                tracing::error!("No psp reference on split tender charge={}, unable to cancel", wallet_card_charge.id);
+                None
+            }
+        };
+        let cancel_status = match &cancel {
+            Some(cancel) if cancel.received => CancelStatus::Received,
+            _ => CancelStatus::Failed
+        };
+
+        let ledger_service = self.ledger_service.clone();
+        let dao = self.dao.clone();
+        let registered_transaction = registered_transaction.clone();
+        let wallet_card_charge = wallet_card_charge.clone();
+        transactional(move |conn| {
+            Box::pin(async move {
+                let rolled_back = dao.clone().update_wallet_charge_status(
+                    conn,
+                    wallet_card_charge.id,
+                    &ChargeStatus::Fail,
+                    None
+                ).await?;
+
+                // a leg we couldn't cancel stays authorized on the backing card, the FAILED row is for whoever cleans it up by hand
+                let cancellation = dao.clone().insert_wallet_charge_cancellation(
+                    conn,
+                    &InsertableWalletCardChargeCancellation {
+                        registered_transaction_id: registered_transaction.id,
+                        wallet_card_charge_id: wallet_card_charge.id,
+                        psp_reference: wallet_card_charge.psp_reference.clone(),
+                        cancel_psp_reference: cancel.map(|cancel| cancel.psp_reference),
+                        cancel_status,
+                    }
+                ).await?;
+
+                match wallet_card_charge.capture_status {
+                    // only authorized, the reserve is still pending
+                    Some(CaptureStatus::Authorized) => {
+                        let cancelled = dao.clone().update_wallet_charge_capture(
+                            conn,
+                            wallet_card_charge.id,
+                            &CaptureStatus::Cancelled,
+                            None
+                        ).await?;
+                        let ledger_entry = ledger_service.clone().release_wallet_amount(
+                            conn,
+                            &registered_transaction.clone().into(),
+                            wallet_card_charge.wallet_card_id,
+                            wallet_card_charge.amount_cents
+                        ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                    },
+                    // the charge already settled, so unwinding it goes against the settled side
+                    _ => {
+                        let ledger_entry = ledger_service.clone().refund_wallet_amount(
+                            conn,
+                            &registered_transaction.clone().into(),
+                            wallet_card_charge.wallet_card_id,
+                            wallet_card_charge.amount_cents
+                        ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                    }
+                }
+
+                Ok(rolled_back)
+            })
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))
     }
 
     #[tracing::instrument(skip(self))]
@@ -192,173 +816,1326 @@ impl ChargeService {
         card: &Wallet,
         user: &User,
         transaction_metadata: &TransactionMetadata,
-        registered_transaction: &RegisteredTransactionModel
+        registered_transaction: &RegisteredTransactionModel,
+        budget: &ChargeBudget
+    ) -> Result<(ChargeCardAttemptResult, Option<WalletCardCharge>), ChargeError> {
+        self.charge_card_amount_with_cleanup(
+            idempotency_key,
+            card,
+            user,
+            transaction_metadata,
+            registered_transaction,
+            transaction_metadata.amount_cents,
+            false,
+            budget
+        ).await
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn charge_card_amount_with_cleanup(
+        self: Arc<Self>,
+        idempotency_key: Uuid,
+        card: &Wallet,
+        user: &User,
+        transaction_metadata: &TransactionMetadata,
+        registered_transaction: &RegisteredTransactionModel,
+        amount_cents: i32,
+        allow_partial_authorization: bool,
+        budget: &ChargeBudget
     ) -> Result<(ChargeCardAttemptResult, Option<WalletCardCharge>), ChargeError> {
-        tracing::info!("Charging card with cleanup for user={} card={}", &user.id, card.id);
+        tracing::info!("Charging {} cents to card with cleanup for user={} card={}", amount_cents, &user.id, card.id);
 
         let wallet_reserve = self.clone().register_reserve_wallet_charge(
             registered_transaction,
             card,
-
+            amount_cents
         ).await?;
 
-        let resp = self.footprint_service.clone().proxy_adyen_payment_request(
-            &ChargeThroughProxyRequest {
-                amount_cents: transaction_metadata.amount_cents as i32, // TODO: edit model to be i32
+        let resp = match timeout(budget.attempt_timeout(), self.payment_processor.clone().charge(
+            &ProcessorChargeRequest {
+                amount_cents: registered_transaction.charge_amount(amount_cents),
+                currency: &registered_transaction.charge_currency,
                 mcc: &transaction_metadata.mcc,
                 payment_method_id: &card.payment_method_id,
                 customer_public_id: &user.public_id.to_string(), // needed to proxy the data in correctly. should change arg name
                 footprint_vault_id: &user.footprint_vault_id.to_string(), // needed to proxy the data in correctly. should change arg name
                 idempotency_key: &idempotency_key,
                 reference: &wallet_reserve.reference_id.to_string(),
-                statement: &transaction_metadata.memo
+                statement: &transaction_metadata.memo,
+                allow_partial_authorization,
+                manual_capture: true,
+                avs_address: transaction_metadata.avs_address.as_deref(),
+                avs_zipcode: transaction_metadata.avs_zipcode.as_deref()
             }
-        ).await;
-        tracing::info!("Made request through proxy to charge card");
+        )).await {
+            Ok(resp) => resp,
+            Err(_) => {
+                // the charge may still land after we stop waiting, so cancel it by our reference before moving on
+                tracing::warn!("Charge for card={} user={} ran past its attempt timeout, abandoning", card.id, user.id);
+                let cancel = self.clone().cancel_abandoned_payment(self.payment_processor.clone(), &wallet_reserve.reference_id.to_string(), budget).await;
+                let cancel_status = match &cancel {
+                    Some(cancel) if cancel.received => CancelStatus::Received,
+                    _ => CancelStatus::Failed
+                };
+                let wallet_charge = self.clone().register_abandoned_wallet_charge(
+                    registered_transaction,
+                    card,
+                    &wallet_reserve,
+                    cancel.as_ref(),
+                    &cancel_status
+                ).await?;
+                return match cancel_status {
+                    CancelStatus::Received => {
+                        tracing::warn!("Registered abandoned inner charge in ledger for transaction={} id={}", &registered_transaction.transaction_id, &wallet_charge.id);
+                        Ok((ChargeCardAttemptResult::PartialCancelSucceeded, Some(wallet_charge)))
+                    },
+                    CancelStatus::Failed => {
+                        tracing::error!("Registered uncancelled abandoned inner charge in ledger for transaction={} id={}, requires further cleanup", &registered_transaction.transaction_id, &wallet_charge.id);
+                        Ok((ChargeCardAttemptResult::PartialCancelFailed, Some(wallet_charge)))
+                    }
+                }
+            }
+        };
+        tracing::info!("Made request to processor to charge card");
 
         if let Ok(response) = resp {
-            if let Some(code) = response.result_code {
-                tracing::info!("Checkout returned code={:?} for card={} user={}", code, card.id, user.id);
-                if ACCEPTABLE_STATUSES.contains(&code) {
+            tracing::info!("Processor returned status={:?} code={:?} for card={} user={}", response.status, &response.returned_status, card.id, user.id);
+            match response.status {
+                ProcessorChargeStatus::Approved => {
                     tracing::info!("Charged card={} for user={}", card.id, user.id);
                     let wallet_charge = self.register_successful_wallet_charge(registered_transaction, card, &wallet_reserve, &response).await?;
                     tracing::info!("Registered successful inner charge in ledger for transaction={} id={}", &registered_transaction.transaction_id, &wallet_charge.id);
-                    return Ok((ChargeCardAttemptResult::from(code), Some(wallet_charge)));
+                    return Ok((ChargeCardAttemptResult::from(response.status), Some(wallet_charge)));
                     //add to ledger
-                } else if FINAL_STATE_ERROR_CODES.contains(&code) {
+                },
+                ProcessorChargeStatus::PartiallyApproved if allow_partial_authorization => {
+                    tracing::info!("Partially charged card={} for user={}", card.id, user.id);
+                    let wallet_charge = self.register_successful_wallet_charge(registered_transaction, card, &wallet_reserve, &response).await?;
+                    tracing::info!("Registered partial inner charge of {} cents for transaction={} id={}", wallet_charge.amount_cents, &registered_transaction.transaction_id, &wallet_charge.id);
+                    return Ok((ChargeCardAttemptResult::from(response.status), Some(wallet_charge)));
+                },
+                ProcessorChargeStatus::Declined => {
                     tracing::warn!("Error charging card={} for user={}", card.id, user.id);
                     let wallet_charge = self.clone().register_failed_wallet_charge(registered_transaction, card, &wallet_reserve, &response).await?;
                     tracing::warn!("Registered unsuccessful inner charge in ledger for transaction={} id={}", &registered_transaction.transaction_id, &wallet_charge.id);
-                    return Ok((ChargeCardAttemptResult::Denied, Some(wallet_charge)));
+                    return Ok((ChargeCardAttemptResult::from(response.status), Some(wallet_charge)));
                     //can safely bypass this branch
-                } else {
-                    tracing::warn!("Intermediate state needs cleanup");
-                    let wallet_charge = self.clone().register_partial_wallet_charge(registered_transaction, card, &wallet_reserve, &response).await?;
-                    return Ok((ChargeCardAttemptResult::Denied, Some(wallet_charge)));
-                    /*
+                },
+                ProcessorChargeStatus::PartiallyApproved | ProcessorChargeStatus::Pending => {
                     tracing::warn!("Intermediate state needs cleanup for card={} for user={}", card.id, user.id);
-                    if let Some(psp) = response.psp_reference {
-                        // TODO: move this call to proxy
-                        tracing::warn!("Cancelling transaction for user={} card={}", &user.id, card.id);
-                        let cancel = self.footprint_service.clone().proxy_adyen_cancel_request(
-                            &psp
-                        ).await;
-                        if let Ok(cancel) = cancel {
-                            tracing::info!("Cancelled with status: {:?}", cancel.status);
-                            let ledger_entry = self.ledger_service.clone().register_failed_inner_charge(
-                                registered_transaction,
-                                transaction_metadata,
-                                card
-                            ).await?;
-                            tracing::warn!("Registered unsuccessful inner charge in ledger for transaction={} id={}", &registered_transaction.transaction_id, ledger_entry.id);
-                            return Ok((ChargeCardAttemptResult::PartialCancelSucceeded, Some(ledger_entry)));
-                            //cancel received. block on webhook response?
-                        } else {
-                            tracing::error!("Error cancelling unsuccessful payment with psp={}", psp);
-                            let ledger_entry = self.ledger_service.clone().register_failed_inner_charge(
-                                registered_transaction,
-                                transaction_metadata,
-                                card
-                            ).await?;
-                            tracing::error!("Registered unsuccessful inner charge in ledger for transaction={} id={}, requires further cleanup", &registered_transaction.transaction_id, ledger_entry.id);
-                            return Ok((ChargeCardAttemptResult::PartialCancelFailed, Some(ledger_entry)));
-                            // TODO: error cancelling. figure out what to do
+                    let cancel = match &response.psp_reference {
+                        Some(psp) => {
+                            tracing::warn!("Cancelling transaction for user={} card={} psp={}", &user.id, card.id, psp);
+                            self.clone().cancel_within_budget(self.payment_processor.clone(), psp, budget).await
+                        },
+                        None => {
+                            tracing::error!("No psp reference returned for intermediate state charge, unable to cancel");
+                            None
+                        }
+                    };
+                    let cancel_status = match &cancel {
+                        Some(cancel) if cancel.received => CancelStatus::Received,
+                        _ => CancelStatus::Failed
+                    };
+                    let wallet_charge = self.clone().register_cancelled_wallet_charge(
+                        registered_transaction,
+                        card,
+                        &wallet_reserve,
+                        &response,
+                        cancel.as_ref(),
+                        &cancel_status
+                    ).await?;
+                    return match cancel_status {
+                        CancelStatus::Received => {
+                            tracing::warn!("Registered cancelled inner charge in ledger for transaction={} id={}", &registered_transaction.transaction_id, &wallet_charge.id);
+                            Ok((ChargeCardAttemptResult::PartialCancelSucceeded, Some(wallet_charge)))
+                        },
+                        CancelStatus::Failed => {
+                            tracing::error!("Registered uncancelled inner charge in ledger for transaction={} id={}, requires further cleanup", &registered_transaction.transaction_id, &wallet_charge.id);
+                            Ok((ChargeCardAttemptResult::PartialCancelFailed, Some(wallet_charge)))
                         }
                     }
-                     */
                 }
-            } else {
-                let wallet_charge = self.clone().register_failed_wallet_charge(
-                    &registered_transaction, &card, &wallet_reserve, &response
-                ).await?;
-                return Ok((ChargeCardAttemptResult::Denied, Some(wallet_charge)))
             }
         }
         tracing::warn!("Fell through charge logic");
 
-        let wallet_charge = self.clone().register_failed_wallet_charge_no_response_body(&registered_transaction, &card, &wallet_reserve).await?;
-        tracing::warn!("Registered unsuccessful inner charge in ledger for transaction={} id={}", &registered_transaction.transaction_id, wallet_charge.id);
-        Ok((ChargeCardAttemptResult::Denied, Some(wallet_charge)))
+        let wallet_charge = self.clone().register_failed_wallet_charge_no_response_body(&registered_transaction, &card, &wallet_reserve).await?;
+        tracing::warn!("Registered unsuccessful inner charge in ledger for transaction={} id={}", &registered_transaction.transaction_id, wallet_charge.id);
+        Ok((ChargeCardAttemptResult::Denied, Some(wallet_charge)))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn adjust_authorization(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        wallet: &Vec<Wallet>,
+        metadata: &TransactionMetadata,
+        passthrough_card: &PassthroughCard,
+        user: &User,
+        budget: &ChargeBudget,
+        asa_request_key: &Uuid,
+    ) -> Result<AsaResponseResult, ChargeError> {
+        if registered_transaction.asa_response_result.is_none() {
+            tracing::warn!("Transaction={} still processing, unable to adjust", &registered_transaction.transaction_id);
+            return Err(ChargeError::DuplicateTransaction("Transaction already registered and still processing".into()))
+        }
+        let wallet_card_charges = self.dao.clone().get_successful_wallet_charges_by_registered_transaction(registered_transaction.id).await
+            .map_err(|e| ChargeError::Unexpected(e.into()))?;
+        if wallet_card_charges.is_empty() {
+            tracing::warn!("No approved charge to adjust for transaction={}", &registered_transaction.transaction_id);
+            return Ok(AsaResponseResult::from(ChargeEngineResult::Denied))
+        }
+
+        let delta_cents = metadata.amount_cents - registered_transaction.amount_cents;
+        tracing::info!("Adjusting transaction={} from {} to {} cents", &registered_transaction.transaction_id, registered_transaction.amount_cents, metadata.amount_cents);
+        let charge_result = if delta_cents > 0 && wallet_card_charges.len() > 1 {
+            tracing::warn!("Transaction={} was split across {} cards, declining increment", &registered_transaction.transaction_id, wallet_card_charges.len());
+            ChargeEngineResult::Denied
+        } else if delta_cents > 0 {
+            self.clone().increment_authorization(registered_transaction, &wallet_card_charges[0], wallet, metadata, passthrough_card, user, budget, asa_request_key).await?
+        } else if delta_cents < 0 {
+            self.clone().decrement_authorization(registered_transaction, &wallet_card_charges, metadata, passthrough_card, asa_request_key).await?
+        } else {
+            ChargeEngineResult::Approved
+        };
+
+        let result = AsaResponseResult::from(charge_result);
+        if result == AsaResponseResult::Approved {
+            self.clone().register_asa_response_result(registered_transaction, &result).await?;
+        }
+        Ok(result)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn increment_authorization(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        wallet_card_charge: &WalletCardCharge,
+        wallet: &Vec<Wallet>,
+        metadata: &TransactionMetadata,
+        passthrough_card: &PassthroughCard,
+        user: &User,
+        budget: &ChargeBudget,
+        asa_request_key: &Uuid,
+    ) -> Result<ChargeEngineResult, ChargeError> {
+        if !budget.can_attempt() {
+            tracing::warn!("Asa budget spent with {:?} left, declining increment for transaction={}", budget.remaining(), &registered_transaction.transaction_id);
+            return Ok(ChargeEngineResult::Denied)
+        }
+        // only the card that took the original charge can take the increment
+        let card = match wallet.iter().find(|card| card.id == wallet_card_charge.wallet_card_id) {
+            Some(card) => card,
+            None => {
+                tracing::warn!("Card={} no longer in wallet for user={}, declining increment", wallet_card_charge.wallet_card_id, user.id);
+                return Ok(ChargeEngineResult::Denied)
+            }
+        };
+        let delta_cents = metadata.amount_cents - registered_transaction.amount_cents;
+        let reference = Uuid::new_v4();
+
+        let ledger_service = self.ledger_service.clone();
+        let rtx = registered_transaction.clone();
+        let pc = passthrough_card.clone();
+        let card_id = card.id;
+        transactional(move |conn| {
+            Box::pin(async move {
+                let passthrough_reserve = ledger_service.clone().reserve_passthrough_card_amount(
+                    conn, &rtx, &pc, delta_cents
+                ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                let wallet_reserve = ledger_service.clone().reserve_wallet_amount(
+                    conn, &rtx, card_id, delta_cents
+                ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                Ok(())
+            })
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))?;
+
+        // the increment goes through the processor that took the original charge
+        let payment_processor = self.processor_for(wallet_card_charge)?;
+        let idempotency_key = registered_transaction.idempotency_key_for_adjustment(&card.public_id, metadata.amount_cents);
+        let resp = timeout(budget.attempt_timeout(), payment_processor.clone().charge(
+            &ProcessorChargeRequest {
+                amount_cents: registered_transaction.charge_amount(delta_cents),
+                currency: &registered_transaction.charge_currency,
+                mcc: &metadata.mcc,
+                payment_method_id: &card.payment_method_id,
+                customer_public_id: &user.public_id.to_string(),
+                footprint_vault_id: &user.footprint_vault_id.to_string(),
+                idempotency_key: &idempotency_key,
+                reference: &reference.to_string(),
+                statement: &metadata.memo,
+                allow_partial_authorization: false,
+                // increments have no wallet charge of their own to capture later, so they're captured right away
+                manual_capture: false,
+                avs_address: metadata.avs_address.as_deref(),
+                avs_zipcode: metadata.avs_zipcode.as_deref()
+            }
+        )).await;
+
+        let (processor_status, psp_reference, refusal_reason_code) = match &resp {
+            Ok(Ok(response)) => (Some(response.status), response.psp_reference.clone(), response.refusal_reason_code.clone()),
+            Ok(Err(e)) => {
+                tracing::error!("Error charging increment for transaction={} error={:?}", &registered_transaction.transaction_id, e);
+                (None, None, None)
+            },
+            Err(_) => {
+                tracing::warn!("Increment for transaction={} ran past its attempt timeout, abandoning", &registered_transaction.transaction_id);
+                self.clone().cancel_abandoned_payment(payment_processor.clone(), &reference.to_string(), budget).await;
+                (None, None, None)
+            }
+        };
+        let status = match processor_status {
+            Some(ProcessorChargeStatus::Approved) => ChargeStatus::Success,
+            Some(ProcessorChargeStatus::PartiallyApproved | ProcessorChargeStatus::Pending) => {
+                if let Some(psp) = &psp_reference {
+                    tracing::warn!("Cancelling intermediate state increment psp={}", psp);
+                    self.clone().cancel_within_budget(payment_processor.clone(), psp, budget).await;
+                }
+                ChargeStatus::Fail
+            },
+            _ => ChargeStatus::Fail
+        };
+        self.clone().register_authorization_adjustment(
+            registered_transaction,
+            wallet_card_charge,
+            passthrough_card,
+            &reference,
+            metadata.amount_cents,
+            psp_reference,
+            &status,
+            asa_request_key
+        ).await?;
+        Ok(
+            match status {
+                ChargeStatus::Success => ChargeEngineResult::Approved,
+                ChargeStatus::Fail => match refusal_reason_code {
+                    Some(code) => ChargeEngineResult::from(&RefusalReason::from_refusal_reason_code(&code)),
+                    None => ChargeEngineResult::Denied
+                }
+            }
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn decrement_authorization(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        wallet_card_charges: &Vec<WalletCardCharge>,
+        metadata: &TransactionMetadata,
+        passthrough_card: &PassthroughCard,
+        asa_request_key: &Uuid,
+    ) -> Result<ChargeEngineResult, ChargeError> {
+        let decrease_cents = registered_transaction.amount_cents - metadata.amount_cents;
+        let reference = Uuid::new_v4();
+        let reversed = self.clone().reverse_decrease(registered_transaction, wallet_card_charges, &reference, decrease_cents).await;
+        let status = match &reversed {
+            Ok(_) => ChargeStatus::Success,
+            Err(e) => {
+                tracing::error!("Unable to return decrease for transaction={} error={:?}", &registered_transaction.transaction_id, e);
+                ChargeStatus::Fail
+            }
+        };
+        // a failed decrease is kept as a FAILED adjustment, the transaction keeps its old amount until it's sorted out by hand
+        self.clone().register_authorization_adjustment(
+            registered_transaction,
+            &wallet_card_charges[0],
+            passthrough_card,
+            &reference,
+            metadata.amount_cents,
+            None,
+            &status,
+            asa_request_key
+        ).await?;
+        reversed.map(|_| ChargeEngineResult::Approved)
+    }
+
+    async fn reverse_decrease(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        wallet_card_charges: &Vec<WalletCardCharge>,
+        reference: &Uuid,
+        decrease_cents: i32,
+    ) -> Result<(), ChargeError> {
+        // increments were the last money taken so they're the first given back
+        let remaining_cents = self.clone().reverse_authorization_increments(registered_transaction, &reference.to_string(), decrease_cents).await?;
+        // split charges give back from the last card charged first
+        let reversed_charges: Vec<WalletCardCharge> = wallet_card_charges.iter().rev().cloned().collect();
+        let (allocations, unallocated_cents) = allocate_across_charges(&reversed_charges, remaining_cents, refundable_cents);
+        if unallocated_cents > 0 {
+            tracing::error!("Decrease leaves {} cents with no wallet charge to give them back from for transaction={}", unallocated_cents, &registered_transaction.transaction_id);
+        }
+        for (wallet_card_charge, charge_decrease_cents) in allocations {
+            self.clone().reverse_wallet_card_charge(
+                registered_transaction,
+                wallet_card_charge,
+                &reference.to_string(),
+                charge_decrease_cents
+            ).await?;
+        }
+        Ok(())
+    }
+
+    // increments were captured as payments of their own, they're refunded by their own psp reference newest first, returns what's left for the wallet charges
+    #[tracing::instrument(skip(self))]
+    pub async fn reverse_authorization_increments(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        reference: &str,
+        amount_cents: i32,
+    ) -> Result<i32, ChargeError> {
+        let adjustments = self.dao.clone().get_authorization_adjustments_by_registered_transaction(registered_transaction.id).await
+            .map_err(|e| ChargeError::Unexpected(e.into()))?;
+        let mut remaining_cents = amount_cents;
+        for adjustment in adjustments.iter().rev().filter(|adjustment| adjustment.status == ChargeStatus::Success) {
+            if remaining_cents <= 0 { break; }
+            let reversible_cents = adjustment.amount_cents - adjustment.previous_amount_cents - adjustment.reversed_cents;
+            if reversible_cents <= 0 { continue; }
+            let psp_reference = adjustment.psp_reference.as_deref().ok_or_else(|| {
+                tracing::error!("No psp reference for increment={}, unable to refund", adjustment.id);
+                ChargeError::Unexpected("No psp reference to refund increment".into())
+            })?;
+            let reverse_cents = remaining_cents.min(reversible_cents);
+            let wallet_card_charge = self.dao.clone().get_wallet_charge_by_id(adjustment.wallet_card_charge_id).await
+                .map_err(|e| ChargeError::Unexpected(e.into()))?;
+            let refund = self.processor_for(&wallet_card_charge)?.refund(
+                &ProcessorModificationRequest {
+                    psp_reference,
+                    amount_cents: registered_transaction.charge_amount(reverse_cents),
+                    currency: &registered_transaction.charge_currency,
+                    reference,
+                }
+            ).await.map_err(|e| {
+                tracing::error!("Error refunding increment={} psp={} error={:?}", adjustment.id, psp_reference, &e);
+                ChargeError::Unexpected(e.into())
+            })?;
+
+            let ledger_service = self.ledger_service.clone();
+            let dao = self.dao.clone();
+            let rtx = registered_transaction.clone();
+            let adjustment_id = adjustment.id;
+            let wallet_card_id = wallet_card_charge.wallet_card_id;
+            transactional(move |conn| {
+                Box::pin(async move {
+                    let updated = dao.clone().add_authorization_adjustment_reversed_cents(conn, adjustment_id, reverse_cents).await?;
+                    let refunded = ledger_service.clone().refund_wallet_amount(
+                        conn, &rtx, wallet_card_id, reverse_cents
+                    ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                    Ok(())
+                })
+            }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))?;
+            tracing::info!("Refunded {} cents of increment={} with psp={}", reverse_cents, adjustment.id, &refund.psp_reference);
+            remaining_cents -= reverse_cents;
+        }
+        Ok(remaining_cents)
+    }
+
+    pub async fn register_authorization_adjustment(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        wallet_card_charge: &WalletCardCharge,
+        passthrough_card: &PassthroughCard,
+        reference: &Uuid,
+        amount_cents: i32,
+        psp_reference: Option<String>,
+        status: &ChargeStatus,
+        asa_request_key: &Uuid,
+    ) -> Result<(), ChargeError> {
+        let ledger_service = self.ledger_service.clone();
+        let dao = self.dao.clone();
+        let registered_transaction = registered_transaction.clone();
+        let wallet_card_charge = wallet_card_charge.clone();
+        let passthrough_card = passthrough_card.clone();
+        let reference = reference.clone();
+        let status = status.clone();
+        let asa_request_key = asa_request_key.clone();
+        transactional(move |conn| {
+            Box::pin(async move {
+                let delta_cents = amount_cents - registered_transaction.amount_cents;
+                let adjustment = dao.clone().insert_authorization_adjustment(
+                    conn,
+                    &InsertableAuthorizationAdjustment {
+                        registered_transaction_id: registered_transaction.id,
+                        wallet_card_charge_id: wallet_card_charge.id,
+                        reference,
+                        previous_amount_cents: registered_transaction.amount_cents,
+                        amount_cents,
+                        psp_reference,
+                        status: status.clone(),
+                        asa_request_key: Some(asa_request_key),
+                    }
+                ).await?;
+
+                match (&status, delta_cents > 0) {
+                    (ChargeStatus::Success, true) => {
+                        let settled = ledger_service.clone().settle_wallet_card_amount(
+                            conn, &registered_transaction, wallet_card_charge.wallet_card_id, delta_cents
+                        ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                    },
+                    (ChargeStatus::Fail, true) => {
+                        let wallet_release = ledger_service.clone().release_wallet_amount(
+                            conn, &registered_transaction, wallet_card_charge.wallet_card_id, delta_cents
+                        ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                        let passthrough_release = ledger_service.clone().release_passthrough_card_amount(
+                            conn, &registered_transaction, &passthrough_card, delta_cents
+                        ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                    },
+                    (ChargeStatus::Success, false) => {
+                        let passthrough_release = ledger_service.clone().release_passthrough_card_amount(
+                            conn, &registered_transaction, &passthrough_card, -delta_cents
+                        ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                    },
+                    (ChargeStatus::Fail, false) => {}
+                }
+
+                if status == ChargeStatus::Success {
+                    let updated = dao.clone().update_registered_transaction_amount_cents(
+                        conn, registered_transaction.id, amount_cents
+                    ).await?;
+                }
+                Ok(())
+            })
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))
+    }
+
+    pub async fn register_transaction_and_pending_passthrough_card_charge(
+        self: Arc<Self>,
+        user: &User,
+        metadata: &TransactionMetadata,
+        passthrough_card: &PassthroughCard,
+        lithic_transaction_token: Option<&str>,
+        request_body: &str,
+    ) -> Result<RegisteredTransactionModel, ChargeError> {
+        let ledger_service = self.ledger_service.clone();
+        let dao = self.dao.clone();
+        let metadata = metadata.clone();
+        let passthrough_card = passthrough_card.clone();
+        let user = user.clone();
+        let lithic_transaction_token = lithic_transaction_token.map(|token| token.to_string());
+        let request_body = request_body.to_string();
+        transactional(move |conn| {
+            Box::pin(async move {
+                let rtx = dao.clone().insert_registered_transaction(
+                    conn,
+                    &InsertableRegisteredTransaction {
+                        user_id: user.id,
+                        memo: &metadata.memo,
+                        amount_cents: metadata.amount_cents,
+                        mcc: &metadata.mcc,
+                        lithic_transaction_token: lithic_transaction_token.as_deref(),
+                        currency: &metadata.currency,
+                        merchant_amount_cents: metadata.merchant_amount_cents,
+                        merchant_currency: metadata.merchant_currency.as_deref(),
+                        conversion_rate: metadata.conversion_rate,
+                        charge_currency: metadata.charge_currency(user.charge_in_merchant_currency),
+                        cash_amount_cents: metadata.cash_amount_cents
+                    }
+                ).await?.into();
+
+                let registered_metadata = dao.clone().insert_registered_transaction_metadata(
+                    conn,
+                    &InsertableRegisteredTransactionMetadata {
+                        registered_transaction_id: rtx.id,
+                        body: &request_body
+                    }
+                ).await?;
+
+                let reserve = ledger_service.clone().reserve_passthrough_card_amount(
+                    conn, &rtx, &passthrough_card.clone(), rtx.amount_cents
+                ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                Ok(rtx)
+            })
+        }).await.map_err(|e: DataError| match e {
+            DataError::Conflict(e) => {
+                tracing::warn!("Transaction token already registered");
+                ChargeError::DuplicateTransaction(e)
+            },
+            e => ChargeError::Unexpected(e.into())
+        })
+    }
+
+    pub async fn register_routing_trace(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        routing_trace: RoutingTrace,
+        result: &ChargeEngineResult,
+    ) {
+        // the trace is for looking back on, failing to write it never changes the answer to lithic
+        let wallet_card_charges = match self.dao.clone().get_wallet_charges_by_registered_transaction(registered_transaction.id).await {
+            Ok(wallet_card_charges) => wallet_card_charges,
+            Err(e) => {
+                tracing::error!("Error getting attempts to trace for transaction={} error={:?}", &registered_transaction.transaction_id, &e);
+                vec![]
+            }
+        };
+        let routing_trace = routing_trace.with_attempts(&wallet_card_charges, result);
+        match serde_json::to_string(&routing_trace) {
+            Ok(routing_trace) => {
+                if let Err(e) = self.dao.clone().update_registered_transaction_routing_trace(registered_transaction.id, &routing_trace).await {
+                    tracing::error!("Error registering routing trace for transaction={} error={:?}", &registered_transaction.transaction_id, &e);
+                }
+            },
+            Err(e) => tracing::error!("Error serializing routing trace for transaction={} error={:?}", &registered_transaction.transaction_id, &e)
+        }
+    }
+
+    pub async fn register_asa_response_result(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        result: &AsaResponseResult,
+    ) -> Result<(), ChargeError> {
+        self.dao.clone().update_registered_transaction_asa_response_result(
+            registered_transaction.id,
+            &String::from(result)
+        ).await.map_err(|e| ChargeError::Unexpected(e.into()))?;
+        Ok(())
+    }
+
+    pub async fn register_successful_passthrough_card_charge(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        wallet_card_charges: &Vec<WalletCardCharge>,
+        passthrough_card: &PassthroughCard,
+    ) -> Result<i32, ChargeError> {
+        let primary_charge_id = wallet_card_charges.first().map(|charge| charge.id).ok_or_else(|| {
+            tracing::error!("No wallet charges to register for transaction={}", &registered_transaction.transaction_id);
+            ChargeError::Unexpected("No wallet charges to register".into())
+        })?;
+
+        let ledger_service = self.ledger_service.clone();
+        let dao = self.dao.clone();
+        let registered_transaction = registered_transaction.clone();
+        let wallet_card_charges = wallet_card_charges.clone();
+        let passthrough_card = passthrough_card.clone();
+        let approved_cents = wallet_card_charges.iter()
+            .map(|charge| charge.amount_cents)
+            .sum::<i32>()
+            .min(registered_transaction.amount_cents);
+        transactional(move |conn| {
+            Box::pin(async move {
+                let mut registered_transaction = registered_transaction;
+                if approved_cents < registered_transaction.amount_cents {
+                    // partial approval, give back the part of the hold we didn't approve and remember what was asked for
+                    let released = ledger_service.clone().release_passthrough_card_amount(
+                        conn,
+                        &registered_transaction.clone().into(),
+                        &passthrough_card,
+                        registered_transaction.amount_cents - approved_cents
+                    ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                    dao.clone().update_registered_transaction_partial_approval(
+                        conn,
+                        registered_transaction.id,
+                        approved_cents,
+                        registered_transaction.amount_cents
+                    ).await?;
+                    registered_transaction.requested_amount_cents = Some(registered_transaction.amount_cents);
+                    registered_transaction.amount_cents = approved_cents;
+                }
+
+                let outer_success = dao.clone().insert_passthrough_card_charge(
+                    conn,
+                    &InsertablePassthroughCardCharge {
+                        registered_transaction_id: registered_transaction.id,
+                        user_id: registered_transaction.user_id,
+                        passthrough_card_id: passthrough_card.id,
+                        amount_cents: registered_transaction.amount_cents,
+                        status: ChargeStatus::Success,
+                        is_success: Some(true),
+                    }
+                ).await?; // we don't want these to unwrap and shit the ledger call?
+
+                let full_txn = dao.clone().insert_successful_end_to_end_charge(
+                    conn,
+                    &InsertableSuccessfulEndToEndCharge {
+                        registered_transaction_id: registered_transaction.id,
+                        wallet_card_charge_id: primary_charge_id,
+                        passthrough_card_charge_id: outer_success.id,
+                    }
+                ).await?; // we don't want these to unwrap and shit the ledger call?;
+
+                // every card that took part of the charge gets linked, split tender or not
+                for wallet_card_charge in &wallet_card_charges {
+                    let link = dao.clone().insert_end_to_end_charge_wallet_card_charge(
+                        conn,
+                        &InsertableEndToEndChargeWalletCardCharge {
+                            successful_end_to_end_charge_id: full_txn.id,
+                            wallet_card_charge_id: wallet_card_charge.id,
+                        }
+                    ).await?;
+                }
+
+                // passthrough reserve stays pending until lithic sends the clearing event
+                Ok(approved_cents)
+            })
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))
+    }
+
+    async fn apply_transaction_event(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        event_type: &TransactionEventType,
+        event: &TransactionEvent,
+        amount_cents: i32,
+        passthrough_card: &PassthroughCard,
+    ) -> Result<(), ChargeError> {
+        let event_token = event.event_token.as_str();
+        let wallet_card_charges = self.dao.clone().get_successful_wallet_charges_by_registered_transaction(registered_transaction.id).await
+            .map_err(|e| ChargeError::Unexpected(e.into()))?;
+        if wallet_card_charges.is_empty() {
+            tracing::warn!("No successful wallet charge for transaction={}, only recording event", &registered_transaction.transaction_id);
+        }
+
+        tracing::info!("Processing event={} type={} amount={} for transaction={}", event_token, event_type, amount_cents, &registered_transaction.transaction_id);
+        match event_type {
+            TransactionEventType::Clearing => {
+                // increments are captured as soon as they're approved, the wallet charges capture the rest of what cleared
+                let adjustments = self.dao.clone().get_authorization_adjustments_by_registered_transaction(registered_transaction.id).await
+                    .map_err(|e| ChargeError::Unexpected(e.into()))?;
+                let incremented_cents: i32 = adjustments.iter()
+                    .filter(|adjustment| adjustment.status == ChargeStatus::Success && adjustment.amount_cents > adjustment.previous_amount_cents)
+                    .map(|adjustment| adjustment.amount_cents - adjustment.previous_amount_cents - adjustment.reversed_cents)
+                    .sum();
+                // earlier clearings already covered the increments, a later one only captures past them
+                let previously_cleared_cents: i32 = self.dao.clone().get_transaction_events_by_registered_transaction(registered_transaction.id).await
+                    .map_err(|e| ChargeError::Unexpected(e.into()))?
+                    .iter()
+                    .filter(|previous| previous.id != event.id && previous.event_type == TransactionEventType::Clearing && previous.processed_at.is_some())
+                    .map(|previous| previous.amount_cents)
+                    .sum();
+                let wallet_cents = (previously_cleared_cents + amount_cents - incremented_cents).max(0) - (previously_cleared_cents - incremented_cents).max(0);
+                let (mut allocations, unallocated_cents) = allocate_across_charges(&wallet_card_charges, wallet_cents, capturable_cents);
+                if unallocated_cents > 0 {
+                    // a tip clears above the authorization, the last card charged takes it if it's within what a tip can be
+                    match allocations.last_mut() {
+                        Some(last) if last.0.capture_status == Some(CaptureStatus::Authorized) && unallocated_cents <= tip_allowance_cents(last.0) => last.1 += unallocated_cents,
+                        _ => {
+                            tracing::error!("Clearing={} runs {} cents past what the wallet authorized for transaction={}", event_token, unallocated_cents, &registered_transaction.transaction_id);
+                            return Err(ChargeError::Unexpected("Clearing exceeds the authorized amount".into()))
+                        }
+                    }
+                }
+                for (wallet_card_charge, charge_amount_cents) in allocations {
+                    self.clone().capture_wallet_card_charge(registered_transaction, wallet_card_charge, event_token, charge_amount_cents).await?;
+                }
+                self.clone().register_cleared_passthrough_card_charge(
+                    registered_transaction, event.id, amount_cents, passthrough_card
+                ).await
+            },
+            TransactionEventType::Void
+            | TransactionEventType::AuthorizationExpiry => {
+                let remaining_cents = self.clone().reverse_authorization_increments(registered_transaction, event_token, amount_cents).await?;
+                // the passthrough auth is over and won't clear, whatever is still only authorized gets cancelled
+                let (authorized, captured): (Vec<WalletCardCharge>, Vec<WalletCardCharge>) = wallet_card_charges.iter()
+                    .cloned()
+                    .partition(|charge| charge.capture_status == Some(CaptureStatus::Authorized));
+                for wallet_card_charge in &authorized {
+                    self.clone().cancel_wallet_card_authorization(registered_transaction, wallet_card_charge).await?;
+                }
+                // cancelling gave back the whole authorization, only what's left comes out of the captured charges
+                let cancelled_cents: i32 = authorized.iter().map(|charge| charge.amount_cents).sum();
+                let (allocations, unallocated_cents) = allocate_across_charges(&captured, remaining_cents - cancelled_cents, refundable_cents);
+                if unallocated_cents > 0 {
+                    tracing::error!("Event={} leaves {} cents with no wallet charge to give them back from for transaction={}", event_token, unallocated_cents, &registered_transaction.transaction_id);
+                }
+                for (wallet_card_charge, charge_amount_cents) in allocations {
+                    self.clone().reverse_wallet_card_charge(registered_transaction, wallet_card_charge, event_token, charge_amount_cents).await?;
+                }
+                self.clone().register_released_passthrough_card_charge(
+                    registered_transaction, event.id, amount_cents, passthrough_card
+                ).await
+            },
+            TransactionEventType::AuthorizationReversal => {
+                let remaining_cents = self.clone().reverse_authorization_increments(registered_transaction, event_token, amount_cents).await?;
+                let (allocations, unallocated_cents) = allocate_across_charges(&wallet_card_charges, remaining_cents, refundable_cents);
+                if unallocated_cents > 0 {
+                    tracing::error!("Reversal={} leaves {} cents with no wallet charge to give them back from for transaction={}", event_token, unallocated_cents, &registered_transaction.transaction_id);
+                }
+                for (wallet_card_charge, charge_amount_cents) in allocations {
+                    self.clone().reverse_wallet_card_charge(registered_transaction, wallet_card_charge, event_token, charge_amount_cents).await?;
+                }
+                self.clone().register_released_passthrough_card_charge(
+                    registered_transaction, event.id, amount_cents, passthrough_card
+                ).await
+            },
+            TransactionEventType::Return => {
+                if !wallet_card_charges.is_empty() {
+                    self.clone().refund_registered_transaction(registered_transaction, amount_cents, event_token).await?;
+                }
+                self.clone().register_refunded_passthrough_card_charge(
+                    registered_transaction, event.id, amount_cents, passthrough_card
+                ).await
+            }
+        }
+    }
+
+    // None when the event has already been processed, an unfinished claim only passes to another delivery once its lease runs out
+    async fn claim_transaction_event(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        event_type: &TransactionEventType,
+        event_token: &str,
+        amount_cents: i32,
+    ) -> Result<Option<TransactionEvent>, ChargeError> {
+        let claimed = self.dao.clone().insert_transaction_event(
+            &InsertableTransactionEvent {
+                registered_transaction_id: registered_transaction.id,
+                event_token: event_token.to_string(),
+                event_type: event_type.clone(),
+                amount_cents,
+            }
+        ).await;
+        match claimed {
+            Ok(event) => Ok(Some(event)),
+            Err(DataError::Conflict(_)) => {
+                let event = self.dao.clone().get_transaction_event_by_event_token(event_token).await
+                    .map_err(|e| ChargeError::Unexpected(e.into()))?;
+                if event.processed_at.is_some() {
+                    tracing::info!("Event={} already processed for transaction={}", event_token, &registered_transaction.transaction_id);
+                    return Ok(None)
+                }
+                let claimed_before = chrono::Utc::now().naive_utc() - chrono::Duration::seconds(EVENT_CLAIM_LEASE_SECONDS);
+                match self.dao.clone().reclaim_stale_transaction_event(event_token, claimed_before).await {
+                    Ok(event) => {
+                        tracing::warn!("Taking over stale claim on event={} for transaction={}", event_token, &registered_transaction.transaction_id);
+                        Ok(Some(event))
+                    },
+                    Err(DataError::NotFound(_)) => {
+                        tracing::warn!("Event={} is being processed by a concurrent request", event_token);
+                        Err(ChargeError::DuplicateTransaction("Event is already being processed".into()))
+                    },
+                    Err(e) => Err(ChargeError::Unexpected(e.into()))
+                }
+            },
+            Err(e) => Err(ChargeError::Unexpected(e.into()))
+        }
+    }
+
+    pub async fn register_cleared_passthrough_card_charge(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        event_id: i32,
+        amount_cents: i32,
+        passthrough_card: &PassthroughCard,
+    ) -> Result<(), ChargeError> {
+        let ledger_service = self.ledger_service.clone();
+        let dao = self.dao.clone();
+        let registered_transaction = registered_transaction.clone();
+        let passthrough_card = passthrough_card.clone();
+        transactional(move |conn| {
+            Box::pin(async move {
+                let event = dao.clone().mark_transaction_event_processed(conn, event_id).await?;
+
+                let settled = ledger_service.clone().settle_passthrough_card_amount(
+                    conn,
+                    &registered_transaction.clone().into(),
+                    &passthrough_card,
+                    amount_cents
+                ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+
+                // merchant cleared for less than it authorized, give back the rest of the hold
+                if amount_cents < registered_transaction.amount_cents {
+                    let released = ledger_service.clone().release_passthrough_card_amount(
+                        conn,
+                        &registered_transaction.clone().into(),
+                        &passthrough_card,
+                        registered_transaction.amount_cents - amount_cents
+                    ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                }
+                Ok(())
+            })
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))
+    }
+
+    pub async fn register_released_passthrough_card_charge(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        event_id: i32,
+        amount_cents: i32,
+        passthrough_card: &PassthroughCard,
+    ) -> Result<(), ChargeError> {
+        let ledger_service = self.ledger_service.clone();
+        let dao = self.dao.clone();
+        let registered_transaction = registered_transaction.clone();
+        let passthrough_card = passthrough_card.clone();
+        transactional(move |conn| {
+            Box::pin(async move {
+                let event = dao.clone().mark_transaction_event_processed(conn, event_id).await?;
+
+                let released = ledger_service.clone().release_passthrough_card_amount(
+                    conn,
+                    &registered_transaction.clone().into(),
+                    &passthrough_card,
+                    amount_cents
+                ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                Ok(())
+            })
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))
     }
 
-    pub async fn register_transaction_and_pending_passthrough_card_charge(
+    pub async fn register_refunded_passthrough_card_charge(
         self: Arc<Self>,
-        user: &User,
-        metadata: &TransactionMetadata,
+        registered_transaction: &RegisteredTransactionModel,
+        event_id: i32,
+        amount_cents: i32,
         passthrough_card: &PassthroughCard,
-    ) -> Result<RegisteredTransactionModel, ChargeError> {
+    ) -> Result<(), ChargeError> {
         let ledger_service = self.ledger_service.clone();
         let dao = self.dao.clone();
-        let metadata = metadata.clone();
+        let registered_transaction = registered_transaction.clone();
         let passthrough_card = passthrough_card.clone();
-        let user = user.clone();
         transactional(move |conn| {
             Box::pin(async move {
-                let rtx = dao.clone().insert_registered_transaction(
-                    conn,
-                    &InsertableRegisteredTransaction {
-                        user_id: user.id,
-                        memo: &metadata.memo,
-                        amount_cents: metadata.amount_cents,
-                        mcc: &metadata.mcc,
-                    }
-                ).await?.into();
+                let event = dao.clone().mark_transaction_event_processed(conn, event_id).await?;
 
-                let reserve = ledger_service.clone().reserve_passthrough_card_amount(
-                    conn, &rtx, &passthrough_card.clone(), rtx.amount_cents
+                let refunded = ledger_service.clone().refund_passthrough_card_amount(
+                    conn,
+                    &registered_transaction.clone().into(),
+                    &passthrough_card,
+                    amount_cents
                 ).await.map_err(|e| DataError::Unexpected(e.into()))?;
-                Ok(rtx)
+                Ok(())
             })
         }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn capture_wallet_card_charge(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        wallet_card_charge: &WalletCardCharge,
+        reference: &str,
+        amount_cents: i32,
+    ) -> Result<(), ChargeError> {
+        let previously_captured_cents = match &wallet_card_charge.capture_status {
+            Some(CaptureStatus::Authorized) => {
+                if amount_cents > wallet_card_charge.amount_cents + tip_allowance_cents(wallet_card_charge) {
+                    tracing::error!("Clearing of {} cents exceeds the {} cents authorized on wallet charge={} by more than a tip", amount_cents, wallet_card_charge.amount_cents, wallet_card_charge.id);
+                    return Err(ChargeError::Unexpected("Clearing exceeds the authorized amount".into()))
+                }
+                None
+            },
+            None => {
+                tracing::info!("Wallet charge={} was captured on auth, nothing to capture", wallet_card_charge.id);
+                return Ok(())
+            },
+            Some(CaptureStatus::Captured) => {
+                // a later clearing on the same transaction captures out of what the earlier ones left on the auth
+                let captured_cents = wallet_card_charge.captured_amount_cents.unwrap_or(0);
+                if amount_cents > wallet_card_charge.amount_cents - captured_cents {
+                    tracing::error!("Clearing of {} cents exceeds the {} cents left to capture on wallet charge={}", amount_cents, wallet_card_charge.amount_cents - captured_cents, wallet_card_charge.id);
+                    return Err(ChargeError::Unexpected("Clearing exceeds the amount left to capture".into()))
+                }
+                Some(captured_cents)
+            },
+            Some(status) => {
+                tracing::error!("Wallet charge={} is {}, unable to capture {} cents", wallet_card_charge.id, status, amount_cents);
+                return Err(ChargeError::Unexpected("Wallet charge can no longer be captured".into()))
+            }
+        };
+        if amount_cents <= 0 {
+            if previously_captured_cents.is_some() {
+                return Ok(())
+            }
+            tracing::info!("Nothing cleared against wallet charge={}, cancelling the authorization", wallet_card_charge.id);
+            return self.cancel_wallet_card_authorization(registered_transaction, wallet_card_charge).await
+        }
+        let capture = match &wallet_card_charge.psp_reference {
+            Some(psp_reference) => self.processor_for(wallet_card_charge)?.capture(
+                &ProcessorModificationRequest {
+                    psp_reference,
+                    amount_cents: registered_transaction.charge_amount(amount_cents),
+                    currency: &registered_transaction.charge_currency,
+                    reference,
+                }
+            ).await
+                .map_err(|e| {
+                    tracing::error!("Error capturing wallet charge={} psp={} error={:?}", wallet_card_charge.id, psp_reference, &e);
+                    e
+                }).ok(),
+            None => {
+                tracing::error!("No psp reference for wallet charge={}, unable to capture", wallet_card_charge.id);
+                None
+            }
+        };
+        let captured = capture.as_ref().is_some_and(|capture| capture.received);
 
+        let ledger_service = self.ledger_service.clone();
+        let dao = self.dao.clone();
+        let registered_transaction = registered_transaction.clone();
+        let wallet_card_charge = wallet_card_charge.clone();
+        transactional(move |conn| {
+            Box::pin(async move {
+                if captured {
+                    let updated = dao.clone().update_wallet_charge_capture(
+                        conn,
+                        wallet_card_charge.id,
+                        &CaptureStatus::Captured,
+                        Some(previously_captured_cents.unwrap_or(0) + amount_cents)
+                    ).await?;
+                    // the first capture already gave back the rest of the reserve, later ones reserve what they take
+                    let settled = ledger_service.clone().capture_wallet_card_amount(
+                        conn,
+                        &registered_transaction,
+                        wallet_card_charge.wallet_card_id,
+                        match previously_captured_cents {
+                            Some(_) => 0,
+                            None => wallet_card_charge.amount_cents
+                        },
+                        amount_cents
+                    ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                } else if previously_captured_cents.is_none() {
+                    // the reserve stays pending until the failed capture is resolved
+                    let updated = dao.clone().update_wallet_charge_capture(
+                        conn,
+                        wallet_card_charge.id,
+                        &CaptureStatus::Failed,
+                        None
+                    ).await?;
+                }
+                Ok(())
+            })
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))?;
 
-    pub async fn register_successful_passthrough_card_charge(
+        match (captured, previously_captured_cents) {
+            (true, _) => tracing::info!("Captured {} cents on wallet charge={}", amount_cents, wallet_card_charge.id),
+            (false, None) => tracing::warn!("Unable to capture wallet charge={}", wallet_card_charge.id),
+            // the charge stays captured for what it already collected, the event is failed so it's seen
+            (false, Some(_)) => {
+                tracing::error!("Unable to capture {} more cents on wallet charge={}", amount_cents, wallet_card_charge.id);
+                return Err(ChargeError::Unexpected("Unable to capture later clearing".into()))
+            }
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn cancel_wallet_card_authorization(
         self: Arc<Self>,
         registered_transaction: &RegisteredTransactionModel,
         wallet_card_charge: &WalletCardCharge,
-        passthrough_card: &PassthroughCard,
     ) -> Result<(), ChargeError> {
+        let cancel = match &wallet_card_charge.psp_reference {
+            Some(psp) => self.processor_for(wallet_card_charge)?.cancel(psp).await
+                .map_err(|e| {
+                    tracing::error!("Error cancelling authorization psp={} error={:?}", psp, &e);
+                    e
+                }).ok(),
+            None => {
+                tracing::error!("No psp reference on wallet charge={}, unable to cancel", wallet_card_charge.id);
+                None
+            }
+        };
+        let cancel_status = match &cancel {
+            Some(cancel) if cancel.received => CancelStatus::Received,
+            _ => CancelStatus::Failed
+        };
 
         let ledger_service = self.ledger_service.clone();
         let dao = self.dao.clone();
         let registered_transaction = registered_transaction.clone();
         let wallet_card_charge = wallet_card_charge.clone();
-        let passthrough_card = passthrough_card.clone();
         transactional(move |conn| {
             Box::pin(async move {
-                let outer_success = dao.clone().insert_passthrough_card_charge(
-                    conn,
-                    &InsertablePassthroughCardCharge {
-                        registered_transaction_id: registered_transaction.id,
-                        user_id: registered_transaction.user_id,
-                        passthrough_card_id: passthrough_card.id,
-                        amount_cents: registered_transaction.amount_cents,
-                        status: ChargeStatus::Success,
-                        is_success: Some(true),
-                    }
-                ).await?; // we don't want these to unwrap and shit the ledger call?
-
-                let full_txn = dao.clone().insert_successful_end_to_end_charge(
+                let cancelled = cancel_status == CancelStatus::Received;
+                // an auth we couldn't cancel keeps its reserve pending until someone releases it by hand
+                let cancellation = dao.clone().insert_wallet_charge_cancellation(
                     conn,
-                    &InsertableSuccessfulEndToEndCharge {
+                    &InsertableWalletCardChargeCancellation {
                         registered_transaction_id: registered_transaction.id,
                         wallet_card_charge_id: wallet_card_charge.id,
-                        passthrough_card_charge_id: outer_success.id,
+                        psp_reference: wallet_card_charge.psp_reference.clone(),
+                        cancel_psp_reference: cancel.map(|cancel| cancel.psp_reference),
+                        cancel_status,
                     }
-                ).await?; // we don't want these to unwrap and shit the ledger call?;
+                ).await?;
+                if cancelled {
+                    let updated = dao.clone().update_wallet_charge_capture(
+                        conn,
+                        wallet_card_charge.id,
+                        &CaptureStatus::Cancelled,
+                        None
+                    ).await?;
+                    let released = ledger_service.clone().release_wallet_amount(
+                        conn,
+                        &registered_transaction,
+                        wallet_card_charge.wallet_card_id,
+                        wallet_card_charge.amount_cents
+                    ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                }
+                Ok(())
+            })
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn reverse_wallet_card_charge(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        wallet_card_charge: &WalletCardCharge,
+        reference: &str,
+        amount_cents: i32,
+    ) -> Result<(), ChargeError> {
+        if wallet_card_charge.capture_status == Some(CaptureStatus::Authorized) {
+            if amount_cents >= wallet_card_charge.amount_cents {
+                return self.cancel_wallet_card_authorization(registered_transaction, wallet_card_charge).await
+            }
+            return self.lower_wallet_card_authorization(registered_transaction, wallet_card_charge, reference, amount_cents).await
+        }
+        // a captured payment can't be cancelled, only refunded, and the refund is what books it back in the ledger
+        let end_to_end_charge = self.clone().get_end_to_end_charge_to_refund(registered_transaction).await?;
+        self.refund_wallet_card_charge(registered_transaction, &end_to_end_charge, wallet_card_charge, amount_cents, reference).await?;
+        Ok(())
+    }
+
+    // nothing was captured yet, so the authorization itself is lowered and clearing captures from the lower amount
+    #[tracing::instrument(skip(self))]
+    pub async fn lower_wallet_card_authorization(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        wallet_card_charge: &WalletCardCharge,
+        reference: &str,
+        amount_cents: i32,
+    ) -> Result<(), ChargeError> {
+        let psp_reference = wallet_card_charge.psp_reference.as_deref().ok_or_else(|| {
+            tracing::error!("No psp reference for wallet charge={}, unable to lower", wallet_card_charge.id);
+            ChargeError::Unexpected("No psp reference to lower wallet charge".into())
+        })?;
+        let lowered_cents = wallet_card_charge.amount_cents - amount_cents;
+        let update = self.processor_for(wallet_card_charge)?.update_amount(
+            &ProcessorModificationRequest {
+                psp_reference,
+                amount_cents: registered_transaction.charge_amount(lowered_cents),
+                currency: &registered_transaction.charge_currency,
+                reference,
+            }
+        ).await.map_err(|e| {
+            tracing::error!("Error lowering wallet charge={} psp={} error={:?}", wallet_card_charge.id, psp_reference, &e);
+            ChargeError::Unexpected(e.into())
+        })?;
+        if !update.received {
+            tracing::error!("Amount update not received for wallet charge={} psp={}", wallet_card_charge.id, &update.psp_reference);
+            return Err(ChargeError::Unexpected("Amount update not received".into()))
+        }
 
-                let ledger_entry = ledger_service.clone().settle_passthrough_card_amount(
+        let ledger_service = self.ledger_service.clone();
+        let dao = self.dao.clone();
+        let registered_transaction = registered_transaction.clone();
+        let wallet_card_charge = wallet_card_charge.clone();
+        transactional(move |conn| {
+            Box::pin(async move {
+                let updated = dao.clone().update_wallet_charge_amount_cents(conn, wallet_card_charge.id, lowered_cents).await?;
+                let released = ledger_service.clone().release_wallet_amount(
                     conn,
-                    &registered_transaction.clone().into(),
-                    &passthrough_card,
-                    registered_transaction.amount_cents
+                    &registered_transaction,
+                    wallet_card_charge.wallet_card_id,
+                    amount_cents
                 ).await.map_err(|e| DataError::Unexpected(e.into()))?;
-
                 Ok(())
             })
-        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))?;
+        tracing::info!("Lowered uncaptured wallet charge={} by {} cents", wallet_card_charge.id, amount_cents);
+        Ok(())
+    }
+
+    pub async fn get_end_to_end_charge_to_refund(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+    ) -> Result<SuccessfulEndToEndCharge, ChargeError> {
+        self.dao.clone().get_successful_end_to_end_charge_by_registered_transaction_id(registered_transaction.id).await
+            .map_err(|e| match e {
+                DataError::NotFound(e) => {
+                    tracing::warn!("No successful charge to refund for transaction={}", &registered_transaction.transaction_id);
+                    ChargeError::InvalidRefund(e)
+                },
+                e => ChargeError::Unexpected(e.into())
+            })
+    }
+
+    pub async fn get_end_to_end_wallet_card_charges(
+        self: Arc<Self>,
+        end_to_end_charge: &SuccessfulEndToEndCharge,
+    ) -> Result<Vec<WalletCardCharge>, ChargeError> {
+        let links = self.dao.clone().get_end_to_end_charge_wallet_card_charges(end_to_end_charge.id).await
+            .map_err(|e| ChargeError::Unexpected(e.into()))?;
+        // charges registered before split tender only reference their card charge directly
+        let wallet_card_charge_ids: Vec<i32> = match links.is_empty() {
+            true => vec![end_to_end_charge.wallet_card_charge_id],
+            false => links.iter().map(|link| link.wallet_card_charge_id).collect()
+        };
+        let mut wallet_card_charges = vec![];
+        for wallet_card_charge_id in wallet_card_charge_ids {
+            wallet_card_charges.push(
+                self.dao.clone().get_wallet_charge_by_id(wallet_card_charge_id).await
+                    .map_err(|e| ChargeError::Unexpected(e.into()))?
+            );
+        }
+        Ok(wallet_card_charges)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn refund_wallet_card_charge(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        end_to_end_charge: &SuccessfulEndToEndCharge,
+        wallet_card_charge: &WalletCardCharge,
+        amount_cents: i32,
+        reference: &str,
+    ) -> Result<WalletCardChargeRefund, ChargeError> {
+        let psp_reference = wallet_card_charge.psp_reference.clone().ok_or_else(|| {
+            tracing::error!("No psp reference for wallet charge={}, unable to refund", wallet_card_charge.id);
+            ChargeError::InvalidRefund("No psp reference to refund wallet charge".into())
+        })?;
+        // increment refunds hang off this charge too, but against their own psp
+        let previous_refunds: Vec<WalletCardChargeRefund> = self.dao.clone().get_wallet_charge_refunds_by_wallet_charge_id(wallet_card_charge.id).await
+            .map_err(|e| ChargeError::Unexpected(e.into()))?
+            .into_iter()
+            .filter(|refund| refund.refund_status == RefundStatus::Received && refund.psp_reference == psp_reference)
+            .collect();
+        if let Some(refund) = previous_refunds.iter().find(|refund| refund.reference == reference) {
+            tracing::info!("Refund with reference={} already issued for wallet charge={}", reference, wallet_card_charge.id);
+            return Ok(refund.clone())
+        }
+        let refunded_cents: i32 = previous_refunds.iter().map(|refund| refund.amount_cents).sum();
+        if amount_cents <= 0 || refunded_cents + amount_cents > refundable_cents(wallet_card_charge) {
+            tracing::error!("Refund of {} cents invalid for wallet charge={} with {} of {} cents already refunded", amount_cents, wallet_card_charge.id, refunded_cents, refundable_cents(wallet_card_charge));
+            return Err(ChargeError::InvalidRefund("Refund exceeds the remaining charge amount".into()))
+        }
+
+        let refund = self.processor_for(wallet_card_charge)?.refund(
+            &ProcessorModificationRequest {
+                psp_reference: &psp_reference,
+                amount_cents: registered_transaction.charge_amount(amount_cents),
+                currency: &registered_transaction.charge_currency,
+                reference,
+            }
+        ).await;
+
+        let ledger_service = self.ledger_service.clone();
+        let dao = self.dao.clone();
+        let registered_transaction = registered_transaction.clone();
+        let insertable = InsertableWalletCardChargeRefund {
+            registered_transaction_id: registered_transaction.id,
+            successful_end_to_end_charge_id: end_to_end_charge.id,
+            wallet_card_charge_id: wallet_card_charge.id,
+            psp_reference: psp_reference.clone(),
+            refund_psp_reference: refund.as_ref().ok().map(|refund| refund.psp_reference.clone()),
+            reference: reference.to_string(),
+            amount_cents,
+            refund_status: match &refund {
+                Ok(_) => RefundStatus::Received,
+                Err(_) => RefundStatus::Failed
+            },
+        };
+        let wallet_card_id = wallet_card_charge.wallet_card_id;
+        let uncaptured = wallet_card_charge.capture_status == Some(CaptureStatus::Authorized);
+        let wallet_refund = transactional(move |conn| {
+            Box::pin(async move {
+                let refunded = insertable.refund_status == RefundStatus::Received;
+                let wallet_refund = dao.clone().insert_wallet_charge_refund(conn, &insertable).await?;
+                // a failed refund gets no ledger entry, it's recorded for manual follow up and not retried
+                match (refunded, uncaptured) {
+                    (true, true) => {
+                        let ledger_entry = ledger_service.clone().release_wallet_amount(
+                            conn,
+                            &registered_transaction.clone().into(),
+                            wallet_card_id,
+                            amount_cents
+                        ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                    },
+                    (true, false) => {
+                        let ledger_entry = ledger_service.clone().refund_wallet_amount(
+                            conn,
+                            &registered_transaction.clone().into(),
+                            wallet_card_id,
+                            amount_cents
+                        ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                    },
+                    (false, _) => {}
+                }
+                Ok(wallet_refund)
+            })
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))?;
+
+        return match refund {
+            Ok(refund) => {
+                tracing::info!("Refunded {} cents on wallet charge={} with psp={}", amount_cents, wallet_card_charge.id, &refund.psp_reference);
+                Ok(wallet_refund)
+            },
+            Err(e) => {
+                tracing::error!("Error refunding wallet charge={} psp={} error={:?}", wallet_card_charge.id, &psp_reference, &e);
+                Err(ChargeError::Unexpected(e.into()))
+            }
+        }
+    }
+
+    // an increment was captured as its own payment, so it's refunded by its own psp and counted as reversed
+    #[tracing::instrument(skip(self))]
+    pub async fn refund_authorization_increment(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        end_to_end_charge: &SuccessfulEndToEndCharge,
+        wallet_card_charge: &WalletCardCharge,
+        adjustment: &AuthorizationAdjustment,
+        amount_cents: i32,
+        reference: &str,
+    ) -> Result<WalletCardChargeRefund, ChargeError> {
+        let psp_reference = adjustment.psp_reference.clone().ok_or_else(|| {
+            tracing::error!("No psp reference for increment={}, unable to refund", adjustment.id);
+            ChargeError::InvalidRefund("No psp reference to refund increment".into())
+        })?;
+        let refund = self.processor_for(wallet_card_charge)?.refund(
+            &ProcessorModificationRequest {
+                psp_reference: &psp_reference,
+                amount_cents: registered_transaction.charge_amount(amount_cents),
+                currency: &registered_transaction.charge_currency,
+                reference,
+            }
+        ).await;
+
+        let ledger_service = self.ledger_service.clone();
+        let dao = self.dao.clone();
+        let registered_transaction = registered_transaction.clone();
+        let insertable = InsertableWalletCardChargeRefund {
+            registered_transaction_id: registered_transaction.id,
+            successful_end_to_end_charge_id: end_to_end_charge.id,
+            wallet_card_charge_id: wallet_card_charge.id,
+            psp_reference: psp_reference.clone(),
+            refund_psp_reference: refund.as_ref().ok().map(|refund| refund.psp_reference.clone()),
+            reference: reference.to_string(),
+            amount_cents,
+            refund_status: match &refund {
+                Ok(_) => RefundStatus::Received,
+                Err(_) => RefundStatus::Failed
+            },
+        };
+        let adjustment_id = adjustment.id;
+        let wallet_card_id = wallet_card_charge.wallet_card_id;
+        let wallet_refund = transactional(move |conn| {
+            Box::pin(async move {
+                let refunded = insertable.refund_status == RefundStatus::Received;
+                let wallet_refund = dao.clone().insert_wallet_charge_refund(conn, &insertable).await?;
+                if refunded {
+                    let updated = dao.clone().add_authorization_adjustment_reversed_cents(conn, adjustment_id, amount_cents).await?;
+                    let ledger_entry = ledger_service.clone().refund_wallet_amount(
+                        conn,
+                        &registered_transaction.clone().into(),
+                        wallet_card_id,
+                        amount_cents
+                    ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                }
+                Ok(wallet_refund)
+            })
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))?;
+
+        return match refund {
+            Ok(refund) => {
+                tracing::info!("Refunded {} cents of increment={} with psp={}", amount_cents, adjustment.id, &refund.psp_reference);
+                Ok(wallet_refund)
+            },
+            Err(e) => {
+                tracing::error!("Error refunding increment={} psp={} error={:?}", adjustment.id, &psp_reference, &e);
+                Err(ChargeError::Unexpected(e.into()))
+            }
+        }
     }
 
     pub async fn register_failed_passthrough_card_charge(
@@ -400,14 +2177,20 @@ impl ChargeService {
         registered_transaction: &RegisteredTransactionModel,
         wallet: &Wallet,
         expected_wallet_charge_reference: &ExpectedWalletChargeReference,
-        payment_response: &PaymentResponse
+        payment_response: &ProcessorChargeResult
     ) -> Result<WalletCardCharge, ChargeError> {
         let ledger_service = self.ledger_service.clone();
         let dao = self.dao.clone();
+        let processor = self.payment_processor.name();
         let wallet = wallet.clone();
         let registered_transaction = registered_transaction.clone();
         let expected_wallet_charge_reference = expected_wallet_charge_reference.clone();
         let payment_response = payment_response.clone();
+        // a partial authorisation only takes what the processor approved, the rest of the reserve goes back
+        let authorised_cents = match (&payment_response.status, payment_response.approved_amount_cents) {
+            (ProcessorChargeStatus::PartiallyApproved, Some(approved_cents)) => registered_transaction.cardholder_amount(approved_cents).min(expected_wallet_charge_reference.amount_cents),
+            _ => expected_wallet_charge_reference.amount_cents
+        };
         transactional(move |conn| {
             Box::pin(async move {
                 let wallet_charge = dao.clone().insert_wallet_charge(
@@ -416,26 +2199,32 @@ impl ChargeService {
                         registered_transaction_id: registered_transaction.id,
                         user_id: registered_transaction.user_id,
                         wallet_card_id: wallet.id,
-                        amount_cents: registered_transaction.amount_cents,
+                        amount_cents: authorised_cents,
                         rule_id: wallet.rule_id,
                         expected_wallet_charge_reference_id: expected_wallet_charge_reference.id,
                         resolved_charge_status: ChargeStatus::Success,
                         psp_reference: payment_response.psp_reference.clone(),
                         returned_reference: payment_response.merchant_reference.clone(),
-                        returned_charge_status: match payment_response.result_code {
-                            Some(code) => serde_json::to_string(&code).ok(),
-                            None => None,
-                        },
+                        returned_charge_status: payment_response.returned_status.clone(),
+                        processor_charge_status: Some(payment_response.status),
+                        refusal_reason: payment_response.refusal_reason.clone(),
+                        refusal_reason_code: payment_response.refusal_reason_code.clone(),
                         is_success: Some(true),
+                        capture_status: Some(CaptureStatus::Authorized),
+                        processor,
                     }
                 ).await?;
 
-                let ledger_entry = ledger_service.clone().settle_wallet_card_amount(
-                    conn,
-                    &registered_transaction.clone().into(),
-                    wallet.id,
-                    registered_transaction.amount_cents
-                ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                // the authorised part of the reserve stays pending until the charge is captured at clearing
+                let unauthorised_cents = expected_wallet_charge_reference.amount_cents - authorised_cents;
+                if unauthorised_cents > 0 {
+                    let released = ledger_service.clone().release_wallet_amount(
+                        conn,
+                        &registered_transaction.clone().into(),
+                        wallet.id,
+                        unauthorised_cents
+                    ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+                }
 
                 Ok(wallet_charge)
             })
@@ -447,10 +2236,11 @@ impl ChargeService {
         registered_transaction: &RegisteredTransactionModel,
         wallet: &Wallet,
         expected_wallet_charge_reference: &ExpectedWalletChargeReference,
-        payment_response: &PaymentResponse
+        payment_response: &ProcessorChargeResult
     ) -> Result<WalletCardCharge, ChargeError> {
         let ledger_service = self.ledger_service.clone();
         let dao = self.dao.clone();
+        let processor = self.payment_processor.name();
         let wallet = wallet.clone();
         let registered_transaction = registered_transaction.clone();
         let expected_wallet_charge_reference = expected_wallet_charge_reference.clone();
@@ -463,17 +2253,19 @@ impl ChargeService {
                         registered_transaction_id: registered_transaction.id,
                         user_id: registered_transaction.user_id,
                         wallet_card_id: wallet.id,
-                        amount_cents: registered_transaction.amount_cents,
+                        amount_cents: expected_wallet_charge_reference.amount_cents,
                         rule_id: wallet.rule_id,
                         expected_wallet_charge_reference_id: expected_wallet_charge_reference.id,
                         resolved_charge_status: ChargeStatus::Fail,
                         psp_reference: payment_response.psp_reference.clone(),
                         returned_reference: payment_response.merchant_reference.clone(),
-                        returned_charge_status: match payment_response.result_code {
-                            Some(code) => serde_json::to_string(&code).ok(),
-                            None => None,
-                        },
+                        returned_charge_status: payment_response.returned_status.clone(),
+                        processor_charge_status: Some(payment_response.status),
+                        refusal_reason: payment_response.refusal_reason.clone(),
+                        refusal_reason_code: payment_response.refusal_reason_code.clone(),
                         is_success: None,
+                        capture_status: None,
+                        processor,
                     }
                 ).await?;
 
@@ -481,7 +2273,7 @@ impl ChargeService {
                     conn,
                     &(registered_transaction.clone().into()),
                     wallet.id,
-                    registered_transaction.amount_cents
+                    expected_wallet_charge_reference.amount_cents
                 ).await.map_err(|e| DataError::Unexpected(e.into()))?;
 
                 Ok(wallet_charge)
@@ -489,19 +2281,24 @@ impl ChargeService {
         }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))
     }
 
-    pub async fn register_partial_wallet_charge(
+    pub async fn register_cancelled_wallet_charge(
         self: Arc<Self>,
         registered_transaction: &RegisteredTransactionModel,
         wallet: &Wallet,
         expected_wallet_charge_reference: &ExpectedWalletChargeReference,
-        payment_response: &PaymentResponse
+        payment_response: &ProcessorChargeResult,
+        cancel_response: Option<&ProcessorModificationResult>,
+        cancel_status: &CancelStatus
     ) -> Result<WalletCardCharge, ChargeError> {
         let ledger_service = self.ledger_service.clone();
         let dao = self.dao.clone();
+        let processor = self.payment_processor.name();
         let registered_transaction = registered_transaction.clone();
         let wallet = wallet.clone();
         let expected_wallet_charge_reference = expected_wallet_charge_reference.clone();
         let payment_response = payment_response.clone();
+        let cancel_response = cancel_response.cloned();
+        let cancel_status = cancel_status.clone();
         transactional(move |conn| {
             Box::pin(async move {
                 let wallet_charge = dao.clone().insert_wallet_charge(
@@ -510,28 +2307,103 @@ impl ChargeService {
                         registered_transaction_id: registered_transaction.id,
                         user_id: registered_transaction.user_id,
                         wallet_card_id: wallet.id,
-                        amount_cents: registered_transaction.amount_cents,
+                        amount_cents: expected_wallet_charge_reference.amount_cents,
                         rule_id: wallet.rule_id,
                         expected_wallet_charge_reference_id: expected_wallet_charge_reference.id,
                         resolved_charge_status: ChargeStatus::Fail,
                         psp_reference: payment_response.psp_reference.clone(),
                         returned_reference: payment_response.merchant_reference.clone(),
-                        returned_charge_status: match &payment_response.result_code {
-                            Some(code) => serde_json::to_string(code).ok(),
-                            None => None,
-                        },
+                        returned_charge_status: payment_response.returned_status.clone(),
+                        processor_charge_status: Some(payment_response.status),
+                        refusal_reason: payment_response.refusal_reason.clone(),
+                        refusal_reason_code: payment_response.refusal_reason_code.clone(),
                         is_success: None,
+                        capture_status: None,
+                        processor,
+                    }
+                ).await?;
+
+                // recorded even when the cancel failed, nothing retries a FAILED cancellation so it needs manual follow up
+                let cancellation = dao.clone().insert_wallet_charge_cancellation(
+                    conn,
+                    &InsertableWalletCardChargeCancellation {
+                        registered_transaction_id: registered_transaction.id,
+                        wallet_card_charge_id: wallet_charge.id,
+                        psp_reference: payment_response.psp_reference.clone(),
+                        cancel_psp_reference: cancel_response.map(|cancel| cancel.psp_reference),
+                        cancel_status,
                     }
                 ).await?;
 
-                let ledger_entry = ledger_service.clone().settle_wallet_card_amount(
+                let ledger_entry = ledger_service.clone().release_wallet_amount(
                     conn,
                     &registered_transaction.clone().into(),
                     wallet.id,
-                    match payment_response.amount {
-                        None => 0,
-                        Some(amount) => amount.value as i32 //TODO: rounding / truncation
+                    expected_wallet_charge_reference.amount_cents
+                ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+
+                Ok(wallet_charge)
+            })
+        }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))
+    }
+
+    pub async fn register_abandoned_wallet_charge(
+        self: Arc<Self>,
+        registered_transaction: &RegisteredTransactionModel,
+        wallet: &Wallet,
+        expected_wallet_charge_reference: &ExpectedWalletChargeReference,
+        cancel_response: Option<&ProcessorModificationResult>,
+        cancel_status: &CancelStatus
+    ) -> Result<WalletCardCharge, ChargeError> {
+        let ledger_service = self.ledger_service.clone();
+        let dao = self.dao.clone();
+        let processor = self.payment_processor.name();
+        let registered_transaction = registered_transaction.clone();
+        let wallet = wallet.clone();
+        let expected_wallet_charge_reference = expected_wallet_charge_reference.clone();
+        let cancel_response = cancel_response.cloned();
+        let cancel_status = cancel_status.clone();
+        transactional(move |conn| {
+            Box::pin(async move {
+                // we never saw a response, so our own reference is the only handle on the payment
+                let wallet_charge = dao.clone().insert_wallet_charge(
+                    conn,
+                    &InsertableWalletCardCharge {
+                        registered_transaction_id: registered_transaction.id,
+                        user_id: registered_transaction.user_id,
+                        wallet_card_id: wallet.id,
+                        amount_cents: expected_wallet_charge_reference.amount_cents,
+                        rule_id: wallet.rule_id,
+                        expected_wallet_charge_reference_id: expected_wallet_charge_reference.id,
+                        resolved_charge_status: ChargeStatus::Fail,
+                        psp_reference: None,
+                        returned_reference: Some(expected_wallet_charge_reference.reference_id.to_string()),
+                        returned_charge_status: None,
+                        processor_charge_status: None,
+                        refusal_reason: None,
+                        refusal_reason_code: None,
+                        is_success: None,
+                        capture_status: None,
+                        processor,
+                    }
+                ).await?;
+
+                let cancellation = dao.clone().insert_wallet_charge_cancellation(
+                    conn,
+                    &InsertableWalletCardChargeCancellation {
+                        registered_transaction_id: registered_transaction.id,
+                        wallet_card_charge_id: wallet_charge.id,
+                        psp_reference: None,
+                        cancel_psp_reference: cancel_response.map(|cancel| cancel.psp_reference),
+                        cancel_status,
                     }
+                ).await?;
+
+                let ledger_entry = ledger_service.clone().release_wallet_amount(
+                    conn,
+                    &registered_transaction.clone().into(),
+                    wallet.id,
+                    expected_wallet_charge_reference.amount_cents
                 ).await.map_err(|e| DataError::Unexpected(e.into()))?;
 
                 Ok(wallet_charge)
@@ -539,6 +2411,47 @@ impl ChargeService {
         }).await.map_err(|e: DataError| ChargeError::Unexpected(e.into()))
     }
 
+    // cleanup on the asa path can't wait on the processor indefinitely, a cancel that doesn't come back in time is recorded as failed
+    #[tracing::instrument(skip(self, payment_processor))]
+    pub async fn cancel_within_budget(
+        self: Arc<Self>,
+        payment_processor: Arc<dyn PaymentProcessorTrait>,
+        psp_reference: &str,
+        budget: &ChargeBudget,
+    ) -> Option<ProcessorModificationResult> {
+        match timeout(budget.cleanup_timeout(), payment_processor.cancel(psp_reference)).await {
+            Ok(Ok(cancel)) => Some(cancel),
+            Ok(Err(e)) => {
+                tracing::error!("Error cancelling payment with psp={} error={:?}", psp_reference, &e);
+                None
+            },
+            Err(_) => {
+                tracing::error!("Timed out cancelling payment with psp={}", psp_reference);
+                None
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self, payment_processor))]
+    pub async fn cancel_abandoned_payment(
+        self: Arc<Self>,
+        payment_processor: Arc<dyn PaymentProcessorTrait>,
+        reference: &str,
+        budget: &ChargeBudget,
+    ) -> Option<ProcessorModificationResult> {
+        match timeout(budget.cleanup_timeout(), payment_processor.cancel_by_reference(reference)).await {
+            Ok(Ok(cancel)) => Some(cancel),
+            Ok(Err(e)) => {
+                tracing::error!("Error cancelling abandoned payment with reference={} error={:?}", reference, &e);
+                None
+            },
+            Err(_) => {
+                tracing::error!("Timed out cancelling abandoned payment with reference={}", reference);
+                None
+            }
+        }
+    }
+
     pub async fn register_failed_wallet_charge_no_response_body(
         self: Arc<Self>,
         registered_transaction: &RegisteredTransactionModel,
@@ -547,6 +2460,7 @@ impl ChargeService {
     ) -> Result<WalletCardCharge, ChargeError> {
         let ledger_service = self.ledger_service.clone();
         let dao = self.dao.clone();
+        let processor = self.payment_processor.name();
         let registered_transaction = registered_transaction.clone();
         let wallet = wallet.clone();
         let expected_wallet_charge_reference = expected_wallet_charge_reference.clone();
@@ -558,14 +2472,19 @@ impl ChargeService {
                         registered_transaction_id: registered_transaction.id,
                         user_id: registered_transaction.user_id,
                         wallet_card_id: wallet.id,
-                        amount_cents: registered_transaction.amount_cents,
+                        amount_cents: expected_wallet_charge_reference.amount_cents,
                         rule_id: wallet.rule_id,
                         expected_wallet_charge_reference_id: expected_wallet_charge_reference.id,
                         resolved_charge_status: ChargeStatus::Fail,
                         psp_reference: None,
                         returned_reference: None,
                         returned_charge_status: None,
+                        processor_charge_status: None,
+                        refusal_reason: None,
+                        refusal_reason_code: None,
                         is_success: None,
+                        capture_status: None,
+                        processor,
                     }
                 ).await?;
 
@@ -573,7 +2492,7 @@ impl ChargeService {
                     conn,
                     &registered_transaction.clone().into(),
                     wallet.id,
-                    registered_transaction.amount_cents
+                    expected_wallet_charge_reference.amount_cents
                 ).await.map_err(|e| DataError::Unexpected(e.into()))?;
 
                 Ok(wallet_charge)
@@ -585,6 +2504,7 @@ impl ChargeService {
         self: Arc<Self>,
         registered_transaction: &RegisteredTransactionModel,
         wallet: &Wallet,
+        amount_cents: i32,
     ) -> Result<ExpectedWalletChargeReference, ChargeError> {
         let ledger_service = self.ledger_service.clone();
         let dao = self.dao.clone();
@@ -598,7 +2518,7 @@ impl ChargeService {
                         registered_transaction_id: registered_transaction.id,
                         user_id: wallet.user_id,
                         wallet_card_id: wallet.id,
-                        amount_cents: registered_transaction.amount_cents,
+                        amount_cents: amount_cents,
                     }
                 ).await?;
 
@@ -607,7 +2527,7 @@ impl ChargeService {
                     conn,
                     &rt.into(),
                     wallet.id,
-                    registered_transaction.amount_cents
+                    amount_cents
                 ).await.map_err(|e| DataError::Unexpected(e.into()))?;
 
                 Ok(wallet_success)
@@ -635,6 +2555,13 @@ impl ChargeService {
                         memo: &metadata.memo,
                         amount_cents: metadata.amount_cents,
                         mcc: &metadata.mcc,
+                        lithic_transaction_token: None,
+                        currency: &metadata.currency,
+                        merchant_amount_cents: metadata.merchant_amount_cents,
+                        merchant_currency: metadata.merchant_currency.as_deref(),
+                        conversion_rate: metadata.conversion_rate,
+                        charge_currency: metadata.charge_currency(user.charge_in_merchant_currency),
+                        cash_amount_cents: metadata.cash_amount_cents
                     }
                 ).await?.into();
 
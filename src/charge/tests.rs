@@ -2,9 +2,10 @@
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
     use adyen_checkout::models::payment_response::ResultCode;
-    use adyen_checkout::models::{PaymentCancelResponse, PaymentResponse};
+    use adyen_checkout::models::{Amount, PaymentAmountUpdateResponse, PaymentCancelResponse, PaymentCaptureResponse, PaymentRefundResponse, PaymentResponse};
     use adyen_checkout::models::payment_cancel_response::Status;
     use crate::user::model::{UserModel as User, UserModel};
     use crate::charge::{
@@ -13,8 +14,13 @@ mod tests {
             ChargeServiceTrait
         },
         constant::{
+            CancelStatus,
+            CaptureStatus,
             ChargeCardAttemptResult,
-            ChargeEngineResult
+            ChargeEngineResult,
+            ChargeStatus,
+            RefundStatus,
+            TransactionEventType
         }
     };
     use uuid::Uuid;
@@ -22,17 +28,22 @@ mod tests {
     use crate::footprint::error::FootprintError;
     use crate::test_helper::{
         charge::{
+            create_mock_registered_transaction,
+            default_charge_budget,
             default_transaction_metadata,
         },
     };
     use crate::charge::model::RegisteredTransactionModel as RegisteredTransactionModel;
+    use crate::charge::model::{AsaChargeResult, ChargeBudget, RoutingTrace};
+    use crate::asa::request::AsaRequest;
     use crate::user::service::{UserService, UserServiceTrait};
     use crate::footprint::service::MockFootprintServiceTrait;
     use crate::test_helper::user::create_user;
     use actix_web::test;
     use crate::asa::response::AsaResponseResult;
     use crate::charge::dao::{ChargeDao, ChargeDaoTrait};
-    use crate::charge::entity::InsertableRegisteredTransaction;
+    use crate::charge::entity::{InsertableRegisteredTransaction, InsertableTransactionEvent};
+    use crate::charge::error::ChargeError;
     use crate::common::model::TransactionMetadata;
     use crate::error::data_error::DataError;
     use crate::ledger::service::LedgerService;
@@ -71,7 +82,8 @@ mod tests {
             &wallet,
             &user,
             &metadata,
-            &rtx
+            &rtx,
+            &default_charge_budget()
         ).await.expect("NO error");
         assert_eq!(ChargeCardAttemptResult::Denied, res);
     }
@@ -106,14 +118,13 @@ mod tests {
             &wallet,
             &user,
             &metadata,
-            &rtx
+            &rtx,
+            &default_charge_budget()
         ).await.expect("NO error");
         assert_eq!(ChargeCardAttemptResult::Approved, res);
     }
 
     #[test]
-    #[ignore]
-    // TODO: turned off cancellation logic for now
     async fn test_single_charge_needs_cancel_and_succeeds() {
         crate::test_helper::general::init();
         let mut metadata = default_transaction_metadata();
@@ -123,7 +134,8 @@ mod tests {
 
 
         let mut footprint_mock = MockFootprintServiceTrait::new();
-        let psp_ref = "abc123".to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+        let cancel_psp_ref = Uuid::new_v4().to_string();
         let mut resp = PaymentResponse::new();
         resp.result_code = Some(ResultCode::PartiallyAuthorised);
         resp.psp_reference = Some(psp_ref.clone());
@@ -135,7 +147,7 @@ mod tests {
         let cancel_resp = PaymentCancelResponse::new(
             "SandellEnterprisesECOM".to_string(),
             psp_ref.clone(),
-            "abd124".to_string(),
+            cancel_psp_ref.clone(),
             Status::Received
         );
         footprint_mock.expect_proxy_adyen_cancel_request()
@@ -157,9 +169,65 @@ mod tests {
             &wallet,
             &user,
             &metadata,
-            &rtx
+            &rtx,
+            &default_charge_budget()
         ).await.expect("NO error");
         assert_eq!(ChargeCardAttemptResult::PartialCancelSucceeded, res);
+        let wallet_charge = ledger.expect("wallet charge registered");
+        assert_eq!(ChargeStatus::Fail, wallet_charge.resolved_charge_status);
+        let cancellation = Arc::new(ChargeDao::new()).get_wallet_charge_cancellation_by_wallet_charge_id(wallet_charge.id).await.expect("cancellation registered");
+        assert_eq!(CancelStatus::Received, cancellation.cancel_status);
+        assert_eq!(Some(psp_ref), cancellation.psp_reference);
+        assert_eq!(Some(cancel_psp_ref), cancellation.cancel_psp_reference);
+    }
+
+    #[test]
+    async fn test_single_charge_needs_cancel_and_cancel_fails() {
+        crate::test_helper::general::init();
+        let metadata = default_transaction_metadata();
+        let user = create_user().await;
+        let wallet = create_wallet_with_rule(&user).await;
+        let rtx = create_registered_transaction(&user, &metadata).await;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let psp_ref = Uuid::new_v4().to_string();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Pending);
+        resp.psp_reference = Some(psp_ref.clone());
+
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp));
+        footprint_mock.expect_proxy_adyen_cancel_request()
+            .times(1)
+            .return_once(move |_| Err(FootprintError::Unexpected("error".into())));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let (res, ledger) = engine.clone().charge_card_with_cleanup(
+            Uuid::new_v4(),
+            &wallet,
+            &user,
+            &metadata,
+            &rtx,
+            &default_charge_budget()
+        ).await.expect("NO error");
+        assert_eq!(ChargeCardAttemptResult::PartialCancelFailed, res);
+        let wallet_charge = ledger.expect("wallet charge registered");
+        let cancellation = Arc::new(ChargeDao::new()).get_wallet_charge_cancellation_by_wallet_charge_id(wallet_charge.id).await.expect("cancellation registered");
+        assert_eq!(CancelStatus::Failed, cancellation.cancel_status);
+        assert_eq!(Some(psp_ref), cancellation.psp_reference);
+        assert_eq!(None, cancellation.cancel_psp_reference);
+        let needs_follow_up = Arc::new(ChargeDao::new()).get_wallet_charge_cancellations_by_status(&CancelStatus::Failed).await.expect("ok");
+        assert!(needs_follow_up.iter().any(|c| c.wallet_card_charge_id == wallet_charge.id));
     }
 
     #[test]
@@ -193,7 +261,8 @@ mod tests {
             &wallet,
             &user,
             &metadata,
-            &rtx
+            &rtx,
+            &default_charge_budget()
         ).await.expect("NO error");
         assert_eq!(ChargeCardAttemptResult::Denied, res);
     }
@@ -245,7 +314,8 @@ mod tests {
             &user,
             &vec![card_1.clone().into(), card_2.clone().into()],
             &metadata,
-            &rtx
+            &rtx,
+            &default_charge_budget()
         ).await.expect("NO error");
         assert_eq!(ChargeEngineResult::Approved, res);
     }
@@ -271,9 +341,81 @@ mod tests {
             &user,
             &vec![],
             &metadata,
-            &rtx
+            &rtx,
+            &default_charge_budget()
+        ).await.expect("NO error");
+        assert_eq!(ChargeEngineResult::Denied, res);
+    }
+
+    #[test]
+    async fn test_charge_user_wallet_stops_when_budget_spent() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let rtx = create_registered_transaction(&user, &metadata).await;
+        let card_1 = create_wallet_with_rule(&user).await;
+        let card_2 = create_wallet_with_rule(&user).await;
+
+        // no expectations, any proxy call fails the test
+        let footprint_mock = MockFootprintServiceTrait::new();
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+        let budget = ChargeBudget::new(Duration::ZERO, Duration::from_millis(500), Duration::from_secs(10), Duration::from_secs(1));
+        let (res, ledger) = engine.clone().charge_wallet(
+            &user,
+            &vec![card_1.clone().into(), card_2.clone().into()],
+            &metadata,
+            &rtx,
+            &budget
         ).await.expect("NO error");
         assert_eq!(ChargeEngineResult::Denied, res);
+        assert!(ledger.is_empty());
+        let dao = Arc::new(ChargeDao::new());
+        assert!(dao.clone().get_wallet_charges_by_registered_transaction(rtx.id).await.expect("no error").is_empty());
+    }
+
+    #[test]
+    async fn test_register_abandoned_wallet_charge_records_cancellation() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let rtx = create_registered_transaction(&user, &metadata).await;
+        let card = create_wallet_with_rule(&user).await;
+
+        let footprint_service = Arc::new(MockFootprintServiceTrait::new());
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+        let reserve = engine.clone().register_reserve_wallet_charge(&rtx, &card.clone().into(), rtx.amount_cents).await.expect("no error");
+        let wallet_charge = engine.clone().register_abandoned_wallet_charge(
+            &rtx,
+            &card.clone().into(),
+            &reserve,
+            None,
+            &CancelStatus::Failed
+        ).await.expect("no error");
+        assert_eq!(ChargeStatus::Fail, wallet_charge.resolved_charge_status);
+        assert_eq!(Some(reserve.reference_id.to_string()), wallet_charge.returned_reference);
+        assert!(wallet_charge.psp_reference.is_none());
+
+        let dao = Arc::new(ChargeDao::new());
+        let cancellation = dao.clone().get_wallet_charge_cancellation_by_wallet_charge_id(wallet_charge.id).await.expect("no error");
+        assert_eq!(CancelStatus::Failed, cancellation.cancel_status);
+        assert!(cancellation.psp_reference.is_none());
     }
 
 
@@ -346,7 +488,8 @@ mod tests {
             &user,
             &vec![card_1.clone().into(), card_2.clone().into()],
             &metadata,
-            &rtx
+            &rtx,
+            &default_charge_budget()
         ).await.expect("NO error");
         assert_eq!(ChargeEngineResult::Approved, res);
     }
@@ -415,9 +558,220 @@ mod tests {
             &asa,
             &vec![card_1.clone().into(), card_2.clone().into()],
             &pc,
-            &user
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(res.result, AsaResponseResult::Approved);
+        let previous = engine.clone().get_previous_result_for_request(&asa).await.expect("no error");
+        assert_eq!(Some(AsaResponseResult::Approved), previous.map(|previous| previous.result));
+    }
+
+    #[test]
+    async fn test_previous_result_for_request() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let pc = create_passthrough_card(&user).await;
+        let metadata = default_transaction_metadata();
+        let footprint_service = Arc::new(MockFootprintServiceTrait::new());
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let asa = create_example_asa(metadata.amount_cents, metadata.mcc.clone());
+        let previous = engine.clone().get_previous_result_for_request(&asa).await.expect("no error");
+        assert_eq!(None, previous);
+
+        let rtx = engine.clone().register_transaction_and_pending_passthrough_card_charge(
+            &user,
+            &metadata,
+            &pc,
+            asa.token.as_deref(),
+            &serde_json::to_string(&asa).expect("serializes")
+        ).await.expect("no error");
+        assert_eq!(asa.token, rtx.lithic_transaction_token);
+        let previous = engine.clone().get_previous_result_for_request(&asa).await.expect_err("still processing");
+        assert_eq!(ChargeError::DuplicateTransaction("test".into()), previous);
+
+        let duplicate = engine.clone().register_transaction_and_pending_passthrough_card_charge(
+            &user,
+            &metadata,
+            &pc,
+            asa.token.as_deref(),
+            &serde_json::to_string(&asa).expect("serializes")
+        ).await.expect_err("duplicate token");
+        assert_eq!(ChargeError::DuplicateTransaction("test".into()), duplicate);
+
+        engine.clone().register_asa_response_result(&rtx, &AsaResponseResult::UnauthorizedMerchant).await.expect("no error");
+        let previous = engine.clone().get_previous_result_for_request(&asa).await.expect("no error");
+        assert_eq!(Some(AsaResponseResult::UnauthorizedMerchant), previous.map(|previous| previous.result));
+    }
+
+    #[test]
+    async fn test_errored_transaction_is_closed_out_as_declined() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let pc = create_passthrough_card(&user).await;
+        let metadata = default_transaction_metadata();
+        let footprint_service = Arc::new(MockFootprintServiceTrait::new());
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let asa = create_example_asa(metadata.amount_cents, metadata.mcc.clone());
+        let rtx = engine.clone().register_transaction_and_pending_passthrough_card_charge(
+            &user,
+            &metadata,
+            &pc,
+            asa.token.as_deref(),
+            &serde_json::to_string(&asa).expect("serializes")
         ).await.expect("no error");
-        assert_eq!(res, AsaResponseResult::Approved);
+
+        // as if the charge errored after registering, a retry would otherwise conflict forever
+        engine.clone().register_errored_transaction(&rtx, &pc, &default_charge_budget()).await;
+        let previous = engine.clone().get_previous_result_for_request(&asa).await.expect("no error");
+        assert_eq!(Some(AsaResponseResult::from(ChargeEngineResult::Denied)), previous.map(|previous| previous.result));
+        let dao = Arc::new(ChargeDao::new());
+        let outer = dao.clone().get_passthrough_card_charge_by_registered_transaction(rtx.id).await.expect("hold released");
+        assert_eq!(ChargeStatus::Fail, outer.status);
+
+        // closing it out again leaves the released hold alone
+        engine.clone().register_errored_transaction(&rtx, &pc, &default_charge_budget()).await;
+        let previous = engine.clone().get_previous_result_for_request(&asa).await.expect("no error");
+        assert_eq!(Some(AsaResponseResult::from(ChargeEngineResult::Denied)), previous.map(|previous| previous.result));
+    }
+
+    #[test]
+    async fn test_collecting_stand_in_after_errored_authorization_uses_new_key() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let idempotency_keys: Arc<Mutex<Vec<Uuid>>> = Arc::new(Mutex::new(vec![]));
+        let seen_keys = idempotency_keys.clone();
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(2)
+            .returning(move |request| {
+                seen_keys.lock().unwrap().push(*request.idempotency_key);
+                let mut resp = PaymentResponse::new();
+                resp.result_code = Some(ResultCode::Authorised);
+                resp.psp_reference = Some(Uuid::new_v4().to_string());
+                Ok(resp)
+            });
+        let cancel_resp = PaymentCancelResponse::new(
+            "SandellEnterprisesECOM".to_string(),
+            psp_ref.clone(),
+            Uuid::new_v4().to_string(),
+            Status::Received
+        );
+        footprint_mock.expect_proxy_adyen_cancel_request()
+            .times(1)
+            .return_once(move |_| Ok(cancel_resp));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(metadata.amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        // as if the asa errored after the card authorized, closing it out cancels that authorization
+        let dao = Arc::new(ChargeDao::new());
+        let rtx: RegisteredTransactionModel = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists").into();
+        engine.clone().register_errored_transaction(&rtx, &pc, &default_charge_budget()).await;
+
+        let res = engine.clone().collect_stand_in(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &Uuid::new_v4(),
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaResponseResult::Approved, res.result);
+        // reusing the first key would replay the cancelled authorization and never collect the money
+        let idempotency_keys = idempotency_keys.lock().unwrap();
+        assert_eq!(2, idempotency_keys.len());
+        assert_ne!(idempotency_keys[0], idempotency_keys[1]);
+        let previous = engine.clone().get_previous_result_for_request(&asa).await.expect("no error");
+        assert_eq!(Some(AsaResponseResult::Approved), previous.map(|previous| previous.result));
+    }
+
+    #[test]
+    async fn test_idempotency_key_for_wallet_card() {
+        let metadata = default_transaction_metadata();
+        let mut rtx: RegisteredTransactionModel = create_mock_registered_transaction(&metadata).into();
+        rtx.lithic_transaction_token = Some("lithic_token".to_string());
+        let card_1 = Uuid::new_v4();
+        let card_2 = Uuid::new_v4();
+        assert_eq!(rtx.idempotency_key_for_wallet_card(&card_1), rtx.idempotency_key_for_wallet_card(&card_1));
+        assert_ne!(rtx.idempotency_key_for_wallet_card(&card_1), rtx.idempotency_key_for_wallet_card(&card_2));
+
+        let mut replayed: RegisteredTransactionModel = create_mock_registered_transaction(&metadata).into();
+        replayed.transaction_id = Uuid::new_v4();
+        replayed.lithic_transaction_token = Some("lithic_token".to_string());
+        assert_eq!(rtx.idempotency_key_for_wallet_card(&card_1), replayed.idempotency_key_for_wallet_card(&card_1));
+
+        replayed.collection_key = Some(Uuid::new_v4());
+        assert_ne!(rtx.idempotency_key_for_wallet_card(&card_1), replayed.idempotency_key_for_wallet_card(&card_1));
+    }
+
+    #[test]
+    async fn test_idempotency_key_for_adjustment() {
+        let metadata = default_transaction_metadata();
+        let mut rtx: RegisteredTransactionModel = create_mock_registered_transaction(&metadata).into();
+        rtx.lithic_transaction_token = Some("lithic_token".to_string());
+        let card = Uuid::new_v4();
+        assert_eq!(rtx.idempotency_key_for_adjustment(&card, 200), rtx.idempotency_key_for_adjustment(&card, 200));
+        assert_ne!(rtx.idempotency_key_for_adjustment(&card, 200), rtx.idempotency_key_for_adjustment(&card, 300));
+        assert_ne!(rtx.idempotency_key_for_wallet_card(&card), rtx.idempotency_key_for_adjustment(&card, 200));
+    }
+
+    #[test]
+    async fn test_charge_budget_can_attempt() {
+        let budget = ChargeBudget::new(Duration::from_secs(30), Duration::from_millis(500), Duration::from_secs(10), Duration::from_secs(1));
+        assert!(budget.can_attempt());
+        assert!(budget.remaining() <= Duration::from_secs(30));
+        assert_eq!(Duration::from_secs(1), budget.cleanup_timeout());
+        // an attempt is capped so the next card still has time
+        assert_eq!(Duration::from_secs(10), budget.attempt_timeout());
+
+        let short = ChargeBudget::new(Duration::from_millis(100), Duration::from_millis(500), Duration::from_secs(10), Duration::from_secs(1));
+        assert!(!short.can_attempt());
+        assert!(short.attempt_timeout() <= Duration::from_millis(100));
+
+        let spent = ChargeBudget::new(Duration::ZERO, Duration::ZERO, Duration::from_secs(10), Duration::from_secs(1));
+        assert!(!spent.can_attempt());
+        assert_eq!(Duration::ZERO, spent.remaining());
+        assert_eq!(Duration::ZERO, spent.cleanup_timeout());
     }
 
 
@@ -488,11 +842,77 @@ mod tests {
             &user,
             &vec![card_1.clone().into(), card_2.clone().into()],
             &metadata,
-            &rtx
+            &rtx,
+            &default_charge_budget()
         ).await.expect("NO error");
         assert_eq!(ChargeEngineResult::Denied, res);
     }
 
+    #[test]
+    async fn test_charge_user_wallet_maps_refusal_reasons() {
+        crate::test_helper::general::init();
+        let metadata = default_transaction_metadata();
+        let user = create_user().await;
+        let rtx = create_registered_transaction(&user, &metadata).await;
+
+        let card_1 = create_wallet_with_rule(&user).await;
+        let card_2 = create_wallet_with_rule(&user).await;
+        let payment_method_1 = card_1.payment_method_id.clone();
+        let payment_method_2 = card_2.payment_method_id.clone();
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+
+        let mut resp_1 = PaymentResponse::new();
+        resp_1.result_code = Some(ResultCode::Refused);
+        resp_1.psp_reference = Some(Uuid::new_v4().to_string());
+        resp_1.refusal_reason = Some("Expired Card".to_string());
+        resp_1.refusal_reason_code = Some("6".to_string());
+        let mut resp_2 = PaymentResponse::new();
+        resp_2.result_code = Some(ResultCode::Refused);
+        resp_2.psp_reference = Some(Uuid::new_v4().to_string());
+        resp_2.refusal_reason = Some("Not enough balance".to_string());
+        resp_2.refusal_reason_code = Some("12".to_string());
+
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(move |charge_request| charge_request.payment_method_id == payment_method_1.to_string())
+            .times(1)
+            .return_once(move |_| Ok(resp_1));
+
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(move |charge_request| charge_request.payment_method_id == payment_method_2.to_string())
+            .times(1)
+            .return_once(move |_| Ok(resp_2));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let (res, ledger) = engine.clone().charge_wallet(
+            &user,
+            &vec![card_1.clone().into(), card_2.clone().into()],
+            &metadata,
+            &rtx,
+            &default_charge_budget()
+        ).await.expect("NO error");
+        assert_eq!(ChargeEngineResult::InsufficientFunds, res);
+        assert_eq!(AsaResponseResult::InsufficientFunds, AsaResponseResult::from(res));
+
+        let dao = Arc::new(ChargeDao::new());
+        let charges = dao.clone().get_wallet_charges_by_registered_transaction(rtx.id).await.expect("ok");
+        assert_eq!(2, charges.len());
+        let expired = charges.iter().find(|charge| charge.wallet_card_id == card_1.id).expect("exists");
+        assert_eq!(Some("6".to_string()), expired.refusal_reason_code);
+        assert_eq!(Some("Expired Card".to_string()), expired.refusal_reason);
+        let declined = charges.iter().find(|charge| charge.wallet_card_id == card_2.id).expect("exists");
+        assert_eq!(Some("12".to_string()), declined.refusal_reason_code);
+    }
+
     #[test]
     async fn test_register_transaction() {
         crate::test_helper::general::init();
@@ -518,6 +938,1888 @@ mod tests {
     }
 
 
+    #[test]
+    async fn test_clearing_event_captures_and_is_recorded_once() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let event_token = Uuid::new_v4().to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(psp_ref.clone());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp));
+
+        let capture_psp_ref = psp_ref.clone();
+        let capture_event_token = event_token.clone();
+        footprint_mock.expect_proxy_adyen_capture_request()
+            .withf(
+                move |capture_request| {
+                    capture_request.psp_reference == capture_psp_ref
+                        && capture_request.reference == capture_event_token
+                        && capture_request.amount_cents == amount_cents
+                }
+            )
+            .times(1)
+            .return_once(move |request| Ok(PaymentCaptureResponse::new(
+                Amount { currency: "USD".to_string(), value: amount_cents as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_capture_response::Status::Received
+            )));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        let res = engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(res.result, AsaResponseResult::Approved);
+
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Clearing,
+            &event_token,
+            amount_cents,
+            &pc
+        ).await.expect("no error");
+        // lithic retries the webhook, nothing should be captured or recorded again
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Clearing,
+            &event_token,
+            amount_cents,
+            &pc
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists");
+        let events = dao.clone().get_transaction_events_by_registered_transaction(rtx.id).await.expect("ok");
+        assert_eq!(1, events.len());
+        assert_eq!(TransactionEventType::Clearing, events[0].event_type);
+        assert_eq!(event_token, events[0].event_token);
+        assert_eq!(amount_cents, events[0].amount_cents);
+        assert!(events[0].processed_at.is_some());
+        let wallet_charge = dao.clone().get_successful_wallet_charge_by_registered_transaction(rtx.id).await.expect("exists");
+        assert_eq!(Some(CaptureStatus::Captured), wallet_charge.capture_status);
+        assert_eq!(Some(amount_cents), wallet_charge.captured_amount_cents);
+    }
+
+    #[test]
+    async fn test_void_event_cancels_wallet_charge() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let event_token = Uuid::new_v4().to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(psp_ref.clone());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp));
+
+        let cancel_psp_ref = psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_cancel_request()
+            .withf(move |psp| psp == cancel_psp_ref)
+            .times(1)
+            .return_once(move |psp| Ok(PaymentCancelResponse::new(
+                "merchant".to_string(),
+                psp.to_string(),
+                Uuid::new_v4().to_string(),
+                Status::Received
+            )));
+        footprint_mock.expect_proxy_adyen_refund_request()
+            .times(0);
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Void,
+            &event_token,
+            amount_cents,
+            &pc
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let event = dao.clone().get_transaction_event_by_event_token(&event_token).await.expect("exists");
+        assert_eq!(TransactionEventType::Void, event.event_type);
+    }
+
+    #[test]
+    async fn test_void_after_clearing_refunds_captured_charge() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let clearing_token = Uuid::new_v4().to_string();
+        let void_token = Uuid::new_v4().to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(psp_ref.clone());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp));
+        footprint_mock.expect_proxy_adyen_capture_request()
+            .times(1)
+            .return_once(move |request| Ok(PaymentCaptureResponse::new(
+                Amount { currency: "USD".to_string(), value: amount_cents as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_capture_response::Status::Received
+            )));
+        // cancelling a captured payment is accepted and then fails, so it's never tried
+        footprint_mock.expect_proxy_adyen_cancel_request()
+            .times(0);
+        let refund_psp_ref = psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_refund_request()
+            .withf(
+                move |refund_request| {
+                    refund_request.psp_reference == refund_psp_ref
+                        && refund_request.amount_cents == amount_cents
+                }
+            )
+            .times(1)
+            .return_once(move |request| Ok(PaymentRefundResponse::new(
+                Amount { currency: "USD".to_string(), value: amount_cents as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_refund_response::Status::Received
+            )));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Clearing,
+            &clearing_token,
+            amount_cents,
+            &pc
+        ).await.expect("no error");
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Void,
+            &void_token,
+            amount_cents,
+            &pc
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists");
+        let wallet_charge = dao.clone().get_successful_wallet_charge_by_registered_transaction(rtx.id).await.expect("exists");
+        let refunds = dao.clone().get_wallet_charge_refunds_by_wallet_charge_id(wallet_charge.id).await.expect("ok");
+        assert_eq!(1, refunds.len());
+        assert_eq!(RefundStatus::Received, refunds[0].refund_status);
+        assert_eq!(amount_cents, refunds[0].amount_cents);
+        assert_eq!(void_token, refunds[0].reference);
+    }
+
+    #[test]
+    async fn test_partial_return_event_refunds_wallet_charge() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let event_token = Uuid::new_v4().to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+        let refund_cents = amount_cents / 2;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(psp_ref.clone());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp));
+
+        let refund_psp_ref = psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_refund_request()
+            .withf(
+                move |refund_request| {
+                    refund_request.psp_reference == refund_psp_ref
+                        && refund_request.amount_cents == refund_cents
+                }
+            )
+            .times(1)
+            .return_once(move |request| Ok(PaymentRefundResponse::new(
+                Amount { currency: "USD".to_string(), value: refund_cents as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_refund_response::Status::Received
+            )));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Return,
+            &event_token,
+            refund_cents,
+            &pc
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let event = dao.clone().get_transaction_event_by_event_token(&event_token).await.expect("exists");
+        assert_eq!(TransactionEventType::Return, event.event_type);
+        assert_eq!(refund_cents, event.amount_cents);
+    }
+
+    #[test]
+    async fn test_multiple_partial_refunds_until_fully_refunded() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+        let first_refund_cents = amount_cents / 2;
+        let second_refund_cents = amount_cents - first_refund_cents;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(psp_ref.clone());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp));
+
+        let refund_psp_ref = psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_refund_request()
+            .withf(move |refund_request| refund_request.psp_reference == refund_psp_ref)
+            .times(2)
+            .returning(move |request| Ok(PaymentRefundResponse::new(
+                Amount { currency: "USD".to_string(), value: request.amount_cents as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_refund_response::Status::Received
+            )));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx: RegisteredTransactionModel = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists").into();
+
+        let first_reference = Uuid::new_v4().to_string();
+        let first = engine.clone().refund_registered_transaction(&rtx, first_refund_cents, &first_reference).await.expect("no error").remove(0);
+        assert_eq!(RefundStatus::Received, first.refund_status);
+        assert_eq!(first_refund_cents, first.amount_cents);
+        assert_eq!(psp_ref, first.psp_reference);
+
+        // replaying the same reference does not refund twice
+        let replay = engine.clone().refund_registered_transaction(&rtx, first_refund_cents, &first_reference).await.expect("no error").remove(0);
+        assert_eq!(first.id, replay.id);
+
+        let second = engine.clone().refund_registered_transaction(&rtx, second_refund_cents, &Uuid::new_v4().to_string()).await.expect("no error").remove(0);
+        assert_eq!(RefundStatus::Received, second.refund_status);
+
+        let error = engine.clone().refund_registered_transaction(&rtx, 1, &Uuid::new_v4().to_string()).await.expect_err("fully refunded");
+        assert_eq!(ChargeError::InvalidRefund("test".into()), error);
+
+        let refunds = dao.clone().get_wallet_charge_refunds_by_wallet_charge_id(first.wallet_card_charge_id).await.expect("ok");
+        assert_eq!(2, refunds.len());
+    }
+
+    #[test]
+    async fn test_failed_refund_is_recorded() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(psp_ref.clone());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp));
+        footprint_mock.expect_proxy_adyen_refund_request()
+            .times(1)
+            .return_once(move |_| Err(FootprintError::Unexpected("error".into())));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx: RegisteredTransactionModel = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists").into();
+        let error = engine.clone().refund_registered_transaction(&rtx, amount_cents, &Uuid::new_v4().to_string()).await.expect_err("refund fails");
+        assert_eq!(ChargeError::Unexpected("test".into()), error);
+
+        let wallet_charge = dao.clone().get_successful_wallet_charge_by_registered_transaction(rtx.id).await.expect("exists");
+        let refunds = dao.clone().get_wallet_charge_refunds_by_wallet_charge_id(wallet_charge.id).await.expect("ok");
+        assert_eq!(1, refunds.len());
+        assert_eq!(RefundStatus::Failed, refunds[0].refund_status);
+        assert_eq!(None, refunds[0].refund_psp_reference);
+    }
+
+    #[test]
+    async fn test_incremental_authorization_charges_delta_on_same_card() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card_1 = create_wallet(&user).await;
+        let card_2 = create_wallet(&user).await;
+        let payment_method_1 = card_1.payment_method_id.clone();
+        let payment_method_1_increment = card_1.payment_method_id.clone();
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+        let increment_cents = 250;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp_1 = PaymentResponse::new();
+        resp_1.result_code = Some(ResultCode::Authorised);
+        resp_1.psp_reference = Some(Uuid::new_v4().to_string());
+        let mut resp_2 = PaymentResponse::new();
+        resp_2.result_code = Some(ResultCode::Authorised);
+        resp_2.psp_reference = Some(Uuid::new_v4().to_string());
+
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(
+                move |charge_request| {
+                    charge_request.payment_method_id == payment_method_1.to_string()
+                        && charge_request.amount_cents == amount_cents
+                }
+            )
+            .times(1)
+            .return_once(move |_| Ok(resp_1));
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(
+                move |charge_request| {
+                    charge_request.payment_method_id == payment_method_1_increment.to_string()
+                        && charge_request.amount_cents == increment_cents
+                }
+            )
+            .times(1)
+            .return_once(move |_| Ok(resp_2));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        let res = engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card_1.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaResponseResult::Approved, res.result);
+
+        let mut increment = create_example_asa(amount_cents + increment_cents, metadata.mcc.clone());
+        increment.token = Some(transaction_token.clone());
+        assert_eq!(None, engine.clone().get_previous_result_for_request(&increment).await.expect("no error"));
+        // the higher ranked card is skipped, increments stay on the original card
+        let res = engine.clone().charge_from_asa_request(
+            &increment,
+            &vec![card_2.clone().into(), card_1.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaResponseResult::Approved, res.result);
+        assert_eq!(Some(AsaResponseResult::Approved), engine.clone().get_previous_result_for_request(&increment).await.expect("no error").map(|previous| previous.result));
+        // a retry of the original asa is answered as before, not taken for a decrease back to its amount
+        assert_eq!(
+            Some(AsaChargeResult::from(AsaResponseResult::Approved)),
+            engine.clone().get_previous_result_for_request(&asa).await.expect("no error")
+        );
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists");
+        assert_eq!(amount_cents + increment_cents, rtx.amount_cents);
+        let adjustments = dao.clone().get_authorization_adjustments_by_registered_transaction(rtx.id).await.expect("ok");
+        assert_eq!(1, adjustments.len());
+        assert_eq!(ChargeStatus::Success, adjustments[0].status);
+        assert_eq!(amount_cents, adjustments[0].previous_amount_cents);
+        assert_eq!(amount_cents + increment_cents, adjustments[0].amount_cents);
+    }
+
+    #[test]
+    async fn test_void_refunds_captured_increment() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let event_token = Uuid::new_v4().to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+        let increment_psp_ref = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+        let increment_cents = 250;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp_1 = PaymentResponse::new();
+        resp_1.result_code = Some(ResultCode::Authorised);
+        resp_1.psp_reference = Some(psp_ref.clone());
+        let mut resp_2 = PaymentResponse::new();
+        resp_2.result_code = Some(ResultCode::Authorised);
+        resp_2.psp_reference = Some(increment_psp_ref.clone());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(move |charge_request| charge_request.amount_cents == amount_cents)
+            .times(1)
+            .return_once(move |_| Ok(resp_1));
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(move |charge_request| charge_request.amount_cents == increment_cents)
+            .times(1)
+            .return_once(move |_| Ok(resp_2));
+
+        let cancel_psp_ref = psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_cancel_request()
+            .withf(move |psp| psp == cancel_psp_ref)
+            .times(1)
+            .return_once(move |psp| Ok(PaymentCancelResponse::new(
+                "merchant".to_string(),
+                psp.to_string(),
+                Uuid::new_v4().to_string(),
+                Status::Received
+            )));
+        // the increment was captured on its own, only a refund of its psp gets it back
+        let refund_psp_ref = increment_psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_refund_request()
+            .withf(
+                move |refund_request| {
+                    refund_request.psp_reference == refund_psp_ref
+                        && refund_request.amount_cents == increment_cents
+                }
+            )
+            .times(1)
+            .return_once(move |request| Ok(PaymentRefundResponse::new(
+                Amount { currency: "USD".to_string(), value: increment_cents as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_refund_response::Status::Received
+            )));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        let mut increment = create_example_asa(amount_cents + increment_cents, metadata.mcc.clone());
+        increment.token = Some(transaction_token.clone());
+        let res = engine.clone().charge_from_asa_request(
+            &increment,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaResponseResult::Approved, res.result);
+
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Void,
+            &event_token,
+            amount_cents + increment_cents,
+            &pc
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists");
+        let adjustments = dao.clone().get_authorization_adjustments_by_registered_transaction(rtx.id).await.expect("ok");
+        assert_eq!(1, adjustments.len());
+        assert_eq!(increment_cents, adjustments[0].reversed_cents);
+        let wallet_charge = dao.clone().get_successful_wallet_charge_by_registered_transaction(rtx.id).await.expect("exists");
+        assert_eq!(Some(CaptureStatus::Cancelled), wallet_charge.capture_status);
+    }
+
+    #[test]
+    async fn test_full_return_refunds_captured_increment() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let clearing_token = Uuid::new_v4().to_string();
+        let return_token = Uuid::new_v4().to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+        let increment_psp_ref = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+        let increment_cents = 250;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp_1 = PaymentResponse::new();
+        resp_1.result_code = Some(ResultCode::Authorised);
+        resp_1.psp_reference = Some(psp_ref.clone());
+        let mut resp_2 = PaymentResponse::new();
+        resp_2.result_code = Some(ResultCode::Authorised);
+        resp_2.psp_reference = Some(increment_psp_ref.clone());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(move |charge_request| charge_request.amount_cents == amount_cents)
+            .times(1)
+            .return_once(move |_| Ok(resp_1));
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(move |charge_request| charge_request.amount_cents == increment_cents)
+            .times(1)
+            .return_once(move |_| Ok(resp_2));
+
+        // the increment was captured when approved, clearing only captures the original charge
+        let capture_psp_ref = psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_capture_request()
+            .withf(
+                move |capture_request| {
+                    capture_request.psp_reference == capture_psp_ref
+                        && capture_request.amount_cents == amount_cents
+                }
+            )
+            .times(1)
+            .return_once(move |request| Ok(PaymentCaptureResponse::new(
+                Amount { currency: "USD".to_string(), value: amount_cents as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_capture_response::Status::Received
+            )));
+
+        let refund_increment_psp_ref = increment_psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_refund_request()
+            .withf(
+                move |refund_request| {
+                    refund_request.psp_reference == refund_increment_psp_ref
+                        && refund_request.amount_cents == increment_cents
+                }
+            )
+            .times(1)
+            .return_once(move |request| Ok(PaymentRefundResponse::new(
+                Amount { currency: "USD".to_string(), value: increment_cents as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_refund_response::Status::Received
+            )));
+        let refund_psp_ref = psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_refund_request()
+            .withf(
+                move |refund_request| {
+                    refund_request.psp_reference == refund_psp_ref
+                        && refund_request.amount_cents == amount_cents
+                }
+            )
+            .times(1)
+            .return_once(move |request| Ok(PaymentRefundResponse::new(
+                Amount { currency: "USD".to_string(), value: amount_cents as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_refund_response::Status::Received
+            )));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        let mut increment = create_example_asa(amount_cents + increment_cents, metadata.mcc.clone());
+        increment.token = Some(transaction_token.clone());
+        let res = engine.clone().charge_from_asa_request(
+            &increment,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaResponseResult::Approved, res.result);
+
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Clearing,
+            &clearing_token,
+            amount_cents + increment_cents,
+            &pc
+        ).await.expect("no error");
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Return,
+            &return_token,
+            amount_cents + increment_cents,
+            &pc
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists");
+        let adjustments = dao.clone().get_authorization_adjustments_by_registered_transaction(rtx.id).await.expect("ok");
+        assert_eq!(increment_cents, adjustments[0].reversed_cents);
+        let wallet_charge = dao.clone().get_successful_wallet_charge_by_registered_transaction(rtx.id).await.expect("exists");
+        let refunds = dao.clone().get_wallet_charge_refunds_by_wallet_charge_id(wallet_charge.id).await.expect("ok");
+        assert_eq!(2, refunds.len());
+        assert!(refunds.iter().all(|refund| refund.refund_status == RefundStatus::Received && refund.reference == return_token));
+        assert_eq!(amount_cents + increment_cents, refunds.iter().map(|refund| refund.amount_cents).sum::<i32>());
+        let event = dao.clone().get_transaction_event_by_event_token(&return_token).await.expect("exists");
+        assert!(event.processed_at.is_some());
+    }
+
+    #[test]
+    async fn test_decreased_authorization_captures_less_at_clearing() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let event_token = Uuid::new_v4().to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+        let decrease_cents = amount_cents / 2;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(psp_ref.clone());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(|charge_request| charge_request.manual_capture)
+            .times(1)
+            .return_once(move |_| Ok(resp));
+        // the charge was only authorized, so lowering it doesn't need a refund
+        footprint_mock.expect_proxy_adyen_refund_request()
+            .times(0);
+        let lower_psp_ref = psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_amount_update_request()
+            .withf(
+                move |amount_update_request| {
+                    amount_update_request.psp_reference == lower_psp_ref
+                        && amount_update_request.amount_cents == amount_cents - decrease_cents
+                }
+            )
+            .times(1)
+            .return_once(move |request| Ok(PaymentAmountUpdateResponse::new(
+                Amount { currency: "USD".to_string(), value: (amount_cents - decrease_cents) as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                request.reference.to_string(),
+                adyen_checkout::models::payment_amount_update_response::Status::Received
+            )));
+        let capture_psp_ref = psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_capture_request()
+            .withf(
+                move |capture_request| {
+                    capture_request.psp_reference == capture_psp_ref
+                        && capture_request.amount_cents == amount_cents - decrease_cents
+                }
+            )
+            .times(1)
+            .return_once(move |request| Ok(PaymentCaptureResponse::new(
+                Amount { currency: "USD".to_string(), value: (amount_cents - decrease_cents) as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_capture_response::Status::Received
+            )));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        let mut decrease = create_example_asa(amount_cents - decrease_cents, metadata.mcc.clone());
+        decrease.token = Some(transaction_token.clone());
+        let res = engine.clone().charge_from_asa_request(
+            &decrease,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaResponseResult::Approved, res.result);
+
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Clearing,
+            &event_token,
+            amount_cents - decrease_cents,
+            &pc
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists");
+        assert_eq!(amount_cents - decrease_cents, rtx.amount_cents);
+        let wallet_charge = dao.clone().get_successful_wallet_charge_by_registered_transaction(rtx.id).await.expect("exists");
+        assert_eq!(amount_cents - decrease_cents, wallet_charge.amount_cents);
+        let refunds = dao.clone().get_wallet_charge_refunds_by_wallet_charge_id(wallet_charge.id).await.expect("ok");
+        assert!(refunds.is_empty());
+        assert_eq!(Some(CaptureStatus::Captured), wallet_charge.capture_status);
+        assert_eq!(Some(amount_cents - decrease_cents), wallet_charge.captured_amount_cents);
+    }
+
+    #[test]
+    async fn test_second_clearing_captures_what_is_left() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let first_event_token = Uuid::new_v4().to_string();
+        let second_event_token = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+        let first_cleared_cents = 600;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(Uuid::new_v4().to_string());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp));
+        footprint_mock.expect_proxy_adyen_capture_request()
+            .withf(move |capture_request| capture_request.amount_cents == first_cleared_cents)
+            .times(1)
+            .return_once(move |request| Ok(PaymentCaptureResponse::new(
+                Amount { currency: "USD".to_string(), value: first_cleared_cents as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_capture_response::Status::Received
+            )));
+        footprint_mock.expect_proxy_adyen_capture_request()
+            .withf(move |capture_request| capture_request.amount_cents == amount_cents - first_cleared_cents)
+            .times(1)
+            .return_once(move |request| Ok(PaymentCaptureResponse::new(
+                Amount { currency: "USD".to_string(), value: (amount_cents - first_cleared_cents) as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_capture_response::Status::Received
+            )));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Clearing,
+            &first_event_token,
+            first_cleared_cents,
+            &pc
+        ).await.expect("no error");
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Clearing,
+            &second_event_token,
+            amount_cents - first_cleared_cents,
+            &pc
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists");
+        let wallet_charge = dao.clone().get_successful_wallet_charge_by_registered_transaction(rtx.id).await.expect("exists");
+        assert_eq!(Some(CaptureStatus::Captured), wallet_charge.capture_status);
+        assert_eq!(Some(amount_cents), wallet_charge.captured_amount_cents);
+
+        // nothing is left on the auth, a third clearing fails instead of passing as handled
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Clearing,
+            &Uuid::new_v4().to_string(),
+            100,
+            &pc
+        ).await.expect_err("nothing left to capture");
+    }
+
+    #[test]
+    async fn test_clearing_captures_tip_above_authorization() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let event_token = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+        let cleared_cents = amount_cents + 200;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(Uuid::new_v4().to_string());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp));
+        footprint_mock.expect_proxy_adyen_capture_request()
+            .withf(move |capture_request| capture_request.amount_cents == cleared_cents)
+            .times(1)
+            .return_once(move |request| Ok(PaymentCaptureResponse::new(
+                Amount { currency: "USD".to_string(), value: cleared_cents as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_capture_response::Status::Received
+            )));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Clearing,
+            &event_token,
+            cleared_cents,
+            &pc
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists");
+        let wallet_charge = dao.clone().get_successful_wallet_charge_by_registered_transaction(rtx.id).await.expect("exists");
+        assert_eq!(amount_cents, wallet_charge.amount_cents);
+        assert_eq!(Some(CaptureStatus::Captured), wallet_charge.capture_status);
+        assert_eq!(Some(cleared_cents), wallet_charge.captured_amount_cents);
+    }
+
+    #[test]
+    async fn test_failed_capture_is_recorded() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let event_token = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(Uuid::new_v4().to_string());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp));
+        footprint_mock.expect_proxy_adyen_capture_request()
+            .times(1)
+            .return_once(|_| Err(FootprintError::Unexpected("error".into())));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        // lithic has already cleared, the event is still recorded
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Clearing,
+            &event_token,
+            amount_cents,
+            &pc
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists");
+        let wallet_charge = dao.clone().get_successful_wallet_charge_by_registered_transaction(rtx.id).await.expect("exists");
+        assert_eq!(Some(CaptureStatus::Failed), wallet_charge.capture_status);
+        assert_eq!(None, wallet_charge.captured_amount_cents);
+        dao.clone().get_transaction_event_by_event_token(&event_token).await.expect("recorded");
+    }
+
+    #[test]
+    async fn test_expiry_cancels_uncaptured_authorization() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let event_token = Uuid::new_v4().to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(psp_ref.clone());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp));
+        let cancel_psp_ref = psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_cancel_request()
+            .withf(move |psp| psp == cancel_psp_ref)
+            .times(1)
+            .return_once(move |psp| Ok(PaymentCancelResponse::new(
+                "merchant".to_string(),
+                psp.to_string(),
+                Uuid::new_v4().to_string(),
+                Status::Received
+            )));
+        footprint_mock.expect_proxy_adyen_capture_request()
+            .times(0);
+        footprint_mock.expect_proxy_adyen_refund_request()
+            .times(0);
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        // lithic only expires what's left of the hold, the whole backing auth still goes
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::AuthorizationExpiry,
+            &event_token,
+            amount_cents / 2,
+            &pc
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists");
+        let wallet_charge = dao.clone().get_successful_wallet_charge_by_registered_transaction(rtx.id).await.expect("exists");
+        assert_eq!(Some(CaptureStatus::Cancelled), wallet_charge.capture_status);
+        let cancellation = dao.clone().get_wallet_charge_cancellation_by_wallet_charge_id(wallet_charge.id).await.expect("exists");
+        assert_eq!(CancelStatus::Received, cancellation.cancel_status);
+    }
+
+    #[test]
+    async fn test_event_for_unknown_transaction_is_redelivered() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let pc = create_passthrough_card(&user).await;
+        let footprint_service = Arc::new(MockFootprintServiceTrait::new());
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+        let event_token = Uuid::new_v4().to_string();
+        // erroring is what gets lithic to send it again
+        let error = engine.clone().process_transaction_event(
+            &Uuid::new_v4().to_string(),
+            &TransactionEventType::Clearing,
+            &event_token,
+            100,
+            &pc
+        ).await.expect_err("unknown transaction");
+        assert_eq!(ChargeError::Unexpected("test".into()), error);
+
+        let dao = Arc::new(ChargeDao::new());
+        let event = dao.clone().get_transaction_event_by_event_token(&event_token).await.expect_err("not recorded");
+        assert_eq!(DataError::NotFound("test".into()), event);
+    }
+
+    #[test]
+    async fn test_event_claimed_by_concurrent_delivery_conflicts() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let pc = create_passthrough_card(&user).await;
+        let metadata = default_transaction_metadata();
+        let footprint_service = Arc::new(MockFootprintServiceTrait::new());
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+        let dao = Arc::new(ChargeDao::new());
+        let transaction_token = Uuid::new_v4().to_string();
+        let insert_dao = dao.clone();
+        let insert_token = transaction_token.clone();
+        let user_id = user.id;
+        let rtx = transactional::<_, DataError, _>(move |conn|
+            Box::pin(async move {
+                insert_dao.clone().insert_registered_transaction(conn, &InsertableRegisteredTransaction {
+                    user_id,
+                    memo: &metadata.memo,
+                    amount_cents: metadata.amount_cents,
+                    mcc: &metadata.mcc,
+                    lithic_transaction_token: Some(&insert_token),
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
+                }).await
+            })).await.expect("inserted");
+
+        // another delivery claimed the event and hasn't finished, this one backs off without touching the charge
+        let event_token = Uuid::new_v4().to_string();
+        let claim = dao.clone().insert_transaction_event(&InsertableTransactionEvent {
+            registered_transaction_id: rtx.id,
+            event_token: event_token.clone(),
+            event_type: TransactionEventType::Clearing,
+            amount_cents: 100,
+        }).await.expect("claimed");
+        let error = engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Clearing,
+            &event_token,
+            100,
+            &pc
+        ).await.expect_err("claimed elsewhere");
+        assert_eq!(ChargeError::DuplicateTransaction("test".into()), error);
+
+        let event = dao.clone().get_transaction_event_by_event_token(&event_token).await.expect("exists");
+        assert_eq!(claim.id, event.id);
+        assert_eq!(None, event.processed_at);
+    }
+
+    #[test]
+    async fn test_split_tender_covers_charge_across_cards() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let mut user = create_user().await;
+        user.split_tender_enabled = true;
+        let card_1 = create_wallet(&user).await;
+        let card_2 = create_wallet(&user).await;
+        let payment_method_1 = card_1.payment_method_id.clone();
+        let payment_method_2 = card_2.payment_method_id.clone();
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+        let partial_cents = 600;
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp_1 = PaymentResponse::new();
+        resp_1.result_code = Some(ResultCode::PartiallyAuthorised);
+        resp_1.psp_reference = Some(Uuid::new_v4().to_string());
+        resp_1.amount = Some(Box::new(Amount { currency: "USD".to_string(), value: partial_cents as i64 }));
+        let mut resp_2 = PaymentResponse::new();
+        resp_2.result_code = Some(ResultCode::Authorised);
+        resp_2.psp_reference = Some(Uuid::new_v4().to_string());
+
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(
+                move |charge_request| {
+                    charge_request.payment_method_id == payment_method_1.to_string()
+                        && charge_request.amount_cents == amount_cents
+                        && charge_request.allow_partial_authorization
+                }
+            )
+            .times(1)
+            .return_once(move |_| Ok(resp_1));
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(
+                move |charge_request| {
+                    charge_request.payment_method_id == payment_method_2.to_string()
+                        && charge_request.amount_cents == amount_cents - partial_cents
+                }
+            )
+            .times(1)
+            .return_once(move |_| Ok(resp_2));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        let res = engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card_1.clone().into(), card_2.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaResponseResult::Approved, res.result);
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx: RegisteredTransactionModel = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists").into();
+        let wallet_charges = dao.clone().get_successful_wallet_charges_by_registered_transaction(rtx.id).await.expect("ok");
+        assert_eq!(2, wallet_charges.len());
+        assert_eq!(card_1.id, wallet_charges[0].wallet_card_id);
+        assert_eq!(partial_cents, wallet_charges[0].amount_cents);
+        assert_eq!(card_2.id, wallet_charges[1].wallet_card_id);
+        assert_eq!(amount_cents - partial_cents, wallet_charges[1].amount_cents);
+
+        let end_to_end_charge = dao.clone().get_successful_end_to_end_charge_by_registered_transaction_id(rtx.id).await.expect("exists");
+        let links = dao.clone().get_end_to_end_charge_wallet_card_charges(end_to_end_charge.id).await.expect("ok");
+        assert_eq!(2, links.len());
+    }
+
+    #[test]
+    async fn test_void_after_partial_clearing_only_cancels_uncaptured_split() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let mut user = create_user().await;
+        user.split_tender_enabled = true;
+        let card_1 = create_wallet(&user).await;
+        let card_2 = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let amount_cents = metadata.amount_cents;
+        let partial_cents = 600;
+        let psp_ref_2 = Uuid::new_v4().to_string();
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp_1 = PaymentResponse::new();
+        resp_1.result_code = Some(ResultCode::PartiallyAuthorised);
+        resp_1.psp_reference = Some(Uuid::new_v4().to_string());
+        resp_1.amount = Some(Box::new(Amount { currency: "USD".to_string(), value: partial_cents as i64 }));
+        let mut resp_2 = PaymentResponse::new();
+        resp_2.result_code = Some(ResultCode::Authorised);
+        resp_2.psp_reference = Some(psp_ref_2.clone());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp_1));
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .times(1)
+            .return_once(move |_| Ok(resp_2));
+        footprint_mock.expect_proxy_adyen_capture_request()
+            .withf(move |capture_request| capture_request.amount_cents == partial_cents)
+            .times(1)
+            .return_once(move |request| Ok(PaymentCaptureResponse::new(
+                Amount { currency: "USD".to_string(), value: partial_cents as i64 },
+                "merchant".to_string(),
+                request.psp_reference.to_string(),
+                Uuid::new_v4().to_string(),
+                adyen_checkout::models::payment_capture_response::Status::Received
+            )));
+        let cancel_psp_ref = psp_ref_2.clone();
+        let cancel_resp = PaymentCancelResponse::new(
+            "SandellEnterprisesECOM".to_string(),
+            psp_ref_2.clone(),
+            Uuid::new_v4().to_string(),
+            Status::Received
+        );
+        footprint_mock.expect_proxy_adyen_cancel_request()
+            .withf(move |psp_reference| psp_reference == cancel_psp_ref)
+            .times(1)
+            .return_once(move |_| Ok(cancel_resp));
+        // the void only covers what never cleared, so the captured card keeps its money
+        footprint_mock.expect_proxy_adyen_refund_request()
+            .times(0);
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card_1.clone().into(), card_2.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Clearing,
+            &Uuid::new_v4().to_string(),
+            partial_cents,
+            &pc
+        ).await.expect("no error");
+        engine.clone().process_transaction_event(
+            &transaction_token,
+            &TransactionEventType::Void,
+            &Uuid::new_v4().to_string(),
+            amount_cents - partial_cents,
+            &pc
+        ).await.expect("no error");
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists");
+        let wallet_charges = dao.clone().get_successful_wallet_charges_by_registered_transaction(rtx.id).await.expect("ok");
+        let captured = dao.clone().get_wallet_charge_by_id(wallet_charges[0].id).await.expect("exists");
+        assert_eq!(Some(CaptureStatus::Captured), captured.capture_status);
+        assert_eq!(Some(partial_cents), captured.captured_amount_cents);
+        assert!(dao.clone().get_wallet_charge_refunds_by_wallet_charge_id(captured.id).await.expect("ok").is_empty());
+        let cancelled = dao.clone().get_wallet_charge_by_id(wallet_charges[1].id).await.expect("exists");
+        assert_eq!(Some(CaptureStatus::Cancelled), cancelled.capture_status);
+    }
+
+    #[test]
+    async fn test_split_tender_rolls_back_when_not_covered() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let mut user = create_user().await;
+        user.split_tender_enabled = true;
+        let rtx = create_registered_transaction(&user, &metadata).await;
+        let card_1 = create_wallet(&user).await;
+        let card_2 = create_wallet(&user).await;
+        let payment_method_1 = card_1.payment_method_id.clone();
+        let payment_method_2 = card_2.payment_method_id.clone();
+        let psp_ref = Uuid::new_v4().to_string();
+        let cancel_psp_ref = Uuid::new_v4().to_string();
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp_1 = PaymentResponse::new();
+        resp_1.result_code = Some(ResultCode::PartiallyAuthorised);
+        resp_1.psp_reference = Some(psp_ref.clone());
+        resp_1.amount = Some(Box::new(Amount { currency: "USD".to_string(), value: 600 }));
+        let mut resp_2 = PaymentResponse::new();
+        resp_2.result_code = Some(ResultCode::Refused);
+        resp_2.psp_reference = Some(Uuid::new_v4().to_string());
+
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(move |charge_request| charge_request.payment_method_id == payment_method_1.to_string())
+            .times(1)
+            .return_once(move |_| Ok(resp_1));
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(move |charge_request| charge_request.payment_method_id == payment_method_2.to_string())
+            .times(1)
+            .return_once(move |_| Ok(resp_2));
+
+        let cancel_resp = PaymentCancelResponse::new(
+            "SandellEnterprisesECOM".to_string(),
+            psp_ref.clone(),
+            cancel_psp_ref.clone(),
+            Status::Received
+        );
+        let expected_psp_ref = psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_cancel_request()
+            .withf(move |psp| psp == expected_psp_ref)
+            .times(1)
+            .return_once(move |_| Ok(cancel_resp));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let (res, charges) = engine.clone().charge_wallet(
+            &user,
+            &vec![card_1.clone().into(), card_2.clone().into()],
+            &metadata,
+            &rtx,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(ChargeEngineResult::Denied, res);
+        assert!(charges.is_empty());
+
+        let dao = Arc::new(ChargeDao::new());
+        let successful = dao.clone().get_successful_wallet_charges_by_registered_transaction(rtx.id).await.expect("ok");
+        assert!(successful.is_empty());
+        let wallet_charges = dao.clone().get_wallet_charges_by_registered_transaction(rtx.id).await.expect("ok");
+        let rolled_back = wallet_charges.iter().find(|charge| charge.wallet_card_id == card_1.id).expect("charge registered");
+        assert_eq!(ChargeStatus::Fail, rolled_back.resolved_charge_status);
+        let cancellation = dao.clone().get_wallet_charge_cancellation_by_wallet_charge_id(rolled_back.id).await.expect("cancellation registered");
+        assert_eq!(CancelStatus::Received, cancellation.cancel_status);
+        assert_eq!(Some(cancel_psp_ref), cancellation.cancel_psp_reference);
+    }
+
+    #[test]
+    async fn test_asa_payload_and_routing_trace_are_registered() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let card_1 = create_wallet(&user).await;
+        let card_2 = create_wallet(&user).await;
+        let payment_method_1 = card_1.payment_method_id.clone();
+        let payment_method_2 = card_2.payment_method_id.clone();
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp_1 = PaymentResponse::new();
+        resp_1.result_code = Some(ResultCode::Refused);
+        resp_1.psp_reference = Some(Uuid::new_v4().to_string());
+        resp_1.refusal_reason_code = Some("5".to_string());
+        let mut resp_2 = PaymentResponse::new();
+        resp_2.result_code = Some(ResultCode::Authorised);
+        resp_2.psp_reference = Some(Uuid::new_v4().to_string());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(move |charge_request| charge_request.payment_method_id == payment_method_1.to_string())
+            .times(1)
+            .return_once(move |_| Ok(resp_1));
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(move |charge_request| charge_request.payment_method_id == payment_method_2.to_string())
+            .times(1)
+            .return_once(move |_| Ok(resp_2));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(metadata.amount_cents, metadata.mcc.clone());
+        asa.token = Some(transaction_token.clone());
+        let res = engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card_1.clone().into(), card_2.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaResponseResult::Approved, res.result);
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists");
+        let registered_metadata = dao.clone().get_registered_transaction_metadata(rtx.id).await.expect("metadata registered");
+        let body: AsaRequest = serde_json::from_str(&registered_metadata.body.expect("body stored")).expect("body is the asa");
+        assert_eq!(Some(transaction_token), body.token);
+        assert_eq!(asa.amount, body.amount);
+
+        let routing_trace: RoutingTrace = serde_json::from_str(&registered_metadata.routing_trace.expect("trace stored")).expect("trace parses");
+        assert_eq!(Some(ChargeEngineResult::Approved), routing_trace.result);
+        assert_eq!(2, routing_trace.candidates.len());
+        assert_eq!(card_1.id, routing_trace.candidates[0].wallet_card_id);
+        assert_eq!(2, routing_trace.attempts.len());
+        assert_eq!(card_1.id, routing_trace.attempts[0].wallet_card_id);
+        assert_eq!(ChargeStatus::Fail, routing_trace.attempts[0].resolved_charge_status);
+        assert_eq!(Some("5".to_string()), routing_trace.attempts[0].refusal_reason_code);
+        assert_eq!(card_2.id, routing_trace.attempts[1].wallet_card_id);
+        assert!(routing_trace.attempts[1].is_success);
+    }
+
+    #[test]
+    async fn test_foreign_charge_in_merchant_currency() {
+        crate::test_helper::general::init();
+        let mut user = create_user().await;
+        user.charge_in_merchant_currency = true;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(Uuid::new_v4().to_string());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            // $10.00 at 1.1 dollars to the euro
+            .withf(|charge_request| charge_request.currency == "EUR" && charge_request.amount_cents == 909)
+            .times(1)
+            .return_once(move |_| Ok(resp));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(1000, "7184".to_string());
+        asa.token = Some(transaction_token.clone());
+        asa.merchant_amount = Some(909);
+        asa.merchant_currency = Some("EUR".to_string());
+        asa.conversion_rate = Some(1.1);
+        let res = engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaResponseResult::Approved, res.result);
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx: RegisteredTransactionModel = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists").into();
+        assert_eq!(1000, rtx.amount_cents);
+        assert_eq!("USD", rtx.currency);
+        assert_eq!(Some(909), rtx.merchant_amount_cents);
+        assert_eq!(Some("EUR".to_string()), rtx.merchant_currency);
+        assert_eq!(Some(1.1), rtx.conversion_rate);
+        assert_eq!("EUR", rtx.charge_currency);
+        assert_eq!(909, rtx.charge_amount(1000));
+        assert_eq!(1000, rtx.cardholder_amount(909));
+        let wallet_card_charges = dao.clone().get_wallet_charges_by_registered_transaction(rtx.id).await.expect("charges");
+        // the ledger stays in the cardholder currency
+        assert_eq!(1000, wallet_card_charges[0].amount_cents);
+    }
+
+    #[test]
+    async fn test_foreign_charge_in_cardholder_currency_by_default() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(Uuid::new_v4().to_string());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(|charge_request| charge_request.currency == "USD" && charge_request.amount_cents == 1000)
+            .times(1)
+            .return_once(move |_| Ok(resp));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(1000, "7184".to_string());
+        asa.token = Some(transaction_token.clone());
+        asa.merchant_amount = Some(909);
+        asa.merchant_currency = Some("EUR".to_string());
+        asa.conversion_rate = Some(1.1);
+        let res = engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaResponseResult::Approved, res.result);
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx: RegisteredTransactionModel = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists").into();
+        assert_eq!(Some("EUR".to_string()), rtx.merchant_currency);
+        assert_eq!(Some(1.1), rtx.conversion_rate);
+        assert_eq!("USD", rtx.charge_currency);
+        assert_eq!(1000, rtx.charge_amount(1000));
+    }
+
+    #[test]
+    async fn test_cash_portion_is_registered() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Authorised);
+        resp.psp_reference = Some(Uuid::new_v4().to_string());
+        // the card is charged for the whole amount, cash included
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(|charge_request| charge_request.amount_cents == 1000)
+            .times(1)
+            .return_once(move |_| Ok(resp));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(1000, "5411".to_string());
+        asa.token = Some(transaction_token.clone());
+        asa.cash_amount = Some(400);
+        let res = engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaResponseResult::Approved, res.result);
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx: RegisteredTransactionModel = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists").into();
+        assert_eq!(1000, rtx.amount_cents);
+        assert_eq!(Some(400), rtx.cash_amount_cents);
+    }
+
+    #[test]
+    async fn test_partial_approval_for_capable_terminal() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::PartiallyAuthorised);
+        resp.psp_reference = Some(Uuid::new_v4().to_string());
+        resp.amount = Some(Box::new(Amount { currency: "USD".to_string(), value: 600 }));
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(|charge_request| charge_request.amount_cents == 1000 && charge_request.allow_partial_authorization)
+            .times(1)
+            .return_once(move |_| Ok(resp));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(1000, "7184".to_string());
+        asa.token = Some(transaction_token.clone());
+        let res = engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaChargeResult { result: AsaResponseResult::Approved, approved_amount_cents: Some(600) }, res);
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx: RegisteredTransactionModel = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists").into();
+        assert_eq!(600, rtx.amount_cents);
+        assert_eq!(Some(1000), rtx.requested_amount_cents);
+
+        // lithic retrying the same asa gets the same partial answer
+        let previous = engine.clone().get_previous_result_for_request(&asa).await.expect("no error");
+        assert_eq!(Some(res), previous);
+    }
+
+    #[test]
+    async fn test_full_approval_releases_held_partial() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card_1 = create_wallet(&user).await;
+        let card_2 = create_wallet(&user).await;
+        let payment_method_1 = card_1.payment_method_id.clone();
+        let payment_method_2 = card_2.payment_method_id.clone();
+        let pc = create_passthrough_card(&user).await;
+        let transaction_token = Uuid::new_v4().to_string();
+        let psp_ref = Uuid::new_v4().to_string();
+
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp_1 = PaymentResponse::new();
+        resp_1.result_code = Some(ResultCode::PartiallyAuthorised);
+        resp_1.psp_reference = Some(psp_ref.clone());
+        resp_1.amount = Some(Box::new(Amount { currency: "USD".to_string(), value: 600 }));
+        let mut resp_2 = PaymentResponse::new();
+        resp_2.result_code = Some(ResultCode::Authorised);
+        resp_2.psp_reference = Some(Uuid::new_v4().to_string());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(move |charge_request| charge_request.payment_method_id == payment_method_1.to_string())
+            .times(1)
+            .return_once(move |_| Ok(resp_1));
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(move |charge_request| charge_request.payment_method_id == payment_method_2.to_string() && charge_request.amount_cents == 1000)
+            .times(1)
+            .return_once(move |_| Ok(resp_2));
+
+        let cancel_resp = PaymentCancelResponse::new(
+            "SandellEnterprisesECOM".to_string(),
+            psp_ref.clone(),
+            Uuid::new_v4().to_string(),
+            Status::Received
+        );
+        let expected_psp_ref = psp_ref.clone();
+        footprint_mock.expect_proxy_adyen_cancel_request()
+            .withf(move |psp| psp == expected_psp_ref)
+            .times(1)
+            .return_once(move |_| Ok(cancel_resp));
+
+        let footprint_service = Arc::new(footprint_mock);
+        let user_service = Arc::new(UserService::new_with_services(footprint_service.clone()));
+        let ledger_serivice = Arc::new(LedgerService::new());
+        let engine = Arc::new(ChargeService::new_with_services(
+            user_service.clone(),
+            ledger_serivice.clone(),
+            footprint_service
+        ));
+
+        let mut asa = create_example_asa(1000, "7184".to_string());
+        asa.token = Some(transaction_token.clone());
+        let res = engine.clone().charge_from_asa_request(
+            &asa,
+            &vec![card_1.clone().into(), card_2.clone().into()],
+            &pc,
+            &user,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(AsaChargeResult::from(AsaResponseResult::Approved), res);
+
+        let dao = Arc::new(ChargeDao::new());
+        let rtx: RegisteredTransactionModel = dao.clone().get_registered_transaction_by_lithic_transaction_token(&transaction_token).await.expect("exists").into();
+        assert_eq!(1000, rtx.amount_cents);
+        assert_eq!(None, rtx.requested_amount_cents);
+        let successful = dao.clone().get_successful_wallet_charges_by_registered_transaction(rtx.id).await.expect("ok");
+        assert_eq!(1, successful.len());
+        assert_eq!(card_2.id, successful[0].wallet_card_id);
+    }
+
     async fn create_registered_transaction(
         user: &UserModel,
         metadata: &TransactionMetadata
@@ -533,6 +2835,13 @@ mod tests {
                     memo: &metadata_clone.memo,
                     amount_cents: metadata_clone.amount_cents,
                     mcc: &metadata_clone.mcc,
+                    lithic_transaction_token: None,
+                    currency: "USD",
+                    merchant_amount_cents: None,
+                    merchant_currency: None,
+                    conversion_rate: None,
+                    charge_currency: "USD",
+                    cash_amount_cents: None
                 }).await
             })).await.unwrap().into();
         rtx
@@ -0,0 +1,66 @@
+// ISO 4217 currencies whose minor unit isn't a hundredth, anything not listed has two decimals
+const ZERO_DECIMAL_CURRENCIES: [&str; 16] = [
+    "BIF", "CLP", "DJF", "GNF", "ISK", "JPY", "KMF", "KRW",
+    "PYG", "RWF", "UGX", "VND", "VUV", "XAF", "XOF", "XPF"
+];
+const THREE_DECIMAL_CURRENCIES: [&str; 7] = ["BHD", "IQD", "JOD", "KWD", "LYD", "OMR", "TND"];
+
+pub fn minor_unit_exponent(currency: &str) -> i32 {
+    if ZERO_DECIMAL_CURRENCIES.contains(&currency) {
+        0
+    } else if THREE_DECIMAL_CURRENCIES.contains(&currency) {
+        3
+    } else {
+        2
+    }
+}
+
+pub fn is_currency_code(currency: &str) -> bool {
+    currency.len() == 3 && currency.chars().all(|c| c.is_ascii_uppercase())
+}
+
+// lithic's conversion rate is cardholder currency per unit of merchant currency
+pub fn cardholder_to_merchant_amount(amount: i32, cardholder_currency: &str, merchant_currency: &str, conversion_rate: f64) -> i32 {
+    let major = amount as f64 / 10f64.powi(minor_unit_exponent(cardholder_currency));
+    (major / conversion_rate * 10f64.powi(minor_unit_exponent(merchant_currency))).round() as i32
+}
+
+pub fn merchant_to_cardholder_amount(amount: i32, cardholder_currency: &str, merchant_currency: &str, conversion_rate: f64) -> i32 {
+    let major = amount as f64 / 10f64.powi(minor_unit_exponent(merchant_currency));
+    (major * conversion_rate * 10f64.powi(minor_unit_exponent(cardholder_currency))).round() as i32
+}
+
+#[cfg(test)]
+mod test {
+    use crate::common::currency::{cardholder_to_merchant_amount, is_currency_code, merchant_to_cardholder_amount, minor_unit_exponent};
+
+    #[test]
+    pub fn test_minor_unit_exponent() {
+        assert_eq!(2, minor_unit_exponent("USD"));
+        assert_eq!(2, minor_unit_exponent("EUR"));
+        assert_eq!(0, minor_unit_exponent("JPY"));
+        assert_eq!(3, minor_unit_exponent("KWD"));
+    }
+
+    #[test]
+    pub fn test_is_currency_code() {
+        assert!(is_currency_code("EUR"));
+        assert!(!is_currency_code("eur"));
+        assert!(!is_currency_code("978"));
+        assert!(!is_currency_code(""));
+    }
+
+    #[test]
+    pub fn test_convert_two_decimal_currencies() {
+        // 1 EUR = 1.10 USD
+        assert_eq!(1000, cardholder_to_merchant_amount(1100, "USD", "EUR", 1.1));
+        assert_eq!(1100, merchant_to_cardholder_amount(1000, "USD", "EUR", 1.1));
+    }
+
+    #[test]
+    pub fn test_convert_zero_decimal_currency() {
+        // 1 JPY = 0.0065 USD, $6.50 is 1000 yen
+        assert_eq!(1000, cardholder_to_merchant_amount(650, "USD", "JPY", 0.0065));
+        assert_eq!(650, merchant_to_cardholder_amount(1000, "USD", "JPY", 0.0065));
+    }
+}
@@ -1,12 +1,22 @@
 use serde::{Deserialize, Serialize};
 use crate::asa::request::AsaRequest;
+use crate::common::currency::is_currency_code;
+use crate::constant::financial_constant;
 use crate::error::data_error::DataError;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TransactionMetadata {
     pub memo: String,
     pub amount_cents: i32,
-    pub mcc: String
+    pub mcc: String,
+    pub currency: String,
+    pub merchant_amount_cents: Option<i32>,
+    pub merchant_currency: Option<String>,
+    pub conversion_rate: Option<f64>,
+    pub partial_approval_capable: bool,
+    pub avs_address: Option<String>,
+    pub avs_zipcode: Option<String>,
+    pub cash_amount_cents: Option<i32>
 }
 
 
@@ -17,20 +27,62 @@ impl TransactionMetadata {
         let descriptor = merchant.descriptor.clone().ok_or(DataError::Format("missing descriptor".into()))?;
         let mcc = merchant.mcc.clone().ok_or(DataError::Format("missing mcc".into()))?;
         let amount = request.amount.ok_or(DataError::Format("missing amount".into()))?;
+        let merchant_currency = request.merchant_currency.clone()
+            .map(|currency| currency.to_uppercase())
+            .filter(|currency| is_currency_code(currency));
+        let partial_approval_capable = request.pos.as_ref()
+            .and_then(|pos| pos.terminal.as_ref())
+            .and_then(|terminal| terminal.partial_approval_capable)
+            .unwrap_or(false);
+        let avs_address = request.avs.as_ref()
+            .and_then(|avs| avs.address.clone())
+            .filter(|address| !address.trim().is_empty());
+        let avs_zipcode = request.avs.as_ref()
+            .and_then(|avs| avs.zipcode.clone())
+            .filter(|zipcode| !zipcode.trim().is_empty());
+        // atm withdrawals and cashback come in as part of the amount
+        let cash_amount_cents = request.cash_amount
+            .filter(|cash| *cash > 0)
+            .map(|cash| cash.min(amount));
+        // going through the string keeps 1.1 as 1.1 instead of the f32 noise widening would add
+        let conversion_rate = request.conversion_rate
+            .and_then(|rate| rate.to_string().parse::<f64>().ok())
+            .filter(|rate| *rate > 0.0);
         Ok(
             TransactionMetadata {
                 memo: descriptor,
                 amount_cents: amount,
-                mcc: mcc
+                mcc: mcc,
+                // lithic authorizes in the cardholder currency, which is always dollars for our cards
+                currency: financial_constant::USD.to_string(),
+                merchant_amount_cents: request.merchant_amount,
+                merchant_currency,
+                conversion_rate,
+                partial_approval_capable,
+                avs_address,
+                avs_zipcode,
+                cash_amount_cents
             }
         )
     }
+
+    pub fn is_foreign(&self) -> bool {
+        self.merchant_currency.as_ref().is_some_and(|currency| currency != &self.currency)
+    }
+
+    // a foreign purchase can only go to the card in the merchant currency when we have the rate to convert back with
+    pub fn charge_currency(&self, charge_in_merchant_currency: bool) -> &str {
+        match (&self.merchant_currency, self.conversion_rate) {
+            (Some(merchant_currency), Some(_)) if charge_in_merchant_currency && self.is_foreign() => merchant_currency,
+            _ => &self.currency
+        }
+    }
 }
 
 
 #[cfg(test)]
 mod test {
-    use crate::asa::request::{AsaRequest, Merchant};
+    use crate::asa::request::{create_example_asa, AsaRequest, Avs, Merchant};
     use crate::common::model::TransactionMetadata;
     use crate::error::data_error::DataError;
 
@@ -76,6 +128,80 @@ mod test {
         assert_eq!(AMOUNT, txn.amount_cents);
         assert_eq!(DESCRIPTOR, txn.memo.as_str());
         assert_eq!(MCC, txn.mcc.as_str());
+        assert_eq!("USD", txn.currency.as_str());
+        assert!(!txn.is_foreign());
+        assert_eq!("USD", txn.charge_currency(true));
+        assert!(!txn.partial_approval_capable);
+        assert_eq!(None, txn.avs_address);
+        assert_eq!(None, txn.avs_zipcode);
+        assert_eq!(None, txn.cash_amount_cents);
+    }
+
+    #[test]
+    pub fn test_convert_cash() {
+        let mut req = create_example_asa(AMOUNT, MCC.to_string());
+        let txn = TransactionMetadata::convert(&req).expect("Should be no error");
+        assert_eq!(None, txn.cash_amount_cents);
+
+        req.cash_amount = Some(40);
+        let txn = TransactionMetadata::convert(&req).expect("Should be no error");
+        assert_eq!(Some(40), txn.cash_amount_cents);
+        assert_eq!(AMOUNT, txn.amount_cents);
+
+        req.cash_amount = Some(AMOUNT + 1);
+        let txn = TransactionMetadata::convert(&req).expect("Should be no error");
+        assert_eq!(Some(AMOUNT), txn.cash_amount_cents);
+    }
+
+    #[test]
+    pub fn test_convert_avs() {
+        let mut req = create_example_asa(AMOUNT, MCC.to_string());
+        let txn = TransactionMetadata::convert(&req).expect("Should be no error");
+        assert_eq!(Some("test address".to_string()), txn.avs_address);
+        assert_eq!(Some("10017".to_string()), txn.avs_zipcode);
+
+        req.avs = Some(Avs { address: Some(" ".to_string()), zipcode: Some("10017".to_string()) });
+        let txn = TransactionMetadata::convert(&req).expect("Should be no error");
+        assert_eq!(None, txn.avs_address);
+        assert_eq!(Some("10017".to_string()), txn.avs_zipcode);
+    }
+
+    #[test]
+    pub fn test_convert_partial_approval_capable() {
+        let req = create_example_asa(AMOUNT, MCC.to_string());
+        let txn = TransactionMetadata::convert(&req).expect("Should be no error");
+        assert!(txn.partial_approval_capable);
+    }
+
+    #[test]
+    pub fn test_convert_foreign() {
+        let mut req = create_example_asa(AMOUNT, MCC.to_string());
+        req.merchant_amount = Some(91);
+        req.merchant_currency = Some("eur".to_string());
+        req.conversion_rate = Some(1.1);
+
+        let txn = TransactionMetadata::convert(&req).expect("Should be no error");
+        assert_eq!(AMOUNT, txn.amount_cents);
+        assert_eq!("USD", txn.currency.as_str());
+        assert_eq!(Some(91), txn.merchant_amount_cents);
+        assert_eq!(Some("EUR".to_string()), txn.merchant_currency);
+        assert_eq!(Some(1.1), txn.conversion_rate);
+        assert!(txn.is_foreign());
+        assert_eq!("EUR", txn.charge_currency(true));
+        assert_eq!("USD", txn.charge_currency(false));
+    }
+
+    #[test]
+    pub fn test_convert_foreign_without_rate_charges_cardholder_currency() {
+        let mut req = create_example_asa(AMOUNT, MCC.to_string());
+        req.merchant_amount = Some(91);
+        req.merchant_currency = Some("EUR".to_string());
+        req.conversion_rate = Some(0.0);
+
+        let txn = TransactionMetadata::convert(&req).expect("Should be no error");
+        assert_eq!(None, txn.conversion_rate);
+        assert!(txn.is_foreign());
+        assert_eq!("USD", txn.charge_currency(true));
     }
 
     #[test]
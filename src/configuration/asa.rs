@@ -0,0 +1,15 @@
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_number_from_string;
+
+
+#[derive(Deserialize, Clone)]
+pub struct AsaConfiguration {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub budget_ms: u64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub minimum_attempt_ms: u64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub maximum_attempt_ms: u64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub cleanup_timeout_ms: u64
+}
@@ -0,0 +1,15 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AvsPolicy {
+    Ignore,
+    DeclineOnFail,
+    RequireMatch
+}
+
+#[derive(Deserialize, Clone)]
+pub struct AvsConfiguration {
+    pub enabled: bool,
+    pub policy: AvsPolicy
+}
@@ -0,0 +1,17 @@
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_number_from_string;
+
+#[derive(Deserialize, Clone)]
+pub struct CardHealthConfiguration {
+    pub enabled: bool,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub lookback: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub demote_after_failures: i32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub open_after_failures: i32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub open_seconds: i64,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub trial_seconds: i64
+}
@@ -0,0 +1,13 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum CashPolicy {
+    Decline,
+    CashAdvanceCardsOnly
+}
+
+#[derive(Deserialize, Clone)]
+pub struct CashConfiguration {
+    pub policy: CashPolicy
+}
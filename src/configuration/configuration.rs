@@ -3,13 +3,18 @@ use serde::Deserialize;
 use tokio::sync::OnceCell;
 use crate::configuration::adyen::AdyenConfiguration;
 use crate::configuration::application::ApplicationConfiguration;
+use crate::configuration::asa::AsaConfiguration;
 use crate::configuration::auth0::Auth0Configuration;
+use crate::configuration::avs::AvsConfiguration;
+use crate::configuration::card_health::CardHealthConfiguration;
+use crate::configuration::cash::CashConfiguration;
 use crate::configuration::database::DatabaseConfiguration;
 use crate::configuration::environment::Environment;
 use crate::configuration::footprint::FootprintConfiguration;
 use crate::configuration::lithic::LithicConfiguration;
 use crate::configuration::otel::OtelConfiguration;
 use crate::configuration::redis::RedisConfiguration;
+use crate::configuration::stand_in::StandInConfiguration;
 
 static CONFIGURATION: OnceCell<Configuration> = OnceCell::const_new();
 
@@ -22,7 +27,12 @@ pub struct Configuration {
     pub adyen: AdyenConfiguration,
     pub auth0: Auth0Configuration,
     pub otel: OtelConfiguration,
-    pub lithic: LithicConfiguration
+    pub lithic: LithicConfiguration,
+    pub asa: AsaConfiguration,
+    pub stand_in: StandInConfiguration,
+    pub avs: AvsConfiguration,
+    pub card_health: CardHealthConfiguration,
+    pub cash: CashConfiguration
 }
 
 
@@ -7,4 +7,9 @@ pub mod application;
 pub mod adyen;
 pub mod auth0;
 pub mod otel;
-pub mod lithic;
\ No newline at end of file
+pub mod lithic;
+pub mod asa;
+pub mod stand_in;
+pub mod avs;
+pub mod card_health;
+pub mod cash;
\ No newline at end of file
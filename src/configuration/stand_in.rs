@@ -0,0 +1,20 @@
+use serde::Deserialize;
+use serde_aux::field_attributes::deserialize_number_from_string;
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StandInPolicy {
+    Decline,
+    ApproveUnderThreshold
+}
+
+#[derive(Deserialize, Clone)]
+pub struct StandInConfiguration {
+    pub policy: StandInPolicy,
+    pub decline_result: String,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub approve_threshold_cents: i32,
+    pub journal_path: String,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub reconcile_interval_seconds: u64
+}
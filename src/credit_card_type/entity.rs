@@ -21,7 +21,8 @@ pub struct CreditCard {
     pub credit_card_issuer_id: i32,
     pub card_image_url: String,
     pub created_at: NaiveDateTime,
-    pub updated_at: NaiveDateTime
+    pub updated_at: NaiveDateTime,
+    pub rewards_program_id: Option<i32>
 }
 
 #[derive(Queryable, Debug, Identifiable, Selectable, Clone)]
@@ -11,6 +11,7 @@ pub struct CreditCardModel {
     pub credit_card_type_id: i32,
     pub credit_card_issuer_id: i32,
     pub card_image_url: String,
+    pub rewards_program_id: Option<i32>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -49,6 +50,7 @@ impl From<CreditCard> for CreditCardModel {
             credit_card_type_id: value.credit_card_type_id,
             credit_card_issuer_id: value.credit_card_issuer_id,
             card_image_url: value.card_image_url,
+            rewards_program_id: value.rewards_program_id,
         }
     }
 }
@@ -118,6 +120,7 @@ mod test {
             card_image_url: CARD_IMAGE_URL.to_string(),
             created_at: Default::default(),
             updated_at: Default::default(),
+            rewards_program_id: Some(1),
         };
         let model = CreditCardModel::from(card.clone());
         assert_eq!(model.id, card.id);
@@ -126,6 +129,7 @@ mod test {
         assert_eq!(model.name, card.name);
         assert_eq!(model.credit_card_issuer_id, card.credit_card_issuer_id);
         assert_eq!(model.credit_card_type_id, card.credit_card_type_id);
+        assert_eq!(model.rewards_program_id, card.rewards_program_id);
     }
 
     #[test]
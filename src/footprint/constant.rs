@@ -3,6 +3,18 @@ pub mod Constant {
     pub const PROXY_METHOD: &str = "POST";
     pub const PROXY_ACCESS_REASON: &str = "Charge Proxy";
     pub const PROXY_URL: &str = "https://checkout-test.adyen.com/v71/payments";
+    pub const PROXY_STANDALONE_CANCEL_URL: &str = "https://checkout-test.adyen.com/v71/cancels";
+    pub const PROXY_CANCEL_SUFFIX: &str = "/cancels";
+    pub const PROXY_CAPTURE_SUFFIX: &str = "/captures";
+    pub const PROXY_REFUND_SUFFIX: &str = "/refunds";
+    pub const PROXY_AMOUNT_UPDATE_SUFFIX: &str = "/amountUpdates";
+    pub const ALLOW_PARTIAL_AUTH_KEY: &str = "allowPartialAuth";
+    pub const MANUAL_CAPTURE_KEY: &str = "manualCapture";
+    pub const DECRYPT_ACCESS_REASON: &str = "Address Verification";
+    pub const ADDRESS_LINE_1_FIELD: &str = "id.address_line1";
+    pub const ZIP_FIELD: &str = "id.zip";
+    pub const CITY_FIELD: &str = "id.city";
+    pub const COUNTRY_FIELD: &str = "id.country";
 
     pub const TTL: i32 = 120; // 120 seconds to create a card after issuing token
 }
@@ -17,5 +29,17 @@ mod test {
         assert_eq!("Charge Proxy", Constant::PROXY_ACCESS_REASON);
         assert_eq!(120, Constant::TTL);
         assert_eq!("https://checkout-test.adyen.com/v71/payments", Constant::PROXY_URL);
+        assert_eq!("https://checkout-test.adyen.com/v71/cancels", Constant::PROXY_STANDALONE_CANCEL_URL);
+        assert_eq!("/cancels", Constant::PROXY_CANCEL_SUFFIX);
+        assert_eq!("/captures", Constant::PROXY_CAPTURE_SUFFIX);
+        assert_eq!("/refunds", Constant::PROXY_REFUND_SUFFIX);
+        assert_eq!("/amountUpdates", Constant::PROXY_AMOUNT_UPDATE_SUFFIX);
+        assert_eq!("allowPartialAuth", Constant::ALLOW_PARTIAL_AUTH_KEY);
+        assert_eq!("manualCapture", Constant::MANUAL_CAPTURE_KEY);
+        assert_eq!("Address Verification", Constant::DECRYPT_ACCESS_REASON);
+        assert_eq!("id.address_line1", Constant::ADDRESS_LINE_1_FIELD);
+        assert_eq!("id.zip", Constant::ZIP_FIELD);
+        assert_eq!("id.city", Constant::CITY_FIELD);
+        assert_eq!("id.country", Constant::COUNTRY_FIELD);
     }
 }
\ No newline at end of file
@@ -1,7 +1,7 @@
 use std::ops::Add;
 use footprint::models::CreateClientTokenRequest;
 use crate::footprint::r#enum::CardPart;
-use crate::footprint::constant::Constant::TTL;
+use crate::footprint::constant::Constant::{PROXY_AMOUNT_UPDATE_SUFFIX, PROXY_CANCEL_SUFFIX, PROXY_CAPTURE_SUFFIX, PROXY_REFUND_SUFFIX, PROXY_URL, TTL};
 
 pub fn card_request_parts_for_card_id(card_id: &str) -> Vec<String> {
     // given card, return
@@ -42,6 +42,40 @@ pub fn individual_request_part_for_customer_with_suffix_template(customer_id: &s
     return "{{ ".to_string().add(&customer_id).add(".card.").add(card_id).add(".").add(part.as_str()).add(" | suffix(4) }}");
 }
 
+pub fn vault_field_template(customer_id: &str, field: &str) -> String {
+    // {{ CCCC.id.city }}
+    return "{{ ".to_string().add(customer_id).add(".").add(field).add(" }}");
+}
+
+pub fn cancel_url_for_psp_reference(psp_reference: &str) -> String {
+    // https://checkout-test.adyen.com/v71/payments/PSP/cancels
+    return PROXY_URL.to_string().add("/").add(psp_reference).add(PROXY_CANCEL_SUFFIX);
+}
+
+pub fn capture_url_for_psp_reference(psp_reference: &str) -> String {
+    // https://checkout-test.adyen.com/v71/payments/PSP/captures
+    return PROXY_URL.to_string().add("/").add(psp_reference).add(PROXY_CAPTURE_SUFFIX);
+}
+
+pub fn refund_url_for_psp_reference(psp_reference: &str) -> String {
+    // https://checkout-test.adyen.com/v71/payments/PSP/refunds
+    return PROXY_URL.to_string().add("/").add(psp_reference).add(PROXY_REFUND_SUFFIX);
+}
+
+pub fn amount_update_url_for_psp_reference(psp_reference: &str) -> String {
+    // https://checkout-test.adyen.com/v71/payments/PSP/amountUpdates
+    return PROXY_URL.to_string().add("/").add(psp_reference).add(PROXY_AMOUNT_UPDATE_SUFFIX);
+}
+
+pub fn split_street_address(address: &str) -> (String, String) {
+    // "123 Main St" -> ("123", "Main St"), adyen wants the house number on its own
+    let address = address.trim();
+    match address.split_once(char::is_whitespace) {
+        Some((number, street)) if number.chars().any(|c| c.is_ascii_digit()) => (number.to_string(), street.trim().to_string()),
+        _ => (String::new(), address.to_string())
+    }
+}
+
 pub fn get_scopes_for_request() -> Vec<String> {
     vec!["vault".to_string()]
 }
@@ -61,7 +95,7 @@ mod helper_tests {
     use crate::footprint::r#enum::CardPart;
     use actix_web;
     use crate::footprint::constant::Constant::TTL;
-    use crate::footprint::helper::{card_request_parts_for_card_id, individual_request_part, individual_request_part_for_customer_with_prefix_template, individual_request_part_for_customer_with_suffix_template, individual_request_part_for_customer_template, individual_request_part_for_customer, get_scopes_for_request, create_get_token_request};
+    use crate::footprint::helper::{card_request_parts_for_card_id, individual_request_part, individual_request_part_for_customer_with_prefix_template, individual_request_part_for_customer_with_suffix_template, individual_request_part_for_customer_template, individual_request_part_for_customer, get_scopes_for_request, create_get_token_request, cancel_url_for_psp_reference, capture_url_for_psp_reference, refund_url_for_psp_reference, amount_update_url_for_psp_reference, split_street_address, vault_field_template};
 
     #[test]
     fn test_get_scopes_for_request() {
@@ -126,6 +160,12 @@ mod helper_tests {
         assert_eq!("{{ abc.card.1234.name }}", &individual_request_part_for_customer_template(customer_id, card_id, &CardPart::Name));
     }
 
+    #[test]
+    fn test_vault_field_template() {
+        assert_eq!("{{ abc.id.city }}", &vault_field_template("abc", "id.city"));
+        assert_eq!("{{ abc.id.country }}", &vault_field_template("abc", "id.country"));
+    }
+
     #[test]
     fn test_individual_request_part_for_customer_template_suffix() {
         let card_id = "1234";
@@ -139,4 +179,32 @@ mod helper_tests {
         let customer_id = "abc";
         assert_eq!("{{ abc.card.1234.expiration | prefix(2) }}", &individual_request_part_for_customer_with_prefix_template(customer_id, card_id, &CardPart::Expiration));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_cancel_url_for_psp_reference() {
+        assert_eq!("https://checkout-test.adyen.com/v71/payments/abc123/cancels", &cancel_url_for_psp_reference("abc123"));
+    }
+
+    #[test]
+    fn test_capture_url_for_psp_reference() {
+        assert_eq!("https://checkout-test.adyen.com/v71/payments/abc123/captures", &capture_url_for_psp_reference("abc123"));
+    }
+
+    #[test]
+    fn test_refund_url_for_psp_reference() {
+        assert_eq!("https://checkout-test.adyen.com/v71/payments/abc123/refunds", &refund_url_for_psp_reference("abc123"));
+    }
+
+    #[test]
+    fn test_amount_update_url_for_psp_reference() {
+        assert_eq!("https://checkout-test.adyen.com/v71/payments/abc123/amountUpdates", &amount_update_url_for_psp_reference("abc123"));
+    }
+
+    #[test]
+    fn test_split_street_address() {
+        assert_eq!(("123".to_string(), "Main St".to_string()), split_street_address("123 Main St"));
+        assert_eq!(("12B".to_string(), "Main St".to_string()), split_street_address(" 12B  Main St "));
+        assert_eq!(("".to_string(), "Main St".to_string()), split_street_address("Main St"));
+        assert_eq!(("".to_string(), "".to_string()), split_street_address(""));
+    }
+}
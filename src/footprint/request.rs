@@ -3,6 +3,7 @@ use uuid::Uuid;
 #[derive(Debug)]
 pub struct ChargeThroughProxyRequest<'a> {
     pub amount_cents: i32,
+    pub currency: &'a str,
     pub mcc: &'a str,
     pub payment_method_id: &'a str,
     pub customer_public_id: &'a str,
@@ -10,4 +11,16 @@ pub struct ChargeThroughProxyRequest<'a> {
     pub idempotency_key: &'a Uuid,
     pub reference: &'a str,
     pub statement: &'a str,
-}
\ No newline at end of file
+    pub allow_partial_authorization: bool,
+    pub manual_capture: bool,
+    pub avs_address: Option<&'a str>,
+    pub avs_zipcode: Option<&'a str>,
+}
+
+#[derive(Debug)]
+pub struct ModificationThroughProxyRequest<'a> {
+    pub psp_reference: &'a str,
+    pub amount_cents: i32,
+    pub currency: &'a str,
+    pub reference: &'a str,
+}
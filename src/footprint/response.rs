@@ -4,6 +4,12 @@ pub struct AddVaultResponse {
     pub id: String
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct VaultAddress {
+    pub address_line1: Option<String>,
+    pub zip: Option<String>
+}
+
 pub struct ClientTokenResponse {
     pub expires_at: NaiveDateTime,
     pub token: String
@@ -1,24 +1,26 @@
+use std::collections::HashMap;
 use std::env;
 use std::sync::Arc;
 use std::time::Duration;
-use adyen_checkout::models::{Amount, PaymentCancelResponse, PaymentRequest, PaymentRequestPaymentMethod, PaymentResponse};
+use adyen_checkout::models::{Amount, BillingAddress, PaymentAmountUpdateRequest, PaymentAmountUpdateResponse, PaymentCancelRequest, PaymentCancelResponse, PaymentCaptureRequest, PaymentCaptureResponse, PaymentRefundRequest, PaymentRefundResponse, PaymentRequest, PaymentRequestPaymentMethod, PaymentResponse, StandalonePaymentCancelRequest, StandalonePaymentCancelResponse};
 use adyen_checkout::models::payment_response::ResultCode;
 use async_trait::async_trait;
 
 use super::error::FootprintError;
 use footprint::apis::configuration::{ApiKey, BasicAuth, Configuration};
 use crate::configuration::configuration::Configuration as RouterConfiguration;
-use footprint::apis::default_api::{post_vault_proxy, create_user_vault, create_client_token, post_vault_proxy_jit};
-use footprint::models::{CreateClientTokenRequest, CreateClientTokenResponse, CreateUserVaultResponse};
+use footprint::apis::default_api::{post_vault_proxy, create_user_vault, create_client_token, decrypt_user_vault, post_vault_proxy_jit};
+use footprint::models::{CreateClientTokenRequest, CreateClientTokenResponse, CreateUserVaultResponse, DecryptUserVaultRequest};
 use mockall::automock;
 use rand::Rng;
 use secrecy::ExposeSecret;
 use serde_json::to_value;
-use crate::footprint::helper::{card_request_parts_for_card_id, get_scopes_for_request, individual_request_part_for_customer_template, individual_request_part_for_customer_with_prefix_template, individual_request_part_for_customer_with_suffix_template};
+use crate::footprint::helper::{amount_update_url_for_psp_reference, cancel_url_for_psp_reference, capture_url_for_psp_reference, refund_url_for_psp_reference, card_request_parts_for_card_id, get_scopes_for_request, split_street_address, vault_field_template, individual_request_part_for_customer_template, individual_request_part_for_customer_with_prefix_template, individual_request_part_for_customer_with_suffix_template};
 use crate::footprint::r#enum::CardPart;
-use crate::footprint::request::ChargeThroughProxyRequest;
+use crate::footprint::request::{ChargeThroughProxyRequest, ModificationThroughProxyRequest};
+use crate::footprint::response::VaultAddress;
 use crate::constant::financial_constant;
-use crate::footprint::constant::Constant::{CONTENT_TYPE, PROXY_ACCESS_REASON, PROXY_METHOD, PROXY_URL, TTL};
+use crate::footprint::constant::Constant::{ADDRESS_LINE_1_FIELD, ALLOW_PARTIAL_AUTH_KEY, CITY_FIELD, CONTENT_TYPE, COUNTRY_FIELD, DECRYPT_ACCESS_REASON, MANUAL_CAPTURE_KEY, PROXY_ACCESS_REASON, PROXY_METHOD, PROXY_STANDALONE_CANCEL_URL, PROXY_URL, TTL, ZIP_FIELD};
 use crate::user::model::UserModel as User;
 use tokio::time::sleep;
 use tonic::transport::server::Router;
@@ -33,6 +35,11 @@ pub trait FootprintServiceTrait {
     async fn proxy_adyen_payment_request<'a>(self: Arc<Self>, request: &ChargeThroughProxyRequest<'a>) -> Result<PaymentResponse, FootprintError>;
     async fn create_client_token(self: Arc<Self>, user: &User, card_id: &str) -> Result<CreateClientTokenResponse, FootprintError>;
     async fn proxy_adyen_cancel_request<'a>(self: Arc<Self>, psp_reference: &str) -> Result<PaymentCancelResponse, FootprintError>;
+    async fn proxy_adyen_cancel_by_reference_request<'a>(self: Arc<Self>, payment_reference: &str) -> Result<StandalonePaymentCancelResponse, FootprintError>;
+    async fn proxy_adyen_capture_request<'a>(self: Arc<Self>, request: &ModificationThroughProxyRequest<'a>) -> Result<PaymentCaptureResponse, FootprintError>;
+    async fn proxy_adyen_refund_request<'a>(self: Arc<Self>, request: &ModificationThroughProxyRequest<'a>) -> Result<PaymentRefundResponse, FootprintError>;
+    async fn proxy_adyen_amount_update_request<'a>(self: Arc<Self>, request: &ModificationThroughProxyRequest<'a>) -> Result<PaymentAmountUpdateResponse, FootprintError>;
+    async fn get_vault_address(self: Arc<Self>, footprint_vault_id: &str) -> Result<VaultAddress, FootprintError>;
 }
 
 pub struct FootprintService {
@@ -91,19 +98,43 @@ impl FootprintServiceTrait for FootprintService {
         let name = Some(
             to_value(individual_request_part_for_customer_template(request.footprint_vault_id, request.payment_method_id, &CardPart::Name))?
         );
+        // adyen only answers with a partial amount when asked to, and captures on auth unless told not to
+        let mut additional_data = HashMap::new();
+        if request.allow_partial_authorization {
+            additional_data.insert(ALLOW_PARTIAL_AUTH_KEY.to_string(), true.to_string());
+        }
+        if request.manual_capture {
+            additional_data.insert(MANUAL_CAPTURE_KEY.to_string(), true.to_string());
+        }
+        let additional_data = (!additional_data.is_empty()).then_some(additional_data);
+        // forward the merchant's avs data so the backing card's issuer checks the same address,
+        // the merchant doesn't send a city or country so footprint fills those in from the vault
+        let billing_address = request.avs_zipcode.map(|postal_code| {
+            let (house_number_or_name, street) = split_street_address(request.avs_address.unwrap_or_default());
+            Box::new(
+                BillingAddress {
+                    city: vault_field_template(request.footprint_vault_id, CITY_FIELD),
+                    country: vault_field_template(request.footprint_vault_id, COUNTRY_FIELD),
+                    house_number_or_name,
+                    postal_code: postal_code.to_string(),
+                    state_or_province: None,
+                    street
+                }
+            )
+        });
         let payment_request = Some(PaymentRequest {
             account_info: None,
             additional_amount: None,
-            additional_data: None,
+            additional_data,
             amount: Box::new(
                 Amount {
-                    currency: financial_constant::USD.to_string(),
+                    currency: request.currency.to_string(),
                     value: request.amount_cents as i64
                 }
             ),
             application_info: None,
             authentication_data: None,
-            billing_address: None,
+            billing_address,
             browser_info: None,
             capture_delay_hours: None,
             channel: None,
@@ -238,7 +269,154 @@ impl FootprintServiceTrait for FootprintService {
 
     #[tracing::instrument(skip(self))]
     async fn proxy_adyen_cancel_request<'a>(self: Arc<Self>, psp_reference: &str) -> Result<PaymentCancelResponse, FootprintError> {
-        Err(FootprintError::NotImplemented)
+        tracing::info!("Proxying cancel request for psp={}", psp_reference);
+        let cancel_request = PaymentCancelRequest {
+            application_info: None,
+            merchant_account: self.adyen_configuration.merchant_account_name.clone(),
+            reference: Some(psp_reference.to_string()),
+        };
+        let response = wrap_api_call(post_vault_proxy_jit(
+            &self.configuration,
+            CONTENT_TYPE,
+            &cancel_url_for_psp_reference(psp_reference),
+            PROXY_METHOD,
+            PROXY_ACCESS_REASON,
+            &self.adyen_configuration.api_key.expose_secret().clone(),
+            Some(
+                to_value(cancel_request)?
+            )
+        ).await)?;
+        tracing::info!("Successfully proxied cancel request");
+        let cancel_response: PaymentCancelResponse = serde_json::from_value(response)?;
+        tracing::info!("Successfully deserialized cancel response body");
+        Ok(cancel_response)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn proxy_adyen_cancel_by_reference_request<'a>(self: Arc<Self>, payment_reference: &str) -> Result<StandalonePaymentCancelResponse, FootprintError> {
+        // used when we gave up on a payment before seeing its psp reference
+        tracing::info!("Proxying cancel request for reference={}", payment_reference);
+        let mut cancel_request = StandalonePaymentCancelRequest::new(
+            self.adyen_configuration.merchant_account_name.clone(),
+            payment_reference.to_string()
+        );
+        cancel_request.reference = Some(payment_reference.to_string());
+        let response = wrap_api_call(post_vault_proxy_jit(
+            &self.configuration,
+            CONTENT_TYPE,
+            PROXY_STANDALONE_CANCEL_URL,
+            PROXY_METHOD,
+            PROXY_ACCESS_REASON,
+            &self.adyen_configuration.api_key.expose_secret().clone(),
+            Some(
+                to_value(cancel_request)?
+            )
+        ).await)?;
+        tracing::info!("Successfully proxied cancel request");
+        let cancel_response: StandalonePaymentCancelResponse = serde_json::from_value(response)?;
+        tracing::info!("Successfully deserialized cancel response body");
+        Ok(cancel_response)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn proxy_adyen_capture_request<'a>(self: Arc<Self>, request: &ModificationThroughProxyRequest<'a>) -> Result<PaymentCaptureResponse, FootprintError> {
+        tracing::info!("Proxying capture request for psp={}", request.psp_reference);
+        let mut capture_request = PaymentCaptureRequest::new(
+            Amount {
+                currency: request.currency.to_string(),
+                value: request.amount_cents as i64
+            },
+            self.adyen_configuration.merchant_account_name.clone()
+        );
+        capture_request.reference = Some(request.reference.to_string());
+        let response = wrap_api_call(post_vault_proxy_jit(
+            &self.configuration,
+            CONTENT_TYPE,
+            &capture_url_for_psp_reference(request.psp_reference),
+            PROXY_METHOD,
+            PROXY_ACCESS_REASON,
+            &self.adyen_configuration.api_key.expose_secret().clone(),
+            Some(
+                to_value(capture_request)?
+            )
+        ).await)?;
+        tracing::info!("Successfully proxied capture request");
+        let capture_response: PaymentCaptureResponse = serde_json::from_value(response)?;
+        Ok(capture_response)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn proxy_adyen_refund_request<'a>(self: Arc<Self>, request: &ModificationThroughProxyRequest<'a>) -> Result<PaymentRefundResponse, FootprintError> {
+        tracing::info!("Proxying refund request for psp={}", request.psp_reference);
+        let mut refund_request = PaymentRefundRequest::new(
+            Amount {
+                currency: request.currency.to_string(),
+                value: request.amount_cents as i64
+            },
+            self.adyen_configuration.merchant_account_name.clone()
+        );
+        refund_request.reference = Some(request.reference.to_string());
+        let response = wrap_api_call(post_vault_proxy_jit(
+            &self.configuration,
+            CONTENT_TYPE,
+            &refund_url_for_psp_reference(request.psp_reference),
+            PROXY_METHOD,
+            PROXY_ACCESS_REASON,
+            &self.adyen_configuration.api_key.expose_secret().clone(),
+            Some(
+                to_value(refund_request)?
+            )
+        ).await)?;
+        tracing::info!("Successfully proxied refund request");
+        let refund_response: PaymentRefundResponse = serde_json::from_value(response)?;
+        Ok(refund_response)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn proxy_adyen_amount_update_request<'a>(self: Arc<Self>, request: &ModificationThroughProxyRequest<'a>) -> Result<PaymentAmountUpdateResponse, FootprintError> {
+        // amount is the new total for the authorization, not the difference
+        tracing::info!("Proxying amount update request for psp={}", request.psp_reference);
+        let mut amount_update_request = PaymentAmountUpdateRequest::new(
+            Amount {
+                currency: request.currency.to_string(),
+                value: request.amount_cents as i64
+            },
+            self.adyen_configuration.merchant_account_name.clone()
+        );
+        amount_update_request.reference = Some(request.reference.to_string());
+        let response = wrap_api_call(post_vault_proxy_jit(
+            &self.configuration,
+            CONTENT_TYPE,
+            &amount_update_url_for_psp_reference(request.psp_reference),
+            PROXY_METHOD,
+            PROXY_ACCESS_REASON,
+            &self.adyen_configuration.api_key.expose_secret().clone(),
+            Some(
+                to_value(amount_update_request)?
+            )
+        ).await)?;
+        tracing::info!("Successfully proxied amount update request");
+        let amount_update_response: PaymentAmountUpdateResponse = serde_json::from_value(response)?;
+        Ok(amount_update_response)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_vault_address(self: Arc<Self>, footprint_vault_id: &str) -> Result<VaultAddress, FootprintError> {
+        tracing::info!("Decrypting address on file for vault");
+        let decrypted = to_value(wrap_api_call(decrypt_user_vault(
+            &self.configuration,
+            footprint_vault_id,
+            DecryptUserVaultRequest {
+                fields: vec![ADDRESS_LINE_1_FIELD.to_string(), ZIP_FIELD.to_string()],
+                reason: Some(DECRYPT_ACCESS_REASON.to_string()),
+            }
+        ).await)?)?;
+        Ok(
+            VaultAddress {
+                address_line1: decrypted.get(ADDRESS_LINE_1_FIELD).and_then(|value| value.as_str()).map(|value| value.to_string()),
+                zip: decrypted.get(ZIP_FIELD).and_then(|value| value.as_str()).map(|value| value.to_string()),
+            }
+        )
     }
 }
 
@@ -329,4 +507,29 @@ impl FootprintServiceTrait for FakeFootprintService {
     async fn proxy_adyen_cancel_request<'a>(self: Arc<Self>, psp_reference: &str) -> Result<PaymentCancelResponse, FootprintError> {
         Err(FootprintError::NotImplemented)
     }
-}
\ No newline at end of file
+
+    #[tracing::instrument(skip(self))]
+    async fn proxy_adyen_cancel_by_reference_request<'a>(self: Arc<Self>, payment_reference: &str) -> Result<StandalonePaymentCancelResponse, FootprintError> {
+        Err(FootprintError::NotImplemented)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn proxy_adyen_capture_request<'a>(self: Arc<Self>, request: &ModificationThroughProxyRequest<'a>) -> Result<PaymentCaptureResponse, FootprintError> {
+        Err(FootprintError::NotImplemented)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn proxy_adyen_refund_request<'a>(self: Arc<Self>, request: &ModificationThroughProxyRequest<'a>) -> Result<PaymentRefundResponse, FootprintError> {
+        Err(FootprintError::NotImplemented)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn proxy_adyen_amount_update_request<'a>(self: Arc<Self>, request: &ModificationThroughProxyRequest<'a>) -> Result<PaymentAmountUpdateResponse, FootprintError> {
+        Err(FootprintError::NotImplemented)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_vault_address(self: Arc<Self>, footprint_vault_id: &str) -> Result<VaultAddress, FootprintError> {
+        Err(FootprintError::NotImplemented)
+    }
+}
@@ -64,6 +64,7 @@ mod tests {
         let res = svc.clone().proxy_adyen_payment_request(
             &ChargeThroughProxyRequest {
                 amount_cents: 100,
+                currency: "USD",
                 mcc: "7184",
                 payment_method_id: "cb93d028-2a9f-4a57-9118-8a8933aa14f7",
                 customer_public_id: &Uuid::new_v4().to_string(),
@@ -71,6 +72,10 @@ mod tests {
                 idempotency_key: &Uuid::new_v4(),
                 reference:  &Uuid::new_v4().to_string(),
                 statement: "coffee",
+                allow_partial_authorization: false,
+                manual_capture: true,
+                avs_address: Some("1 Main St"),
+                avs_zipcode: Some("10017"),
             }
         ).await;
         match res {
@@ -21,7 +21,9 @@ pub enum MoneyMovementType {
     PassthroughCardRelease,
     WalletReserve,
     WalletSettle,
-    WalletRelease
+    WalletRelease,
+    PassthroughCardRefund,
+    WalletRefund
 }
 
 #[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, AsExpression, FromSqlRow)]
@@ -48,6 +50,8 @@ impl FromSql<Text, Pg> for MoneyMovementType {
             b"WALLET_RESERVE" => Ok(MoneyMovementType::WalletReserve),
             b"WALLET_RELEASE" => Ok(MoneyMovementType::WalletRelease),
             b"WALLET_SETTLE" => Ok(MoneyMovementType::WalletSettle),
+            b"PASSTHROUGH_CARD_REFUND" => Ok(MoneyMovementType::PassthroughCardRefund),
+            b"WALLET_REFUND" => Ok(MoneyMovementType::WalletRefund),
             v => Err(format!("Unknown value for MoneyMovementType found").into()),
         }
     }
@@ -62,6 +66,8 @@ impl fmt::Display for MoneyMovementType {
             MoneyMovementType::WalletReserve => "WALLET_RESERVE",
             MoneyMovementType::WalletSettle => "WALLET_SETTLE",
             MoneyMovementType::WalletRelease => "WALLET_RELEASE",
+            MoneyMovementType::PassthroughCardRefund => "PASSTHROUGH_CARD_REFUND",
+            MoneyMovementType::WalletRefund => "WALLET_REFUND",
         })
     }
 }
@@ -28,6 +28,9 @@ pub struct PendingPassthroughCardTransactionLedger {
     pub amount_cents: i32,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub currency: String,
+    pub conversion_rate: Option<f64>,
+    pub cash_amount_cents: Option<i32>,
 }
 
 #[derive(Serialize, Deserialize, Insertable, AsChangeset)]
@@ -39,6 +42,9 @@ pub struct InsertablePendingPassthroughCardTransactionLedger {
     pub money_movement_direction: MoneyMovementDirection,
     pub money_movement_type: MoneyMovementType,
     pub amount_cents: i32,
+    pub currency: String,
+    pub conversion_rate: Option<f64>,
+    pub cash_amount_cents: Option<i32>,
 }
 
 #[derive(Identifiable, Serialize, Deserialize, Queryable, Debug, Selectable, Clone, PartialEq)]
@@ -53,6 +59,9 @@ pub struct SettledPassthroughCardTransactionLedger {
     pub amount_cents: i32,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub currency: String,
+    pub conversion_rate: Option<f64>,
+    pub cash_amount_cents: Option<i32>,
 }
 
 #[derive(Serialize, Deserialize, Insertable, AsChangeset)]
@@ -64,6 +73,9 @@ pub struct InsertableSettledPassthroughCardTransactionLedger {
     pub money_movement_direction: MoneyMovementDirection,
     pub money_movement_type: MoneyMovementType,
     pub amount_cents: i32,
+    pub currency: String,
+    pub conversion_rate: Option<f64>,
+    pub cash_amount_cents: Option<i32>,
 }
 
 
@@ -79,6 +91,8 @@ pub struct PendingWalletTransactionLedger {
     pub amount_cents: i32,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub currency: String,
+    pub conversion_rate: Option<f64>,
 }
 
 #[derive(Serialize, Deserialize, Insertable, AsChangeset)]
@@ -90,6 +104,8 @@ pub struct InsertablePendingWalletTransactionLedger {
     pub money_movement_direction: MoneyMovementDirection,
     pub money_movement_type: MoneyMovementType,
     pub amount_cents: i32,
+    pub currency: String,
+    pub conversion_rate: Option<f64>,
 }
 
 #[derive(Identifiable, Serialize, Deserialize, Queryable, Debug, Selectable, Clone, PartialEq)]
@@ -104,6 +120,8 @@ pub struct SettledWalletTransactionLedger {
     pub amount_cents: i32,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    pub currency: String,
+    pub conversion_rate: Option<f64>,
 }
 
 
@@ -116,6 +134,8 @@ pub struct InsertableSettledWalletTransactionLedger {
     pub money_movement_direction: MoneyMovementDirection,
     pub money_movement_type: MoneyMovementType,
     pub amount_cents: i32,
+    pub currency: String,
+    pub conversion_rate: Option<f64>,
 }
 
 
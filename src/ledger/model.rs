@@ -11,6 +11,9 @@ pub struct PendingPassthroughCardTransactionLedgerModel {
     pub money_movement_direction: MoneyMovementDirection,
     pub money_movement_type: MoneyMovementType,
     pub amount_cents: i32,
+    pub currency: String,
+    pub conversion_rate: Option<f64>,
+    pub cash_amount_cents: Option<i32>,
 }
 
 #[derive(Debug, Clone)]
@@ -22,6 +25,9 @@ pub struct SettledPassthroughCardTransactionLedgerModel {
     pub money_movement_direction: MoneyMovementDirection,
     pub money_movement_type: MoneyMovementType,
     pub amount_cents: i32,
+    pub currency: String,
+    pub conversion_rate: Option<f64>,
+    pub cash_amount_cents: Option<i32>,
 }
 
 #[derive(Debug, Clone)]
@@ -33,6 +39,8 @@ pub struct PendingWalletTransactionLedgerModel {
     pub money_movement_direction: MoneyMovementDirection,
     pub money_movement_type: MoneyMovementType,
     pub amount_cents: i32,
+    pub currency: String,
+    pub conversion_rate: Option<f64>,
 }
 
 #[derive(Debug, Clone)]
@@ -44,6 +52,8 @@ pub struct SettledWalletTransactionLedgerModel {
     pub money_movement_direction: MoneyMovementDirection,
     pub money_movement_type: MoneyMovementType,
     pub amount_cents: i32,
+    pub currency: String,
+    pub conversion_rate: Option<f64>,
 }
 
 impl From<PendingPassthroughCardTransactionLedger> for PendingPassthroughCardTransactionLedgerModel {
@@ -56,6 +66,9 @@ impl From<PendingPassthroughCardTransactionLedger> for PendingPassthroughCardTra
             money_movement_direction: value.money_movement_direction,
             money_movement_type: value.money_movement_type,
             amount_cents: value.amount_cents,
+            currency: value.currency,
+            conversion_rate: value.conversion_rate,
+            cash_amount_cents: value.cash_amount_cents,
         }
     }
 }
@@ -70,6 +83,9 @@ impl From<SettledPassthroughCardTransactionLedger> for SettledPassthroughCardTra
             money_movement_direction: value.money_movement_direction,
             money_movement_type: value.money_movement_type,
             amount_cents: value.amount_cents,
+            currency: value.currency,
+            conversion_rate: value.conversion_rate,
+            cash_amount_cents: value.cash_amount_cents,
         }
     }
 }
@@ -84,6 +100,8 @@ impl From<PendingWalletTransactionLedger> for PendingWalletTransactionLedgerMode
             money_movement_direction: value.money_movement_direction,
             money_movement_type: value.money_movement_type,
             amount_cents: value.amount_cents,
+            currency: value.currency,
+            conversion_rate: value.conversion_rate,
         }
     }
 }
@@ -98,6 +116,8 @@ impl From<SettledWalletTransactionLedger> for SettledWalletTransactionLedgerMode
             money_movement_direction: value.money_movement_direction,
             money_movement_type: value.money_movement_type,
             amount_cents: value.amount_cents,
+            currency: value.currency,
+            conversion_rate: value.conversion_rate,
         }
     }
 }
\ No newline at end of file
@@ -61,6 +61,31 @@ pub trait LedgerServiceTrait {
         card_id: i32,
         amount_cents: i32
     ) -> Result<SettledWalletTransactionLedgerModel, LedgerError>;
+
+    async fn capture_wallet_card_amount<'a>(
+        self: Arc<Self>,
+        database_transaction: &mut Transaction<'_, '_>,
+        registered_transaction: &RegisteredTransactionModel,
+        card_id: i32,
+        authorized_cents: i32,
+        captured_cents: i32
+    ) -> Result<SettledWalletTransactionLedgerModel, LedgerError>;
+
+    async fn refund_passthrough_card_amount<'a>(
+        self: Arc<Self>,
+        database_transaction: &mut Transaction<'_, '_>,
+        registered_transaction: &RegisteredTransactionModel,
+        card: &PassthroughCardModel,
+        amount_cents: i32
+    ) -> Result<SettledPassthroughCardTransactionLedgerModel, LedgerError>;
+
+    async fn refund_wallet_amount<'a>(
+        self: Arc<Self>,
+        database_transaction: &mut Transaction<'_, '_>,
+        registered_transaction: &RegisteredTransactionModel,
+        card_id: i32,
+        amount_cents: i32
+    ) -> Result<SettledWalletTransactionLedgerModel, LedgerError>;
 }
 
 pub struct LedgerService {
@@ -93,6 +118,9 @@ impl LedgerServiceTrait for LedgerService {
                 money_movement_direction: MoneyMovementDirection::Debit,
                 money_movement_type: MoneyMovementType::PassthroughCardReserve,
                 amount_cents,
+                currency: registered_transaction.currency.clone(),
+                conversion_rate: registered_transaction.conversion_rate,
+                cash_amount_cents: registered_transaction.cash_portion(amount_cents),
             }
         ).await?;
         Ok(record.into())
@@ -115,6 +143,9 @@ impl LedgerServiceTrait for LedgerService {
                 money_movement_direction: MoneyMovementDirection::Credit,
                 money_movement_type: MoneyMovementType::PassthroughCardRelease,
                 amount_cents,
+                currency: registered_transaction.currency.clone(),
+                conversion_rate: registered_transaction.conversion_rate,
+                cash_amount_cents: registered_transaction.cash_portion(amount_cents),
             }
         ).await?;
         Ok(record.into())
@@ -137,6 +168,9 @@ impl LedgerServiceTrait for LedgerService {
                 money_movement_direction: MoneyMovementDirection::Credit,
                 money_movement_type: MoneyMovementType::PassthroughCardSettle,
                 amount_cents,
+                currency: registered_transaction.currency.clone(),
+                conversion_rate: registered_transaction.conversion_rate,
+                cash_amount_cents: registered_transaction.cash_portion(amount_cents),
             }
         ).await?;
 
@@ -149,6 +183,9 @@ impl LedgerServiceTrait for LedgerService {
                 money_movement_direction: MoneyMovementDirection::Debit,
                 money_movement_type: MoneyMovementType::PassthroughCardSettle,
                 amount_cents,
+                currency: registered_transaction.currency.clone(),
+                conversion_rate: registered_transaction.conversion_rate,
+                cash_amount_cents: registered_transaction.cash_portion(amount_cents),
             }
         ).await?;
         Ok(settlement_record.into())
@@ -170,6 +207,8 @@ impl LedgerServiceTrait for LedgerService {
                 money_movement_direction: MoneyMovementDirection::Credit,
                 money_movement_type: MoneyMovementType::WalletReserve,
                 amount_cents,
+                currency: registered_transaction.currency.clone(),
+                conversion_rate: registered_transaction.conversion_rate,
             }
         ).await?;
         Ok(record.into())
@@ -191,6 +230,8 @@ impl LedgerServiceTrait for LedgerService {
                 money_movement_direction: MoneyMovementDirection::Debit,
                 money_movement_type: MoneyMovementType::WalletRelease,
                 amount_cents,
+                currency: registered_transaction.currency.clone(),
+                conversion_rate: registered_transaction.conversion_rate,
             }
         ).await?;
         Ok(record.into())
@@ -212,6 +253,8 @@ impl LedgerServiceTrait for LedgerService {
                 money_movement_direction: MoneyMovementDirection::Debit,
                 money_movement_type: MoneyMovementType::WalletSettle,
                 amount_cents,
+                currency: registered_transaction.currency.clone(),
+                conversion_rate: registered_transaction.conversion_rate,
             }
         ).await?;
         let settled_record = self.dao.clone().insert_settled_wallet_transaction(
@@ -223,8 +266,93 @@ impl LedgerServiceTrait for LedgerService {
                 money_movement_direction: MoneyMovementDirection::Credit,
                 money_movement_type: MoneyMovementType::WalletSettle,
                 amount_cents,
+                currency: registered_transaction.currency.clone(),
+                conversion_rate: registered_transaction.conversion_rate,
             }
         ).await?;
         Ok(settled_record.into())
     }
-}
\ No newline at end of file
+
+    // the authorization sits in the pending ledger until capture, which settles what was captured and
+    // releases the rest. captures above the authorization (tips) reserve the difference first
+    async fn capture_wallet_card_amount<'a>(
+        self: Arc<Self>,
+        database_transaction: &mut Transaction<'_, '_>,
+        registered_transaction: &RegisteredTransactionModel,
+        card_id: i32,
+        authorized_cents: i32,
+        captured_cents: i32
+    ) -> Result<SettledWalletTransactionLedgerModel, LedgerError> {
+        if captured_cents > authorized_cents {
+            self.clone().reserve_wallet_amount(
+                database_transaction,
+                registered_transaction,
+                card_id,
+                captured_cents - authorized_cents
+            ).await?;
+        }
+        let settled_record = self.clone().settle_wallet_card_amount(
+            database_transaction,
+            registered_transaction,
+            card_id,
+            captured_cents
+        ).await?;
+        if captured_cents < authorized_cents {
+            self.clone().release_wallet_amount(
+                database_transaction,
+                registered_transaction,
+                card_id,
+                authorized_cents - captured_cents
+            ).await?;
+        }
+        Ok(settled_record)
+    }
+
+    // refunds only happen after settlement so they reverse the settled side directly
+    async fn refund_passthrough_card_amount<'a>(
+        self: Arc<Self>,
+        database_transaction: &mut Transaction<'_, '_>,
+        registered_transaction: &RegisteredTransactionModel,
+        card: &PassthroughCardModel,
+        amount_cents: i32
+    ) -> Result<SettledPassthroughCardTransactionLedgerModel, LedgerError> {
+        let record = self.dao.clone().insert_settled_passthrough_card_transaction(
+            database_transaction,
+            &InsertableSettledPassthroughCardTransactionLedger {
+                registered_transaction_id: registered_transaction.id,
+                user_id: card.user_id,
+                passthrough_card_id: card.id,
+                money_movement_direction: MoneyMovementDirection::Credit,
+                money_movement_type: MoneyMovementType::PassthroughCardRefund,
+                amount_cents,
+                currency: registered_transaction.currency.clone(),
+                conversion_rate: registered_transaction.conversion_rate,
+                cash_amount_cents: registered_transaction.cash_portion(amount_cents),
+            }
+        ).await?;
+        Ok(record.into())
+    }
+
+    async fn refund_wallet_amount<'a>(
+        self: Arc<Self>,
+        database_transaction: &mut Transaction<'_, '_>,
+        registered_transaction: &RegisteredTransactionModel,
+        card_id: i32,
+        amount_cents: i32
+    ) -> Result<SettledWalletTransactionLedgerModel, LedgerError> {
+        let record = self.dao.clone().insert_settled_wallet_transaction(
+            database_transaction,
+            &InsertableSettledWalletTransactionLedger {
+                registered_transaction_id: registered_transaction.id,
+                user_id: registered_transaction.user_id,
+                wallet_id: card_id,
+                money_movement_direction: MoneyMovementDirection::Debit,
+                money_movement_type: MoneyMovementType::WalletRefund,
+                amount_cents,
+                currency: registered_transaction.currency.clone(),
+                conversion_rate: registered_transaction.conversion_rate,
+            }
+        ).await?;
+        Ok(record.into())
+    }
+}
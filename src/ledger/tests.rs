@@ -66,6 +66,67 @@ mod service_tests {
 
     }
 
+    #[test]
+    async fn test_ledger_records_currency_and_rate() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let wallet = create_wallet_with_rule(&user).await;
+        let mut metadata = default_transaction_metadata();
+        metadata.merchant_amount_cents = Some(0);
+        metadata.merchant_currency = Some("EUR".to_string());
+        metadata.conversion_rate = Some(1.1);
+        let rtx = create_registered_transaction(&user, &metadata).await;
+        let rtx_clone = rtx.clone();
+        let ledger = Arc::new(LedgerService::new());
+        let wallet_id = wallet.id;
+        let amount_cents = rtx.amount_cents;
+        let charge = transactional::<_, DataError, _>(move |txn| Box::pin(async move {
+            ledger.clone().reserve_wallet_amount(
+                txn,
+                &rtx_clone,
+                wallet_id,
+                amount_cents
+            ).await.map_err(|e| DataError::Unexpected(e.into()))
+        })).await.unwrap();
+        assert_eq!(charge.registered_transaction_id, rtx.id);
+        assert_eq!(charge.currency, "USD");
+        assert_eq!(charge.conversion_rate, Some(1.1));
+    }
+
+    #[test]
+    async fn test_passthrough_ledger_records_cash_portion() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        metadata.cash_amount_cents = Some(400);
+        let rtx = create_registered_transaction(&user, &metadata).await;
+        assert_eq!(Some(400), rtx.cash_amount_cents);
+        let rtx_clone = rtx.clone();
+        let card_clone = card.clone();
+        let ledger = Arc::new(LedgerService::new());
+
+        let (reserved, settled) = transactional::<_, DataError, _>(move |txn| Box::pin(async move {
+            let reserved = ledger.clone().reserve_passthrough_card_amount(
+                txn,
+                &rtx_clone,
+                &card_clone,
+                1000
+            ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+            // only part of the hold settles, the cash went out first
+            let settled = ledger.clone().settle_passthrough_card_amount(
+                txn,
+                &rtx_clone,
+                &card_clone,
+                300
+            ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+            Ok((reserved, settled))
+        })).await.unwrap();
+        assert_eq!(Some(400), reserved.cash_amount_cents);
+        assert_eq!(Some(300), settled.cash_amount_cents);
+    }
+
     #[test]
     async fn test_settle_wallet() {
         crate::test_helper::general::init();
@@ -96,6 +157,50 @@ mod service_tests {
         // todo: find the pending release created by this
     }
 
+    #[test]
+    async fn test_capture_wallet_settles_captured_amount() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let wallet = create_wallet_with_rule(&user).await;
+        let metadata = default_transaction_metadata();
+        let rtx = create_registered_transaction(&user, &metadata).await;
+        let ledger = Arc::new(LedgerService::new());
+        let wallet_id = wallet.id;
+        let authorized_cents = 1000;
+
+        // cleared for less than authorized
+        let mut lc = ledger.clone();
+        let mut rtx_clone = rtx.clone();
+        let settled = transactional::<_, DataError, _>(move |txn| Box::pin(async move {
+            lc.clone().capture_wallet_card_amount(
+                txn,
+                &rtx_clone,
+                wallet_id,
+                authorized_cents,
+                authorized_cents - 200
+            ).await.map_err(|e| DataError::Unexpected(e.into()))
+        })).await.unwrap();
+        assert_eq!(settled.amount_cents, authorized_cents - 200);
+        assert_eq!(settled.wallet_id, wallet.id);
+        assert_eq!(settled.money_movement_direction, MoneyMovementDirection::Credit);
+        assert_eq!(settled.money_movement_type, MoneyMovementType::WalletSettle);
+
+        // cleared with a tip on top
+        lc = ledger.clone();
+        rtx_clone = rtx.clone();
+        let settled = transactional::<_, DataError, _>(move |txn| Box::pin(async move {
+            lc.clone().capture_wallet_card_amount(
+                txn,
+                &rtx_clone,
+                wallet_id,
+                authorized_cents,
+                authorized_cents + 200
+            ).await.map_err(|e| DataError::Unexpected(e.into()))
+        })).await.unwrap();
+        assert_eq!(settled.amount_cents, authorized_cents + 200);
+        assert_eq!(settled.registered_transaction_id, rtx.id);
+    }
+
     #[test]
     async fn test_wallet_registered_transaction_not_found() {
         crate::test_helper::general::init();
@@ -284,6 +389,62 @@ mod service_tests {
         // todo: find the pending release created by this
     }
 
+    #[test]
+    async fn test_refund_wallet() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let wallet = create_wallet_with_rule(&user).await;
+        let metadata = default_transaction_metadata();
+        let rtx = create_registered_transaction(&user, &metadata).await;
+        let rtx_clone = rtx.clone();
+        let ledger = Arc::new(LedgerService::new());
+        let wallet_id = wallet.id;
+        let amount_cents = rtx.amount_cents / 2;
+
+        let refunded = transactional::<_, DataError, _>(move |txn| Box::pin(async move {
+            ledger.clone().refund_wallet_amount(
+                txn,
+                &rtx_clone,
+                wallet_id,
+                amount_cents
+            ).await.map_err(|e| DataError::Unexpected(e.into()))
+        })).await.unwrap();
+        assert_eq!(refunded.registered_transaction_id, rtx.id);
+        assert_eq!(refunded.amount_cents, amount_cents);
+        assert_eq!(refunded.user_id, user.id);
+        assert_eq!(refunded.wallet_id, wallet.id);
+        assert_eq!(refunded.money_movement_direction, MoneyMovementDirection::Debit);
+        assert_eq!(refunded.money_movement_type, MoneyMovementType::WalletRefund);
+    }
+
+    #[test]
+    async fn test_refund_passthrough() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let metadata = default_transaction_metadata();
+        let rtx = create_registered_transaction(&user, &metadata).await;
+        let rtx_clone = rtx.clone();
+        let card_clone = card.clone();
+        let ledger = Arc::new(LedgerService::new());
+        let amount_cents = rtx.amount_cents / 2;
+
+        let refunded = transactional::<_, DataError, _>(move |txn| Box::pin(async move {
+            ledger.clone().refund_passthrough_card_amount(
+                txn,
+                &rtx_clone,
+                &card_clone,
+                amount_cents
+            ).await.map_err(|e| DataError::Unexpected(e.into()))
+        })).await.unwrap();
+        assert_eq!(refunded.registered_transaction_id, rtx.id);
+        assert_eq!(refunded.amount_cents, amount_cents);
+        assert_eq!(refunded.user_id, user.id);
+        assert_eq!(refunded.passthrough_card_id, card.id);
+        assert_eq!(refunded.money_movement_direction, MoneyMovementDirection::Credit);
+        assert_eq!(refunded.money_movement_type, MoneyMovementType::PassthroughCardRefund);
+    }
+
     #[test]
     async fn test_passthrough_registered_transaction_not_found() {
         crate::test_helper::general::init();
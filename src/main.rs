@@ -65,6 +65,16 @@ mod configuration;
 mod otel;
 mod user_transaction;
 mod pagination;
+mod stand_in;
+mod avs;
+mod spend_control;
+mod merchant_control;
+mod card_health;
+mod payment_processor;
+mod cash;
+mod rewards_program;
+mod rotating_category;
+mod merchant;
 
 
 async fn health_check() -> impl Responder {
@@ -80,6 +90,27 @@ async fn ping_db() -> Result<(), DataError>{
     Ok(())
 }
 
+// services aren't Send, so reconciliation gets a thread and runtime of its own like an actix worker does
+fn spawn_stand_in_reconciler(interval_seconds: u64) {
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("builds stand in runtime");
+        runtime.block_on(async move {
+            let configuration = get_configuration_sync().expect("gets configuration");
+            let services = middleware::services::Services::new(&configuration);
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(interval_seconds));
+            loop {
+                interval.tick().await;
+                if let Err(e) = services.lithic_handler.clone().reconcile_stand_in_transactions().await {
+                    tracing::error!("Unable to reconcile stand in transactions error={:?}", e);
+                }
+            }
+        });
+    });
+}
+
 // TODO: why does tokio vs actix cause inner requests not to hang
 #[tokio::main(flavor = "multi_thread", worker_threads = 64)]
 //#[tokio::main(flavor = "current_thread")]
@@ -126,6 +157,7 @@ async fn main() -> std::io::Result<()> {
 
     let res = ping_db().await.expect("No issue");
 
+    spawn_stand_in_reconciler(configuration.stand_in.reconcile_interval_seconds);
 
 
     HttpServer::new(move || {
@@ -143,6 +175,10 @@ async fn main() -> std::io::Result<()> {
             .service(web::scope("/passthrough").configure(passthrough_card::config::config))
             .service(web::scope("/credit-card-type").configure(credit_card_type::config::config))
             .service(web::scope("/transactions").configure(user_transaction::config::config))
+            .service(web::scope("/spend-control").configure(spend_control::config::config))
+            .service(web::scope("/rule").configure(rule::config::config))
+            .service(web::scope("/rewards-program").configure(rewards_program::config::config))
+            .service(web::scope("/rotating-category").configure(rotating_category::config::config))
             .service(
                 web::scope("/")
             )
@@ -0,0 +1,59 @@
+use std::fmt;
+use std::io::Write;
+use diesel::backend::Backend;
+use diesel::deserialize::{FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::Pg;
+use diesel::serialize::{IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use serde::{Deserialize, Serialize};
+
+// processors that put their own name ahead of the merchant's, as in "SQ *BLUE BOTTLE"
+pub const PROCESSOR_PREFIXES: &[&str] = &["sq", "tst", "sp", "paypal", "pp", "py", "in", "ez", "dd"];
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, AsExpression, FromSqlRow)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[diesel(sql_type = Text)]
+pub enum MerchantAliasType {
+    // a pattern over the cleaned descriptor
+    Descriptor,
+    // an exact acceptor id, wins over any descriptor
+    AcceptorId
+}
+
+impl ToSql<Text, Pg> for MerchantAliasType {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.to_string().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for MerchantAliasType {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"DESCRIPTOR" => Ok(MerchantAliasType::Descriptor),
+            b"ACCEPTOR_ID" => Ok(MerchantAliasType::AcceptorId),
+            v => Err(format!("Unknown value for MerchantAliasType found").into()),
+        }
+    }
+}
+
+impl fmt::Display for MerchantAliasType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            MerchantAliasType::Descriptor => "DESCRIPTOR",
+            MerchantAliasType::AcceptorId => "ACCEPTOR_ID"
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::merchant::constant::MerchantAliasType;
+
+    #[test]
+    fn test_alias_type_display() {
+        assert_eq!("DESCRIPTOR", MerchantAliasType::Descriptor.to_string());
+        assert_eq!("ACCEPTOR_ID", MerchantAliasType::AcceptorId.to_string());
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use crate::error::data_error::DataError;
+use crate::merchant::entity::MerchantAlias;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::helper::try_redis_fallback_db;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::key::Key;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::services::RedisService;
+
+#[cfg(test)]
+use mockall::{automock, predicate::*};
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait MerchantDaoTrait {
+    async fn get_aliases(self: Arc<Self>) -> Result<Vec<MerchantAlias>, DataError>;
+}
+
+pub struct MerchantDao {
+    #[cfg(not(feature = "no-redis"))]
+    redis: Arc<RedisService>
+}
+
+impl MerchantDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub fn new() -> Self {
+        #[cfg(not(feature = "no-redis"))] {
+            Self {
+                redis: Arc::new(RedisService::new())
+            }
+        }
+        #[cfg(feature = "no-redis")] {
+            Self {}
+        }
+    }
+}
+
+#[async_trait]
+impl MerchantDaoTrait for MerchantDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_aliases(self: Arc<Self>) -> Result<Vec<MerchantAlias>, DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            // the whole table is small and read on every asa that hits a merchant rule
+            Ok(try_redis_fallback_db(
+                self.redis.clone(),
+                Key::MerchantAliases,
+                || async {MerchantAlias::list_all().await},
+                false
+            ).await?)
+        }
+        #[cfg(feature = "no-redis")] {
+            MerchantAlias::list_all().await
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::error::data_error::DataError;
+use crate::merchant::constant::MerchantAliasType;
+use crate::schema::{merchant, merchant_alias};
+use crate::util::db;
+
+#[derive(Debug, Clone, Identifiable, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = merchant)]
+pub struct Merchant {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Identifiable, Serialize, Deserialize, Queryable, Selectable, PartialEq)]
+#[diesel(table_name = merchant_alias)]
+pub struct MerchantAlias {
+    pub id: i32,
+    pub merchant_id: i32,
+    pub alias_type: MerchantAliasType,
+    pub pattern: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl MerchantAlias {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn list_all() -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let aliases = merchant_alias::table
+            .order(merchant_alias::id.asc())
+            .load::<MerchantAlias>(&mut conn).await?;
+        Ok(aliases)
+    }
+}
@@ -0,0 +1,43 @@
+use crate::error::data_error::DataError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MerchantError {
+    #[error("Unexpected error")]
+    Unexpected(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl From<DataError> for MerchantError {
+    fn from(value: DataError) -> Self {
+        match value {
+            DataError::Conflict(e) => MerchantError::Unexpected(e),
+            DataError::NotFound(e) => MerchantError::Unexpected(e),
+            DataError::Format(e) => MerchantError::Unexpected(e),
+            DataError::Unexpected(e) => MerchantError::Unexpected(e),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for MerchantError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MerchantError::Unexpected(_), MerchantError::Unexpected(_)) => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::data_error::DataError;
+    use crate::merchant::error::MerchantError;
+
+    const BASE_ERROR: &str = "test";
+
+    #[test]
+    pub fn test_from_data_error() {
+        assert_eq!(MerchantError::Unexpected(BASE_ERROR.into()), MerchantError::from(DataError::Conflict(BASE_ERROR.into())));
+        assert_eq!(MerchantError::Unexpected(BASE_ERROR.into()), MerchantError::from(DataError::NotFound(BASE_ERROR.into())));
+        assert_eq!(MerchantError::Unexpected(BASE_ERROR.into()), MerchantError::from(DataError::Format(BASE_ERROR.into())));
+        assert_eq!(MerchantError::Unexpected(BASE_ERROR.into()), MerchantError::from(DataError::Unexpected(BASE_ERROR.into())));
+    }
+}
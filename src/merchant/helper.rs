@@ -0,0 +1,170 @@
+use crate::asa::request::Merchant;
+use crate::merchant::constant::{MerchantAliasType, PROCESSOR_PREFIXES};
+use crate::merchant::model::MerchantAliasModel;
+use crate::merchant_control::helper::matches_pattern;
+
+// "SQ *BLUE BOTTLE #1234 OAKLAND CA" becomes "blue bottle", the part of a descriptor that names the merchant
+pub fn normalize_descriptor(descriptor: &str, city: Option<&str>, state: Option<&str>) -> String {
+    let mut descriptor = descriptor.trim().to_lowercase();
+    // a processor prefix hides the merchant behind the *, anything else before the * is the merchant
+    while let Some((head, tail)) = descriptor.split_once('*') {
+        descriptor = if PROCESSOR_PREFIXES.contains(&head.trim()) {
+            tail.to_string()
+        } else {
+            head.to_string()
+        };
+    }
+    let mut tokens: Vec<String> = tokenize(&descriptor).into_iter()
+        .enumerate()
+        // store numbers go, a number leading the name like "7 eleven" stays
+        .filter(|(i, token)| *i == 0 || !token.chars().all(|c| c.is_ascii_digit()))
+        .map(|(_, token)| token)
+        .collect();
+    if let Some(state) = state {
+        strip_suffix(&mut tokens, &tokenize(state));
+    }
+    if let Some(city) = city {
+        strip_suffix(&mut tokens, &tokenize(city));
+    }
+    tokens.join(" ")
+}
+
+// an acceptor id alias wins outright, otherwise the longest matching descriptor pattern is the most specific
+pub fn resolve(aliases: &Vec<MerchantAliasModel>, merchant: &Merchant) -> Option<i32> {
+    if let Some(acceptor_id) = merchant.acceptor_id.as_deref().map(str::trim).filter(|id| !id.is_empty()) {
+        if let Some(alias) = aliases.iter()
+            .filter(|alias| alias.alias_type == MerchantAliasType::AcceptorId)
+            .find(|alias| alias.pattern.trim() == acceptor_id) {
+            return Some(alias.merchant_id)
+        }
+    }
+    let descriptor = normalize_descriptor(
+        merchant.descriptor.as_deref()?,
+        merchant.city.as_deref(),
+        merchant.state.as_deref()
+    );
+    if descriptor.is_empty() {
+        return None
+    }
+    aliases.iter()
+        .filter(|alias| alias.alias_type == MerchantAliasType::Descriptor)
+        .filter(|alias| matches_pattern(&alias.pattern, &descriptor))
+        .max_by_key(|alias| alias.pattern.chars().filter(|c| *c != '*').count())
+        .map(|alias| alias.merchant_id)
+}
+
+fn tokenize(value: &str) -> Vec<String> {
+    value.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+// never strips the whole descriptor, a merchant named after its city keeps its name
+fn strip_suffix(tokens: &mut Vec<String>, suffix: &Vec<String>) {
+    if !suffix.is_empty() && tokens.len() > suffix.len() && tokens.ends_with(suffix) {
+        tokens.truncate(tokens.len() - suffix.len());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::asa::request::Merchant;
+    use crate::merchant::constant::MerchantAliasType;
+    use crate::merchant::helper::{normalize_descriptor, resolve};
+    use crate::merchant::model::MerchantAliasModel;
+
+    const AMAZON_ID: i32 = 1;
+    const BLUE_BOTTLE_ID: i32 = 2;
+    const AMAZON_FRESH_ID: i32 = 3;
+
+    fn alias(id: i32, merchant_id: i32, alias_type: MerchantAliasType, pattern: &str) -> MerchantAliasModel {
+        MerchantAliasModel {
+            id,
+            merchant_id,
+            alias_type,
+            pattern: pattern.to_string(),
+        }
+    }
+
+    fn aliases() -> Vec<MerchantAliasModel> {
+        vec![
+            alias(1, AMAZON_ID, MerchantAliasType::Descriptor, "amazon*"),
+            alias(2, AMAZON_ID, MerchantAliasType::Descriptor, "amzn*"),
+            alias(3, BLUE_BOTTLE_ID, MerchantAliasType::Descriptor, "blue bottle"),
+            alias(4, AMAZON_FRESH_ID, MerchantAliasType::Descriptor, "amazon fresh*"),
+            alias(5, BLUE_BOTTLE_ID, MerchantAliasType::AcceptorId, "174030075991"),
+        ]
+    }
+
+    fn merchant(descriptor: &str, acceptor_id: Option<&str>, city: Option<&str>, state: Option<&str>) -> Merchant {
+        Merchant {
+            acceptor_id: acceptor_id.map(str::to_string),
+            city: city.map(str::to_string),
+            country: Some("USA".to_string()),
+            descriptor: Some(descriptor.to_string()),
+            mcc: Some("5812".to_string()),
+            state: state.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_normalize_descriptor_processor_prefix() {
+        assert_eq!("blue bottle", normalize_descriptor("SQ *BLUE BOTTLE 1234 OAKLAND", Some("Oakland"), Some("CA")));
+        assert_eq!("blue bottle", normalize_descriptor("TST* Blue Bottle", None, None));
+        assert_eq!("uber", normalize_descriptor("PAYPAL *UBER*TRIP", None, None));
+        // the head is the merchant when it isn't a processor
+        assert_eq!("amzn mktp us", normalize_descriptor("AMZN Mktp US*2K3", None, None));
+    }
+
+    #[test]
+    fn test_normalize_descriptor_store_numbers() {
+        assert_eq!("whole foods", normalize_descriptor("WHOLE FOODS #10234", None, None));
+        assert_eq!("shell oil", normalize_descriptor("SHELL OIL 57444 0012", None, None));
+        assert_eq!("7 eleven", normalize_descriptor("7-ELEVEN 35210", None, None));
+    }
+
+    #[test]
+    fn test_normalize_descriptor_city_state_suffix() {
+        assert_eq!("starbucks", normalize_descriptor("STARBUCKS #00123 SAN FRANCISCO CA", Some("SAN FRANCISCO"), Some("CA")));
+        assert_eq!("starbucks store", normalize_descriptor("STARBUCKS STORE SEATTLE", Some("Seattle"), None));
+        // a city in the middle of the name isn't a suffix
+        assert_eq!("boston market", normalize_descriptor("BOSTON MARKET", Some("Boston"), None));
+        // and a merchant named after its city keeps its name
+        assert_eq!("oakland", normalize_descriptor("OAKLAND", Some("Oakland"), None));
+    }
+
+    #[test]
+    fn test_resolve_descriptor() {
+        let aliases = aliases();
+        assert_eq!(Some(AMAZON_ID), resolve(&aliases, &merchant("AMZN Mktp US*2K3", None, None, None)));
+        assert_eq!(Some(BLUE_BOTTLE_ID), resolve(&aliases, &merchant("SQ *BLUE BOTTLE 1234 OAKLAND", None, Some("Oakland"), None)));
+        assert_eq!(None, resolve(&aliases, &merchant("SQ *BLUE BOTTLE ROASTERS", None, None, None)));
+        assert_eq!(None, resolve(&aliases, &merchant("NETFLIX.COM", None, None, None)));
+    }
+
+    #[test]
+    fn test_resolve_longest_pattern_wins() {
+        let aliases = aliases();
+        assert_eq!(Some(AMAZON_FRESH_ID), resolve(&aliases, &merchant("AMAZON FRESH*1A2B3", None, None, None)));
+        assert_eq!(Some(AMAZON_ID), resolve(&aliases, &merchant("AMAZON.COM*1A2B3", None, None, None)));
+    }
+
+    #[test]
+    fn test_resolve_acceptor_id_wins() {
+        let aliases = aliases();
+        assert_eq!(Some(BLUE_BOTTLE_ID), resolve(&aliases, &merchant("AMZN Mktp US*2K3", Some("174030075991"), None, None)));
+        assert_eq!(Some(AMAZON_ID), resolve(&aliases, &merchant("AMZN Mktp US*2K3", Some("000000000000"), None, None)));
+    }
+
+    #[test]
+    fn test_resolve_without_descriptor() {
+        let aliases = aliases();
+        let mut without_descriptor = merchant("", Some("174030075991"), None, None);
+        without_descriptor.descriptor = None;
+        assert_eq!(Some(BLUE_BOTTLE_ID), resolve(&aliases, &without_descriptor));
+        without_descriptor.acceptor_id = None;
+        assert_eq!(None, resolve(&aliases, &without_descriptor));
+    }
+}
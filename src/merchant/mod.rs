@@ -0,0 +1,9 @@
+pub mod constant;
+pub mod error;
+pub mod helper;
+pub mod model;
+pub mod service;
+
+mod dao;
+mod entity;
+mod tests;
@@ -0,0 +1,21 @@
+use crate::merchant::constant::MerchantAliasType;
+use crate::merchant::entity::MerchantAlias;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MerchantAliasModel {
+    pub id: i32,
+    pub merchant_id: i32,
+    pub alias_type: MerchantAliasType,
+    pub pattern: String,
+}
+
+impl From<MerchantAlias> for MerchantAliasModel {
+    fn from(value: MerchantAlias) -> Self {
+        MerchantAliasModel {
+            id: value.id,
+            merchant_id: value.merchant_id,
+            alias_type: value.alias_type,
+            pattern: value.pattern,
+        }
+    }
+}
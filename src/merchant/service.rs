@@ -0,0 +1,50 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use crate::asa::request::Merchant;
+use crate::merchant::dao::{MerchantDao, MerchantDaoTrait};
+use crate::merchant::error::MerchantError;
+use crate::merchant::helper::resolve;
+use crate::merchant::model::MerchantAliasModel;
+
+#[cfg(test)]
+use mockall::{automock, predicate::*};
+
+#[cfg_attr(test, automock)]
+#[async_trait(?Send)]
+pub trait MerchantServiceTrait {
+    async fn resolve_merchant_id(self: Arc<Self>, merchant: &Merchant) -> Result<Option<i32>, MerchantError>;
+}
+
+pub struct MerchantService {
+    dao: Arc<dyn MerchantDaoTrait>
+}
+
+impl MerchantService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub fn new() -> Self {
+        Self {
+            dao: Arc::new(MerchantDao::new())
+        }
+    }
+
+    #[cfg(test)]
+    pub fn new_with_mocks(dao: Arc<dyn MerchantDaoTrait>) -> Self {
+        Self {
+            dao
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl MerchantServiceTrait for MerchantService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn resolve_merchant_id(self: Arc<Self>, merchant: &Merchant) -> Result<Option<i32>, MerchantError> {
+        let aliases: Vec<MerchantAliasModel> = self.dao.clone().get_aliases().await?
+            .into_iter()
+            .map(MerchantAliasModel::from)
+            .collect();
+        let merchant_id = resolve(&aliases, merchant);
+        tracing::info!("Resolved merchant descriptor={:?} acceptor_id={:?} to merchant_id={:?}", &merchant.descriptor, &merchant.acceptor_id, merchant_id);
+        Ok(merchant_id)
+    }
+}
@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::test;
+    use crate::asa::request::create_example_asa;
+    use crate::merchant::service::{MerchantService, MerchantServiceTrait};
+
+    const AMAZON_ID: i32 = 1;
+    const WHOLE_FOODS_ID: i32 = 2;
+
+    #[test]
+    async fn test_resolve_seeded_merchants() {
+        crate::test_helper::general::init();
+        let service = Arc::new(MerchantService::new());
+        let mut merchant = create_example_asa(1000, "5411".to_string()).merchant.expect("has merchant");
+
+        merchant.descriptor = Some("AMZN Mktp US*2K3".to_string());
+        assert_eq!(Some(AMAZON_ID), service.clone().resolve_merchant_id(&merchant).await.expect("resolves"));
+        merchant.descriptor = Some("WHOLE FOODS #10234 NEW YORK NY".to_string());
+        assert_eq!(Some(WHOLE_FOODS_ID), service.clone().resolve_merchant_id(&merchant).await.expect("resolves"));
+        merchant.descriptor = Some("SQ *BLUE BOTTLE 1234".to_string());
+        assert_eq!(None, service.clone().resolve_merchant_id(&merchant).await.expect("resolves"));
+    }
+}
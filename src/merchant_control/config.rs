@@ -0,0 +1,11 @@
+use actix_web::web;
+use super::controller;
+
+// mounted under /passthrough, the caller wraps it in auth
+pub fn config(cfg: &mut web::ServiceConfig) -> () {
+    cfg
+        .service(controller::get_controls)
+        .service(controller::create_control)
+        .service(controller::update_control)
+        .service(controller::delete_control);
+}
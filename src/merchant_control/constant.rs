@@ -0,0 +1,118 @@
+use std::fmt;
+use std::io::Write;
+use diesel::backend::Backend;
+use diesel::deserialize::{FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::Pg;
+use diesel::serialize::{IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use serde::{Deserialize, Serialize};
+
+pub const NOT_ON_ALLOW_LIST_REASON: &str = "merchant is not on the allow list";
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, AsExpression, FromSqlRow)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[diesel(sql_type = Text)]
+pub enum MerchantListType {
+    Allow,
+    Block
+}
+
+impl ToSql<Text, Pg> for MerchantListType {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.to_string().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for MerchantListType {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"ALLOW" => Ok(MerchantListType::Allow),
+            b"BLOCK" => Ok(MerchantListType::Block),
+            v => Err(format!("Unknown value for MerchantListType found").into()),
+        }
+    }
+}
+
+impl fmt::Display for MerchantListType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            MerchantListType::Allow => "ALLOW",
+            MerchantListType::Block => "BLOCK"
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, AsExpression, FromSqlRow)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[diesel(sql_type = Text)]
+pub enum MerchantMatchType {
+    Mcc,
+    MccRange,
+    Descriptor,
+    AcceptorId,
+    Country,
+    Category
+}
+
+impl ToSql<Text, Pg> for MerchantMatchType {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.to_string().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for MerchantMatchType {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"MCC" => Ok(MerchantMatchType::Mcc),
+            b"MCC_RANGE" => Ok(MerchantMatchType::MccRange),
+            b"DESCRIPTOR" => Ok(MerchantMatchType::Descriptor),
+            b"ACCEPTOR_ID" => Ok(MerchantMatchType::AcceptorId),
+            b"COUNTRY" => Ok(MerchantMatchType::Country),
+            b"CATEGORY" => Ok(MerchantMatchType::Category),
+            v => Err(format!("Unknown value for MerchantMatchType found").into()),
+        }
+    }
+}
+
+impl fmt::Display for MerchantMatchType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            MerchantMatchType::Mcc => "MCC",
+            MerchantMatchType::MccRange => "MCC_RANGE",
+            MerchantMatchType::Descriptor => "DESCRIPTOR",
+            MerchantMatchType::AcceptorId => "ACCEPTOR_ID",
+            MerchantMatchType::Country => "COUNTRY",
+            MerchantMatchType::Category => "CATEGORY"
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::merchant_control::constant::{MerchantListType, MerchantMatchType};
+
+    #[test]
+    fn test_list_type_display() {
+        assert_eq!("ALLOW", MerchantListType::Allow.to_string());
+        assert_eq!("BLOCK", MerchantListType::Block.to_string());
+    }
+
+    #[test]
+    fn test_match_type_display() {
+        assert_eq!("MCC", MerchantMatchType::Mcc.to_string());
+        assert_eq!("MCC_RANGE", MerchantMatchType::MccRange.to_string());
+        assert_eq!("DESCRIPTOR", MerchantMatchType::Descriptor.to_string());
+        assert_eq!("ACCEPTOR_ID", MerchantMatchType::AcceptorId.to_string());
+        assert_eq!("COUNTRY", MerchantMatchType::Country.to_string());
+        assert_eq!("CATEGORY", MerchantMatchType::Category.to_string());
+    }
+
+    #[test]
+    fn test_match_type_serde() {
+        assert_eq!("\"MCC_RANGE\"", serde_json::to_string(&MerchantMatchType::MccRange).expect("serializes"));
+        assert_eq!(MerchantMatchType::AcceptorId, serde_json::from_str::<MerchantMatchType>("\"ACCEPTOR_ID\"").expect("deserializes"));
+    }
+}
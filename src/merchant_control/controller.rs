@@ -0,0 +1,62 @@
+use actix_web::{web, get, post, put, delete, HttpResponse};
+use uuid::Uuid;
+use crate::merchant_control::request::MerchantControlRequest;
+use crate::merchant_control::response::MerchantControlResponse;
+use crate::merchant_control::service::MerchantControlServiceTrait;
+use crate::middleware::services::Services;
+use crate::user::model::UserModel as User;
+use super::error::MerchantControlError;
+
+#[get("/")]
+async fn get_controls(
+    user: web::ReqData<User>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, MerchantControlError> {
+    let user = user.into_inner();
+    let controls = services.merchant_control_service.clone().get_controls(&user).await?;
+    Ok(HttpResponse::Ok().json(
+        controls.iter().map(MerchantControlResponse::from).collect::<Vec<MerchantControlResponse>>()
+    ))
+}
+
+#[post("/")]
+async fn create_control(
+    user: web::ReqData<User>,
+    request: web::Json<MerchantControlRequest>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, MerchantControlError> {
+    let user = user.into_inner();
+    let request = request.into_inner();
+    let control = services.merchant_control_service.clone().create_control(&user, &request).await?;
+    Ok(HttpResponse::Ok().json(
+        MerchantControlResponse::from(&control)
+    ))
+}
+
+#[put("/{public_id}/")]
+async fn update_control(
+    user: web::ReqData<User>,
+    public_id: web::Path<Uuid>,
+    request: web::Json<MerchantControlRequest>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, MerchantControlError> {
+    let user = user.into_inner();
+    let public_id = public_id.into_inner();
+    let request = request.into_inner();
+    let control = services.merchant_control_service.clone().update_control(&user, &public_id, &request).await?;
+    Ok(HttpResponse::Ok().json(
+        MerchantControlResponse::from(&control)
+    ))
+}
+
+#[delete("/{public_id}/")]
+async fn delete_control(
+    user: web::ReqData<User>,
+    public_id: web::Path<Uuid>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, MerchantControlError> {
+    let user = user.into_inner();
+    let public_id = public_id.into_inner();
+    services.merchant_control_service.clone().delete_control(&user, &public_id).await?;
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,111 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use uuid::Uuid;
+use crate::error::data_error::DataError;
+use crate::merchant_control::entity::{InsertableMerchantControl, InsertableMerchantControlDecline, MerchantControl, MerchantControlChanges, MerchantControlDecline};
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::services::{
+    RedisService,
+    RedisServiceTrait
+};
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::helper::try_redis_fallback_db;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::key::Key;
+
+#[cfg(test)]
+use mockall::{automock, predicate::*};
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait MerchantControlDaoTrait {
+    async fn insert(self: Arc<Self>, control: &InsertableMerchantControl) -> Result<MerchantControl, DataError>;
+    async fn get_for_user(self: Arc<Self>, user_id: i32) -> Result<Vec<MerchantControl>, DataError>;
+    async fn get_by_public_id_for_user(self: Arc<Self>, user_id: i32, public_id: &Uuid) -> Result<MerchantControl, DataError>;
+    async fn update(self: Arc<Self>, control: &MerchantControl, changes: &MerchantControlChanges) -> Result<MerchantControl, DataError>;
+    async fn delete(self: Arc<Self>, control: &MerchantControl) -> Result<(), DataError>;
+    async fn insert_decline(self: Arc<Self>, decline: &InsertableMerchantControlDecline) -> Result<MerchantControlDecline, DataError>;
+    async fn get_declines_for_user(self: Arc<Self>, user_id: i32) -> Result<Vec<MerchantControlDecline>, DataError>;
+}
+
+pub struct MerchantControlDao {
+    #[cfg(not(feature = "no-redis"))]
+    redis: Arc<RedisService>
+}
+
+impl MerchantControlDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub fn new() -> Self {
+        #[cfg(not(feature = "no-redis"))] {
+            Self {
+                redis: Arc::new(RedisService::new())
+            }
+        }
+        #[cfg(feature = "no-redis")] {
+            Self {}
+        }
+    }
+
+    async fn expire_controls_for_user(&self, user_id: i32) {
+        #[cfg(not(feature = "no-redis"))] {
+            tracing::info!("Expiring merchant controls in redis for user_id={}", user_id);
+            self.redis.clone().expire_now::<_>(&Key::MerchantControlsForUser(user_id)).await;
+        }
+    }
+}
+
+#[async_trait]
+impl MerchantControlDaoTrait for MerchantControlDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert(self: Arc<Self>, control: &InsertableMerchantControl) -> Result<MerchantControl, DataError> {
+        let control = MerchantControl::insert(control).await;
+        if let Ok(control) = &control {
+            self.expire_controls_for_user(control.user_id).await;
+        }
+        control
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_for_user(self: Arc<Self>, user_id: i32) -> Result<Vec<MerchantControl>, DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            Ok(try_redis_fallback_db(
+                self.redis.clone(),
+                Key::MerchantControlsForUser(user_id),
+                || async {MerchantControl::get_for_user(user_id).await},
+                true
+            ).await?)
+        }
+        #[cfg(feature = "no-redis")] {
+            MerchantControl::get_for_user(user_id).await
+        }
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_by_public_id_for_user(self: Arc<Self>, user_id: i32, public_id: &Uuid) -> Result<MerchantControl, DataError> {
+        MerchantControl::get_by_public_id_for_user(user_id, public_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update(self: Arc<Self>, control: &MerchantControl, changes: &MerchantControlChanges) -> Result<MerchantControl, DataError> {
+        let updated = MerchantControl::update(control.id, changes).await;
+        self.expire_controls_for_user(control.user_id).await;
+        updated
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn delete(self: Arc<Self>, control: &MerchantControl) -> Result<(), DataError> {
+        let deleted = MerchantControl::delete(control.id).await;
+        self.expire_controls_for_user(control.user_id).await;
+        deleted
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert_decline(self: Arc<Self>, decline: &InsertableMerchantControlDecline) -> Result<MerchantControlDecline, DataError> {
+        MerchantControlDecline::insert(decline).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_declines_for_user(self: Arc<Self>, user_id: i32) -> Result<Vec<MerchantControlDecline>, DataError> {
+        MerchantControlDecline::get_for_user(user_id).await
+    }
+}
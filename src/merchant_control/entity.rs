@@ -0,0 +1,147 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::error::data_error::DataError;
+use crate::merchant_control::constant::{MerchantListType, MerchantMatchType};
+use crate::schema::{merchant_control, merchant_control_decline};
+use crate::util::db;
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize, PartialEq)]
+#[diesel(table_name = merchant_control)]
+pub struct InsertableMerchantControl {
+    pub public_id: Uuid,
+    pub user_id: i32,
+    pub list_type: MerchantListType,
+    pub match_type: MerchantMatchType,
+    pub match_value: Option<String>,
+    pub mcc_range_end: Option<String>,
+    pub category_id: Option<i32>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, AsChangeset, Serialize, Deserialize, PartialEq)]
+#[diesel(table_name = merchant_control)]
+#[diesel(treat_none_as_null = true)]
+pub struct MerchantControlChanges {
+    pub list_type: MerchantListType,
+    pub match_type: MerchantMatchType,
+    pub match_value: Option<String>,
+    pub mcc_range_end: Option<String>,
+    pub category_id: Option<i32>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Identifiable, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = merchant_control)]
+pub struct MerchantControl {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub user_id: i32,
+    pub list_type: MerchantListType,
+    pub match_type: MerchantMatchType,
+    pub match_value: Option<String>,
+    pub mcc_range_end: Option<String>,
+    pub category_id: Option<i32>,
+    pub reason: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize, PartialEq)]
+#[diesel(table_name = merchant_control_decline)]
+pub struct InsertableMerchantControlDecline {
+    pub user_id: i32,
+    pub passthrough_card_id: i32,
+    pub merchant_control_id: Option<i32>,
+    pub transaction_token: Option<String>,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Identifiable, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = merchant_control_decline)]
+pub struct MerchantControlDecline {
+    pub id: i32,
+    pub user_id: i32,
+    pub passthrough_card_id: i32,
+    pub merchant_control_id: Option<i32>,
+    pub transaction_token: Option<String>,
+    pub reason: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl MerchantControl {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert(control: &InsertableMerchantControl) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let control = diesel::insert_into(merchant_control::table)
+            .values(control)
+            .get_result(&mut conn).await?;
+        Ok(control)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_for_user(user_id: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let controls = merchant_control::table
+            .filter(merchant_control::user_id.eq(user_id))
+            .order(merchant_control::id.asc())
+            .load::<MerchantControl>(&mut conn).await?;
+        Ok(controls)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_by_public_id_for_user(user_id: i32, public_id: &Uuid) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let control = merchant_control::table
+            .filter(merchant_control::user_id.eq(user_id))
+            .filter(merchant_control::public_id.eq(public_id))
+            .first::<MerchantControl>(&mut conn).await?;
+        Ok(control)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update(id: i32, changes: &MerchantControlChanges) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let control = diesel::update(merchant_control::table)
+            .filter(merchant_control::id.eq(id))
+            .set((
+                changes,
+                merchant_control::updated_at.eq(chrono::Utc::now().naive_utc())
+            ))
+            .get_result(&mut conn).await?;
+        Ok(control)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn delete(id: i32) -> Result<(), DataError> {
+        let mut conn = db::connection().await?;
+        diesel::delete(merchant_control::table)
+            .filter(merchant_control::id.eq(id))
+            .execute(&mut conn).await?;
+        Ok(())
+    }
+}
+
+impl MerchantControlDecline {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert(decline: &InsertableMerchantControlDecline) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let decline = diesel::insert_into(merchant_control_decline::table)
+            .values(decline)
+            .get_result(&mut conn).await?;
+        Ok(decline)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_for_user(user_id: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let declines = merchant_control_decline::table
+            .filter(merchant_control_decline::user_id.eq(user_id))
+            .order(merchant_control_decline::id.asc())
+            .load::<MerchantControlDecline>(&mut conn).await?;
+        Ok(declines)
+    }
+}
@@ -0,0 +1,71 @@
+use actix_web::http::StatusCode;
+use actix_web::ResponseError;
+use crate::error::data_error::DataError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum MerchantControlError {
+    #[error("Not found")]
+    NotFound(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Invalid merchant control")]
+    Invalid(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Unexpected error")]
+    Unexpected(#[source] Box<dyn std::error::Error + Send + Sync>)
+}
+
+impl ResponseError for MerchantControlError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            MerchantControlError::NotFound(_) => StatusCode::NOT_FOUND,
+            MerchantControlError::Invalid(_) => StatusCode::BAD_REQUEST,
+            MerchantControlError::Unexpected(_) => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+impl From<DataError> for MerchantControlError {
+    fn from(value: DataError) -> Self {
+        match value {
+            DataError::Conflict(e) => MerchantControlError::Unexpected(e),
+            DataError::NotFound(e) => MerchantControlError::NotFound(e),
+            DataError::Format(e) => MerchantControlError::Unexpected(e),
+            DataError::Unexpected(e) => MerchantControlError::Unexpected(e),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for MerchantControlError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MerchantControlError::NotFound(_), MerchantControlError::NotFound(_))
+            | (MerchantControlError::Invalid(_), MerchantControlError::Invalid(_))
+            | (MerchantControlError::Unexpected(_), MerchantControlError::Unexpected(_)) => true,
+            _ => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::http::StatusCode;
+    use actix_web::ResponseError;
+    use crate::error::data_error::DataError;
+    use crate::merchant_control::error::MerchantControlError;
+
+    const BASE_ERROR: &str = "test";
+
+    #[test]
+    pub fn test_from_data_error() {
+        assert_eq!(MerchantControlError::Unexpected(BASE_ERROR.into()), MerchantControlError::from(DataError::Conflict(BASE_ERROR.into())));
+        assert_eq!(MerchantControlError::NotFound(BASE_ERROR.into()), MerchantControlError::from(DataError::NotFound(BASE_ERROR.into())));
+        assert_eq!(MerchantControlError::Unexpected(BASE_ERROR.into()), MerchantControlError::from(DataError::Format(BASE_ERROR.into())));
+        assert_eq!(MerchantControlError::Unexpected(BASE_ERROR.into()), MerchantControlError::from(DataError::Unexpected(BASE_ERROR.into())));
+    }
+
+    #[test]
+    pub fn test_status_codes() {
+        assert_eq!(StatusCode::NOT_FOUND, MerchantControlError::NotFound(BASE_ERROR.into()).status_code());
+        assert_eq!(StatusCode::BAD_REQUEST, MerchantControlError::Invalid(BASE_ERROR.into()).status_code());
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, MerchantControlError::Unexpected(BASE_ERROR.into()).status_code());
+    }
+}
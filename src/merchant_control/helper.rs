@@ -0,0 +1,172 @@
+use crate::merchant_control::constant::{MerchantListType, MerchantMatchType, NOT_ON_ALLOW_LIST_REASON};
+use crate::merchant_control::model::{MerchantControlDecision, MerchantControlModel, MerchantDetails};
+
+// block entries win over allow entries, and once a user has any allow entry everything else is declined
+pub fn evaluate(controls: &Vec<MerchantControlModel>, merchant: &MerchantDetails) -> Option<MerchantControlDecision> {
+    if let Some(block) = controls.iter()
+        .filter(|control| control.list_type == MerchantListType::Block)
+        .find(|control| matches(control, merchant)) {
+        return Some(
+            MerchantControlDecision {
+                merchant_control_id: Some(block.id),
+                reason: block.reason.clone()
+            }
+        )
+    }
+    let mut allows = controls.iter()
+        .filter(|control| control.list_type == MerchantListType::Allow)
+        .peekable();
+    if allows.peek().is_some() && !allows.any(|control| matches(control, merchant)) {
+        return Some(
+            MerchantControlDecision {
+                merchant_control_id: None,
+                reason: NOT_ON_ALLOW_LIST_REASON.to_string()
+            }
+        )
+    }
+    None
+}
+
+pub fn matches(control: &MerchantControlModel, merchant: &MerchantDetails) -> bool {
+    let value = control.match_value.as_deref().unwrap_or_default();
+    match control.match_type {
+        MerchantMatchType::Mcc => merchant.mcc.as_deref().is_some_and(|mcc| mcc == value),
+        MerchantMatchType::MccRange => {
+            let mcc = merchant.mcc.as_deref().and_then(|mcc| mcc.parse::<u16>().ok());
+            let start = value.parse::<u16>().ok();
+            let end = control.mcc_range_end.as_deref().and_then(|end| end.parse::<u16>().ok());
+            match (mcc, start, end) {
+                (Some(mcc), Some(start), Some(end)) => start <= mcc && mcc <= end,
+                _ => false
+            }
+        },
+        MerchantMatchType::Descriptor => merchant.descriptor.as_deref().is_some_and(|descriptor| matches_pattern(value, descriptor)),
+        MerchantMatchType::AcceptorId => merchant.acceptor_id.as_deref().is_some_and(|acceptor_id| acceptor_id.trim() == value),
+        MerchantMatchType::Country => merchant.country.as_deref().is_some_and(|country| country.trim().eq_ignore_ascii_case(value)),
+        MerchantMatchType::Category => control.category_id.is_some() && merchant.category_id == control.category_id
+    }
+}
+
+// case insensitive match where * stands in for any run of characters, so "*netflix*" finds "NETFLIX.COM 866-579"
+pub fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.trim().to_lowercase().chars().collect();
+    let text: Vec<char> = text.trim().to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // where the last * was and how much of the text it has swallowed so far
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+    use crate::merchant_control::constant::{MerchantListType, MerchantMatchType, NOT_ON_ALLOW_LIST_REASON};
+    use crate::merchant_control::helper::{evaluate, matches, matches_pattern};
+    use crate::merchant_control::model::{MerchantControlModel, MerchantDetails};
+
+    fn control(id: i32, list_type: MerchantListType, match_type: MerchantMatchType, value: &str) -> MerchantControlModel {
+        MerchantControlModel {
+            id,
+            public_id: Uuid::new_v4(),
+            user_id: 1,
+            list_type,
+            match_type,
+            match_value: Some(value.to_string()),
+            mcc_range_end: None,
+            category_id: None,
+            reason: format!("control {}", id),
+        }
+    }
+
+    fn merchant() -> MerchantDetails {
+        MerchantDetails {
+            mcc: Some("7995".to_string()),
+            descriptor: Some("NETFLIX.COM 866-579".to_string()),
+            acceptor_id: Some("174030075991".to_string()),
+            country: Some("USA".to_string()),
+            category_id: Some(4),
+        }
+    }
+
+    #[test]
+    fn test_matches_pattern() {
+        assert!(matches_pattern("*netflix*", "NETFLIX.COM 866-579"));
+        assert!(matches_pattern("netflix*", "NETFLIX.COM 866-579"));
+        assert!(matches_pattern("NETFLIX.COM 866-579", "netflix.com 866-579"));
+        assert!(matches_pattern("*", "anything"));
+        assert!(matches_pattern("net*579", "NETFLIX.COM 866-579"));
+        assert!(!matches_pattern("netflix", "NETFLIX.COM 866-579"));
+        assert!(!matches_pattern("*spotify*", "NETFLIX.COM 866-579"));
+        assert!(!matches_pattern("*579*x", "NETFLIX.COM 866-579"));
+    }
+
+    #[test]
+    fn test_matches_each_type() {
+        let merchant = merchant();
+        assert!(matches(&control(1, MerchantListType::Block, MerchantMatchType::Mcc, "7995"), &merchant));
+        assert!(!matches(&control(1, MerchantListType::Block, MerchantMatchType::Mcc, "6011"), &merchant));
+        let mut range = control(1, MerchantListType::Block, MerchantMatchType::MccRange, "7990");
+        range.mcc_range_end = Some("7999".to_string());
+        assert!(matches(&range, &merchant));
+        range.match_value = Some("7996".to_string());
+        assert!(!matches(&range, &merchant));
+        assert!(matches(&control(1, MerchantListType::Block, MerchantMatchType::Descriptor, "*netflix*"), &merchant));
+        assert!(matches(&control(1, MerchantListType::Block, MerchantMatchType::AcceptorId, "174030075991"), &merchant));
+        assert!(matches(&control(1, MerchantListType::Block, MerchantMatchType::Country, "usa"), &merchant));
+        assert!(!matches(&control(1, MerchantListType::Block, MerchantMatchType::Country, "CAN"), &merchant));
+        let mut category = control(1, MerchantListType::Block, MerchantMatchType::Category, "dining");
+        category.category_id = Some(4);
+        assert!(matches(&category, &merchant));
+        category.category_id = Some(1);
+        assert!(!matches(&category, &merchant));
+    }
+
+    #[test]
+    fn test_missing_merchant_fields_dont_match() {
+        let merchant = MerchantDetails::default();
+        assert!(!matches(&control(1, MerchantListType::Block, MerchantMatchType::Mcc, "7995"), &merchant));
+        assert!(!matches(&control(1, MerchantListType::Block, MerchantMatchType::Descriptor, "*"), &merchant));
+        assert!(!matches(&control(1, MerchantListType::Block, MerchantMatchType::Category, "dining"), &merchant));
+    }
+
+    #[test]
+    fn test_evaluate_block_wins() {
+        let controls = vec![
+            control(1, MerchantListType::Allow, MerchantMatchType::Country, "USA"),
+            control(2, MerchantListType::Block, MerchantMatchType::Mcc, "7995"),
+        ];
+        let decision = evaluate(&controls, &merchant()).expect("declines");
+        assert_eq!(Some(2), decision.merchant_control_id);
+        assert_eq!("control 2", decision.reason);
+    }
+
+    #[test]
+    fn test_evaluate_allow_list() {
+        let controls = vec![control(1, MerchantListType::Allow, MerchantMatchType::Country, "USA")];
+        assert_eq!(None, evaluate(&controls, &merchant()));
+        let controls = vec![control(1, MerchantListType::Allow, MerchantMatchType::Country, "CAN")];
+        let decision = evaluate(&controls, &merchant()).expect("declines");
+        assert_eq!(None, decision.merchant_control_id);
+        assert_eq!(NOT_ON_ALLOW_LIST_REASON, decision.reason);
+    }
+
+    #[test]
+    fn test_evaluate_no_controls() {
+        assert_eq!(None, evaluate(&vec![], &merchant()));
+    }
+}
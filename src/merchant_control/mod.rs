@@ -0,0 +1,13 @@
+pub mod config;
+pub mod constant;
+pub mod error;
+pub mod helper;
+pub mod model;
+pub mod request;
+pub mod response;
+pub mod service;
+
+mod controller;
+mod dao;
+mod entity;
+mod tests;
\ No newline at end of file
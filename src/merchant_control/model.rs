@@ -0,0 +1,49 @@
+use uuid::Uuid;
+use crate::merchant_control::constant::{MerchantListType, MerchantMatchType};
+use crate::merchant_control::entity::MerchantControl;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MerchantControlModel {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub user_id: i32,
+    pub list_type: MerchantListType,
+    pub match_type: MerchantMatchType,
+    pub match_value: Option<String>,
+    pub mcc_range_end: Option<String>,
+    pub category_id: Option<i32>,
+    pub reason: String,
+}
+
+impl From<MerchantControl> for MerchantControlModel {
+    fn from(value: MerchantControl) -> Self {
+        MerchantControlModel {
+            id: value.id,
+            public_id: value.public_id,
+            user_id: value.user_id,
+            list_type: value.list_type,
+            match_type: value.match_type,
+            match_value: value.match_value,
+            mcc_range_end: value.mcc_range_end,
+            category_id: value.category_id,
+            reason: value.reason,
+        }
+    }
+}
+
+/// The merchant fields a control can match on, pulled out of the asa once per request.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MerchantDetails {
+    pub mcc: Option<String>,
+    pub descriptor: Option<String>,
+    pub acceptor_id: Option<String>,
+    pub country: Option<String>,
+    pub category_id: Option<i32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MerchantControlDecision {
+    // none when the decline comes from the allow list as a whole rather than one control
+    pub merchant_control_id: Option<i32>,
+    pub reason: String,
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use crate::merchant_control::constant::{MerchantListType, MerchantMatchType};
+
+// value is the mcc, start of the mcc range, descriptor pattern, acceptor id, country or category name
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MerchantControlRequest {
+    pub list_type: MerchantListType,
+    pub match_type: MerchantMatchType,
+    pub value: String,
+    pub mcc_range_end: Option<String>,
+    pub reason: Option<String>,
+}
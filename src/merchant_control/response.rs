@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::merchant_control::constant::{MerchantListType, MerchantMatchType};
+use crate::merchant_control::model::MerchantControlModel;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MerchantControlResponse {
+    pub public_id: Uuid,
+    pub list_type: MerchantListType,
+    pub match_type: MerchantMatchType,
+    pub value: Option<String>,
+    pub mcc_range_end: Option<String>,
+    pub reason: String,
+}
+
+impl From<&MerchantControlModel> for MerchantControlResponse {
+    fn from(value: &MerchantControlModel) -> Self {
+        MerchantControlResponse {
+            public_id: value.public_id,
+            list_type: value.list_type.clone(),
+            match_type: value.match_type.clone(),
+            value: value.match_value.clone(),
+            mcc_range_end: value.mcc_range_end.clone(),
+            reason: value.reason.clone(),
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use uuid::Uuid;
+use crate::asa::request::AsaRequest;
+use crate::category::error::CategoryError;
+use crate::category::service::CategoryServiceTrait;
+use crate::merchant_control::constant::{MerchantListType, MerchantMatchType};
+use crate::merchant_control::dao::{MerchantControlDao, MerchantControlDaoTrait};
+use crate::merchant_control::entity::{InsertableMerchantControl, InsertableMerchantControlDecline, MerchantControlChanges};
+use crate::merchant_control::error::MerchantControlError;
+use crate::merchant_control::helper::evaluate;
+use crate::merchant_control::model::{MerchantControlDecision, MerchantControlModel, MerchantDetails};
+use crate::merchant_control::request::MerchantControlRequest;
+use crate::passthrough_card::model::PassthroughCardModel as PassthroughCard;
+use crate::user::model::UserModel as User;
+
+#[async_trait(?Send)]
+pub trait MerchantControlServiceTrait {
+    async fn get_controls(self: Arc<Self>, user: &User) -> Result<Vec<MerchantControlModel>, MerchantControlError>;
+    async fn create_control(self: Arc<Self>, user: &User, request: &MerchantControlRequest) -> Result<MerchantControlModel, MerchantControlError>;
+    async fn update_control(self: Arc<Self>, user: &User, public_id: &Uuid, request: &MerchantControlRequest) -> Result<MerchantControlModel, MerchantControlError>;
+    async fn delete_control(self: Arc<Self>, user: &User, public_id: &Uuid) -> Result<(), MerchantControlError>;
+    async fn check(self: Arc<Self>, request: &AsaRequest, passthrough_card: &PassthroughCard) -> Result<Option<MerchantControlDecision>, MerchantControlError>;
+}
+
+pub struct MerchantControlService {
+    dao: Arc<dyn MerchantControlDaoTrait>,
+    category_service: Arc<dyn CategoryServiceTrait>,
+}
+
+impl MerchantControlService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
+    pub fn new_with_services(category_service: Arc<dyn CategoryServiceTrait>) -> Self {
+        Self {
+            dao: Arc::new(MerchantControlDao::new()),
+            category_service
+        }
+    }
+
+    #[cfg(test)]
+    pub fn new_with_mocks(
+        dao: Arc<dyn MerchantControlDaoTrait>,
+        category_service: Arc<dyn CategoryServiceTrait>
+    ) -> Self {
+        Self {
+            dao,
+            category_service
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl MerchantControlServiceTrait for MerchantControlService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_controls(self: Arc<Self>, user: &User) -> Result<Vec<MerchantControlModel>, MerchantControlError> {
+        Ok(
+            self.dao.clone().get_for_user(user.id).await?
+                .into_iter()
+                .map(MerchantControlModel::from)
+                .collect()
+        )
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn create_control(self: Arc<Self>, user: &User, request: &MerchantControlRequest) -> Result<MerchantControlModel, MerchantControlError> {
+        let changes = self.clone().validate(request).await?;
+        let control = self.dao.clone().insert(
+            &InsertableMerchantControl {
+                public_id: Uuid::new_v4(),
+                user_id: user.id,
+                list_type: changes.list_type,
+                match_type: changes.match_type,
+                match_value: changes.match_value,
+                mcc_range_end: changes.mcc_range_end,
+                category_id: changes.category_id,
+                reason: changes.reason,
+            }
+        ).await?;
+        Ok(MerchantControlModel::from(control))
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_control(self: Arc<Self>, user: &User, public_id: &Uuid, request: &MerchantControlRequest) -> Result<MerchantControlModel, MerchantControlError> {
+        let control = self.dao.clone().get_by_public_id_for_user(user.id, public_id).await?;
+        let changes = self.clone().validate(request).await?;
+        Ok(MerchantControlModel::from(self.dao.clone().update(&control, &changes).await?))
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn delete_control(self: Arc<Self>, user: &User, public_id: &Uuid) -> Result<(), MerchantControlError> {
+        let control = self.dao.clone().get_by_public_id_for_user(user.id, public_id).await?;
+        Ok(self.dao.clone().delete(&control).await?)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn check(self: Arc<Self>, request: &AsaRequest, passthrough_card: &PassthroughCard) -> Result<Option<MerchantControlDecision>, MerchantControlError> {
+        let controls: Vec<MerchantControlModel> = self.dao.clone().get_for_user(passthrough_card.user_id).await?
+            .into_iter()
+            .map(MerchantControlModel::from)
+            .collect();
+        if controls.is_empty() {
+            return Ok(None)
+        }
+        let merchant = request.merchant.as_ref();
+        let mcc = merchant.and_then(|merchant| merchant.mcc.clone());
+        // the mapping is only looked up when a control needs it, it's cached but still a hop on the asa path
+        let category_id = match mcc.as_deref() {
+            Some(mcc) if controls.iter().any(|control| control.match_type == MerchantMatchType::Category) => {
+                match self.category_service.clone().get_mcc_mapping_by_mcc(mcc).await {
+                    Ok(mapping) => Some(mapping.category_id),
+                    Err(CategoryError::NotFound(_)) => {
+                        tracing::info!("No category for mcc={}", mcc);
+                        None
+                    },
+                    // failing the lookup would let a category block through, so the handler declines instead
+                    Err(e) => return Err(MerchantControlError::Unexpected(e.into()))
+                }
+            },
+            _ => None
+        };
+        let details = MerchantDetails {
+            mcc,
+            descriptor: merchant.and_then(|merchant| merchant.descriptor.clone()),
+            acceptor_id: merchant.and_then(|merchant| merchant.acceptor_id.clone()),
+            country: merchant.and_then(|merchant| merchant.country.clone()),
+            category_id,
+        };
+        let decision = evaluate(&controls, &details);
+        if let Some(decision) = decision.as_ref() {
+            // the decline stands even if we can't write down why
+            if let Err(e) = self.dao.clone().insert_decline(
+                &InsertableMerchantControlDecline {
+                    user_id: passthrough_card.user_id,
+                    passthrough_card_id: passthrough_card.id,
+                    merchant_control_id: decision.merchant_control_id,
+                    transaction_token: request.token.clone(),
+                    reason: decision.reason.clone(),
+                }
+            ).await {
+                tracing::error!("Unable to record merchant control decline for card={} error={:?}", passthrough_card.id, &e);
+            }
+        }
+        Ok(decision)
+    }
+}
+
+impl MerchantControlService {
+    async fn validate(self: Arc<Self>, request: &MerchantControlRequest) -> Result<MerchantControlChanges, MerchantControlError> {
+        let value = request.value.trim().to_string();
+        if value.is_empty() {
+            return Err(MerchantControlError::Invalid("value is required".into()))
+        }
+        if value.len() > 255 {
+            return Err(MerchantControlError::Invalid("value is too long".into()))
+        }
+        let mut mcc_range_end = None;
+        let mut category_id = None;
+        let match_value = match request.match_type {
+            MerchantMatchType::Mcc => parse_mcc(&value)?,
+            MerchantMatchType::MccRange => {
+                let start = parse_mcc(&value)?;
+                let end = parse_mcc(request.mcc_range_end.as_deref().unwrap_or_default())?;
+                if end < start {
+                    return Err(MerchantControlError::Invalid("mcc range ends before it starts".into()))
+                }
+                mcc_range_end = Some(end);
+                start
+            },
+            MerchantMatchType::Descriptor | MerchantMatchType::AcceptorId => value,
+            MerchantMatchType::Country => {
+                if value.len() != 3 || !value.chars().all(|c| c.is_ascii_alphabetic()) {
+                    return Err(MerchantControlError::Invalid("country should be an iso 3166 alpha-3 code".into()))
+                }
+                value.to_uppercase()
+            },
+            MerchantMatchType::Category => {
+                let category = self.category_service.clone().get_category_by_name(&value).await
+                    .map_err(|e| MerchantControlError::Invalid(e.into()))?;
+                category_id = Some(category.id);
+                category.name
+            }
+        };
+        let reason = request.reason.as_deref()
+            .map(str::trim)
+            .filter(|reason| !reason.is_empty())
+            .map(|reason| reason.chars().take(255).collect())
+            .unwrap_or_else(|| default_reason(&request.list_type, &request.match_type, &match_value, mcc_range_end.as_deref()));
+        Ok(
+            MerchantControlChanges {
+                list_type: request.list_type.clone(),
+                match_type: request.match_type.clone(),
+                match_value: Some(match_value),
+                mcc_range_end,
+                category_id,
+                reason,
+            }
+        )
+    }
+}
+
+fn parse_mcc(value: &str) -> Result<String, MerchantControlError> {
+    let value = value.trim();
+    if value.len() != 4 || !value.chars().all(|c| c.is_ascii_digit()) {
+        return Err(MerchantControlError::Invalid("mcc should be four digits".into()))
+    }
+    Ok(value.to_string())
+}
+
+fn default_reason(list_type: &MerchantListType, match_type: &MerchantMatchType, value: &str, mcc_range_end: Option<&str>) -> String {
+    let described = match match_type {
+        MerchantMatchType::Mcc => format!("mcc {}", value),
+        MerchantMatchType::MccRange => format!("mcc {}-{}", value, mcc_range_end.unwrap_or(value)),
+        MerchantMatchType::Descriptor => format!("merchant {}", value),
+        MerchantMatchType::AcceptorId => format!("merchant id {}", value),
+        MerchantMatchType::Country => format!("country {}", value),
+        MerchantMatchType::Category => format!("category {}", value)
+    };
+    let reason = match list_type {
+        MerchantListType::Block => format!("blocked {}", described),
+        MerchantListType::Allow => format!("allowed {}", described)
+    };
+    reason.chars().take(255).collect()
+}
@@ -0,0 +1,213 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::test;
+    use uuid::Uuid;
+    use crate::asa::request::create_example_asa;
+    use crate::category::constant::Category;
+    use crate::category::error::CategoryError;
+    use crate::category::service::{CategoryService, MockCategoryServiceTrait};
+    use crate::error::data_error::DataError;
+    use crate::merchant_control::constant::{MerchantListType, MerchantMatchType, NOT_ON_ALLOW_LIST_REASON};
+    use crate::merchant_control::dao::MockMerchantControlDaoTrait;
+    use crate::merchant_control::entity::{MerchantControl, MerchantControlDecline};
+    use crate::merchant_control::error::MerchantControlError;
+    use crate::merchant_control::request::MerchantControlRequest;
+    use crate::merchant_control::service::{MerchantControlService, MerchantControlServiceTrait};
+    use crate::test_helper::passthrough_card::{create_mock_passthrough_card, create_passthrough_card};
+    use crate::test_helper::user::create_user;
+
+    const DINING_MCC: &str = "5812";
+    const GAMBLING_MCC: &str = "7995";
+
+    fn service() -> Arc<MerchantControlService> {
+        Arc::new(MerchantControlService::new_with_services(Arc::new(CategoryService::new())))
+    }
+
+    fn request(list_type: MerchantListType, match_type: MerchantMatchType, value: &str) -> MerchantControlRequest {
+        MerchantControlRequest {
+            list_type,
+            match_type,
+            value: value.to_string(),
+            mcc_range_end: None,
+            reason: None,
+        }
+    }
+
+    #[test]
+    async fn test_create_update_delete_control() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let service = service();
+        let created = service.clone().create_control(&user, &request(MerchantListType::Block, MerchantMatchType::Mcc, GAMBLING_MCC)).await.expect("creates");
+        assert_eq!(Some(GAMBLING_MCC.to_string()), created.match_value);
+        assert_eq!("blocked mcc 7995", &created.reason);
+
+        let mut update = request(MerchantListType::Block, MerchantMatchType::MccRange, "7990");
+        update.mcc_range_end = Some("7999".to_string());
+        update.reason = Some("no gambling".to_string());
+        let updated = service.clone().update_control(&user, &created.public_id, &update).await.expect("updates");
+        assert_eq!(created.public_id, updated.public_id);
+        assert_eq!(MerchantMatchType::MccRange, updated.match_type);
+        assert_eq!(Some("7999".to_string()), updated.mcc_range_end);
+        assert_eq!("no gambling", &updated.reason);
+        assert_eq!(vec![updated.clone()], service.clone().get_controls(&user).await.expect("gets controls"));
+
+        service.clone().delete_control(&user, &created.public_id).await.expect("deletes");
+        assert!(service.clone().get_controls(&user).await.expect("gets controls").is_empty());
+        assert_eq!(
+            MerchantControlError::NotFound("test".into()),
+            service.clone().delete_control(&user, &created.public_id).await.expect_err("already deleted")
+        );
+    }
+
+    #[test]
+    async fn test_controls_are_per_user() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let other_user = create_user().await;
+        let service = service();
+        let created = service.clone().create_control(&user, &request(MerchantListType::Block, MerchantMatchType::Country, "can")).await.expect("creates");
+        assert_eq!(Some("CAN".to_string()), created.match_value);
+        assert_eq!(
+            MerchantControlError::NotFound("test".into()),
+            service.clone().delete_control(&other_user, &created.public_id).await.expect_err("not theirs")
+        );
+        assert!(service.clone().get_controls(&other_user).await.expect("gets controls").is_empty());
+    }
+
+    #[test]
+    async fn test_invalid_controls() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let service = service();
+        let invalid = vec![
+            request(MerchantListType::Block, MerchantMatchType::Mcc, "79"),
+            request(MerchantListType::Block, MerchantMatchType::Mcc, ""),
+            request(MerchantListType::Block, MerchantMatchType::MccRange, "7990"),
+            request(MerchantListType::Block, MerchantMatchType::Country, "US"),
+            request(MerchantListType::Block, MerchantMatchType::Category, "gambling halls"),
+        ];
+        for invalid in invalid.iter() {
+            assert_eq!(
+                MerchantControlError::Invalid("test".into()),
+                service.clone().create_control(&user, invalid).await.expect_err("invalid")
+            );
+        }
+        let mut backwards = request(MerchantListType::Block, MerchantMatchType::MccRange, "7999");
+        backwards.mcc_range_end = Some("7990".to_string());
+        assert_eq!(
+            MerchantControlError::Invalid("test".into()),
+            service.clone().create_control(&user, &backwards).await.expect_err("invalid")
+        );
+    }
+
+    #[test]
+    async fn test_check_blocks_mcc_and_records_reason() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let service = service();
+        let mut block = request(MerchantListType::Block, MerchantMatchType::Mcc, GAMBLING_MCC);
+        block.reason = Some("no gambling".to_string());
+        let control = service.clone().create_control(&user, &block).await.expect("creates");
+
+        assert_eq!(None, service.clone().check(&create_example_asa(100, DINING_MCC.to_string()), &card).await.expect("checks"));
+        let asa = create_example_asa(100, GAMBLING_MCC.to_string());
+        let decision = service.clone().check(&asa, &card).await.expect("checks").expect("declines");
+        assert_eq!(Some(control.id), decision.merchant_control_id);
+        assert_eq!("no gambling", &decision.reason);
+
+        let declines = MerchantControlDecline::get_for_user(user.id).await.expect("gets declines");
+        assert_eq!(1, declines.len());
+        assert_eq!(asa.token, declines[0].transaction_token);
+        assert_eq!(Some(control.id), declines[0].merchant_control_id);
+        assert_eq!(card.id, declines[0].passthrough_card_id);
+        assert_eq!("no gambling", &declines[0].reason);
+    }
+
+    #[test]
+    async fn test_check_blocks_category() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let service = service();
+        let control = service.clone().create_control(&user, &request(MerchantListType::Block, MerchantMatchType::Category, "Dining")).await.expect("creates");
+        assert_eq!(Some(Category::Dining as i32), control.category_id);
+        assert_eq!(Some("dining".to_string()), control.match_value);
+        assert!(service.clone().check(&create_example_asa(100, DINING_MCC.to_string()), &card).await.expect("checks").is_some());
+        assert!(service.clone().check(&create_example_asa(100, GAMBLING_MCC.to_string()), &card).await.expect("checks").is_none());
+    }
+
+    #[test]
+    async fn test_check_errors_when_category_lookup_fails() {
+        crate::test_helper::general::init();
+        let mut dao = MockMerchantControlDaoTrait::new();
+        dao.expect_get_for_user()
+            .times(1)
+            .return_once(|user_id| Ok(vec![MerchantControl {
+                id: 1,
+                public_id: Uuid::new_v4(),
+                user_id,
+                list_type: MerchantListType::Block,
+                match_type: MerchantMatchType::Category,
+                match_value: Some("dining".to_string()),
+                mcc_range_end: None,
+                category_id: Some(Category::Dining as i32),
+                reason: "no dining".to_string(),
+                created_at: Default::default(),
+                updated_at: Default::default(),
+            }]));
+        dao.expect_insert_decline().times(0);
+        let mut category_service = MockCategoryServiceTrait::new();
+        category_service.expect_get_mcc_mapping_by_mcc()
+            .times(1)
+            .return_once(|_| Err(CategoryError::Unexpected("database unavailable".into())));
+        // treating this as no category would let the dining block through
+        let service = Arc::new(MerchantControlService::new_with_mocks(Arc::new(dao), Arc::new(category_service)));
+        let error = service.clone().check(&create_example_asa(100, DINING_MCC.to_string()), &create_mock_passthrough_card()).await.expect_err("errors");
+        assert_eq!(MerchantControlError::Unexpected("test".into()), error);
+    }
+
+    #[test]
+    async fn test_check_allow_list() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let service = service();
+        service.clone().create_control(&user, &request(MerchantListType::Allow, MerchantMatchType::Descriptor, "*test*")).await.expect("creates");
+        let mut asa = create_example_asa(100, DINING_MCC.to_string());
+        assert!(service.clone().check(&asa, &card).await.expect("checks").is_none());
+        asa.merchant.as_mut().unwrap().descriptor = Some("SOMEWHERE ELSE".to_string());
+        let decision = service.clone().check(&asa, &card).await.expect("checks").expect("declines");
+        assert_eq!(None, decision.merchant_control_id);
+        assert_eq!(NOT_ON_ALLOW_LIST_REASON, &decision.reason);
+    }
+
+    #[test]
+    async fn test_check_declines_when_decline_cannot_be_recorded() {
+        crate::test_helper::general::init();
+        let mut dao = MockMerchantControlDaoTrait::new();
+        dao.expect_get_for_user()
+            .times(1)
+            .return_once(|user_id| Ok(vec![MerchantControl {
+                id: 1,
+                public_id: Uuid::new_v4(),
+                user_id,
+                list_type: MerchantListType::Block,
+                match_type: MerchantMatchType::AcceptorId,
+                match_value: Some("1".to_string()),
+                mcc_range_end: None,
+                category_id: None,
+                reason: "blocked merchant id 1".to_string(),
+                created_at: Default::default(),
+                updated_at: Default::default(),
+            }]));
+        dao.expect_insert_decline()
+            .times(1)
+            .return_once(|_| Err(DataError::Unexpected("database unavailable".into())));
+        let service = Arc::new(MerchantControlService::new_with_mocks(Arc::new(dao), Arc::new(CategoryService::new())));
+        let decision = service.clone().check(&create_example_asa(100, DINING_MCC.to_string()), &create_mock_passthrough_card()).await.expect("checks");
+        assert_eq!(Some(1), decision.map(|decision| decision.merchant_control_id.unwrap_or_default()));
+    }
+}
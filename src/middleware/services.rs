@@ -4,6 +4,9 @@ use crate::lithic::service::LithicService as LithicService;
 use crate::charge::service::ChargeService;
 use crate::user::service::{UserService, UserServiceTrait};
 use crate::adyen::checkout::service::AdyenCheckoutService as AdyenChargeService;
+use crate::avs::service::AvsService;
+use crate::card_health::service::CardHealthService;
+use crate::cash::service::CashService;
 use crate::category::service::{CategoryService, CategoryServiceTrait};
 use crate::configuration::configuration::Configuration;
 use crate::credit_card_type::service::{
@@ -12,7 +15,13 @@ use crate::credit_card_type::service::{
 };
 use crate::footprint::service::{FakeFootprintService, FootprintService};
 use crate::passthrough_card::service::{PassthroughCardService, PassthroughCardServiceTrait};
+use crate::merchant::service::MerchantService;
+use crate::merchant_control::service::MerchantControlService;
+use crate::rewards_program::service::RewardsProgramService;
+use crate::rotating_category::service::RotatingCategoryService;
 use crate::rule::service::RuleService;
+use crate::spend_control::service::SpendControlService;
+use crate::stand_in::service::StandInService;
 use crate::ledger::service::LedgerService as LedgerEngine;
 use crate::user_transaction::service::UserTransactionService;
 use crate::wallet::{
@@ -34,7 +43,12 @@ pub struct Services {
     pub footprint_service: Arc<FakeFootprintService>,
     #[cfg(not(feature = "fake-footprint"))]
     pub footprint_service: Arc<FootprintService>,
-    pub user_transaction_service: Arc<UserTransactionService>
+    pub user_transaction_service: Arc<UserTransactionService>,
+    pub spend_control_service: Arc<SpendControlService>,
+    pub merchant_control_service: Arc<MerchantControlService>,
+    pub card_health_service: Arc<CardHealthService>,
+    pub rewards_program_service: Arc<RewardsProgramService>,
+    pub rotating_category_service: Arc<RotatingCategoryService>
 }
 
 impl Services {
@@ -65,13 +79,32 @@ impl Services {
             footprint_service.clone()
         ));
         let category_service = Arc::new(CategoryService::new());
+        let rewards_program_service = Arc::new(RewardsProgramService::new());
+        let rotating_category_service = Arc::new(RotatingCategoryService::new_with_services(
+            wallet_service.clone()
+        ));
+        let merchant_service = Arc::new(MerchantService::new());
         let rule_service = Arc::new(RuleService::new_with_services(
             category_service.clone(),
-            wallet_service.clone()
+            wallet_service.clone(),
+            rewards_program_service.clone(),
+            rotating_category_service.clone(),
+            merchant_service.clone()
         ));
         let user_transaction_service = Arc::new(UserTransactionService::new_with_services(
             wallet_service.clone()
         ));
+        let stand_in_service = Arc::new(StandInService::new(&configuration.stand_in));
+        let avs_service = Arc::new(AvsService::new_with_services(
+            footprint_service.clone(),
+            &configuration.avs
+        ));
+        let spend_control_service = Arc::new(SpendControlService::new());
+        let merchant_control_service = Arc::new(MerchantControlService::new_with_services(
+            category_service.clone()
+        ));
+        let card_health_service = Arc::new(CardHealthService::new(&configuration.card_health));
+        let cash_service = Arc::new(CashService::new(&configuration.cash));
         Self {
             passthrough_card_service: Arc::new(PassthroughCardService::new_with_services(
                 lithic_service.clone(),
@@ -82,13 +115,25 @@ impl Services {
                 charge_service.clone(),
                 rule_service.clone(),
                 passthrough_card_service.clone(),
-                user_service.clone()
+                user_service.clone(),
+                stand_in_service.clone(),
+                avs_service.clone(),
+                spend_control_service.clone(),
+                merchant_control_service.clone(),
+                card_health_service.clone(),
+                cash_service.clone(),
+                &configuration.asa
             )),
             user_service: user_service.clone(),
             credit_card_service: credit_card_service.clone(),
             rule_service: rule_service.clone(),
             footprint_service: footprint_service.clone(),
-            user_transaction_service: user_transaction_service.clone()
+            user_transaction_service: user_transaction_service.clone(),
+            spend_control_service: spend_control_service.clone(),
+            merchant_control_service: merchant_control_service.clone(),
+            card_health_service: card_health_service.clone(),
+            rewards_program_service: rewards_program_service.clone(),
+            rotating_category_service: rotating_category_service.clone()
         }
     }
 }
@@ -4,6 +4,10 @@ use super::controller;
 pub fn config(cfg: &mut web::ServiceConfig) -> () {
     if cfg!(test) {
         cfg
+            .service(
+                web::scope("/merchant-control")
+                    .configure(crate::merchant_control::config::config)
+            )
             .service(
                 web::scope("")
                     .service(controller::create_card)
@@ -15,6 +19,11 @@ pub fn config(cfg: &mut web::ServiceConfig) -> () {
             );
     } else {
         cfg
+            .service(
+                web::scope("/merchant-control")
+                    .wrap(crate::middleware::auth::Auth)
+                    .configure(crate::merchant_control::config::config)
+            )
             .service(
                 web::scope("")
                     .wrap(crate::middleware::auth::Auth)
@@ -0,0 +1,181 @@
+use std::sync::Arc;
+use adyen_checkout::models::{PaymentCancelResponse, PaymentResponse, StandalonePaymentCancelResponse};
+use adyen_checkout::models::payment_cancel_response::Status;
+use adyen_checkout::models::payment_response::ResultCode;
+use adyen_checkout::models::standalone_payment_cancel_response::Status as StandaloneCancelStatus;
+use async_trait::async_trait;
+use crate::footprint::request::{ChargeThroughProxyRequest, ModificationThroughProxyRequest};
+use crate::footprint::service::FootprintServiceTrait;
+use crate::payment_processor::constant::{PaymentProcessorName, ProcessorChargeStatus};
+use crate::payment_processor::error::PaymentProcessorError;
+use crate::payment_processor::model::{ProcessorChargeRequest, ProcessorChargeResult, ProcessorModificationRequest, ProcessorModificationResult};
+use crate::payment_processor::service::PaymentProcessorTrait;
+
+/// Adyen reached through the footprint vault proxy, which fills in the card details.
+pub struct AdyenFootprintProcessor {
+    footprint_service: Arc<dyn FootprintServiceTrait>,
+}
+
+impl AdyenFootprintProcessor {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
+    pub fn new_with_services(footprint_service: Arc<dyn FootprintServiceTrait>) -> Self {
+        Self {
+            footprint_service
+        }
+    }
+}
+
+impl From<ResultCode> for ProcessorChargeStatus {
+    fn from(value: ResultCode) -> Self {
+        match value {
+            ResultCode::Authorised => ProcessorChargeStatus::Approved,
+            ResultCode::Pending => ProcessorChargeStatus::Approved,
+            ResultCode::Received => ProcessorChargeStatus::Approved,
+            ResultCode::Success => ProcessorChargeStatus::Approved,
+
+            ResultCode::AuthenticationFinished => ProcessorChargeStatus::Declined,
+            ResultCode::AuthenticationNotRequired => ProcessorChargeStatus::Declined,
+            ResultCode::Cancelled => ProcessorChargeStatus::Declined,
+            ResultCode::ChallengeShopper => ProcessorChargeStatus::Declined,
+            ResultCode::Error => ProcessorChargeStatus::Declined,
+            ResultCode::IdentifyShopper => ProcessorChargeStatus::Declined,
+            ResultCode::PartiallyAuthorised => ProcessorChargeStatus::PartiallyApproved,
+            ResultCode::PresentToShopper => ProcessorChargeStatus::Declined,
+            ResultCode::RedirectShopper => ProcessorChargeStatus::Declined,
+            ResultCode::Refused => ProcessorChargeStatus::Declined,
+        }
+    }
+}
+
+impl From<PaymentResponse> for ProcessorChargeResult {
+    fn from(value: PaymentResponse) -> Self {
+        let status = match value.result_code.clone() {
+            // only an authorisation can be captured later, anything adyen hasn't settled on yet is cancelled
+            Some(ResultCode::Authorised) => ProcessorChargeStatus::Approved,
+            Some(ResultCode::PartiallyAuthorised) => ProcessorChargeStatus::PartiallyApproved,
+            Some(ResultCode::Cancelled | ResultCode::Error | ResultCode::Refused) => ProcessorChargeStatus::Declined,
+            Some(_) => ProcessorChargeStatus::Pending,
+            // nothing to cancel without a result, and nothing was approved
+            None => ProcessorChargeStatus::Declined
+        };
+        ProcessorChargeResult {
+            status,
+            approved_amount_cents: match status {
+                ProcessorChargeStatus::PartiallyApproved => value.amount.as_ref().map(|amount| amount.value as i32),
+                _ => None
+            },
+            psp_reference: value.psp_reference,
+            merchant_reference: value.merchant_reference,
+            returned_status: value.result_code.and_then(|code| serde_json::to_string(&code).ok()),
+            refusal_reason: value.refusal_reason,
+            // adyen's raw refusal codes are the numbering the engine reads
+            refusal_reason_code: value.refusal_reason_code,
+        }
+    }
+}
+
+impl From<PaymentCancelResponse> for ProcessorModificationResult {
+    fn from(value: PaymentCancelResponse) -> Self {
+        ProcessorModificationResult {
+            received: value.status == Status::Received,
+            psp_reference: value.psp_reference,
+        }
+    }
+}
+
+impl From<StandalonePaymentCancelResponse> for ProcessorModificationResult {
+    fn from(value: StandalonePaymentCancelResponse) -> Self {
+        ProcessorModificationResult {
+            received: value.status == StandaloneCancelStatus::Received,
+            psp_reference: value.psp_reference,
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl PaymentProcessorTrait for AdyenFootprintProcessor {
+    fn name(&self) -> PaymentProcessorName {
+        PaymentProcessorName::Adyen
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn charge<'a>(self: Arc<Self>, request: &ProcessorChargeRequest<'a>) -> Result<ProcessorChargeResult, PaymentProcessorError> {
+        let response = self.footprint_service.clone().proxy_adyen_payment_request(
+            &ChargeThroughProxyRequest {
+                amount_cents: request.amount_cents,
+                currency: request.currency,
+                mcc: request.mcc,
+                payment_method_id: request.payment_method_id,
+                customer_public_id: request.customer_public_id,
+                footprint_vault_id: request.footprint_vault_id,
+                idempotency_key: request.idempotency_key,
+                reference: request.reference,
+                statement: request.statement,
+                allow_partial_authorization: request.allow_partial_authorization,
+                manual_capture: request.manual_capture,
+                avs_address: request.avs_address,
+                avs_zipcode: request.avs_zipcode
+            }
+        ).await?;
+        Ok(ProcessorChargeResult::from(response))
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn cancel(self: Arc<Self>, psp_reference: &str) -> Result<ProcessorModificationResult, PaymentProcessorError> {
+        Ok(self.footprint_service.clone().proxy_adyen_cancel_request(psp_reference).await?.into())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn cancel_by_reference(self: Arc<Self>, reference: &str) -> Result<ProcessorModificationResult, PaymentProcessorError> {
+        Ok(self.footprint_service.clone().proxy_adyen_cancel_by_reference_request(reference).await?.into())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn capture<'a>(self: Arc<Self>, request: &ProcessorModificationRequest<'a>) -> Result<ProcessorModificationResult, PaymentProcessorError> {
+        let capture = self.footprint_service.clone().proxy_adyen_capture_request(
+            &ModificationThroughProxyRequest {
+                psp_reference: request.psp_reference,
+                amount_cents: request.amount_cents,
+                currency: request.currency,
+                reference: request.reference,
+            }
+        ).await?;
+        // adyen answers every accepted modification with received, the outcome comes later by webhook
+        Ok(ProcessorModificationResult {
+            psp_reference: capture.psp_reference,
+            received: true,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn refund<'a>(self: Arc<Self>, request: &ProcessorModificationRequest<'a>) -> Result<ProcessorModificationResult, PaymentProcessorError> {
+        let refund = self.footprint_service.clone().proxy_adyen_refund_request(
+            &ModificationThroughProxyRequest {
+                psp_reference: request.psp_reference,
+                amount_cents: request.amount_cents,
+                currency: request.currency,
+                reference: request.reference,
+            }
+        ).await?;
+        Ok(ProcessorModificationResult {
+            psp_reference: refund.psp_reference,
+            received: true,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn update_amount<'a>(self: Arc<Self>, request: &ProcessorModificationRequest<'a>) -> Result<ProcessorModificationResult, PaymentProcessorError> {
+        let amount_update = self.footprint_service.clone().proxy_adyen_amount_update_request(
+            &ModificationThroughProxyRequest {
+                psp_reference: request.psp_reference,
+                amount_cents: request.amount_cents,
+                currency: request.currency,
+                reference: request.reference,
+            }
+        ).await?;
+        Ok(ProcessorModificationResult {
+            psp_reference: amount_update.psp_reference,
+            received: true,
+        })
+    }
+}
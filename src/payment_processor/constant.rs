@@ -0,0 +1,86 @@
+use std::fmt;
+use std::io::Write;
+use diesel::backend::Backend;
+use diesel::deserialize::{FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::Pg;
+use diesel::serialize::{IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use serde::{Deserialize, Serialize};
+
+/// Where a processor left a charge, whatever its own result codes are.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy, AsExpression, FromSqlRow)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[diesel(sql_type = Text)]
+pub enum ProcessorChargeStatus {
+    Approved,
+    PartiallyApproved,
+    Declined,
+    // neither approved nor declined, the charge has to be cancelled before moving on
+    Pending
+}
+
+impl ToSql<Text, Pg> for ProcessorChargeStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.to_string().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for ProcessorChargeStatus {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"APPROVED" => Ok(ProcessorChargeStatus::Approved),
+            b"PARTIALLY_APPROVED" => Ok(ProcessorChargeStatus::PartiallyApproved),
+            b"DECLINED" => Ok(ProcessorChargeStatus::Declined),
+            b"PENDING" => Ok(ProcessorChargeStatus::Pending),
+            _ => Err(format!("Unknown value for ProcessorChargeStatus found").into()),
+        }
+    }
+}
+
+impl fmt::Display for ProcessorChargeStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            ProcessorChargeStatus::Approved => "APPROVED",
+            ProcessorChargeStatus::PartiallyApproved => "PARTIALLY_APPROVED",
+            ProcessorChargeStatus::Declined => "DECLINED",
+            ProcessorChargeStatus::Pending => "PENDING"
+        })
+    }
+}
+
+/// The processor a wallet card charge went through, captures, refunds and cancels have to go back to it.
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, Copy, AsExpression, FromSqlRow)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[diesel(sql_type = Text)]
+pub enum PaymentProcessorName {
+    Adyen,
+    Fake
+}
+
+impl ToSql<Text, Pg> for PaymentProcessorName {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.to_string().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for PaymentProcessorName {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"ADYEN" => Ok(PaymentProcessorName::Adyen),
+            b"FAKE" => Ok(PaymentProcessorName::Fake),
+            _ => Err(format!("Unknown value for PaymentProcessorName found").into()),
+        }
+    }
+}
+
+impl fmt::Display for PaymentProcessorName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            PaymentProcessorName::Adyen => "ADYEN",
+            PaymentProcessorName::Fake => "FAKE"
+        })
+    }
+}
@@ -0,0 +1,54 @@
+use crate::footprint::error::FootprintError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum PaymentProcessorError {
+    #[error("Not supported by the processor")]
+    NotSupported,
+    #[error("Payment not found")]
+    NotFound(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Unexpected processor error")]
+    Unexpected(#[source] Box<dyn std::error::Error + Send + Sync>)
+}
+
+impl From<FootprintError> for PaymentProcessorError {
+    fn from(value: FootprintError) -> Self {
+        match value {
+            FootprintError::NotImplemented => PaymentProcessorError::NotSupported,
+            FootprintError::NotFound(e) => PaymentProcessorError::NotFound(e),
+            FootprintError::Unauthorized(e) => PaymentProcessorError::Unexpected(e),
+            FootprintError::BadRequest(e) => PaymentProcessorError::Unexpected(e),
+            FootprintError::Conflict(e) => PaymentProcessorError::Unexpected(e),
+            FootprintError::Unexpected(e) => PaymentProcessorError::Unexpected(e),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for PaymentProcessorError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (PaymentProcessorError::NotSupported, PaymentProcessorError::NotSupported)
+            | (PaymentProcessorError::NotFound(_), PaymentProcessorError::NotFound(_))
+            | (PaymentProcessorError::Unexpected(_), PaymentProcessorError::Unexpected(_)) => true,
+            _ => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::footprint::error::FootprintError;
+    use crate::payment_processor::error::PaymentProcessorError;
+
+    const BASE_ERROR: &str = "test";
+
+    #[test]
+    pub fn test_from_footprint_error() {
+        assert_eq!(PaymentProcessorError::NotSupported, PaymentProcessorError::from(FootprintError::NotImplemented));
+        assert_eq!(PaymentProcessorError::NotFound(BASE_ERROR.into()), PaymentProcessorError::from(FootprintError::NotFound(BASE_ERROR.into())));
+        assert_eq!(PaymentProcessorError::Unexpected(BASE_ERROR.into()), PaymentProcessorError::from(FootprintError::Unauthorized(BASE_ERROR.into())));
+        assert_eq!(PaymentProcessorError::Unexpected(BASE_ERROR.into()), PaymentProcessorError::from(FootprintError::BadRequest(BASE_ERROR.into())));
+        assert_eq!(PaymentProcessorError::Unexpected(BASE_ERROR.into()), PaymentProcessorError::from(FootprintError::Conflict(BASE_ERROR.into())));
+        assert_eq!(PaymentProcessorError::Unexpected(BASE_ERROR.into()), PaymentProcessorError::from(FootprintError::Unexpected(BASE_ERROR.into())));
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use uuid::Uuid;
+use crate::payment_processor::constant::{PaymentProcessorName, ProcessorChargeStatus};
+use crate::payment_processor::error::PaymentProcessorError;
+use crate::payment_processor::model::{ProcessorChargeRequest, ProcessorChargeResult, ProcessorModificationRequest, ProcessorModificationResult};
+use crate::payment_processor::service::PaymentProcessorTrait;
+
+// insufficient funds in the numbering RefusalReason reads
+const FAKE_REFUSAL_REASON_CODE: &str = "12";
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FakePayment {
+    pub psp_reference: String,
+    pub reference: String,
+    pub payment_method_id: String,
+    pub status: ProcessorChargeStatus,
+    pub amount_cents: i32,
+    pub captured_cents: i32,
+    pub refunded_cents: i32,
+    pub cancelled: bool,
+}
+
+/// Keeps payments in memory and answers with whatever status was set for the payment method,
+/// approving anything it hasn't been told about. Partial approvals take half the amount.
+pub struct FakePaymentProcessor {
+    outcomes: Mutex<HashMap<String, ProcessorChargeStatus>>,
+    payments: Mutex<Vec<FakePayment>>,
+}
+
+impl FakePaymentProcessor {
+    pub fn new() -> Self {
+        Self {
+            outcomes: Mutex::new(HashMap::new()),
+            payments: Mutex::new(vec![]),
+        }
+    }
+
+    pub fn set_outcome(&self, payment_method_id: &str, status: ProcessorChargeStatus) {
+        self.outcomes.lock().unwrap().insert(payment_method_id.to_string(), status);
+    }
+
+    pub fn get_payments(&self) -> Vec<FakePayment> {
+        self.payments.lock().unwrap().clone()
+    }
+
+    fn modify<F>(&self, psp_reference: &str, modification: F) -> Result<ProcessorModificationResult, PaymentProcessorError>
+    where F: FnOnce(&mut FakePayment) -> Result<(), PaymentProcessorError> {
+        let mut payments = self.payments.lock().unwrap();
+        let payment = payments.iter_mut()
+            .find(|payment| payment.psp_reference == psp_reference)
+            .ok_or(PaymentProcessorError::NotFound(format!("no payment with psp={}", psp_reference).into()))?;
+        modification(payment)?;
+        Ok(ProcessorModificationResult {
+            psp_reference: Uuid::new_v4().to_string(),
+            received: true,
+        })
+    }
+}
+
+#[async_trait(?Send)]
+impl PaymentProcessorTrait for FakePaymentProcessor {
+    fn name(&self) -> PaymentProcessorName {
+        PaymentProcessorName::Fake
+    }
+
+    async fn charge<'a>(self: Arc<Self>, request: &ProcessorChargeRequest<'a>) -> Result<ProcessorChargeResult, PaymentProcessorError> {
+        let status = self.outcomes.lock().unwrap()
+            .get(request.payment_method_id)
+            .copied()
+            .unwrap_or(ProcessorChargeStatus::Approved);
+        let amount_cents = match status {
+            ProcessorChargeStatus::PartiallyApproved => request.amount_cents / 2,
+            _ => request.amount_cents
+        };
+        let psp_reference = Uuid::new_v4().to_string();
+        self.payments.lock().unwrap().push(FakePayment {
+            psp_reference: psp_reference.clone(),
+            reference: request.reference.to_string(),
+            payment_method_id: request.payment_method_id.to_string(),
+            status,
+            amount_cents,
+            captured_cents: match (request.manual_capture, status) {
+                (false, ProcessorChargeStatus::Approved | ProcessorChargeStatus::PartiallyApproved) => amount_cents,
+                _ => 0
+            },
+            refunded_cents: 0,
+            cancelled: false,
+        });
+        Ok(ProcessorChargeResult {
+            status,
+            approved_amount_cents: match status {
+                ProcessorChargeStatus::PartiallyApproved => Some(amount_cents),
+                _ => None
+            },
+            psp_reference: Some(psp_reference),
+            merchant_reference: Some(request.reference.to_string()),
+            returned_status: serde_json::to_string(&status).ok(),
+            refusal_reason: match status {
+                ProcessorChargeStatus::Declined => Some("Not enough balance".to_string()),
+                _ => None
+            },
+            refusal_reason_code: match status {
+                ProcessorChargeStatus::Declined => Some(FAKE_REFUSAL_REASON_CODE.to_string()),
+                _ => None
+            },
+        })
+    }
+
+    async fn cancel(self: Arc<Self>, psp_reference: &str) -> Result<ProcessorModificationResult, PaymentProcessorError> {
+        self.modify(psp_reference, |payment| {
+            payment.cancelled = true;
+            Ok(())
+        })
+    }
+
+    async fn cancel_by_reference(self: Arc<Self>, reference: &str) -> Result<ProcessorModificationResult, PaymentProcessorError> {
+        let psp_reference = self.payments.lock().unwrap().iter()
+            .find(|payment| payment.reference == reference)
+            .map(|payment| payment.psp_reference.clone());
+        match psp_reference {
+            Some(psp_reference) => self.cancel(&psp_reference).await,
+            // like a real processor, a cancel for a payment that never landed is still accepted
+            None => Ok(ProcessorModificationResult {
+                psp_reference: Uuid::new_v4().to_string(),
+                received: true,
+            })
+        }
+    }
+
+    async fn capture<'a>(self: Arc<Self>, request: &ProcessorModificationRequest<'a>) -> Result<ProcessorModificationResult, PaymentProcessorError> {
+        self.modify(request.psp_reference, |payment| {
+            if payment.cancelled || payment.captured_cents + request.amount_cents > payment.amount_cents {
+                return Err(PaymentProcessorError::Unexpected("capture exceeds the authorised amount".into()))
+            }
+            payment.captured_cents += request.amount_cents;
+            Ok(())
+        })
+    }
+
+    async fn refund<'a>(self: Arc<Self>, request: &ProcessorModificationRequest<'a>) -> Result<ProcessorModificationResult, PaymentProcessorError> {
+        self.modify(request.psp_reference, |payment| {
+            if payment.refunded_cents + request.amount_cents > payment.amount_cents {
+                return Err(PaymentProcessorError::Unexpected("refund exceeds the charged amount".into()))
+            }
+            payment.refunded_cents += request.amount_cents;
+            Ok(())
+        })
+    }
+
+    async fn update_amount<'a>(self: Arc<Self>, request: &ProcessorModificationRequest<'a>) -> Result<ProcessorModificationResult, PaymentProcessorError> {
+        self.modify(request.psp_reference, |payment| {
+            if payment.cancelled || payment.captured_cents > 0 || request.amount_cents > payment.amount_cents {
+                return Err(PaymentProcessorError::Unexpected("amount update only lowers an uncaptured authorization".into()))
+            }
+            payment.amount_cents = request.amount_cents;
+            Ok(())
+        })
+    }
+}
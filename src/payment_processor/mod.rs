@@ -0,0 +1,8 @@
+pub mod adyen;
+pub mod constant;
+pub mod error;
+pub mod fake;
+pub mod model;
+pub mod service;
+
+mod tests;
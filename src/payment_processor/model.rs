@@ -0,0 +1,48 @@
+use uuid::Uuid;
+use crate::payment_processor::constant::ProcessorChargeStatus;
+
+#[derive(Debug)]
+pub struct ProcessorChargeRequest<'a> {
+    pub amount_cents: i32,
+    pub currency: &'a str,
+    pub mcc: &'a str,
+    pub payment_method_id: &'a str,
+    pub customer_public_id: &'a str,
+    pub footprint_vault_id: &'a str,
+    pub idempotency_key: &'a Uuid,
+    pub reference: &'a str,
+    pub statement: &'a str,
+    pub allow_partial_authorization: bool,
+    // authorize only, the charge is captured separately once the passthrough transaction clears
+    pub manual_capture: bool,
+    pub avs_address: Option<&'a str>,
+    pub avs_zipcode: Option<&'a str>,
+}
+
+#[derive(Debug)]
+pub struct ProcessorModificationRequest<'a> {
+    pub psp_reference: &'a str,
+    pub amount_cents: i32,
+    pub currency: &'a str,
+    pub reference: &'a str,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProcessorChargeResult {
+    pub status: ProcessorChargeStatus,
+    // what the processor approved in the charge currency, only set on partial approvals
+    pub approved_amount_cents: Option<i32>,
+    pub psp_reference: Option<String>,
+    pub merchant_reference: Option<String>,
+    // the processor's own result code, stored as is on the wallet card charge
+    pub returned_status: Option<String>,
+    pub refusal_reason: Option<String>,
+    // adapters translate their decline codes into the numbering RefusalReason reads
+    pub refusal_reason_code: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProcessorModificationResult {
+    pub psp_reference: String,
+    pub received: bool,
+}
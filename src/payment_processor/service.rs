@@ -0,0 +1,24 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use crate::payment_processor::constant::PaymentProcessorName;
+use crate::payment_processor::error::PaymentProcessorError;
+use crate::payment_processor::model::{ProcessorChargeRequest, ProcessorChargeResult, ProcessorModificationRequest, ProcessorModificationResult};
+
+#[cfg(test)]
+use mockall::automock;
+
+/// An acquirer the wallet cards can be charged through. Implementations own the mapping from their
+/// result codes into `ProcessorChargeResult` so the charge engine never sees them.
+#[cfg_attr(test, automock)]
+#[async_trait(?Send)]
+pub trait PaymentProcessorTrait {
+    fn name(&self) -> PaymentProcessorName;
+    async fn charge<'a>(self: Arc<Self>, request: &ProcessorChargeRequest<'a>) -> Result<ProcessorChargeResult, PaymentProcessorError>;
+    async fn cancel(self: Arc<Self>, psp_reference: &str) -> Result<ProcessorModificationResult, PaymentProcessorError>;
+    // for charges we stopped waiting on before the processor told us their psp reference
+    async fn cancel_by_reference(self: Arc<Self>, reference: &str) -> Result<ProcessorModificationResult, PaymentProcessorError>;
+    async fn capture<'a>(self: Arc<Self>, request: &ProcessorModificationRequest<'a>) -> Result<ProcessorModificationResult, PaymentProcessorError>;
+    async fn refund<'a>(self: Arc<Self>, request: &ProcessorModificationRequest<'a>) -> Result<ProcessorModificationResult, PaymentProcessorError>;
+    // lowers an uncaptured authorization to amount_cents, what's above it goes back to the card
+    async fn update_amount<'a>(self: Arc<Self>, request: &ProcessorModificationRequest<'a>) -> Result<ProcessorModificationResult, PaymentProcessorError>;
+}
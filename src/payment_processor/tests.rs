@@ -0,0 +1,244 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::test;
+    use adyen_checkout::models::{Amount, PaymentCancelResponse, PaymentResponse};
+    use adyen_checkout::models::payment_cancel_response::Status;
+    use adyen_checkout::models::payment_response::ResultCode;
+    use uuid::Uuid;
+    use crate::charge::constant::{ChargeCardAttemptResult, ChargeEngineResult};
+    use crate::charge::service::ChargeService;
+    use crate::footprint::service::MockFootprintServiceTrait;
+    use crate::ledger::service::LedgerService;
+    use crate::payment_processor::adyen::AdyenFootprintProcessor;
+    use crate::payment_processor::constant::{PaymentProcessorName, ProcessorChargeStatus};
+    use crate::payment_processor::error::PaymentProcessorError;
+    use crate::payment_processor::fake::FakePaymentProcessor;
+    use crate::payment_processor::model::{ProcessorChargeRequest, ProcessorChargeResult, ProcessorModificationRequest};
+    use crate::payment_processor::service::PaymentProcessorTrait;
+    use crate::test_helper::charge::{default_charge_budget, default_transaction_metadata};
+    use crate::test_helper::user::create_user;
+    use crate::test_helper::wallet::create_wallet_with_rule;
+    use crate::user::service::UserService;
+
+    fn charge_request<'a>(payment_method_id: &'a str, idempotency_key: &'a Uuid) -> ProcessorChargeRequest<'a> {
+        ProcessorChargeRequest {
+            amount_cents: 1000,
+            currency: "USD",
+            mcc: "7184",
+            payment_method_id,
+            customer_public_id: "customer",
+            footprint_vault_id: "vault",
+            idempotency_key,
+            reference: "reference",
+            statement: "statement",
+            allow_partial_authorization: true,
+            manual_capture: true,
+            avs_address: None,
+            avs_zipcode: None,
+        }
+    }
+
+    #[test]
+    async fn test_adyen_result_code_to_processor_status() {
+        assert_eq!(ProcessorChargeStatus::Approved, ProcessorChargeStatus::from(ResultCode::Authorised));
+        assert_eq!(ProcessorChargeStatus::PartiallyApproved, ProcessorChargeStatus::from(ResultCode::PartiallyAuthorised));
+        assert_eq!(ProcessorChargeStatus::Declined, ProcessorChargeStatus::from(ResultCode::Refused));
+        assert_eq!(ProcessorChargeStatus::Declined, ProcessorChargeStatus::from(ResultCode::Error));
+        assert_eq!(ProcessorChargeStatus::Declined, ProcessorChargeStatus::from(ResultCode::Cancelled));
+        assert_eq!(ProcessorChargeStatus::Approved, ProcessorChargeStatus::from(ResultCode::Pending));
+        assert_eq!(ProcessorChargeStatus::Declined, ProcessorChargeStatus::from(ResultCode::RedirectShopper));
+        assert_eq!(ProcessorChargeStatus::Approved, ProcessorChargeStatus::from(ResultCode::Received));
+    }
+
+    #[test]
+    async fn test_adyen_payment_response_to_charge_result() {
+        let mut partial = PaymentResponse::new();
+        partial.result_code = Some(ResultCode::PartiallyAuthorised);
+        partial.psp_reference = Some("psp".to_string());
+        partial.amount = Some(Box::new(Amount { currency: "USD".to_string(), value: 400 }));
+        let result = ProcessorChargeResult::from(partial);
+        assert_eq!(ProcessorChargeStatus::PartiallyApproved, result.status);
+        assert_eq!(Some(400), result.approved_amount_cents);
+        assert_eq!(Some("psp".to_string()), result.psp_reference);
+        assert_eq!(serde_json::to_string(&ResultCode::PartiallyAuthorised).ok(), result.returned_status);
+
+        let mut full = PaymentResponse::new();
+        full.result_code = Some(ResultCode::Authorised);
+        full.amount = Some(Box::new(Amount { currency: "USD".to_string(), value: 1000 }));
+        // the approved amount only matters when it differs from the request
+        assert_eq!(None, ProcessorChargeResult::from(full).approved_amount_cents);
+
+        // not settled yet, so the engine cancels it rather than capturing later
+        let mut pending = PaymentResponse::new();
+        pending.result_code = Some(ResultCode::Pending);
+        assert_eq!(ProcessorChargeStatus::Pending, ProcessorChargeResult::from(pending).status);
+
+        let result = ProcessorChargeResult::from(PaymentResponse::new());
+        assert_eq!(ProcessorChargeStatus::Declined, result.status);
+        assert_eq!(None, result.returned_status);
+    }
+
+    #[test]
+    async fn test_adyen_processor_charges_through_footprint() {
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        let mut resp = PaymentResponse::new();
+        resp.result_code = Some(ResultCode::Refused);
+        resp.refusal_reason = Some("Not enough balance".to_string());
+        resp.refusal_reason_code = Some("12".to_string());
+        footprint_mock.expect_proxy_adyen_payment_request()
+            .withf(|request| request.payment_method_id == "pm" && request.amount_cents == 1000 && request.allow_partial_authorization)
+            .times(1)
+            .return_once(move |_| Ok(resp));
+
+        let processor = Arc::new(AdyenFootprintProcessor::new_with_services(Arc::new(footprint_mock)));
+        let idempotency_key = Uuid::new_v4();
+        let result = processor.charge(&charge_request("pm", &idempotency_key)).await.expect("charges");
+        assert_eq!(ProcessorChargeStatus::Declined, result.status);
+        assert_eq!(Some("12".to_string()), result.refusal_reason_code);
+    }
+
+    #[test]
+    async fn test_adyen_processor_cancel() {
+        let mut footprint_mock = MockFootprintServiceTrait::new();
+        footprint_mock.expect_proxy_adyen_cancel_request()
+            .times(1)
+            .return_once(|psp| Ok(PaymentCancelResponse::new(
+                "SandellEnterprisesECOM".to_string(),
+                psp.to_string(),
+                "cancel_psp".to_string(),
+                Status::Received
+            )));
+
+        let processor = Arc::new(AdyenFootprintProcessor::new_with_services(Arc::new(footprint_mock)));
+        let result = processor.cancel("psp").await.expect("cancels");
+        assert!(result.received);
+        assert_eq!("cancel_psp", result.psp_reference);
+    }
+
+    #[test]
+    async fn test_fake_processor_outcomes() {
+        let processor = Arc::new(FakePaymentProcessor::new());
+        processor.set_outcome("declined", ProcessorChargeStatus::Declined);
+        processor.set_outcome("partial", ProcessorChargeStatus::PartiallyApproved);
+        let idempotency_key = Uuid::new_v4();
+
+        let approved = processor.clone().charge(&charge_request("unknown", &idempotency_key)).await.expect("charges");
+        assert_eq!(ProcessorChargeStatus::Approved, approved.status);
+        assert_eq!(None, approved.approved_amount_cents);
+
+        let declined = processor.clone().charge(&charge_request("declined", &idempotency_key)).await.expect("charges");
+        assert_eq!(ProcessorChargeStatus::Declined, declined.status);
+        assert!(declined.refusal_reason_code.is_some());
+
+        let partial = processor.clone().charge(&charge_request("partial", &idempotency_key)).await.expect("charges");
+        assert_eq!(ProcessorChargeStatus::PartiallyApproved, partial.status);
+        assert_eq!(Some(500), partial.approved_amount_cents);
+
+        assert_eq!(3, processor.get_payments().len());
+    }
+
+    #[test]
+    async fn test_fake_processor_modifications() {
+        let processor = Arc::new(FakePaymentProcessor::new());
+        let idempotency_key = Uuid::new_v4();
+        let charge = processor.clone().charge(&charge_request("pm", &idempotency_key)).await.expect("charges");
+        let psp_reference = charge.psp_reference.expect("psp reference");
+
+        let capture = ProcessorModificationRequest {
+            psp_reference: &psp_reference,
+            amount_cents: 1000,
+            currency: "USD",
+            reference: "reference",
+        };
+        assert!(processor.clone().capture(&capture).await.expect("captures").received);
+        assert!(processor.clone().refund(&capture).await.expect("refunds").received);
+        // nothing left to refund
+        assert!(processor.clone().refund(&capture).await.is_err());
+
+        let payment = processor.get_payments().pop().expect("payment");
+        assert_eq!(1000, payment.captured_cents);
+        assert_eq!(1000, payment.refunded_cents);
+
+        assert_eq!(
+            PaymentProcessorError::NotFound("no payment with psp=missing".into()),
+            processor.clone().cancel("missing").await.expect_err("unknown payment")
+        );
+        assert!(processor.clone().cancel_by_reference("missing").await.expect("accepted").received);
+        assert!(processor.clone().cancel_by_reference("reference").await.expect("cancels").received);
+        assert!(processor.get_payments().pop().expect("payment").cancelled);
+    }
+
+    #[test]
+    async fn test_fake_processor_amount_update() {
+        let processor = Arc::new(FakePaymentProcessor::new());
+        let idempotency_key = Uuid::new_v4();
+        let charge = processor.clone().charge(&charge_request("pm", &idempotency_key)).await.expect("charges");
+        let psp_reference = charge.psp_reference.expect("psp reference");
+
+        let lower = ProcessorModificationRequest {
+            psp_reference: &psp_reference,
+            amount_cents: 600,
+            currency: "USD",
+            reference: "reference",
+        };
+        assert!(processor.clone().update_amount(&lower).await.expect("lowers").received);
+        assert_eq!(600, processor.get_payments().pop().expect("payment").amount_cents);
+
+        // once captured the authorization can't be lowered
+        assert!(processor.clone().capture(&lower).await.expect("captures").received);
+        assert!(processor.clone().update_amount(&ProcessorModificationRequest { amount_cents: 500, ..lower }).await.is_err());
+    }
+
+    #[test]
+    async fn test_charge_engine_runs_against_fake_processor() {
+        crate::test_helper::general::init();
+        let mut metadata = default_transaction_metadata();
+        metadata.amount_cents = 1000;
+        let user = create_user().await;
+        let declining = create_wallet_with_rule(&user).await;
+        let approving = create_wallet_with_rule(&user).await;
+
+        let processor = Arc::new(FakePaymentProcessor::new());
+        processor.set_outcome(&declining.payment_method_id, ProcessorChargeStatus::Declined);
+
+        let footprint_service = Arc::new(MockFootprintServiceTrait::new());
+        let engine = Arc::new(ChargeService::new_with_processor(
+            Arc::new(UserService::new_with_services(footprint_service)),
+            Arc::new(LedgerService::new()),
+            processor.clone()
+        ));
+        let rtx = engine.clone().register_transaction_only(&user, &metadata).await.expect("registers");
+
+        let (res, _) = engine.clone().charge_card_with_cleanup(
+            Uuid::new_v4(),
+            &declining,
+            &user,
+            &metadata,
+            &rtx,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(ChargeCardAttemptResult::Denied, res);
+
+        let (res, charges) = engine.clone().charge_wallet(
+            &user,
+            &vec![declining.clone(), approving.clone()],
+            &metadata,
+            &rtx,
+            &default_charge_budget()
+        ).await.expect("no error");
+        assert_eq!(ChargeEngineResult::Approved, res);
+        assert_eq!(1, charges.len());
+        assert_eq!(approving.id, charges[0].wallet_card_id);
+        assert_eq!(PaymentProcessorName::Fake, charges[0].processor);
+        assert_eq!(3, processor.get_payments().len());
+
+        // modifications go back to the processor that took the charge, never to adyen
+        let adyen_engine = Arc::new(ChargeService::new_with_services(
+            Arc::new(UserService::new_with_services(Arc::new(MockFootprintServiceTrait::new()))),
+            Arc::new(LedgerService::new()),
+            Arc::new(MockFootprintServiceTrait::new())
+        ));
+        assert!(adyen_engine.capture_wallet_card_charge(&rtx, &charges[0], "capture", metadata.amount_cents).await.is_err());
+    }
+}
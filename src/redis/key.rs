@@ -10,7 +10,16 @@ pub enum Key<'a> {
     MccMapping(&'a str),
     CardsForUser(i32),
     RulesForCards(&'a Vec<i32>),
-    PassthroughCardByToken(&'a str)
+    PassthroughCardByToken(&'a str),
+    SpendControlsForUser(i32),
+    MerchantControlsForUser(i32),
+    CardHealth(i32),
+    CardHealthTrial(i32),
+    RewardsValuationsForUser(i32),
+    // user id, year, quarter
+    RotatingActivationsForUser(i32, i32, i32),
+    MerchantAliases,
+    StandInExposureForUser(i32)
 }
 
 impl StableRedisKey for Key<'_> {
@@ -32,7 +41,15 @@ impl StableRedisKey for Key<'_> {
             },
             Key::PassthroughCardByToken(token) => {
                 format!("passthrough_card_{}", token)
-            }
+            },
+            Key::SpendControlsForUser(id) => format!("spend_controls_for_user_{}", id),
+            Key::MerchantControlsForUser(id) => format!("merchant_controls_for_user_{}", id),
+            Key::CardHealth(id) => format!("card_health_{}", id),
+            Key::CardHealthTrial(id) => format!("card_health_trial_{}", id),
+            Key::RewardsValuationsForUser(id) => format!("rewards_valuations_for_user_{}", id),
+            Key::RotatingActivationsForUser(id, year, quarter) => format!("rotating_activations_for_user_{}_{}_q{}", id, year, quarter),
+            Key::MerchantAliases => "merchant_aliases".to_string(),
+            Key::StandInExposureForUser(id) => format!("stand_in_exposure_for_user_{}", id)
         }
     }
 }
@@ -66,6 +83,41 @@ mod test {
         assert_eq!("passthrough_card_1234-5678".to_string(), Key::PassthroughCardByToken("1234-5678").to_key());
     }
 
+    #[test]
+    fn test_spend_controls_for_user() {
+        assert_eq!("spend_controls_for_user_1".to_string(), Key::SpendControlsForUser(1).to_key());
+    }
+
+    #[test]
+    fn test_merchant_controls_for_user() {
+        assert_eq!("merchant_controls_for_user_1".to_string(), Key::MerchantControlsForUser(1).to_key());
+    }
+
+    #[test]
+    fn test_card_health() {
+        assert_eq!("card_health_1".to_string(), Key::CardHealth(1).to_key());
+        assert_eq!("card_health_trial_1".to_string(), Key::CardHealthTrial(1).to_key());
+    }
+
+    #[test]
+    fn test_rewards_valuations_for_user() {
+        assert_eq!("rewards_valuations_for_user_1".to_string(), Key::RewardsValuationsForUser(1).to_key());
+    }
+
+    #[test]
+    fn test_rotating_activations_for_user() {
+        assert_eq!("rotating_activations_for_user_1_2024_q3".to_string(), Key::RotatingActivationsForUser(1, 2024, 3).to_key());
+    }
+
+    #[test]
+    fn test_merchant_aliases() {
+        assert_eq!("merchant_aliases".to_string(), Key::MerchantAliases.to_key());
+    }
+
+    #[test]
+    fn test_stand_in_exposure_for_user() {
+        assert_eq!("stand_in_exposure_for_user_1".to_string(), Key::StandInExposureForUser(1).to_key());
+    }
 
     #[test]
     fn test_rules_for_cards() {
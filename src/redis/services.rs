@@ -5,7 +5,7 @@ use mockall::concretize;
 use serde::{Deserialize, Serialize};
 use crate::redis::error::RedisError;
 use crate::redis::key::StableRedisKey;
-use redis::{AsyncCommands, Client, FromRedisValue, ToRedisArgs};
+use redis::{cmd, AsyncCommands, Client, FromRedisValue, Script, ToRedisArgs};
 use std::sync::Arc;
 use std::marker::{Send, Sync};
 use chrono::Duration;
@@ -27,6 +27,10 @@ pub trait RedisServiceTrait {
         where K: StableRedisKey;
     async fn expire_now<K>(self: Arc<Self>, key: &K) -> Result<(), RedisError>
         where K: StableRedisKey;
+    async fn set_primitive_if_absent<K, T>(self: Arc<Self>, key: &K, val: T, time: Duration) -> Result<bool, RedisError>
+        where T: ToRedisArgs + Send + Sync, K: StableRedisKey;
+    async fn invoke_script<K, T>(self: Arc<Self>, script: &Script, keys: &[K], args: &[i64]) -> Result<T, RedisError>
+        where T: FromRedisValue, K: StableRedisKey;
 }
 
 /// Standard Redis Service
@@ -89,6 +93,36 @@ impl RedisServiceTrait for RedisService {
         where K: StableRedisKey {
         self.clone().expire_in(key, Duration::nanoseconds(0)).await
     }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
+    async fn set_primitive_if_absent<K, T>(self: Arc<Self>, key: &K, val: T, time: Duration) -> Result<bool, RedisError>
+        where T: ToRedisArgs + Send + Sync, K: StableRedisKey {
+        let mut conn = get_connection().await?;
+        // SET NX EX in one command so the key never exists without its expiry
+        let set: Option<String> = cmd("SET")
+            .arg(key.to_key())
+            .arg(val)
+            .arg("NX")
+            .arg("EX")
+            .arg(time.num_seconds().max(1))
+            .query_async(&mut *conn).await?;
+        Ok(set.is_some())
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
+    async fn invoke_script<K, T>(self: Arc<Self>, script: &Script, keys: &[K], args: &[i64]) -> Result<T, RedisError>
+        where T: FromRedisValue, K: StableRedisKey {
+        let mut conn = get_connection().await?;
+        let mut invocation = script.prepare_invoke();
+        for key in keys {
+            invocation.key(key.to_key());
+        }
+        for arg in args {
+            invocation.arg(*arg);
+        }
+        let result: T = invocation.invoke_async(&mut *conn).await?;
+        Ok(result)
+    }
 }
 
 
@@ -204,6 +238,29 @@ mod test {
         assert_eq!(RedisError::NotFound("test".into()), error);
     }
 
+    #[test]
+    async fn test_set_primitive_if_absent() {
+        crate::test_helper::general::init();
+        let svc: Arc<RedisService> = Arc::new(RedisService::new());
+        let key = TestKey::new();
+        let duration = chrono::Duration::try_seconds(10).expect("Should create delta");
+        assert!(svc.clone().set_primitive_if_absent::<_, _>(&key, 1, duration).await.expect("should be ok"));
+        assert!(!svc.clone().set_primitive_if_absent::<_, _>(&key, 2, duration).await.expect("should be ok"));
+        let val = svc.clone().get_primitive::<_, i32>(&key).await.expect("should give val");
+        assert_eq!(1, val);
+    }
+
+    #[test]
+    async fn test_invoke_script() {
+        crate::test_helper::general::init();
+        let svc: Arc<RedisService> = Arc::new(RedisService::new());
+        let key = TestKey::new();
+        svc.clone().set_primitive::<_, _>(&key, 5).await.expect("should be ok");
+        let script = redis::Script::new("return redis.call('INCRBY', KEYS[1], ARGV[1])");
+        let val = svc.clone().invoke_script::<_, i64>(&script, &[key], &[3]).await.expect("should give val");
+        assert_eq!(8, val);
+    }
+
     #[test]
     async fn test_expire_now() {
         crate::test_helper::general::init();
@@ -0,0 +1,4 @@
+// cards without a rewards program keep the old assumption of a cent a point
+pub const DEFAULT_CENTS_PER_POINT_HUNDREDTHS: i32 = 100;
+// a point worth more than a dollar is a typo, not a valuation
+pub const MAX_CENTS_PER_POINT_HUNDREDTHS: i32 = 10000;
@@ -0,0 +1,54 @@
+use actix_web::{web, get, put, delete, HttpResponse};
+use uuid::Uuid;
+use crate::middleware::services::Services;
+use crate::rewards_program::request::UpdateValuationRequest;
+use crate::rewards_program::response::RewardsProgramResponse;
+use crate::rewards_program::service::RewardsProgramServiceTrait;
+use crate::user::model::UserModel as User;
+use super::error::RewardsProgramError;
+
+#[get("/")]
+async fn get_programs(
+    user: web::ReqData<User>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, RewardsProgramError> {
+    let user = user.into_inner();
+    let programs = services.rewards_program_service.clone().get_programs(&user).await?;
+    Ok(HttpResponse::Ok().json(
+        programs.iter().map(RewardsProgramResponse::from).collect::<Vec<RewardsProgramResponse>>()
+    ))
+}
+
+#[put("/{public_id}/valuation/")]
+async fn set_valuation(
+    user: web::ReqData<User>,
+    public_id: web::Path<Uuid>,
+    request: web::Json<UpdateValuationRequest>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, RewardsProgramError> {
+    let user = user.into_inner();
+    let public_id = public_id.into_inner();
+    let request = request.into_inner();
+    let program = services.rewards_program_service.clone().set_valuation(
+        &user,
+        &public_id,
+        request.cents_per_point_hundredths
+    ).await?;
+    Ok(HttpResponse::Ok().json(
+        RewardsProgramResponse::from(&program)
+    ))
+}
+
+#[delete("/{public_id}/valuation/")]
+async fn clear_valuation(
+    user: web::ReqData<User>,
+    public_id: web::Path<Uuid>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, RewardsProgramError> {
+    let user = user.into_inner();
+    let public_id = public_id.into_inner();
+    let program = services.rewards_program_service.clone().clear_valuation(&user, &public_id).await?;
+    Ok(HttpResponse::Ok().json(
+        RewardsProgramResponse::from(&program)
+    ))
+}
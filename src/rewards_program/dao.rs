@@ -0,0 +1,116 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use uuid::Uuid;
+use crate::error::data_error::DataError;
+use crate::rewards_program::entity::{CardValuation, InsertableUserRewardsProgramValuation, RewardsProgram, UserRewardsProgramValuation};
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::helper::try_redis_fallback_db;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::key::Key;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::services::{
+    RedisService,
+    RedisServiceTrait
+};
+
+#[cfg(test)]
+use mockall::{automock, predicate::*};
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait RewardsProgramDaoTrait {
+    async fn list_all(self: Arc<Self>) -> Result<Vec<RewardsProgram>, DataError>;
+    async fn find_by_public_id(self: Arc<Self>, public_id: &Uuid) -> Result<RewardsProgram, DataError>;
+    async fn get_valuations_for_user(self: Arc<Self>, user_id: i32) -> Result<Vec<UserRewardsProgramValuation>, DataError>;
+    async fn find_valuation(self: Arc<Self>, user_id: i32, rewards_program_id: i32) -> Result<UserRewardsProgramValuation, DataError>;
+    async fn insert_valuation(self: Arc<Self>, valuation: &InsertableUserRewardsProgramValuation) -> Result<UserRewardsProgramValuation, DataError>;
+    async fn update_valuation(self: Arc<Self>, valuation: &UserRewardsProgramValuation, cents_per_point_hundredths: i32) -> Result<UserRewardsProgramValuation, DataError>;
+    async fn delete_valuation(self: Arc<Self>, valuation: &UserRewardsProgramValuation) -> Result<(), DataError>;
+    async fn get_card_valuations_for_user(self: Arc<Self>, user_id: i32) -> Result<Vec<CardValuation>, DataError>;
+}
+
+pub struct RewardsProgramDao {
+    #[cfg(not(feature = "no-redis"))]
+    redis: Arc<RedisService>
+}
+
+impl RewardsProgramDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub fn new() -> Self {
+        #[cfg(not(feature = "no-redis"))] {
+            Self {
+                redis: Arc::new(RedisService::new())
+            }
+        }
+        #[cfg(feature = "no-redis")] {
+            Self {}
+        }
+    }
+
+    async fn expire_card_valuations_for_user(&self, user_id: i32) {
+        #[cfg(not(feature = "no-redis"))] {
+            tracing::info!("Expiring card valuations in redis for user_id={}", user_id);
+            self.redis.clone().expire_now::<_>(&Key::RewardsValuationsForUser(user_id)).await;
+        }
+    }
+}
+
+#[async_trait]
+impl RewardsProgramDaoTrait for RewardsProgramDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn list_all(self: Arc<Self>) -> Result<Vec<RewardsProgram>, DataError> {
+        RewardsProgram::list_all().await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn find_by_public_id(self: Arc<Self>, public_id: &Uuid) -> Result<RewardsProgram, DataError> {
+        RewardsProgram::find_by_public_id(public_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_valuations_for_user(self: Arc<Self>, user_id: i32) -> Result<Vec<UserRewardsProgramValuation>, DataError> {
+        UserRewardsProgramValuation::get_for_user(user_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn find_valuation(self: Arc<Self>, user_id: i32, rewards_program_id: i32) -> Result<UserRewardsProgramValuation, DataError> {
+        UserRewardsProgramValuation::find_for_user_and_program(user_id, rewards_program_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert_valuation(self: Arc<Self>, valuation: &InsertableUserRewardsProgramValuation) -> Result<UserRewardsProgramValuation, DataError> {
+        let inserted = UserRewardsProgramValuation::insert(valuation).await;
+        self.expire_card_valuations_for_user(valuation.user_id).await;
+        inserted
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_valuation(self: Arc<Self>, valuation: &UserRewardsProgramValuation, cents_per_point_hundredths: i32) -> Result<UserRewardsProgramValuation, DataError> {
+        let updated = UserRewardsProgramValuation::update_cents_per_point(valuation.id, cents_per_point_hundredths).await;
+        self.expire_card_valuations_for_user(valuation.user_id).await;
+        updated
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn delete_valuation(self: Arc<Self>, valuation: &UserRewardsProgramValuation) -> Result<(), DataError> {
+        let deleted = UserRewardsProgramValuation::delete(valuation.id).await;
+        self.expire_card_valuations_for_user(valuation.user_id).await;
+        deleted
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_card_valuations_for_user(self: Arc<Self>, user_id: i32) -> Result<Vec<CardValuation>, DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            // read on every asa, the ttl isn't renewed so a changed program valuation still gets picked up
+            Ok(try_redis_fallback_db(
+                self.redis.clone(),
+                Key::RewardsValuationsForUser(user_id),
+                || async {CardValuation::get_for_user(user_id).await},
+                false
+            ).await?)
+        }
+        #[cfg(feature = "no-redis")] {
+            CardValuation::get_for_user(user_id).await
+        }
+    }
+}
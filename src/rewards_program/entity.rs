@@ -0,0 +1,140 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::error::data_error::DataError;
+use crate::schema::{credit_card, rewards_program, user_rewards_program_valuation};
+use crate::util::db;
+
+#[derive(Debug, Clone, Identifiable, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = rewards_program)]
+pub struct RewardsProgram {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub name: String,
+    pub cents_per_point_hundredths: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize, PartialEq)]
+#[diesel(table_name = user_rewards_program_valuation)]
+pub struct InsertableUserRewardsProgramValuation {
+    pub user_id: i32,
+    pub rewards_program_id: i32,
+    pub cents_per_point_hundredths: i32,
+}
+
+#[derive(Debug, Clone, Identifiable, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = user_rewards_program_valuation)]
+pub struct UserRewardsProgramValuation {
+    pub id: i32,
+    pub user_id: i32,
+    pub rewards_program_id: i32,
+    pub cents_per_point_hundredths: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+// what a card's points are worth, the user's valuation wins over the program's
+#[derive(Debug, Clone, Serialize, Deserialize, Queryable, PartialEq)]
+pub struct CardValuation {
+    pub credit_card_id: i32,
+    pub program_cents_per_point_hundredths: Option<i32>,
+    pub user_cents_per_point_hundredths: Option<i32>,
+}
+
+impl RewardsProgram {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn list_all() -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let programs = rewards_program::table
+            .order(rewards_program::id.asc())
+            .load::<RewardsProgram>(&mut conn).await?;
+        Ok(programs)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn find_by_public_id(public_id: &Uuid) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let program = rewards_program::table
+            .filter(rewards_program::public_id.eq(public_id))
+            .first::<RewardsProgram>(&mut conn).await?;
+        Ok(program)
+    }
+}
+
+impl UserRewardsProgramValuation {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert(valuation: &InsertableUserRewardsProgramValuation) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let valuation = diesel::insert_into(user_rewards_program_valuation::table)
+            .values(valuation)
+            .get_result(&mut conn).await?;
+        Ok(valuation)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_for_user(user_id: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let valuations = user_rewards_program_valuation::table
+            .filter(user_rewards_program_valuation::user_id.eq(user_id))
+            .load::<UserRewardsProgramValuation>(&mut conn).await?;
+        Ok(valuations)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn find_for_user_and_program(user_id: i32, rewards_program_id: i32) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let valuation = user_rewards_program_valuation::table
+            .filter(user_rewards_program_valuation::user_id.eq(user_id))
+            .filter(user_rewards_program_valuation::rewards_program_id.eq(rewards_program_id))
+            .first::<UserRewardsProgramValuation>(&mut conn).await?;
+        Ok(valuation)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_cents_per_point(id: i32, cents_per_point_hundredths: i32) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let valuation = diesel::update(user_rewards_program_valuation::table)
+            .filter(user_rewards_program_valuation::id.eq(id))
+            .set((
+                user_rewards_program_valuation::cents_per_point_hundredths.eq(cents_per_point_hundredths),
+                user_rewards_program_valuation::updated_at.eq(chrono::Utc::now().naive_utc())
+            ))
+            .get_result(&mut conn).await?;
+        Ok(valuation)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn delete(id: i32) -> Result<(), DataError> {
+        let mut conn = db::connection().await?;
+        diesel::delete(user_rewards_program_valuation::table)
+            .filter(user_rewards_program_valuation::id.eq(id))
+            .execute(&mut conn).await?;
+        Ok(())
+    }
+}
+
+impl CardValuation {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_for_user(user_id: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let valuations = credit_card::table
+            .left_join(rewards_program::table)
+            .left_join(
+                user_rewards_program_valuation::table.on(
+                    user_rewards_program_valuation::rewards_program_id.nullable().eq(credit_card::rewards_program_id)
+                        .and(user_rewards_program_valuation::user_id.eq(user_id))
+                )
+            )
+            .select((
+                credit_card::id,
+                rewards_program::cents_per_point_hundredths.nullable(),
+                user_rewards_program_valuation::cents_per_point_hundredths.nullable()
+            ))
+            .load::<CardValuation>(&mut conn).await?;
+        Ok(valuations)
+    }
+}
@@ -0,0 +1,71 @@
+use actix_web::http::StatusCode;
+use actix_web::ResponseError;
+use crate::error::data_error::DataError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum RewardsProgramError {
+    #[error("Not found")]
+    NotFound(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Invalid valuation")]
+    Invalid(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Unexpected error")]
+    Unexpected(#[source] Box<dyn std::error::Error + Send + Sync>)
+}
+
+impl ResponseError for RewardsProgramError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RewardsProgramError::NotFound(_) => StatusCode::NOT_FOUND,
+            RewardsProgramError::Invalid(_) => StatusCode::BAD_REQUEST,
+            RewardsProgramError::Unexpected(_) => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+impl From<DataError> for RewardsProgramError {
+    fn from(value: DataError) -> Self {
+        match value {
+            DataError::NotFound(e) => RewardsProgramError::NotFound(e),
+            DataError::Conflict(e) => RewardsProgramError::Unexpected(e),
+            DataError::Format(e) => RewardsProgramError::Unexpected(e),
+            DataError::Unexpected(e) => RewardsProgramError::Unexpected(e),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for RewardsProgramError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RewardsProgramError::NotFound(_), RewardsProgramError::NotFound(_))
+            | (RewardsProgramError::Invalid(_), RewardsProgramError::Invalid(_))
+            | (RewardsProgramError::Unexpected(_), RewardsProgramError::Unexpected(_)) => true,
+            _ => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::http::StatusCode;
+    use actix_web::ResponseError;
+    use crate::error::data_error::DataError;
+    use crate::rewards_program::error::RewardsProgramError;
+
+    const BASE_ERROR: &str = "test";
+
+    #[test]
+    pub fn test_from_data_error() {
+        assert_eq!(RewardsProgramError::NotFound(BASE_ERROR.into()), RewardsProgramError::from(DataError::NotFound(BASE_ERROR.into())));
+        assert_eq!(RewardsProgramError::Unexpected(BASE_ERROR.into()), RewardsProgramError::from(DataError::Conflict(BASE_ERROR.into())));
+        assert_eq!(RewardsProgramError::Unexpected(BASE_ERROR.into()), RewardsProgramError::from(DataError::Format(BASE_ERROR.into())));
+        assert_eq!(RewardsProgramError::Unexpected(BASE_ERROR.into()), RewardsProgramError::from(DataError::Unexpected(BASE_ERROR.into())));
+    }
+
+    #[test]
+    pub fn test_status_codes() {
+        assert_eq!(StatusCode::NOT_FOUND, RewardsProgramError::NotFound(BASE_ERROR.into()).status_code());
+        assert_eq!(StatusCode::BAD_REQUEST, RewardsProgramError::Invalid(BASE_ERROR.into()).status_code());
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, RewardsProgramError::Unexpected(BASE_ERROR.into()).status_code());
+    }
+}
@@ -0,0 +1,36 @@
+use uuid::Uuid;
+use crate::rewards_program::constant::DEFAULT_CENTS_PER_POINT_HUNDREDTHS;
+use crate::rewards_program::entity::{CardValuation, RewardsProgram, UserRewardsProgramValuation};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RewardsProgramModel {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub name: String,
+    pub program_cents_per_point_hundredths: i32,
+    pub user_cents_per_point_hundredths: Option<i32>,
+}
+
+impl RewardsProgramModel {
+    pub fn from_program_and_valuation(program: RewardsProgram, valuation: Option<&UserRewardsProgramValuation>) -> Self {
+        RewardsProgramModel {
+            id: program.id,
+            public_id: program.public_id,
+            name: program.name,
+            program_cents_per_point_hundredths: program.cents_per_point_hundredths,
+            user_cents_per_point_hundredths: valuation.map(|valuation| valuation.cents_per_point_hundredths),
+        }
+    }
+
+    pub fn cents_per_point_hundredths(&self) -> i32 {
+        self.user_cents_per_point_hundredths.unwrap_or(self.program_cents_per_point_hundredths)
+    }
+}
+
+impl CardValuation {
+    pub fn cents_per_point_hundredths(&self) -> i32 {
+        self.user_cents_per_point_hundredths
+            .or(self.program_cents_per_point_hundredths)
+            .unwrap_or(DEFAULT_CENTS_PER_POINT_HUNDREDTHS)
+    }
+}
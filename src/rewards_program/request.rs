@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+
+// hundredths of a cent, 1.5 cents a point is 150
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UpdateValuationRequest {
+    pub cents_per_point_hundredths: i32,
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::rewards_program::model::RewardsProgramModel;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RewardsProgramResponse {
+    pub public_id: Uuid,
+    pub name: String,
+    pub program_cents_per_point_hundredths: i32,
+    pub user_cents_per_point_hundredths: Option<i32>,
+    // what routing uses
+    pub cents_per_point_hundredths: i32,
+}
+
+impl From<&RewardsProgramModel> for RewardsProgramResponse {
+    fn from(value: &RewardsProgramModel) -> Self {
+        RewardsProgramResponse {
+            public_id: value.public_id,
+            name: value.name.clone(),
+            program_cents_per_point_hundredths: value.program_cents_per_point_hundredths,
+            user_cents_per_point_hundredths: value.user_cents_per_point_hundredths,
+            cents_per_point_hundredths: value.cents_per_point_hundredths(),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+use uuid::Uuid;
+use crate::error::data_error::DataError;
+use crate::rewards_program::constant::MAX_CENTS_PER_POINT_HUNDREDTHS;
+use crate::rewards_program::dao::{RewardsProgramDao, RewardsProgramDaoTrait};
+use crate::rewards_program::entity::InsertableUserRewardsProgramValuation;
+use crate::rewards_program::error::RewardsProgramError;
+use crate::rewards_program::model::RewardsProgramModel;
+use crate::user::model::UserModel as User;
+
+#[async_trait(?Send)]
+pub trait RewardsProgramServiceTrait {
+    async fn get_programs(self: Arc<Self>, user: &User) -> Result<Vec<RewardsProgramModel>, RewardsProgramError>;
+    async fn set_valuation(self: Arc<Self>, user: &User, public_id: &Uuid, cents_per_point_hundredths: i32) -> Result<RewardsProgramModel, RewardsProgramError>;
+    async fn clear_valuation(self: Arc<Self>, user: &User, public_id: &Uuid) -> Result<RewardsProgramModel, RewardsProgramError>;
+    async fn get_card_valuations(self: Arc<Self>, user: &User) -> Result<HashMap<i32, i32>, RewardsProgramError>;
+}
+
+pub struct RewardsProgramService {
+    dao: Arc<dyn RewardsProgramDaoTrait>,
+}
+
+impl RewardsProgramService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub fn new() -> Self {
+        Self {
+            dao: Arc::new(RewardsProgramDao::new())
+        }
+    }
+
+    #[cfg(test)]
+    pub fn new_with_mocks(dao: Arc<dyn RewardsProgramDaoTrait>) -> Self {
+        Self {
+            dao
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl RewardsProgramServiceTrait for RewardsProgramService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_programs(self: Arc<Self>, user: &User) -> Result<Vec<RewardsProgramModel>, RewardsProgramError> {
+        let valuations = self.dao.clone().get_valuations_for_user(user.id).await?;
+        Ok(
+            self.dao.clone().list_all().await?
+                .into_iter()
+                .map(|program| {
+                    let valuation = valuations.iter().find(|valuation| valuation.rewards_program_id == program.id);
+                    RewardsProgramModel::from_program_and_valuation(program, valuation)
+                })
+                .collect()
+        )
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn set_valuation(self: Arc<Self>, user: &User, public_id: &Uuid, cents_per_point_hundredths: i32) -> Result<RewardsProgramModel, RewardsProgramError> {
+        if cents_per_point_hundredths <= 0 || cents_per_point_hundredths > MAX_CENTS_PER_POINT_HUNDREDTHS {
+            return Err(RewardsProgramError::Invalid(format!("valuation must be between 1 and {}", MAX_CENTS_PER_POINT_HUNDREDTHS).into()))
+        }
+        let program = self.dao.clone().find_by_public_id(public_id).await?;
+        let valuation = match self.dao.clone().find_valuation(user.id, program.id).await {
+            Ok(valuation) => self.dao.clone().update_valuation(&valuation, cents_per_point_hundredths).await?,
+            Err(DataError::NotFound(_)) => self.dao.clone().insert_valuation(
+                &InsertableUserRewardsProgramValuation {
+                    user_id: user.id,
+                    rewards_program_id: program.id,
+                    cents_per_point_hundredths,
+                }
+            ).await?,
+            Err(e) => return Err(e.into())
+        };
+        tracing::info!("Set valuation of {} for rewards program={} for user_id={}", cents_per_point_hundredths, &program.public_id, user.id);
+        Ok(RewardsProgramModel::from_program_and_valuation(program, Some(&valuation)))
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn clear_valuation(self: Arc<Self>, user: &User, public_id: &Uuid) -> Result<RewardsProgramModel, RewardsProgramError> {
+        let program = self.dao.clone().find_by_public_id(public_id).await?;
+        match self.dao.clone().find_valuation(user.id, program.id).await {
+            Ok(valuation) => self.dao.clone().delete_valuation(&valuation).await?,
+            // already on the program's valuation
+            Err(DataError::NotFound(_)) => {},
+            Err(e) => return Err(e.into())
+        };
+        Ok(RewardsProgramModel::from_program_and_valuation(program, None))
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_card_valuations(self: Arc<Self>, user: &User) -> Result<HashMap<i32, i32>, RewardsProgramError> {
+        // credit card id to what one of its points is worth to the user
+        Ok(
+            self.dao.clone().get_card_valuations_for_user(user.id).await?
+                .iter()
+                .map(|valuation| (valuation.credit_card_id, valuation.cents_per_point_hundredths()))
+                .collect()
+        )
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::test;
+    use uuid::Uuid;
+    use crate::rewards_program::constant::DEFAULT_CENTS_PER_POINT_HUNDREDTHS;
+    use crate::rewards_program::dao::MockRewardsProgramDaoTrait;
+    use crate::rewards_program::entity::CardValuation;
+    use crate::rewards_program::error::RewardsProgramError;
+    use crate::rewards_program::model::RewardsProgramModel;
+    use crate::rewards_program::service::{RewardsProgramService, RewardsProgramServiceTrait};
+    use crate::test_helper::user::{create_mock_user, create_user};
+
+    const ULTIMATE_REWARDS: &str = "Ultimate Rewards";
+    // the sapphire cards earn ultimate rewards, the bilt card bilt rewards
+    const SAPPHIRE_PREFERRED_ID: i32 = 1;
+    const BILT_WORLD_ELITE_ID: i32 = 3;
+
+    fn service() -> Arc<RewardsProgramService> {
+        Arc::new(RewardsProgramService::new())
+    }
+
+    async fn ultimate_rewards(service: Arc<RewardsProgramService>) -> RewardsProgramModel {
+        service.get_programs(&create_user().await).await.expect("gets programs")
+            .into_iter()
+            .find(|program| program.name == ULTIMATE_REWARDS)
+            .expect("seeded")
+    }
+
+    #[test]
+    async fn test_set_and_clear_valuation() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let service = service();
+        let program = ultimate_rewards(service.clone()).await;
+        assert_eq!(None, program.user_cents_per_point_hundredths);
+
+        let set = service.clone().set_valuation(&user, &program.public_id, 200).await.expect("sets");
+        assert_eq!(Some(200), set.user_cents_per_point_hundredths);
+        assert_eq!(200, set.cents_per_point_hundredths());
+        let updated = service.clone().set_valuation(&user, &program.public_id, 175).await.expect("updates");
+        assert_eq!(175, updated.cents_per_point_hundredths());
+        assert_eq!(program.program_cents_per_point_hundredths, updated.program_cents_per_point_hundredths);
+
+        let programs = service.clone().get_programs(&user).await.expect("gets programs");
+        assert_eq!(Some(&updated), programs.iter().find(|listed| listed.id == program.id));
+        let valuations = service.clone().get_card_valuations(&user).await.expect("gets valuations");
+        assert_eq!(Some(&175), valuations.get(&SAPPHIRE_PREFERRED_ID));
+        assert_eq!(Some(&150), valuations.get(&BILT_WORLD_ELITE_ID));
+
+        let cleared = service.clone().clear_valuation(&user, &program.public_id).await.expect("clears");
+        assert_eq!(None, cleared.user_cents_per_point_hundredths);
+        let valuations = service.clone().get_card_valuations(&user).await.expect("gets valuations");
+        assert_eq!(Some(&program.program_cents_per_point_hundredths), valuations.get(&SAPPHIRE_PREFERRED_ID));
+        // clearing twice is fine
+        service.clone().clear_valuation(&user, &program.public_id).await.expect("clears");
+    }
+
+    #[test]
+    async fn test_valuations_are_per_user() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let other_user = create_user().await;
+        let service = service();
+        let program = ultimate_rewards(service.clone()).await;
+        service.clone().set_valuation(&user, &program.public_id, 225).await.expect("sets");
+
+        let valuations = service.clone().get_card_valuations(&other_user).await.expect("gets valuations");
+        assert_eq!(Some(&program.program_cents_per_point_hundredths), valuations.get(&SAPPHIRE_PREFERRED_ID));
+        let programs = service.clone().get_programs(&other_user).await.expect("gets programs");
+        assert!(programs.iter().all(|program| program.user_cents_per_point_hundredths.is_none()));
+    }
+
+    #[test]
+    async fn test_invalid_valuations() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let service = service();
+        let program = ultimate_rewards(service.clone()).await;
+        assert_eq!(
+            RewardsProgramError::Invalid("test".into()),
+            service.clone().set_valuation(&user, &program.public_id, 0).await.expect_err("too low")
+        );
+        assert_eq!(
+            RewardsProgramError::Invalid("test".into()),
+            service.clone().set_valuation(&user, &program.public_id, 10001).await.expect_err("too high")
+        );
+        assert_eq!(
+            RewardsProgramError::NotFound("test".into()),
+            service.clone().set_valuation(&user, &Uuid::new_v4(), 150).await.expect_err("no program")
+        );
+    }
+
+    #[test]
+    async fn test_card_valuation_precedence() {
+        crate::test_helper::general::init();
+        let mut dao = MockRewardsProgramDaoTrait::new();
+        dao.expect_get_card_valuations_for_user()
+            .times(1)
+            .return_once(|_| Ok(vec![
+                CardValuation { credit_card_id: 1, program_cents_per_point_hundredths: Some(150), user_cents_per_point_hundredths: Some(210) },
+                CardValuation { credit_card_id: 2, program_cents_per_point_hundredths: Some(150), user_cents_per_point_hundredths: None },
+                CardValuation { credit_card_id: 3, program_cents_per_point_hundredths: None, user_cents_per_point_hundredths: None },
+            ]));
+        let service = Arc::new(RewardsProgramService::new_with_mocks(Arc::new(dao)));
+        let valuations = service.get_card_valuations(&create_mock_user()).await.expect("gets valuations");
+        assert_eq!(Some(&210), valuations.get(&1));
+        assert_eq!(Some(&150), valuations.get(&2));
+        assert_eq!(Some(&DEFAULT_CENTS_PER_POINT_HUNDREDTHS), valuations.get(&3));
+    }
+}
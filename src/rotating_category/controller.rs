@@ -0,0 +1,40 @@
+use actix_web::{web, get, put, HttpResponse};
+use uuid::Uuid;
+use crate::middleware::services::Services;
+use crate::rotating_category::request::ActivateRotatingCategoryRequest;
+use crate::rotating_category::response::{PendingActivationResponse, RotatingCategoryActivationResponse};
+use crate::rotating_category::service::RotatingCategoryServiceTrait;
+use crate::user::model::UserModel as User;
+use super::error::RotatingCategoryError;
+
+#[get("/pending-activation/")]
+async fn get_pending_activations(
+    user: web::ReqData<User>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, RotatingCategoryError> {
+    let user = user.into_inner();
+    let pending = services.rotating_category_service.clone().get_pending_activations(&user).await?;
+    Ok(HttpResponse::Ok().json(
+        pending.iter().map(PendingActivationResponse::from).collect::<Vec<PendingActivationResponse>>()
+    ))
+}
+
+#[put("/{public_id}/activation/")]
+async fn activate(
+    user: web::ReqData<User>,
+    public_id: web::Path<Uuid>,
+    request: web::Json<ActivateRotatingCategoryRequest>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, RotatingCategoryError> {
+    let user = user.into_inner();
+    let public_id = public_id.into_inner();
+    let request = request.into_inner();
+    let activation = services.rotating_category_service.clone().activate(
+        &user,
+        &public_id,
+        &request.wallet_card_public_id
+    ).await?;
+    Ok(HttpResponse::Ok().json(
+        RotatingCategoryActivationResponse::from(&activation)
+    ))
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use uuid::Uuid;
+use crate::error::data_error::DataError;
+use crate::rotating_category::entity::{InsertableRotatingCategoryActivation, RotatingCategoryActivation, RotatingCategorySchedule};
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::helper::try_redis_fallback_db;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::key::Key;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::services::{
+    RedisService,
+    RedisServiceTrait
+};
+
+#[cfg(test)]
+use mockall::{automock, predicate::*};
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait RotatingCategoryDaoTrait {
+    async fn get_schedules_for_cards_in_quarter(self: Arc<Self>, credit_card_ids: &Vec<i32>, year: i32, quarter: i32) -> Result<Vec<RotatingCategorySchedule>, DataError>;
+    async fn find_schedule_by_public_id(self: Arc<Self>, public_id: &Uuid) -> Result<RotatingCategorySchedule, DataError>;
+    async fn get_schedule_category_ids(self: Arc<Self>, schedule_ids: &Vec<i32>) -> Result<Vec<(i32, i32)>, DataError>;
+    async fn get_activations_for_user_in_quarter(self: Arc<Self>, user_id: i32, year: i32, quarter: i32) -> Result<Vec<RotatingCategoryActivation>, DataError>;
+    async fn find_activation(self: Arc<Self>, wallet_card_id: i32, rotating_category_schedule_id: i32) -> Result<RotatingCategoryActivation, DataError>;
+    async fn insert_activation(self: Arc<Self>, activation: &InsertableRotatingCategoryActivation, schedule: &RotatingCategorySchedule) -> Result<RotatingCategoryActivation, DataError>;
+}
+
+pub struct RotatingCategoryDao {
+    #[cfg(not(feature = "no-redis"))]
+    redis: Arc<RedisService>
+}
+
+impl RotatingCategoryDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub fn new() -> Self {
+        #[cfg(not(feature = "no-redis"))] {
+            Self {
+                redis: Arc::new(RedisService::new())
+            }
+        }
+        #[cfg(feature = "no-redis")] {
+            Self {}
+        }
+    }
+}
+
+#[async_trait]
+impl RotatingCategoryDaoTrait for RotatingCategoryDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_schedules_for_cards_in_quarter(self: Arc<Self>, credit_card_ids: &Vec<i32>, year: i32, quarter: i32) -> Result<Vec<RotatingCategorySchedule>, DataError> {
+        RotatingCategorySchedule::get_for_card_ids_in_quarter(credit_card_ids, year, quarter).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn find_schedule_by_public_id(self: Arc<Self>, public_id: &Uuid) -> Result<RotatingCategorySchedule, DataError> {
+        RotatingCategorySchedule::find_by_public_id(public_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_schedule_category_ids(self: Arc<Self>, schedule_ids: &Vec<i32>) -> Result<Vec<(i32, i32)>, DataError> {
+        RotatingCategorySchedule::get_category_ids(schedule_ids).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_activations_for_user_in_quarter(self: Arc<Self>, user_id: i32, year: i32, quarter: i32) -> Result<Vec<RotatingCategoryActivation>, DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            // read on every asa, keyed by quarter so last quarter's activations never carry over
+            Ok(try_redis_fallback_db(
+                self.redis.clone(),
+                Key::RotatingActivationsForUser(user_id, year, quarter),
+                || async {RotatingCategoryActivation::get_for_user_in_quarter(user_id, year, quarter).await},
+                false
+            ).await?)
+        }
+        #[cfg(feature = "no-redis")] {
+            RotatingCategoryActivation::get_for_user_in_quarter(user_id, year, quarter).await
+        }
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn find_activation(self: Arc<Self>, wallet_card_id: i32, rotating_category_schedule_id: i32) -> Result<RotatingCategoryActivation, DataError> {
+        RotatingCategoryActivation::find_for_wallet_card_and_schedule(wallet_card_id, rotating_category_schedule_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert_activation(self: Arc<Self>, activation: &InsertableRotatingCategoryActivation, schedule: &RotatingCategorySchedule) -> Result<RotatingCategoryActivation, DataError> {
+        let inserted = RotatingCategoryActivation::insert(activation).await;
+        #[cfg(not(feature = "no-redis"))] {
+            tracing::info!("Expiring rotating activations in redis for user_id={}", activation.user_id);
+            self.redis.clone().expire_now::<_>(&Key::RotatingActivationsForUser(activation.user_id, schedule.year, schedule.quarter)).await;
+        }
+        inserted
+    }
+}
@@ -0,0 +1,137 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::error::data_error::DataError;
+use crate::rule::constant::RuleStatus;
+use crate::schema::{rotating_category_activation, rotating_category_schedule, rule};
+use crate::util::db;
+
+#[derive(Debug, Clone, Identifiable, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = rotating_category_schedule)]
+pub struct RotatingCategorySchedule {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub credit_card_id: i32,
+    pub year: i32,
+    pub quarter: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[cfg(test)]
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = rotating_category_schedule)]
+pub struct InsertableRotatingCategorySchedule {
+    pub credit_card_id: i32,
+    pub year: i32,
+    pub quarter: i32,
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize, PartialEq)]
+#[diesel(table_name = rotating_category_activation)]
+pub struct InsertableRotatingCategoryActivation {
+    pub user_id: i32,
+    pub wallet_card_id: i32,
+    pub rotating_category_schedule_id: i32,
+}
+
+#[derive(Debug, Clone, Identifiable, Serialize, Deserialize, Queryable, Selectable, PartialEq)]
+#[diesel(table_name = rotating_category_activation)]
+pub struct RotatingCategoryActivation {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub user_id: i32,
+    pub wallet_card_id: i32,
+    pub rotating_category_schedule_id: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+impl RotatingCategorySchedule {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_for_card_ids_in_quarter(credit_card_ids: &Vec<i32>, year: i32, quarter: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let schedules = rotating_category_schedule::table
+            .filter(rotating_category_schedule::credit_card_id.eq_any(credit_card_ids))
+            .filter(rotating_category_schedule::year.eq(year))
+            .filter(rotating_category_schedule::quarter.eq(quarter))
+            .load::<RotatingCategorySchedule>(&mut conn).await?;
+        Ok(schedules)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn find_by_public_id(public_id: &Uuid) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let schedule = rotating_category_schedule::table
+            .filter(rotating_category_schedule::public_id.eq(public_id))
+            .first::<RotatingCategorySchedule>(&mut conn).await?;
+        Ok(schedule)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_category_ids(schedule_ids: &Vec<i32>) -> Result<Vec<(i32, i32)>, DataError> {
+        // (schedule id, category id) of the active rules on each schedule
+        let mut conn = db::connection().await?;
+        let category_ids = rule::table
+            .filter(rule::rotating_category_schedule_id.eq_any(schedule_ids))
+            .filter(rule::rule_status.eq(RuleStatus::Active))
+            .select((rule::rotating_category_schedule_id, rule::rule_category_id))
+            .load::<(Option<i32>, Option<i32>)>(&mut conn).await?;
+        Ok(
+            category_ids.into_iter()
+                .filter_map(|(schedule_id, category_id)| Some((schedule_id?, category_id?)))
+                .collect()
+        )
+    }
+
+    #[cfg(test)]
+    pub async fn find_or_insert(schedule: &InsertableRotatingCategorySchedule) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let _ = diesel::insert_into(rotating_category_schedule::table)
+            .values(schedule)
+            .on_conflict_do_nothing()
+            .execute(&mut conn).await?;
+        let schedule = rotating_category_schedule::table
+            .filter(rotating_category_schedule::credit_card_id.eq(schedule.credit_card_id))
+            .filter(rotating_category_schedule::year.eq(schedule.year))
+            .filter(rotating_category_schedule::quarter.eq(schedule.quarter))
+            .first::<RotatingCategorySchedule>(&mut conn).await?;
+        Ok(schedule)
+    }
+}
+
+impl RotatingCategoryActivation {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert(activation: &InsertableRotatingCategoryActivation) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let activation = diesel::insert_into(rotating_category_activation::table)
+            .values(activation)
+            .get_result(&mut conn).await?;
+        Ok(activation)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn find_for_wallet_card_and_schedule(wallet_card_id: i32, rotating_category_schedule_id: i32) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let activation = rotating_category_activation::table
+            .filter(rotating_category_activation::wallet_card_id.eq(wallet_card_id))
+            .filter(rotating_category_activation::rotating_category_schedule_id.eq(rotating_category_schedule_id))
+            .first::<RotatingCategoryActivation>(&mut conn).await?;
+        Ok(activation)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_for_user_in_quarter(user_id: i32, year: i32, quarter: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let activations = rotating_category_activation::table
+            .inner_join(rotating_category_schedule::table)
+            .filter(rotating_category_activation::user_id.eq(user_id))
+            .filter(rotating_category_schedule::year.eq(year))
+            .filter(rotating_category_schedule::quarter.eq(quarter))
+            .select(RotatingCategoryActivation::as_select())
+            .load::<RotatingCategoryActivation>(&mut conn).await?;
+        Ok(activations)
+    }
+}
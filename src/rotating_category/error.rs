@@ -0,0 +1,93 @@
+use actix_web::http::StatusCode;
+use actix_web::ResponseError;
+use crate::error::data_error::DataError;
+use crate::wallet::error::WalletError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum RotatingCategoryError {
+    #[error("Not found")]
+    NotFound(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Schedule is not for this card")]
+    Invalid(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Unexpected error")]
+    Unexpected(#[source] Box<dyn std::error::Error + Send + Sync>)
+}
+
+impl ResponseError for RotatingCategoryError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RotatingCategoryError::NotFound(_) => StatusCode::NOT_FOUND,
+            RotatingCategoryError::Invalid(_) => StatusCode::BAD_REQUEST,
+            RotatingCategoryError::Unexpected(_) => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+impl From<DataError> for RotatingCategoryError {
+    fn from(value: DataError) -> Self {
+        match value {
+            DataError::NotFound(e) => RotatingCategoryError::NotFound(e),
+            DataError::Conflict(e) => RotatingCategoryError::Unexpected(e),
+            DataError::Format(e) => RotatingCategoryError::Unexpected(e),
+            DataError::Unexpected(e) => RotatingCategoryError::Unexpected(e),
+        }
+    }
+}
+
+impl From<WalletError> for RotatingCategoryError {
+    fn from(value: WalletError) -> Self {
+        match value {
+            WalletError::NotFound(e) => RotatingCategoryError::NotFound(e),
+            // another user's card is reported the same as a missing one
+            WalletError::Unauthorized(e) => RotatingCategoryError::NotFound(e),
+            WalletError::Conflict(e) => RotatingCategoryError::Unexpected(e),
+            WalletError::NotAcceptable(e) => RotatingCategoryError::Unexpected(e),
+            WalletError::Unexpected(e) => RotatingCategoryError::Unexpected(e),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for RotatingCategoryError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (RotatingCategoryError::NotFound(_), RotatingCategoryError::NotFound(_))
+            | (RotatingCategoryError::Invalid(_), RotatingCategoryError::Invalid(_))
+            | (RotatingCategoryError::Unexpected(_), RotatingCategoryError::Unexpected(_)) => true,
+            _ => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::http::StatusCode;
+    use actix_web::ResponseError;
+    use crate::error::data_error::DataError;
+    use crate::rotating_category::error::RotatingCategoryError;
+    use crate::wallet::error::WalletError;
+
+    const BASE_ERROR: &str = "test";
+
+    #[test]
+    pub fn test_from_data_error() {
+        assert_eq!(RotatingCategoryError::NotFound(BASE_ERROR.into()), RotatingCategoryError::from(DataError::NotFound(BASE_ERROR.into())));
+        assert_eq!(RotatingCategoryError::Unexpected(BASE_ERROR.into()), RotatingCategoryError::from(DataError::Conflict(BASE_ERROR.into())));
+        assert_eq!(RotatingCategoryError::Unexpected(BASE_ERROR.into()), RotatingCategoryError::from(DataError::Format(BASE_ERROR.into())));
+        assert_eq!(RotatingCategoryError::Unexpected(BASE_ERROR.into()), RotatingCategoryError::from(DataError::Unexpected(BASE_ERROR.into())));
+    }
+
+    #[test]
+    pub fn test_from_wallet_error() {
+        assert_eq!(RotatingCategoryError::NotFound(BASE_ERROR.into()), RotatingCategoryError::from(WalletError::NotFound(BASE_ERROR.into())));
+        assert_eq!(RotatingCategoryError::NotFound(BASE_ERROR.into()), RotatingCategoryError::from(WalletError::Unauthorized(BASE_ERROR.into())));
+        assert_eq!(RotatingCategoryError::Unexpected(BASE_ERROR.into()), RotatingCategoryError::from(WalletError::Unexpected(BASE_ERROR.into())));
+    }
+
+    #[test]
+    pub fn test_status_codes() {
+        assert_eq!(StatusCode::NOT_FOUND, RotatingCategoryError::NotFound(BASE_ERROR.into()).status_code());
+        assert_eq!(StatusCode::BAD_REQUEST, RotatingCategoryError::Invalid(BASE_ERROR.into()).status_code());
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, RotatingCategoryError::Unexpected(BASE_ERROR.into()).status_code());
+    }
+}
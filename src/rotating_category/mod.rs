@@ -0,0 +1,11 @@
+pub mod config;
+pub mod error;
+pub mod model;
+pub mod request;
+pub mod response;
+pub mod service;
+
+mod controller;
+mod dao;
+mod entity;
+mod tests;
@@ -0,0 +1,56 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+use crate::rotating_category::entity::{RotatingCategoryActivation, RotatingCategorySchedule};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RotatingCategoryScheduleModel {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub credit_card_id: i32,
+    pub year: i32,
+    pub quarter: i32,
+    pub category_ids: Vec<i32>,
+}
+
+// a wallet card with a schedule this quarter that the user hasn't activated yet
+#[derive(Clone, Debug, PartialEq)]
+pub struct PendingActivationModel {
+    pub wallet_card_id: i32,
+    pub wallet_card_public_id: Uuid,
+    pub schedule: RotatingCategoryScheduleModel,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RotatingCategoryActivationModel {
+    pub public_id: Uuid,
+    pub wallet_card_public_id: Uuid,
+    pub schedule_public_id: Uuid,
+    pub activated_at: NaiveDateTime,
+}
+
+impl RotatingCategoryScheduleModel {
+    pub fn from_schedule_and_category_ids(schedule: &RotatingCategorySchedule, category_ids: &Vec<(i32, i32)>) -> Self {
+        RotatingCategoryScheduleModel {
+            id: schedule.id,
+            public_id: schedule.public_id,
+            credit_card_id: schedule.credit_card_id,
+            year: schedule.year,
+            quarter: schedule.quarter,
+            category_ids: category_ids.iter()
+                .filter(|(schedule_id, _)| *schedule_id == schedule.id)
+                .map(|(_, category_id)| *category_id)
+                .collect(),
+        }
+    }
+}
+
+impl RotatingCategoryActivationModel {
+    pub fn from_activation(activation: &RotatingCategoryActivation, wallet_card_public_id: Uuid, schedule_public_id: Uuid) -> Self {
+        RotatingCategoryActivationModel {
+            public_id: activation.public_id,
+            wallet_card_public_id,
+            schedule_public_id,
+            activated_at: activation.created_at,
+        }
+    }
+}
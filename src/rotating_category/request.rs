@@ -0,0 +1,7 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ActivateRotatingCategoryRequest {
+    pub wallet_card_public_id: Uuid,
+}
@@ -0,0 +1,44 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::rotating_category::model::{PendingActivationModel, RotatingCategoryActivationModel};
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PendingActivationResponse {
+    pub wallet_card_public_id: Uuid,
+    pub schedule_public_id: Uuid,
+    pub year: i32,
+    pub quarter: i32,
+    pub category_ids: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RotatingCategoryActivationResponse {
+    pub public_id: Uuid,
+    pub wallet_card_public_id: Uuid,
+    pub schedule_public_id: Uuid,
+    pub activated_at: NaiveDateTime,
+}
+
+impl From<&PendingActivationModel> for PendingActivationResponse {
+    fn from(value: &PendingActivationModel) -> Self {
+        PendingActivationResponse {
+            wallet_card_public_id: value.wallet_card_public_id,
+            schedule_public_id: value.schedule.public_id,
+            year: value.schedule.year,
+            quarter: value.schedule.quarter,
+            category_ids: value.schedule.category_ids.clone(),
+        }
+    }
+}
+
+impl From<&RotatingCategoryActivationModel> for RotatingCategoryActivationResponse {
+    fn from(value: &RotatingCategoryActivationModel) -> Self {
+        RotatingCategoryActivationResponse {
+            public_id: value.public_id,
+            wallet_card_public_id: value.wallet_card_public_id,
+            schedule_public_id: value.schedule_public_id,
+            activated_at: value.activated_at,
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{Datelike, Utc};
+use uuid::Uuid;
+use crate::error::data_error::DataError;
+use crate::rotating_category::dao::{RotatingCategoryDao, RotatingCategoryDaoTrait};
+use crate::rotating_category::entity::InsertableRotatingCategoryActivation;
+use crate::rotating_category::error::RotatingCategoryError;
+use crate::rotating_category::model::{PendingActivationModel, RotatingCategoryActivationModel, RotatingCategoryScheduleModel};
+use crate::user::model::UserModel as User;
+use crate::util::date::quarter_of;
+use crate::wallet::service::WalletServiceTrait;
+
+#[async_trait(?Send)]
+pub trait RotatingCategoryServiceTrait {
+    async fn get_pending_activations(self: Arc<Self>, user: &User) -> Result<Vec<PendingActivationModel>, RotatingCategoryError>;
+    async fn activate(self: Arc<Self>, user: &User, schedule_public_id: &Uuid, wallet_card_public_id: &Uuid) -> Result<RotatingCategoryActivationModel, RotatingCategoryError>;
+    async fn get_current_activations(self: Arc<Self>, user: &User) -> Result<HashSet<(i32, i32)>, RotatingCategoryError>;
+}
+
+pub struct RotatingCategoryService {
+    dao: Arc<dyn RotatingCategoryDaoTrait>,
+    wallet_service: Arc<dyn WalletServiceTrait>,
+}
+
+impl RotatingCategoryService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
+    pub fn new_with_services(wallet_service: Arc<dyn WalletServiceTrait>) -> Self {
+        Self {
+            dao: Arc::new(RotatingCategoryDao::new()),
+            wallet_service
+        }
+    }
+
+    #[cfg(test)]
+    pub fn new_with_mocks(dao: Arc<dyn RotatingCategoryDaoTrait>, wallet_service: Arc<dyn WalletServiceTrait>) -> Self {
+        Self {
+            dao,
+            wallet_service
+        }
+    }
+
+    fn current_quarter() -> (i32, i32) {
+        let today = Utc::now().naive_utc().date();
+        (today.year(), quarter_of(today))
+    }
+}
+
+#[async_trait(?Send)]
+impl RotatingCategoryServiceTrait for RotatingCategoryService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_pending_activations(self: Arc<Self>, user: &User) -> Result<Vec<PendingActivationModel>, RotatingCategoryError> {
+        let (year, quarter) = RotatingCategoryService::current_quarter();
+        let cards = self.wallet_service.clone().find_all_active_for_user(user).await?;
+        let credit_card_ids: Vec<i32> = cards.iter().map(|card| card.credit_card_id).collect();
+        let schedules = self.dao.clone().get_schedules_for_cards_in_quarter(&credit_card_ids, year, quarter).await?;
+        if schedules.is_empty() {
+            return Ok(Vec::new());
+        }
+        let activations = self.dao.clone().get_activations_for_user_in_quarter(user.id, year, quarter).await?;
+        let schedule_ids: Vec<i32> = schedules.iter().map(|schedule| schedule.id).collect();
+        let category_ids = self.dao.clone().get_schedule_category_ids(&schedule_ids).await?;
+        Ok(
+            cards.iter()
+                .flat_map(|card| schedules.iter()
+                    .filter(|schedule| schedule.credit_card_id == card.credit_card_id)
+                    .filter(|schedule| !activations.iter().any(|activation|
+                        activation.wallet_card_id == card.id && activation.rotating_category_schedule_id == schedule.id
+                    ))
+                    .map(|schedule| PendingActivationModel {
+                        wallet_card_id: card.id,
+                        wallet_card_public_id: card.public_id,
+                        schedule: RotatingCategoryScheduleModel::from_schedule_and_category_ids(schedule, &category_ids),
+                    })
+                )
+                .collect()
+        )
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn activate(self: Arc<Self>, user: &User, schedule_public_id: &Uuid, wallet_card_public_id: &Uuid) -> Result<RotatingCategoryActivationModel, RotatingCategoryError> {
+        let schedule = self.dao.clone().find_schedule_by_public_id(schedule_public_id).await?;
+        let card = self.wallet_service.clone().find_all_active_for_user(user).await?
+            .into_iter()
+            .find(|card| card.public_id == *wallet_card_public_id)
+            .ok_or(RotatingCategoryError::NotFound("Card with public id not found".into()))?;
+        if card.credit_card_id != schedule.credit_card_id {
+            return Err(RotatingCategoryError::Invalid("schedule is for a different card type".into()));
+        }
+        let activation = match self.dao.clone().find_activation(card.id, schedule.id).await {
+            // activating twice keeps the first activation
+            Ok(activation) => activation,
+            Err(DataError::NotFound(_)) => self.dao.clone().insert_activation(
+                &InsertableRotatingCategoryActivation {
+                    user_id: user.id,
+                    wallet_card_id: card.id,
+                    rotating_category_schedule_id: schedule.id,
+                },
+                &schedule
+            ).await?,
+            Err(e) => return Err(e.into())
+        };
+        tracing::info!("Activated rotating categories schedule={} for wallet_card_id={} user_id={}", &schedule.public_id, card.id, user.id);
+        Ok(RotatingCategoryActivationModel::from_activation(&activation, card.public_id, schedule.public_id))
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_current_activations(self: Arc<Self>, user: &User) -> Result<HashSet<(i32, i32)>, RotatingCategoryError> {
+        // (wallet card id, schedule id) for this quarter's schedules only, so a rotating rule from another quarter never matches
+        let (year, quarter) = RotatingCategoryService::current_quarter();
+        Ok(
+            self.dao.clone().get_activations_for_user_in_quarter(user.id, year, quarter).await?
+                .iter()
+                .map(|activation| (activation.wallet_card_id, activation.rotating_category_schedule_id))
+                .collect()
+        )
+    }
+}
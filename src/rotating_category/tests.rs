@@ -0,0 +1,100 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::test;
+    use chrono::{Datelike, Utc};
+    use crate::credit_card_type::service::CreditCardService;
+    use crate::footprint::service::MockFootprintServiceTrait;
+    use crate::rotating_category::entity::{InsertableRotatingCategorySchedule, RotatingCategorySchedule};
+    use crate::rotating_category::error::RotatingCategoryError;
+    use crate::rotating_category::service::{RotatingCategoryService, RotatingCategoryServiceTrait};
+    use crate::test_helper::user::create_user;
+    use crate::test_helper::wallet::create_wallet;
+    use crate::util::date::quarter_of;
+    use crate::wallet::service::WalletService;
+
+    fn service() -> Arc<RotatingCategoryService> {
+        Arc::new(RotatingCategoryService::new_with_services(
+            Arc::new(WalletService::new_with_services(
+                Arc::new(CreditCardService::new()),
+                Arc::new(MockFootprintServiceTrait::new())
+            ))
+        ))
+    }
+
+    async fn schedule_this_quarter(credit_card_id: i32) -> RotatingCategorySchedule {
+        let today = Utc::now().naive_utc().date();
+        RotatingCategorySchedule::find_or_insert(
+            &InsertableRotatingCategorySchedule {
+                credit_card_id,
+                year: today.year(),
+                quarter: quarter_of(today),
+            }
+        ).await.expect("creates schedule")
+    }
+
+    #[test]
+    async fn test_activate_pending_card() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let schedule = schedule_this_quarter(card.credit_card_id).await;
+        let service = service();
+
+        let pending = service.clone().get_pending_activations(&user).await.expect("gets pending");
+        assert_eq!(1, pending.len());
+        assert_eq!(card.public_id, pending[0].wallet_card_public_id);
+        assert_eq!(schedule.public_id, pending[0].schedule.public_id);
+        assert!(service.clone().get_current_activations(&user).await.expect("gets activations").is_empty());
+
+        let activation = service.clone().activate(&user, &schedule.public_id, &card.public_id).await.expect("activates");
+        assert_eq!(card.public_id, activation.wallet_card_public_id);
+        assert_eq!(schedule.public_id, activation.schedule_public_id);
+        assert!(service.clone().get_pending_activations(&user).await.expect("gets pending").is_empty());
+        let activations = service.clone().get_current_activations(&user).await.expect("gets activations");
+        assert!(activations.contains(&(card.id, schedule.id)));
+
+        // activating twice keeps the first activation
+        let again = service.clone().activate(&user, &schedule.public_id, &card.public_id).await.expect("activates");
+        assert_eq!(activation.public_id, again.public_id);
+    }
+
+    #[test]
+    async fn test_activation_is_per_wallet_card() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_wallet(&user).await;
+        let other_card = create_wallet(&user).await;
+        let schedule = schedule_this_quarter(card.credit_card_id).await;
+        let service = service();
+
+        service.clone().activate(&user, &schedule.public_id, &card.public_id).await.expect("activates");
+        let pending = service.clone().get_pending_activations(&user).await.expect("gets pending");
+        assert_eq!(vec![other_card.public_id], pending.iter().map(|pending| pending.wallet_card_public_id).collect::<Vec<_>>());
+    }
+
+    #[test]
+    async fn test_activate_invalid() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let other_user = create_user().await;
+        let card = create_wallet(&user).await;
+        let schedule = schedule_this_quarter(card.credit_card_id).await;
+        let other_card_type_schedule = schedule_this_quarter(if card.credit_card_id == 1 { 2 } else { 1 }).await;
+        let service = service();
+
+        assert_eq!(
+            RotatingCategoryError::NotFound("test".into()),
+            service.clone().activate(&other_user, &schedule.public_id, &card.public_id).await.expect_err("not their card")
+        );
+        assert_eq!(
+            RotatingCategoryError::Invalid("test".into()),
+            service.clone().activate(&user, &other_card_type_schedule.public_id, &card.public_id).await.expect_err("wrong card type")
+        );
+        assert_eq!(
+            RotatingCategoryError::NotFound("test".into()),
+            service.clone().activate(&user, &card.public_id, &card.public_id).await.expect_err("no such schedule")
+        );
+        assert!(service.clone().get_current_activations(&other_user).await.expect("gets activations").is_empty());
+    }
+}
@@ -0,0 +1,54 @@
+use crate::asa::request::AsaRequest;
+use crate::rule::constant::CardPresence;
+
+const COUNTRY_CODE_LENGTH: usize = 3;
+const MCC_LENGTH: usize = 4;
+
+// one thing a request has to satisfy for a rule to pay, a rule pays only when all of its conditions hold
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuleCondition {
+    MinAmount(i32),
+    MaxAmount(i32),
+    MerchantCountry(String),
+    CardPresence(CardPresence),
+    MccIn(Vec<String>),
+}
+
+impl RuleCondition {
+    pub fn is_valid(&self) -> bool {
+        match self {
+            RuleCondition::MinAmount(amount_cents) => *amount_cents >= 0,
+            RuleCondition::MaxAmount(amount_cents) => *amount_cents > 0,
+            RuleCondition::MerchantCountry(country) => country.len() == COUNTRY_CODE_LENGTH
+                && country.chars().all(|c| c.is_ascii_uppercase()),
+            RuleCondition::CardPresence(_) => true,
+            RuleCondition::MccIn(mccs) => !mccs.is_empty()
+                && mccs.iter().all(|mcc| mcc.len() == MCC_LENGTH && mcc.chars().all(|c| c.is_ascii_digit())),
+        }
+    }
+
+    pub fn matches(&self, request: &AsaRequest) -> bool {
+        // anything the request leaves out fails the condition, a rule that needs it can't be assumed to pay
+        match self {
+            RuleCondition::MinAmount(amount_cents) => request.amount.map_or(false, |amount| amount >= *amount_cents),
+            RuleCondition::MaxAmount(amount_cents) => request.amount.map_or(false, |amount| amount <= *amount_cents),
+            RuleCondition::MerchantCountry(country) => request.merchant.as_ref()
+                .and_then(|merchant| merchant.country.as_ref())
+                .map_or(false, |merchant_country| merchant_country.eq_ignore_ascii_case(country)),
+            RuleCondition::CardPresence(card_presence) => card_presence_of(request).as_ref() == Some(card_presence),
+            RuleCondition::MccIn(mccs) => request.merchant.as_ref()
+                .and_then(|merchant| merchant.mcc.as_ref())
+                .map_or(false, |mcc| mccs.contains(mcc)),
+        }
+    }
+}
+
+pub fn card_presence_of(request: &AsaRequest) -> Option<CardPresence> {
+    // lithic sends PRESENT, NOT_PRESENT, PREAUTHORIZED or UNKNOWN, a preauthorized charge is the card on file
+    let entry_mode = request.pos.as_ref()?.entry_mode.as_ref()?;
+    match entry_mode.card.as_deref()? {
+        "PRESENT" => Some(CardPresence::Present),
+        "NOT_PRESENT" | "PREAUTHORIZED" => Some(CardPresence::NotPresent),
+        _ => None
+    }
+}
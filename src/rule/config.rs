@@ -0,0 +1,13 @@
+use actix_web::web;
+
+use super::controller;
+use crate::middleware::auth;
+
+pub fn config(cfg: &mut web::ServiceConfig) -> () {
+    cfg
+        .service(
+            web::scope("")
+                .wrap(auth::Auth)
+                .service(controller::simulate_routing)
+        );
+}
@@ -26,6 +26,38 @@ pub enum DayOfMonth {
     Last,
 }
 
+// how often a rule's cap starts over, anniversary is counted from when the card was added to the wallet
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, AsExpression, FromSqlRow)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[diesel(sql_type = Text)]
+pub enum CapPeriod {
+    Monthly,
+    Quarterly,
+    Annual,
+    Anniversary,
+}
+
+// whether the card was at the terminal, from the entry mode lithic sends with the asa
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, AsExpression, FromSqlRow)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[diesel(sql_type = Text)]
+pub enum CardPresence {
+    Present,
+    NotPresent,
+}
+
+// why a card ranked below the one that would be charged first
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RoutingLossReason {
+    NoMatchingRule,
+    LowerReward,
+    // the bonus rate is used up for the period, only the fallback rate is left
+    CapReached,
+    // same reward, the wallet order broke the tie
+    TiedReward,
+}
+
 
 impl ToSql<Text, Pg> for RuleStatus {
     fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
@@ -81,9 +113,67 @@ impl fmt::Display for DayOfMonth {
 }
 
 
+impl ToSql<Text, Pg> for CapPeriod {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        out.write_all(self.to_string().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+impl FromSql<Text, Pg> for CapPeriod {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"MONTHLY" => Ok(CapPeriod::Monthly),
+            b"QUARTERLY" => Ok(CapPeriod::Quarterly),
+            b"ANNUAL" => Ok(CapPeriod::Annual),
+            b"ANNIVERSARY" => Ok(CapPeriod::Anniversary),
+            v => Err(format!("Unknown value for CapPeriod found").into()),
+
+        }
+    }
+}
+
+impl fmt::Display for CapPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            CapPeriod::Monthly => "MONTHLY",
+            CapPeriod::Quarterly => "QUARTERLY",
+            CapPeriod::Annual => "ANNUAL",
+            CapPeriod::Anniversary => "ANNIVERSARY"
+        })
+    }
+}
+
+
+impl ToSql<Text, Pg> for CardPresence {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> serialize::Result {
+        out.write_all(self.to_string().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+impl FromSql<Text, Pg> for CardPresence {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"PRESENT" => Ok(CardPresence::Present),
+            b"NOT_PRESENT" => Ok(CardPresence::NotPresent),
+            v => Err(format!("Unknown value for CardPresence found").into()),
+
+        }
+    }
+}
+
+impl fmt::Display for CardPresence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            CardPresence::Present => "PRESENT",
+            CardPresence::NotPresent => "NOT_PRESENT"
+        })
+    }
+}
+
+
 #[cfg(test)]
 mod test {
-    use crate::rule::constant::{DayOfMonth, RuleStatus};
+    use crate::rule::constant::{CapPeriod, CardPresence, DayOfMonth, RuleStatus};
 
     #[test]
     pub fn test_rule_status_serialize() {
@@ -96,4 +186,17 @@ mod test {
         assert_eq!("FIRST", DayOfMonth::First.to_string());
         assert_eq!("LAST", DayOfMonth::Last.to_string());
     }
+
+    #[test]
+    pub fn test_cap_period_serialize() {
+        assert_eq!("MONTHLY", CapPeriod::Monthly.to_string());
+        assert_eq!("QUARTERLY", CapPeriod::Quarterly.to_string());
+        assert_eq!("ANNUAL", CapPeriod::Annual.to_string());
+        assert_eq!("ANNIVERSARY", CapPeriod::Anniversary.to_string());
+    }
+    #[test]
+    pub fn test_card_presence_serialize() {
+        assert_eq!("PRESENT", CardPresence::Present.to_string());
+        assert_eq!("NOT_PRESENT", CardPresence::NotPresent.to_string());
+    }
 }
\ No newline at end of file
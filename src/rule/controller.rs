@@ -0,0 +1,24 @@
+use actix_web::{web, post, HttpResponse};
+use crate::middleware::services::Services;
+use crate::rule::request::SimulateRoutingRequest;
+use crate::rule::response::{SimulateRoutingResponse, SimulatedCardResponse};
+use crate::rule::service::RuleServiceTrait;
+use crate::user::model::UserModel as User;
+use super::error::RuleError;
+
+#[post("/simulate/")]
+async fn simulate_routing(
+    user: web::ReqData<User>,
+    request: web::Json<SimulateRoutingRequest>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, RuleError> {
+    let user = user.into_inner();
+    let request = request.into_inner();
+    let cards = services.rule_service.clone().simulate_routing(&request, &user).await?;
+    Ok(HttpResponse::Ok().json(
+        SimulateRoutingResponse {
+            amount_cents: request.amount_cents,
+            cards: cards.iter().map(SimulatedCardResponse::from).collect()
+        }
+    ))
+}
@@ -1,4 +1,5 @@
 use std::sync::Arc;
+use chrono::NaiveDateTime;
 use crate::error::data_error::DataError;
 use crate::rule::entity::Rule;
 use crate::rule::request::CreateRuleRequest;
@@ -14,6 +15,7 @@ use crate::redis::services::{RedisService, RedisServiceTrait};
 pub trait RuleDaoTrait {
     async fn create(self: Arc<Self>, new_rule: &CreateRuleRequest) -> Result<Rule, DataError>;
     async fn get_rules_for_card_ids(self: Arc<Self>, ids: &Vec<i32>) -> Result<Vec<Rule>, DataError>;
+    async fn get_capped_spend(self: Arc<Self>, wallet_card_id: i32, rule_id: i32, since: NaiveDateTime) -> Result<i64, DataError>;
 
 }
 
@@ -60,4 +62,10 @@ impl RuleDaoTrait for RuleDao {
             Rule::get_rules_for_card_ids(ids).await
         }
     }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_capped_spend(self: Arc<Self>, wallet_card_id: i32, rule_id: i32, since: NaiveDateTime) -> Result<i64, DataError> {
+        // not cached, the next charge on the card moves it
+        Rule::get_capped_spend(wallet_card_id, rule_id, since).await
+    }
 }
\ No newline at end of file
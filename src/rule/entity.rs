@@ -1,6 +1,6 @@
-use crate::schema::rule;
+use crate::schema::{rule, wallet_card_charge};
 use super::request::CreateRuleRequest;
-use chrono::NaiveDate;
+use chrono::{NaiveDate, NaiveDateTime};
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
 use serde::{Deserialize, Serialize};
@@ -9,9 +9,15 @@ use crate::util::db;
 use crate::error::data_error::DataError;
 use crate::util::math::{
     get_cents_of_cashback,
+    get_cents_of_points,
     get_number_of_points
 };
-use super::constant::{DayOfMonth, RuleStatus};
+use crate::charge::constant::CaptureStatus;
+use crate::util::date::{first_of_month, first_of_quarter, first_of_year, last_anniversary};
+use crate::util::error::UtilityError;
+use crate::asa::request::AsaRequest;
+use super::condition::RuleCondition;
+use super::constant::{CapPeriod, CardPresence, DayOfMonth, RuleStatus};
 
 #[derive(Insertable, Debug)]
 #[diesel(table_name = rule)]
@@ -20,13 +26,23 @@ struct InsertableRule {
     pub credit_card_id: i32,
     // pub rule_mcc: Option<String>,
     pub rule_category_id: Option<i32>,
-    pub merchant_name: Option<String>,
+    pub merchant_id: Option<i32>,
     pub points_multiplier: Option<i32>,
     pub cashback_percentage_bips: Option<i32>,
     pub recurring_day_of_month: Option<DayOfMonth>,
     pub start_date: Option<NaiveDate>,
     pub end_date: Option<NaiveDate>,
-    pub rule_status: RuleStatus
+    pub rule_status: RuleStatus,
+    pub cap_amount_cents: Option<i32>,
+    pub cap_period: Option<CapPeriod>,
+    pub fallback_points_multiplier: Option<i32>,
+    pub fallback_cashback_percentage_bips: Option<i32>,
+    pub rotating_category_schedule_id: Option<i32>,
+    pub min_amount_cents: Option<i32>,
+    pub max_amount_cents: Option<i32>,
+    pub merchant_country: Option<String>,
+    pub card_presence: Option<CardPresence>,
+    pub mccs: Option<Vec<String>>,
 }
 
 #[derive(Serialize, Deserialize, Queryable, Insertable, Debug, Identifiable)]
@@ -37,13 +53,24 @@ pub struct Rule {
     pub public_id: Uuid,
     pub credit_card_id: i32,
     pub rule_category_id: Option<i32>,
-    pub merchant_name: Option<String>,
+    pub merchant_id: Option<i32>,
     pub points_multiplier: Option<i32>,
     pub cashback_percentage_bips: Option<i32>,
     pub recurring_day_of_month: Option<DayOfMonth>,
     pub start_date: Option<NaiveDate>,
     pub end_date: Option<NaiveDate>,
     pub rule_status: RuleStatus,
+    pub cap_amount_cents: Option<i32>,
+    pub cap_period: Option<CapPeriod>,
+    // what the rule earns once the cap is used up, in the same unit as the bonus rate
+    pub fallback_points_multiplier: Option<i32>,
+    pub fallback_cashback_percentage_bips: Option<i32>,
+    pub rotating_category_schedule_id: Option<i32>,
+    pub min_amount_cents: Option<i32>,
+    pub max_amount_cents: Option<i32>,
+    pub merchant_country: Option<String>,
+    pub card_presence: Option<CardPresence>,
+    pub mccs: Option<Vec<String>>,
 }
 
 impl Rule {
@@ -77,20 +104,130 @@ impl Rule {
         }
     }
 
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_capped_spend(wallet_card_id: i32, rule_id: i32, since: NaiveDateTime) -> Result<i64, DataError> {
+        let mut conn = db::connection().await?;
+        // refunds aren't taken back off, most issuers only count them once they post
+        let total = wallet_card_charge::table
+            .filter(wallet_card_charge::wallet_card_id.eq(wallet_card_id))
+            .filter(wallet_card_charge::rule_id.eq(rule_id))
+            .filter(wallet_card_charge::created_at.ge(since))
+            .filter(wallet_card_charge::is_success.eq(true))
+            .filter(
+                wallet_card_charge::capture_status.is_null()
+                    .or(wallet_card_charge::capture_status.ne_all(vec![CaptureStatus::Cancelled, CaptureStatus::Failed]))
+            )
+            .select(diesel::dsl::sum(wallet_card_charge::amount_cents))
+            .first::<Option<i64>>(&mut conn).await?;
+        Ok(total.unwrap_or(0))
+    }
+
+    pub fn is_capped(&self) -> bool {
+        self.cap_amount_cents.is_some()
+    }
+
+    pub fn cap_period_start(&self, today: NaiveDate, anniversary: NaiveDate) -> Result<Option<NaiveDate>, UtilityError> {
+        let Some(period) = self.cap_period.as_ref() else { return Ok(None); };
+        Ok(Some(match period {
+            CapPeriod::Monthly => first_of_month(today)?,
+            CapPeriod::Quarterly => first_of_quarter(today)?,
+            CapPeriod::Annual => first_of_year(today)?,
+            CapPeriod::Anniversary => last_anniversary(today, anniversary)?,
+        }))
+    }
+
+    pub fn get_cap_remaining_cents(&self, cap_used_cents: i32) -> Option<i32> {
+        self.cap_amount_cents.map(|cap| (cap - cap_used_cents).max(0))
+    }
+
+    fn split_at_cap(&self, amount_cents: i32, cap_used_cents: i32) -> (i32, i32) {
+        // a purchase that crosses the cap earns the bonus rate up to it and the fallback rate past it
+        match self.get_cap_remaining_cents(cap_used_cents) {
+            Some(remaining) => {
+                let bonus_cents = amount_cents.min(remaining);
+                (bonus_cents, amount_cents - bonus_cents)
+            },
+            None => (amount_cents, 0)
+        }
+    }
+
+    pub fn get_expected_points(&self, amount_cents: i32, cap_used_cents: i32) -> Option<i32> {
+        let (bonus_cents, fallback_cents) = self.split_at_cap(amount_cents, cap_used_cents);
+        self.points_multiplier.map(|pm|
+            get_number_of_points(bonus_cents, pm)
+                + self.fallback_points_multiplier.map_or(0, |fpm| get_number_of_points(fallback_cents, fpm))
+        )
+    }
+
+    pub fn get_expected_cashback_cents(&self, amount_cents: i32, cap_used_cents: i32) -> Option<i32> {
+        let (bonus_cents, fallback_cents) = self.split_at_cap(amount_cents, cap_used_cents);
+        self.cashback_percentage_bips.map(|cpb|
+            get_cents_of_cashback(bonus_cents, cpb)
+                + self.fallback_cashback_percentage_bips.map_or(0, |fcpb| get_cents_of_cashback(fallback_cents, fcpb))
+        )
+    }
+
+    pub fn get_expected_value_cents(&self, amount_cents: i32, cap_used_cents: i32, cents_per_point_hundredths: i32) -> i32 {
+        // points are only worth what the card's rewards program says they are
+        if let Some(points) = self.get_expected_points(amount_cents, cap_used_cents) {
+            get_cents_of_points(points, cents_per_point_hundredths)
+        } else if let Some(cashback_cents) = self.get_expected_cashback_cents(amount_cents, cap_used_cents) {
+            cashback_cents
+        } else {
+            0
+        }
+    }
+
     pub fn is_valid(&self) -> bool {
         self.is_active_rule()
-        && self.is_valid_mcc_merchant_name()
+        && self.is_valid_merchant_category()
         && self.is_valid_cashback_points()
         && self.is_valid_date_combo()
         && self.is_valid_date_range()
+        && self.is_valid_cap()
+        && self.is_valid_rotation()
+        && self.is_valid_conditions()
+    }
+
+    pub fn conditions(&self) -> Vec<RuleCondition> {
+        let mut conditions = Vec::new();
+        if let Some(amount_cents) = self.min_amount_cents {
+            conditions.push(RuleCondition::MinAmount(amount_cents));
+        }
+        if let Some(amount_cents) = self.max_amount_cents {
+            conditions.push(RuleCondition::MaxAmount(amount_cents));
+        }
+        if let Some(country) = self.merchant_country.as_ref() {
+            conditions.push(RuleCondition::MerchantCountry(country.clone()));
+        }
+        if let Some(card_presence) = self.card_presence.as_ref() {
+            conditions.push(RuleCondition::CardPresence(card_presence.clone()));
+        }
+        if let Some(mccs) = self.mccs.as_ref() {
+            conditions.push(RuleCondition::MccIn(mccs.clone()));
+        }
+        conditions
+    }
+
+    pub fn matches_conditions(&self, request: &AsaRequest) -> bool {
+        self.conditions().iter().all(|condition| condition.matches(request))
+    }
+
+    pub fn is_rotating(&self) -> bool {
+        self.rotating_category_schedule_id.is_some()
     }
 
     fn is_active_rule(&self) -> bool {
         self.rule_status == RuleStatus::Active
     }
 
-    fn is_valid_mcc_merchant_name(&self) -> bool {
-        self.merchant_name.is_some() != self.rule_category_id.is_some()
+    fn is_valid_merchant_category(&self) -> bool {
+        // a merchant or a category, or neither when a set of mccs picks the purchases
+        if self.merchant_id.is_some() && self.rule_category_id.is_some() {
+            false
+        } else {
+            self.merchant_id.is_some() || self.rule_category_id.is_some() || self.mccs.is_some()
+        }
     }
 
     fn is_valid_cashback_points(&self) -> bool {
@@ -120,6 +257,37 @@ impl Rule {
         true
     }
 
+    fn is_valid_cap(&self) -> bool {
+        // a cap needs a positive amount and a period, and falls back in the same unit as the bonus rate
+        match self.cap_amount_cents {
+            Some(cap) => cap > 0
+                && self.cap_period.is_some()
+                && (self.fallback_points_multiplier.is_none() || self.points_multiplier.is_some())
+                && (self.fallback_cashback_percentage_bips.is_none() || self.cashback_percentage_bips.is_some()),
+            None => self.cap_period.is_none()
+                && self.fallback_points_multiplier.is_none()
+                && self.fallback_cashback_percentage_bips.is_none()
+        }
+    }
+
+    fn is_valid_rotation(&self) -> bool {
+        // the schedule decides when a rotating rule applies, so it's a category with no dates of its own
+        !self.is_rotating() || (
+            self.rule_category_id.is_some()
+            && self.recurring_day_of_month.is_none()
+            && self.start_date.is_none()
+            && self.end_date.is_none()
+        )
+    }
+
+    fn is_valid_conditions(&self) -> bool {
+        let amount_range_valid = match (self.min_amount_cents, self.max_amount_cents) {
+            (Some(min), Some(max)) => min <= max,
+            _ => true
+        };
+        amount_range_valid && self.conditions().iter().all(|condition| condition.is_valid())
+    }
+
     #[cfg(test)]
     #[tracing::instrument]
     pub async fn delete(id: i32) -> Result<usize, DataError> {
@@ -146,10 +314,7 @@ impl From<&CreateRuleRequest> for InsertableRule {
             credit_card_id: request.credit_card_id,
             //rule_mcc: request.rule_mcc,
             rule_category_id: request.rule_category_id,
-            merchant_name: match &request.merchant_name {
-                Some(merchant_name) => Some(merchant_name.clone()),
-                None => None
-            },
+            merchant_id: request.merchant_id,
             points_multiplier: request.points_multiplier,
             cashback_percentage_bips: request.cashback_percentage_bips,
             recurring_day_of_month: match &request.recurring_day_of_month {
@@ -164,7 +329,17 @@ impl From<&CreateRuleRequest> for InsertableRule {
                 Some(end_date) => Some(end_date.clone()),
                 None => None
             },
-            rule_status: RuleStatus::Active
+            rule_status: RuleStatus::Active,
+            cap_amount_cents: request.cap_amount_cents,
+            cap_period: request.cap_period.clone(),
+            fallback_points_multiplier: request.fallback_points_multiplier,
+            fallback_cashback_percentage_bips: request.fallback_cashback_percentage_bips,
+            rotating_category_schedule_id: request.rotating_category_schedule_id,
+            min_amount_cents: request.min_amount_cents,
+            max_amount_cents: request.max_amount_cents,
+            merchant_country: request.merchant_country.clone(),
+            card_presence: request.card_presence.clone(),
+            mccs: request.mccs.clone(),
         }
     }
 }
@@ -181,13 +356,23 @@ pub fn create_mock_rule_dateless_mcc_points(
         public_id: Uuid::new_v4(),
         credit_card_id: credit_card_id,
         rule_category_id: Some(1),
-        merchant_name: None,
+        merchant_id: None,
         points_multiplier: Some(points_multiplier),
         cashback_percentage_bips: None,
         recurring_day_of_month: None,
         start_date: None,
         end_date: None,
-        rule_status: RuleStatus::Active
+        rule_status: RuleStatus::Active,
+        cap_amount_cents: None,
+        cap_period: None,
+        fallback_points_multiplier: None,
+        fallback_cashback_percentage_bips: None,
+        rotating_category_schedule_id: None,
+        min_amount_cents: None,
+        max_amount_cents: None,
+        merchant_country: None,
+        card_presence: None,
+        mccs: None,
     }
 }
 
@@ -202,12 +387,22 @@ pub fn create_mock_rule_dateless_mcc_cashback(
         public_id: Uuid::new_v4(),
         credit_card_id: credit_card_id,
         rule_category_id: Some(1),
-        merchant_name: None,
+        merchant_id: None,
         points_multiplier: None,
         cashback_percentage_bips: Some(cashback_percentage_bips),
         recurring_day_of_month: None,
         start_date: None,
         end_date: None,
-        rule_status: RuleStatus::Active
+        rule_status: RuleStatus::Active,
+        cap_amount_cents: None,
+        cap_period: None,
+        fallback_points_multiplier: None,
+        fallback_cashback_percentage_bips: None,
+        rotating_category_schedule_id: None,
+        min_amount_cents: None,
+        max_amount_cents: None,
+        merchant_country: None,
+        card_presence: None,
+        mccs: None,
     }
 }
\ No newline at end of file
@@ -1,9 +1,10 @@
 #[cfg(test)]
 mod entity_tests {
     use std::sync::Arc;
-    use chrono::{Utc, Duration};
+    use chrono::{NaiveDate, Utc, Duration};
     use uuid::Uuid;
-    use crate::rule::constant::DayOfMonth;
+    use crate::asa::request::create_example_asa;
+    use crate::rule::constant::{CapPeriod, CardPresence, DayOfMonth};
     //use crate::test_helper::user::create_user;
     use actix_web::test;
     use crate::category::constant::Category;
@@ -34,11 +35,21 @@ mod entity_tests {
             credit_card_id: credit_card_id,
             rule_category_id: Some(Category::Airlines.into()),
             points_multiplier: points_multiplier,
-            merchant_name: None,
+            merchant_id: None,
             cashback_percentage_bips: None,
             recurring_day_of_month: None,
             start_date: None,
-            end_date: None
+            end_date: None,
+            cap_amount_cents: None,
+            cap_period: None,
+            fallback_points_multiplier: None,
+            fallback_cashback_percentage_bips: None,
+            rotating_category_schedule_id: None,
+            min_amount_cents: None,
+            max_amount_cents: None,
+            merchant_country: None,
+            card_presence: None,
+            mccs: None,
         };
         let rule = Rule::create(&rule_to_create).await.expect("Should create");
         assert_eq!(credit_card_id, rule.credit_card_id);
@@ -47,7 +58,7 @@ mod entity_tests {
         assert_eq!(category_id, rule.rule_category_id.expect("expect rule id"));
         assert_eq!(points_multiplier, rule.points_multiplier);
         assert_eq!(RuleStatus::Active, rule.rule_status);
-        assert!(rule.merchant_name.is_none());
+        assert!(rule.merchant_id.is_none());
         assert!(rule.cashback_percentage_bips.is_none());
         assert!(rule.recurring_day_of_month.is_none());
         assert!(rule.start_date.is_none());
@@ -67,12 +78,22 @@ mod entity_tests {
             credit_card_id: credit_card_id,
             rule_category_id: Some(1),
             points_multiplier: points_multiplier,
-            merchant_name: None,
+            merchant_id: None,
             cashback_percentage_bips: None,
             recurring_day_of_month: recurring_day_of_month.clone(),
             start_date: date,
             end_date: None,
-            rule_status: RuleStatus::Active
+            rule_status: RuleStatus::Active,
+            cap_amount_cents: None,
+            cap_period: None,
+            fallback_points_multiplier: None,
+            fallback_cashback_percentage_bips: None,
+            rotating_category_schedule_id: None,
+            min_amount_cents: None,
+            max_amount_cents: None,
+            merchant_country: None,
+            card_presence: None,
+            mccs: None,
         };
         assert!(!rule.is_valid());
         assert_eq!(RuleStatus::Active, rule.rule_status);
@@ -93,12 +114,22 @@ mod entity_tests {
             credit_card_id: credit_card_id,
             rule_category_id: Some(1),
             points_multiplier: points_multiplier,
-            merchant_name: None,
+            merchant_id: None,
             cashback_percentage_bips: None,
             recurring_day_of_month: None,
             start_date: date,
             end_date: None,
-            rule_status: RuleStatus::Active
+            rule_status: RuleStatus::Active,
+            cap_amount_cents: None,
+            cap_period: None,
+            fallback_points_multiplier: None,
+            fallback_cashback_percentage_bips: None,
+            rotating_category_schedule_id: None,
+            min_amount_cents: None,
+            max_amount_cents: None,
+            merchant_country: None,
+            card_presence: None,
+            mccs: None,
         };
         assert!(!rule.is_valid());
         assert_eq!(rule.start_date, date);
@@ -119,12 +150,22 @@ mod entity_tests {
             credit_card_id: credit_card_id,
             rule_category_id: Some(1),
             points_multiplier: points_multiplier,
-            merchant_name: None,
+            merchant_id: None,
             cashback_percentage_bips: None,
             recurring_day_of_month: None,
             start_date: start_date,
             end_date: end_date,
-            rule_status: RuleStatus::Active
+            rule_status: RuleStatus::Active,
+            cap_amount_cents: None,
+            cap_period: None,
+            fallback_points_multiplier: None,
+            fallback_cashback_percentage_bips: None,
+            rotating_category_schedule_id: None,
+            min_amount_cents: None,
+            max_amount_cents: None,
+            merchant_country: None,
+            card_presence: None,
+            mccs: None,
         };
         assert!(!rule.is_valid());
         assert_eq!(rule.start_date, start_date);
@@ -141,16 +182,26 @@ mod entity_tests {
             credit_card_id: credit_card_id,
             rule_category_id: None,
             points_multiplier: points_multiplier,
-            merchant_name: None,
+            merchant_id: None,
             cashback_percentage_bips: None,
             recurring_day_of_month: None,
             start_date: None,
             end_date: None,
-            rule_status: RuleStatus::Active
+            rule_status: RuleStatus::Active,
+            cap_amount_cents: None,
+            cap_period: None,
+            fallback_points_multiplier: None,
+            fallback_cashback_percentage_bips: None,
+            rotating_category_schedule_id: None,
+            min_amount_cents: None,
+            max_amount_cents: None,
+            merchant_country: None,
+            card_presence: None,
+            mccs: None,
         };
         assert!(!rule.is_valid());
         assert!(rule.rule_category_id.is_none());
-        assert!(rule.merchant_name.is_none());
+        assert!(rule.merchant_id.is_none());
     }
 
     #[test]
@@ -158,23 +209,33 @@ mod entity_tests {
         let points_multiplier = Some(2);
         let credit_card_id = 1;
         let mcc = "7184";
-        let merchant_name = "Kyle's Merchant";
+        let merchant_id = 1;
         let rule = Rule {
             id: 1,
             public_id: Uuid::new_v4(),
             credit_card_id: credit_card_id,
             rule_category_id: Some(1),
             points_multiplier: points_multiplier,
-            merchant_name: Some(merchant_name.to_string()),
+            merchant_id: Some(merchant_id),
             cashback_percentage_bips: None,
             recurring_day_of_month: None,
             start_date: None,
             end_date: None,
-            rule_status: RuleStatus::Active
+            rule_status: RuleStatus::Active,
+            cap_amount_cents: None,
+            cap_period: None,
+            fallback_points_multiplier: None,
+            fallback_cashback_percentage_bips: None,
+            rotating_category_schedule_id: None,
+            min_amount_cents: None,
+            max_amount_cents: None,
+            merchant_country: None,
+            card_presence: None,
+            mccs: None,
         };
         assert!(!rule.is_valid());
         assert_eq!(rule.rule_category_id, Some(1));
-        assert_eq!(rule.merchant_name, Some(merchant_name.to_string()));
+        assert_eq!(rule.merchant_id, Some(merchant_id));
     }
 
     #[test]
@@ -187,12 +248,22 @@ mod entity_tests {
             credit_card_id: credit_card_id,
             rule_category_id: Some(1),
             points_multiplier: None,
-            merchant_name: None,
+            merchant_id: None,
             cashback_percentage_bips: None,
             recurring_day_of_month: None,
             start_date: None,
             end_date: None,
-            rule_status: RuleStatus::Active
+            rule_status: RuleStatus::Active,
+            cap_amount_cents: None,
+            cap_period: None,
+            fallback_points_multiplier: None,
+            fallback_cashback_percentage_bips: None,
+            rotating_category_schedule_id: None,
+            min_amount_cents: None,
+            max_amount_cents: None,
+            merchant_country: None,
+            card_presence: None,
+            mccs: None,
         };
         assert!(!rule.is_valid());
         assert!(rule.points_multiplier.is_none());
@@ -211,15 +282,176 @@ mod entity_tests {
             credit_card_id: credit_card_id,
             rule_category_id: Some(1),
             points_multiplier: points_multiplier,
-            merchant_name: None,
+            merchant_id: None,
             cashback_percentage_bips: cashback_percentage_bips,
             recurring_day_of_month: None,
             start_date: None,
             end_date: None,
-            rule_status: RuleStatus::Active
+            rule_status: RuleStatus::Active,
+            cap_amount_cents: None,
+            cap_period: None,
+            fallback_points_multiplier: None,
+            fallback_cashback_percentage_bips: None,
+            rotating_category_schedule_id: None,
+            min_amount_cents: None,
+            max_amount_cents: None,
+            merchant_country: None,
+            card_presence: None,
+            mccs: None,
         };
         assert!(!rule.is_valid());
         assert_eq!(rule.points_multiplier, points_multiplier);
         assert_eq!(rule.cashback_percentage_bips, cashback_percentage_bips);
     }
+    fn capped_points_rule(cap_amount_cents: Option<i32>, cap_period: Option<CapPeriod>) -> Rule {
+        Rule {
+            id: 1,
+            public_id: Uuid::new_v4(),
+            credit_card_id: 1,
+            rule_category_id: Some(1),
+            points_multiplier: Some(4),
+            merchant_id: None,
+            cashback_percentage_bips: None,
+            recurring_day_of_month: None,
+            start_date: None,
+            end_date: None,
+            rule_status: RuleStatus::Active,
+            cap_amount_cents,
+            cap_period,
+            fallback_points_multiplier: Some(1),
+            fallback_cashback_percentage_bips: None,
+            rotating_category_schedule_id: None,
+            min_amount_cents: None,
+            max_amount_cents: None,
+            merchant_country: None,
+            card_presence: None,
+            mccs: None,
+        }
+    }
+
+    #[test]
+    async fn test_rule_cap_validity() {
+        assert!(capped_points_rule(Some(100000), Some(CapPeriod::Quarterly)).is_valid());
+        assert!(!capped_points_rule(Some(100000), None).is_valid());
+        assert!(!capped_points_rule(Some(0), Some(CapPeriod::Quarterly)).is_valid());
+        assert!(!capped_points_rule(None, None).is_valid());
+
+        let mut wrong_unit = capped_points_rule(Some(100000), Some(CapPeriod::Quarterly));
+        wrong_unit.fallback_points_multiplier = None;
+        wrong_unit.fallback_cashback_percentage_bips = Some(100);
+        assert!(!wrong_unit.is_valid());
+    }
+
+    #[test]
+    async fn test_rule_reward_split_at_cap() {
+        let rule = capped_points_rule(Some(100000), Some(CapPeriod::Quarterly));
+        // under the cap, all at 4x
+        assert_eq!(Some(1200), rule.get_expected_points(30000, 0));
+        assert_eq!(Some(70000), rule.get_cap_remaining_cents(30000));
+        // crossing the cap, 15000 at 4x and 15000 at 1x
+        assert_eq!(Some(750), rule.get_expected_points(30000, 85000));
+        assert_eq!(750, rule.get_expected_value_cents(30000, 85000, 100));
+        // past the cap, all at 1x
+        assert_eq!(Some(300), rule.get_expected_points(30000, 120000));
+        assert_eq!(Some(0), rule.get_cap_remaining_cents(120000));
+
+        let mut no_fallback = capped_points_rule(Some(100000), Some(CapPeriod::Quarterly));
+        no_fallback.fallback_points_multiplier = None;
+        assert_eq!(Some(0), no_fallback.get_expected_points(30000, 100000));
+    }
+
+    #[test]
+    async fn test_rule_rotating_validity() {
+        let mut rule = capped_points_rule(None, None);
+        rule.fallback_points_multiplier = None;
+        rule.rotating_category_schedule_id = Some(1);
+        assert!(rule.is_valid());
+        assert!(rule.is_rotating());
+
+        rule.start_date = Some(NaiveDate::from_ymd_opt(2024, 7, 1).unwrap());
+        rule.end_date = Some(NaiveDate::from_ymd_opt(2024, 9, 30).unwrap());
+        assert!(!rule.is_valid());
+    }
+
+    #[test]
+    async fn test_rule_condition_validity() {
+        let mut rule = capped_points_rule(None, None);
+        rule.fallback_points_multiplier = None;
+        rule.min_amount_cents = Some(5000);
+        rule.max_amount_cents = Some(100000);
+        rule.merchant_country = Some("GBR".to_string());
+        rule.card_presence = Some(CardPresence::Present);
+        assert!(rule.is_valid());
+        assert_eq!(4, rule.conditions().len());
+
+        rule.max_amount_cents = Some(1000);
+        assert!(!rule.is_valid());
+        rule.max_amount_cents = None;
+        rule.merchant_country = Some("uk".to_string());
+        assert!(!rule.is_valid());
+        rule.merchant_country = None;
+        rule.mccs = Some(vec!["58a2".to_string()]);
+        assert!(!rule.is_valid());
+        rule.mccs = Some(Vec::new());
+        assert!(!rule.is_valid());
+
+        // a set of mccs can stand in for the category
+        rule.rule_category_id = None;
+        rule.mccs = Some(vec!["4111".to_string(), "4121".to_string()]);
+        assert!(rule.is_valid());
+        rule.mccs = None;
+        assert!(!rule.is_valid());
+    }
+
+    #[test]
+    async fn test_rule_conditions_match_request() {
+        let asa = create_example_asa(30000, "5812".to_string());
+        let mut rule = capped_points_rule(None, None);
+        rule.fallback_points_multiplier = None;
+        assert!(rule.matches_conditions(&asa));
+
+        rule.min_amount_cents = Some(30000);
+        rule.max_amount_cents = Some(30000);
+        rule.merchant_country = Some("USA".to_string());
+        rule.mccs = Some(vec!["5812".to_string(), "5814".to_string()]);
+        assert!(rule.matches_conditions(&asa));
+
+        rule.min_amount_cents = Some(30001);
+        assert!(!rule.matches_conditions(&asa));
+        rule.min_amount_cents = None;
+        rule.max_amount_cents = Some(29999);
+        assert!(!rule.matches_conditions(&asa));
+        rule.max_amount_cents = None;
+        rule.merchant_country = Some("CAN".to_string());
+        assert!(!rule.matches_conditions(&asa));
+        rule.merchant_country = None;
+        rule.mccs = Some(vec!["5411".to_string()]);
+        assert!(!rule.matches_conditions(&asa));
+        rule.mccs = None;
+
+        // the example asa has no known entry mode, so neither presence matches
+        rule.card_presence = Some(CardPresence::Present);
+        assert!(!rule.matches_conditions(&asa));
+        let mut present = asa.clone();
+        present.pos.as_mut().unwrap().entry_mode.as_mut().unwrap().card = Some("PRESENT".to_string());
+        assert!(rule.matches_conditions(&present));
+        let mut preauthorized = asa.clone();
+        preauthorized.pos.as_mut().unwrap().entry_mode.as_mut().unwrap().card = Some("PREAUTHORIZED".to_string());
+        assert!(!rule.matches_conditions(&preauthorized));
+        rule.card_presence = Some(CardPresence::NotPresent);
+        assert!(rule.matches_conditions(&preauthorized));
+    }
+
+    #[test]
+    async fn test_rule_cap_period_start() {
+        let today = NaiveDate::from_ymd_opt(2024, 8, 14).unwrap();
+        let added = NaiveDate::from_ymd_opt(2022, 10, 3).unwrap();
+        let start = |period: CapPeriod| capped_points_rule(Some(100000), Some(period))
+            .cap_period_start(today, added).expect("has a start");
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 8, 1), start(CapPeriod::Monthly));
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 7, 1), start(CapPeriod::Quarterly));
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 1, 1), start(CapPeriod::Annual));
+        assert_eq!(NaiveDate::from_ymd_opt(2023, 10, 3), start(CapPeriod::Anniversary));
+        assert_eq!(None, capped_points_rule(None, None).cap_period_start(today, added).expect("no cap"));
+    }
 }
\ No newline at end of file
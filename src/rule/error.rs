@@ -5,6 +5,8 @@ use actix_web::ResponseError;
 pub enum RuleError {
     #[error("No amount provided")]
     NoAmount(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Invalid request")]
+    InvalidRequest(#[source] Box<dyn std::error::Error + Send + Sync>),
     #[error("Unexpected Error")]
     Unexpected(#[source] Box<dyn std::error::Error + Send + Sync>)
 }
@@ -13,6 +15,7 @@ impl ResponseError for RuleError {
     fn status_code(&self) -> StatusCode {
         match self {
             RuleError::NoAmount(_) => StatusCode::BAD_REQUEST,
+            RuleError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
             RuleError::Unexpected(_) => StatusCode::INTERNAL_SERVER_ERROR
         }
     }
@@ -23,6 +26,7 @@ impl PartialEq for RuleError {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (RuleError::NoAmount(_), RuleError::NoAmount(_))
+            | (RuleError::InvalidRequest(_), RuleError::InvalidRequest(_))
             | (RuleError::Unexpected(_), RuleError::Unexpected(_)) => true,
             _ => false
         }
@@ -41,5 +45,6 @@ mod test {
     pub fn test_status_codes() {
         assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, RuleError::Unexpected(BASE_ERROR.into()).status_code());
         assert_eq!(StatusCode::BAD_REQUEST, RuleError::NoAmount(BASE_ERROR.into()).status_code());
+        assert_eq!(StatusCode::BAD_REQUEST, RuleError::InvalidRequest(BASE_ERROR.into()).status_code());
     }
 }
\ No newline at end of file
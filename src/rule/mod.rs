@@ -1,7 +1,12 @@
+pub mod config;
 pub mod service;
 pub mod constant;
+pub mod condition;
+pub mod model;
 pub mod request;
+pub mod response;
 pub mod error;
+mod controller;
 mod entity;
 
 mod entity_tests;
@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::rule::constant::RoutingLossReason;
+use crate::rule::entity::Rule;
+use crate::wallet::model::WalletModelWithRule;
+
+// everything the ranking looked at, so a dry run can explain the order it came to
+pub struct RankedCardsModel {
+    pub cards: Vec<WalletModelWithRule>,
+    pub rules: Vec<Rule>,
+    // credit card id to hundredths of a cent per point
+    pub valuations: HashMap<i32, i32>,
+    // (wallet card id, rule id) to cents already spent against the rule's cap this period
+    pub cap_usage: HashMap<(i32, i32), i32>,
+}
+
+/// One wallet card as a dry run would route it, in the order the ASA flow would try them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulatedCardModel {
+    pub position: usize,
+    pub wallet_card_id: i32,
+    pub wallet_card_public_id: Uuid,
+    pub credit_card_id: i32,
+    pub rule_public_id: Option<Uuid>,
+    pub points_multiplier: Option<i32>,
+    pub cashback_percentage_bips: Option<i32>,
+    pub expected_points: Option<i32>,
+    pub expected_cashback_cents: Option<i32>,
+    pub cents_per_point_hundredths: Option<i32>,
+    pub cap_remaining_cents: Option<i32>,
+    // what the cards were ranked on
+    pub expected_value_cents: i32,
+    pub loss_reason: Option<RoutingLossReason>,
+    pub lost_to_wallet_card_public_id: Option<Uuid>,
+}
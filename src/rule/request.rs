@@ -1,14 +1,81 @@
 use chrono::NaiveDate;
-use crate::rule::constant::DayOfMonth;
+use serde::{Deserialize, Serialize};
+use crate::asa::request::{AsaRequest, EntryMode, Merchant, POS};
+use crate::rule::constant::{CapPeriod, CardPresence, DayOfMonth};
 
 #[derive(Debug)]
 pub struct CreateRuleRequest {
     pub credit_card_id: i32,
     pub rule_category_id: Option<i32>,
-    pub merchant_name: Option<String>,
+    pub merchant_id: Option<i32>,
     pub points_multiplier: Option<i32>,
     pub cashback_percentage_bips: Option<i32>,
     pub recurring_day_of_month: Option<DayOfMonth>,
     pub start_date: Option<NaiveDate>,
     pub end_date: Option<NaiveDate>,
-}
\ No newline at end of file
+    pub cap_amount_cents: Option<i32>,
+    pub cap_period: Option<CapPeriod>,
+    pub fallback_points_multiplier: Option<i32>,
+    pub fallback_cashback_percentage_bips: Option<i32>,
+    pub rotating_category_schedule_id: Option<i32>,
+    pub min_amount_cents: Option<i32>,
+    pub max_amount_cents: Option<i32>,
+    pub merchant_country: Option<String>,
+    pub card_presence: Option<CardPresence>,
+    pub mccs: Option<Vec<String>>,
+}
+
+// a hypothetical merchant to route against without charging
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SimulateRoutingRequest {
+    pub descriptor: String,
+    pub mcc: String,
+    pub amount_cents: i32,
+    pub country: Option<String>,
+    // left out, rules that depend on card presence won't match
+    pub card_present: Option<bool>,
+}
+
+impl From<&SimulateRoutingRequest> for AsaRequest {
+    fn from(value: &SimulateRoutingRequest) -> Self {
+        // only what rule matching reads is filled in, everything else lithic would send is left empty
+        AsaRequest {
+            amount: Some(value.amount_cents),
+            acquirer_fee: None,
+            authorization_amount: Some(value.amount_cents),
+            avs: None,
+            card: None,
+            cardholder_authentication: None,
+            cash_amount: None,
+            conversion_rate: None,
+            created: None,
+            events: None,
+            funding: None,
+            merchant_amount: None,
+            merchant_currency: None,
+            merchant: Some(Merchant {
+                acceptor_id: None,
+                city: None,
+                country: value.country.as_ref().map(|country| country.to_uppercase()),
+                descriptor: Some(value.descriptor.clone()),
+                mcc: Some(value.mcc.clone()),
+                state: None,
+            }),
+            network: None,
+            network_risk_score: None,
+            pos: value.card_present.map(|card_present| POS {
+                terminal: None,
+                entry_mode: Some(EntryMode {
+                    pan: None,
+                    pin_entered: None,
+                    cardholder: None,
+                    card: Some(if card_present { "PRESENT" } else { "NOT_PRESENT" }.to_string()),
+                }),
+            }),
+            settled_amount: None,
+            status: None,
+            token: None,
+            token_info: None,
+        }
+    }
+}
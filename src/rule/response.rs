@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::rule::constant::RoutingLossReason;
+use crate::rule::model::SimulatedCardModel;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SimulatedCardResponse {
+    pub position: usize,
+    pub wallet_card_public_id: Uuid,
+    pub rule_public_id: Option<Uuid>,
+    pub points_multiplier: Option<i32>,
+    pub cashback_percentage_bips: Option<i32>,
+    pub expected_points: Option<i32>,
+    pub expected_cashback_cents: Option<i32>,
+    pub cents_per_point_hundredths: Option<i32>,
+    pub cap_remaining_cents: Option<i32>,
+    pub expected_value_cents: i32,
+    pub loss_reason: Option<RoutingLossReason>,
+    pub lost_to_wallet_card_public_id: Option<Uuid>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SimulateRoutingResponse {
+    pub amount_cents: i32,
+    pub cards: Vec<SimulatedCardResponse>,
+}
+
+impl From<&SimulatedCardModel> for SimulatedCardResponse {
+    fn from(value: &SimulatedCardModel) -> Self {
+        SimulatedCardResponse {
+            position: value.position,
+            wallet_card_public_id: value.wallet_card_public_id,
+            rule_public_id: value.rule_public_id,
+            points_multiplier: value.points_multiplier,
+            cashback_percentage_bips: value.cashback_percentage_bips,
+            expected_points: value.expected_points,
+            expected_cashback_cents: value.expected_cashback_cents,
+            cents_per_point_hundredths: value.cents_per_point_hundredths,
+            cap_remaining_cents: value.cap_remaining_cents,
+            expected_value_cents: value.expected_value_cents,
+            loss_reason: value.loss_reason.clone(),
+            lost_to_wallet_card_public_id: value.lost_to_wallet_card_public_id,
+        }
+    }
+}
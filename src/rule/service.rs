@@ -1,12 +1,19 @@
-use std::collections::{hash_map::Entry, HashMap};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{NaiveDateTime, NaiveTime, Utc};
 use crate::asa::request::AsaRequest;
 use crate::category::service::{CategoryServiceTrait, CategoryService};
 use crate::category::model::MccMappingModel as MccMapping;
+use crate::merchant::service::MerchantServiceTrait;
 use crate::rule::dao::{RuleDao, RuleDaoTrait};
+use crate::rule::constant::RoutingLossReason;
 use crate::rule::error::RuleError;
+use crate::rewards_program::constant::DEFAULT_CENTS_PER_POINT_HUNDREDTHS;
+use crate::rewards_program::service::RewardsProgramServiceTrait;
+use crate::rotating_category::service::RotatingCategoryServiceTrait;
+use crate::rule::model::{RankedCardsModel, SimulatedCardModel};
+use crate::rule::request::SimulateRoutingRequest;
 use crate::user::model::UserModel as User;
 use crate::util::date::adjust_recurring_to_date;
 use crate::wallet::model::{WalletModel, WalletModelWithRule as Wallet, WalletModelWithRule};
@@ -17,6 +24,7 @@ use super::entity::Rule;
 #[async_trait(?Send)]
 pub trait RuleServiceTrait {
     async fn order_user_cards_for_request(self: Arc<Self>, request: &AsaRequest, user: &User) -> Result<Vec<Wallet>, RuleError>;
+    async fn simulate_routing(self: Arc<Self>, request: &SimulateRoutingRequest, user: &User) -> Result<Vec<SimulatedCardModel>, RuleError>;
 }
 
 
@@ -25,6 +33,9 @@ pub struct RuleService {
     category_service: Arc<dyn CategoryServiceTrait>,
     rule_dao: Arc<dyn RuleDaoTrait>,
     wallet_service: Arc<dyn WalletServiceTrait>,
+    rewards_program_service: Arc<dyn RewardsProgramServiceTrait>,
+    rotating_category_service: Arc<dyn RotatingCategoryServiceTrait>,
+    merchant_service: Arc<dyn MerchantServiceTrait>,
 }
 
 
@@ -36,6 +47,42 @@ impl RuleServiceTrait for RuleService {
         /*
         Given an asa request, and a user, attempt charging against a user's wallet until we get a successful attempt
          */
+        Ok(self.clone().rank_user_cards_for_request(request, user).await?.cards)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn simulate_routing(self: Arc<Self>, request: &SimulateRoutingRequest, user: &User) -> Result<Vec<SimulatedCardModel>, RuleError> {
+        if request.amount_cents <= 0 {
+            return Err(RuleError::InvalidRequest("amount must be positive".into()));
+        }
+        // same ranking the asa flow charges from, nothing is reserved or charged
+        let ranked = self.clone().rank_user_cards_for_request(&AsaRequest::from(request), user).await?;
+        Ok(RuleService::explain_order(&ranked, request.amount_cents))
+    }
+
+}
+
+impl RuleService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
+    pub fn new_with_services(
+        category_service: Arc<dyn CategoryServiceTrait>,
+        wallet_service: Arc<dyn WalletServiceTrait>,
+        rewards_program_service: Arc<dyn RewardsProgramServiceTrait>,
+        rotating_category_service: Arc<dyn RotatingCategoryServiceTrait>,
+        merchant_service: Arc<dyn MerchantServiceTrait>
+    ) -> Self {
+        Self {
+            category_service: category_service.clone(),
+            rule_dao: Arc::new(RuleDao::new()),
+            wallet_service: wallet_service.clone(),
+            rewards_program_service: rewards_program_service.clone(),
+            rotating_category_service: rotating_category_service.clone(),
+            merchant_service: merchant_service.clone(),
+        }
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn rank_user_cards_for_request(self: Arc<Self>, request: &AsaRequest, user: &User) -> Result<RankedCardsModel, RuleError> {
         //wallet, credit_card, credit_card_type, credit_card_issuer
         tracing::info!("Ordering cards in request for user_id={}", &user.id);
         let amount = request.amount.ok_or_else(|| {
@@ -50,77 +97,162 @@ impl RuleServiceTrait for RuleService {
             RuleError::Unexpected(e.into())
         })?.into_iter().map(|e| e.into()).collect();
         let card_type_ids = cards.iter().map(|card_with_info| card_with_info.credit_card_id).collect();
+        let activations = self.rotating_category_service.clone().get_current_activations(user).await
+            .unwrap_or_else(|e| {
+                // without activations rotating rules are skipped, the card still routes on its other rules
+                tracing::error!("Error getting rotating category activations for user_id={} error={:?}", &user.id, &e);
+                HashSet::new()
+            });
         tracing::info!("Filtering rulse for cards");
-        let rules = self.clone().find_and_filter_rules(&request, &card_type_ids).await?;
+        let rules = self.clone().find_and_filter_rules(&request, &card_type_ids, &activations).await?;
         tracing::info!("Using {} rules", rules.len());
-        let ordered_cards = self.clone().order_cards_from_rules_and_attach_rule_id_in_place(&mut cards, &rules, amount).await?;
-        Ok(ordered_cards.into_iter().map(|card| card.to_owned()).collect())
+        let valuations = self.rewards_program_service.clone().get_card_valuations(user).await
+            .unwrap_or_else(|e| {
+                // a cent a point still routes, just not as well
+                tracing::error!("Error getting point valuations for user_id={} error={:?}", &user.id, &e);
+                HashMap::new()
+            });
+        let cap_usage = self.clone().get_cap_usage(&cards, &rules).await;
+        let ordered_cards = self.clone().order_cards_from_rules_and_attach_rule_id_in_place(&mut cards, &rules, &valuations, &cap_usage, &activations, amount).await?;
+        Ok(
+            RankedCardsModel {
+                cards: ordered_cards.into_iter().map(|card| card.to_owned()).collect(),
+                rules,
+                valuations,
+                cap_usage
+            }
+        )
     }
 
-}
-
-impl RuleService {
-    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
-    pub fn new_with_services(
-        category_service: Arc<dyn CategoryServiceTrait>,
-        wallet_service: Arc<dyn WalletServiceTrait>
-    ) -> Self {
-        Self {
-            category_service: category_service.clone(),
-            rule_dao: Arc::new(RuleDao::new()),
-            wallet_service: wallet_service.clone(),
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_cap_usage(self: Arc<Self>, cards: &Vec<Wallet>, rules: &Vec<Rule>) -> HashMap<(i32, i32), i32> {
+        /*
+        Sum what each wallet card has already put through each capped rule this period
+        Anniversary caps count from when the card was added, so this is per wallet card and not per card type
+         */
+        let today = Utc::now().naive_utc().date();
+        let mut cap_usage: HashMap<(i32, i32), i32> = HashMap::new();
+        for rule in rules.iter().filter(|rule| rule.is_capped()) {
+            for card in cards.iter().filter(|card| card.credit_card_id == rule.credit_card_id) {
+                let since = match rule.cap_period_start(today, card.created_at.date()) {
+                    Ok(Some(start)) => NaiveDateTime::new(start, NaiveTime::default()),
+                    Ok(None) => continue,
+                    Err(e) => {
+                        tracing::error!("Error getting cap period for rule_id={} error={:?}", rule.id, &e);
+                        continue;
+                    }
+                };
+                // a failed lookup routes as if the cap was untouched rather than failing the charge
+                let used = self.rule_dao.clone().get_capped_spend(card.id, rule.id, since).await
+                    .unwrap_or_else(|e| {
+                        tracing::error!("Error getting capped spend for wallet_card_id={} rule_id={} error={:?}", card.id, rule.id, &e);
+                        0
+                    });
+                cap_usage.insert((card.id, rule.id), i32::try_from(used).unwrap_or(i32::MAX));
+            }
         }
+        cap_usage
+    }
+
+    pub fn explain_order(ranked: &RankedCardsModel, amount_cents: i32) -> Vec<SimulatedCardModel> {
+        /*
+        Given cards already ordered by the rules, explain what each would earn and why it lost to the first card
+         */
+        let first = ranked.cards.first();
+        ranked.cards.iter().enumerate().map(|(position, card)| {
+            let rule = card.rule_id.and_then(|rule_id| ranked.rules.iter().find(|rule| rule.id == rule_id));
+            let cents_per_point_hundredths = rule
+                .filter(|rule| rule.points_multiplier.is_some())
+                .map(|_| RuleService::cents_per_point_for(&ranked.valuations, card.credit_card_id));
+            let cap_used = rule.map_or(0, |rule| RuleService::cap_used_for(&ranked.cap_usage, card.id, rule.id));
+            let cap_remaining_cents = rule.and_then(|rule| rule.get_cap_remaining_cents(cap_used));
+            let (loss_reason, lost_to) = match first {
+                Some(first) if position > 0 => {
+                    let reason = if rule.is_none() {
+                        RoutingLossReason::NoMatchingRule
+                    } else if card.reward_amount < first.reward_amount && cap_remaining_cents == Some(0) {
+                        RoutingLossReason::CapReached
+                    } else if card.reward_amount < first.reward_amount {
+                        RoutingLossReason::LowerReward
+                    } else {
+                        RoutingLossReason::TiedReward
+                    };
+                    (Some(reason), Some(first.public_id))
+                },
+                _ => (None, None)
+            };
+            SimulatedCardModel {
+                position,
+                wallet_card_id: card.id,
+                wallet_card_public_id: card.public_id,
+                credit_card_id: card.credit_card_id,
+                rule_public_id: rule.map(|rule| rule.public_id),
+                points_multiplier: rule.and_then(|rule| rule.points_multiplier),
+                cashback_percentage_bips: rule.and_then(|rule| rule.cashback_percentage_bips),
+                expected_points: rule.and_then(|rule| rule.get_expected_points(amount_cents, cap_used)),
+                expected_cashback_cents: rule.and_then(|rule| rule.get_expected_cashback_cents(amount_cents, cap_used)),
+                cents_per_point_hundredths,
+                cap_remaining_cents,
+                expected_value_cents: card.reward_amount,
+                loss_reason,
+                lost_to_wallet_card_public_id: lost_to,
+            }
+        }).collect()
     }
 
     // TODO: this lifteime needs to be at class level
     #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
-    pub async fn order_cards_from_rules_and_attach_rule_id_in_place<'a>(self: Arc<Self>, cards: &'a mut Vec<WalletModelWithRule>, rules: &Vec<Rule>, amount_cents: i32) -> Result<&'a Vec<Wallet>, RuleError> {
+    pub async fn order_cards_from_rules_and_attach_rule_id_in_place<'a>(self: Arc<Self>, cards: &'a mut Vec<WalletModelWithRule>, rules: &Vec<Rule>, valuations: &HashMap<i32, i32>, cap_usage: &HashMap<(i32, i32), i32>, activations: &HashSet<(i32, i32)>, amount_cents: i32) -> Result<&'a Vec<Wallet>, RuleError> {
         tracing::info!("Getting card order from rules");
         /*
         Order ever card in the users wallet based on the maximal reward amount we can get
         Precondition: expect rules to be pre-filtered
          */
-        // map from card id to (amount, rule id)
-        let mut max_reward_map: HashMap<i32, (i32, i32)> = HashMap::new();
-        for rule in rules {
-            let reward_amount = rule.get_reward_amount_unitless(amount_cents);
-            match max_reward_map.entry(rule.credit_card_id) {
-                Entry::Vacant(e) => {e.insert((reward_amount, rule.id));}
-                Entry::Occupied(mut e) => {
-                    if (*e.get()).0 < reward_amount {
-                        e.insert((reward_amount, rule.id));
-                    }
+        for card in cards.iter_mut() {
+            // two cards of the same type can be at different points of a cap, so each wallet card is scored on its own
+            // (expected cents, rule id)
+            let mut max_reward: Option<(i32, i32)> = None;
+            for rule in rules.iter().filter(|rule| RuleService::rule_applies_to_card(rule, card, activations)) {
+                let reward_amount = rule.get_expected_value_cents(
+                    amount_cents,
+                    RuleService::cap_used_for(cap_usage, card.id, rule.id),
+                    RuleService::cents_per_point_for(valuations, rule.credit_card_id)
+                );
+                if max_reward.map_or(true, |(max_amount, _)| max_amount < reward_amount) {
+                    max_reward = Some((reward_amount, rule.id));
                 }
             }
-
-        }
-        tracing::info!("Sorting cards");
-        cards.sort_by(|a_card, b_card| {
-            let a_score = match max_reward_map.entry(a_card.credit_card_id) {
-                Entry::Vacant(_) => 0,
-                Entry::Occupied(e) => (*e.get()).0
-            };
-            let b_score = match max_reward_map.entry(b_card.credit_card_id) {
-                Entry::Vacant(_) => 0,
-                Entry::Occupied(e) => (*e.get()).0
-            };
-            b_score.cmp(&a_score)
-        });
-
-        for mut card in cards.iter_mut() {
-            match max_reward_map.entry(card.credit_card_id)  {
-                Entry::Vacant(_) => {},
-                Entry::Occupied(e) => {
-                    card.rule_id = Some((*e.get()).1);
-                }
+            if let Some((reward_amount, rule_id)) = max_reward {
+                card.rule_id = Some(rule_id);
+                card.reward_amount = reward_amount;
             }
-
+            // kept on the card so the routing trace can show every rule that was in the running
+            card.matched_rule_ids = rules.iter()
+                .filter(|rule| RuleService::rule_applies_to_card(rule, card, activations))
+                .map(|rule| rule.id)
+                .collect();
         }
+        tracing::info!("Sorting cards");
+        cards.sort_by(|a_card, b_card| b_card.reward_amount.cmp(&a_card.reward_amount));
         Ok(cards)
     }
 
+    pub fn rule_applies_to_card(rule: &Rule, card: &Wallet, activations: &HashSet<(i32, i32)>) -> bool {
+        // a rotating rule only pays on the wallet cards the user activated it on
+        rule.credit_card_id == card.credit_card_id
+            && rule.rotating_category_schedule_id.map_or(true, |schedule_id| activations.contains(&(card.id, schedule_id)))
+    }
+
+    fn cap_used_for(cap_usage: &HashMap<(i32, i32), i32>, wallet_card_id: i32, rule_id: i32) -> i32 {
+        cap_usage.get(&(wallet_card_id, rule_id)).copied().unwrap_or(0)
+    }
+
+    fn cents_per_point_for(valuations: &HashMap<i32, i32>, credit_card_id: i32) -> i32 {
+        valuations.get(&credit_card_id).copied().unwrap_or(DEFAULT_CENTS_PER_POINT_HUNDREDTHS)
+    }
+
     #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
-    pub async fn find_and_filter_rules(self: Arc<Self>, request: &AsaRequest, card_type_ids: &Vec<i32>) -> Result<Vec<Rule>, RuleError> {
+    pub async fn find_and_filter_rules(self: Arc<Self>, request: &AsaRequest, card_type_ids: &Vec<i32>, activations: &HashSet<(i32, i32)>) -> Result<Vec<Rule>, RuleError> {
         // TODO: remove direct call
         tracing::info!("Find and filter rules based on card types");
         let rules = self.rule_dao.clone().get_rules_for_card_ids(card_type_ids).await
@@ -130,39 +262,60 @@ impl RuleService {
             })?;
         let Some(merchant) = request.merchant.clone() else { return Ok(Vec::new()); };
         let Some(request_mcc) = merchant.mcc.clone() else { return Ok(Vec::new()); };
-        let mcc_mapping = self.category_service.clone().get_mcc_mapping_by_mcc(&request_mcc).await;
-        //.map_err(|e| RuleError::Unexpected(e.into()));
-        let mut filtered_rules: Vec<Rule> = Vec::new();
-        match mcc_mapping {
-            Ok(mapping) => {
-                for rule in rules.into_iter() {
-                    if rule.is_valid() && self.clone().filter_rule_for_request(&rule, &request, &mapping).await {
-                        filtered_rules.push(rule)
-                    }
-                }
-            }
+        let mcc_mapping = match self.category_service.clone().get_mcc_mapping_by_mcc(&request_mcc).await {
+            Ok(mapping) => Some(mapping),
             Err(e) => {
-                // bypass on failed category
+                // category rules are skipped, merchant and mcc set rules don't need the category to match
+                tracing::error!("Error getting category for mcc={} error={:?}", &request_mcc, &e);
+                None
+            }
+        };
+        // resolving the merchant is only worth the lookup when a rule is for one
+        let merchant_id = if rules.iter().any(|rule| rule.merchant_id.is_some()) {
+            self.merchant_service.clone().resolve_merchant_id(&merchant).await
+                .unwrap_or_else(|e| {
+                    // merchant rules are skipped, the card still routes on its category rules
+                    tracing::error!("Error resolving merchant for descriptor={:?} error={:?}", &merchant.descriptor, &e);
+                    None
+                })
+        } else {
+            None
+        };
+        let mut filtered_rules: Vec<Rule> = Vec::new();
+        for rule in rules.into_iter() {
+            if rule.is_valid()
+                && RuleService::filter_rule_by_activation(&rule, activations)
+                && self.clone().filter_rule_for_request(&rule, &request, mcc_mapping.as_ref(), merchant_id).await {
+                filtered_rules.push(rule)
             }
         }
         Ok( filtered_rules )
     }
 
-    pub async fn filter_rule_for_request(self: Arc<Self>, rule: &Rule, asa_request: &AsaRequest, mapping: &MccMapping) -> bool {
-        self.clone().filter_rule_by_merchant(rule, asa_request, mapping).await && self.clone().filter_rule_by_date(rule).await
+    pub fn filter_rule_by_activation(rule: &Rule, activations: &HashSet<(i32, i32)>) -> bool {
+        // activations only cover this quarter's schedules, so this is also the quarter check
+        let Some(schedule_id) = rule.rotating_category_schedule_id else { return true; };
+        activations.iter().any(|(_, activated_schedule_id)| *activated_schedule_id == schedule_id)
+    }
+
+    pub async fn filter_rule_for_request(self: Arc<Self>, rule: &Rule, asa_request: &AsaRequest, mapping: Option<&MccMapping>, merchant_id: Option<i32>) -> bool {
+        self.clone().filter_rule_by_merchant(rule, asa_request, mapping, merchant_id).await
+            && self.clone().filter_rule_by_date(rule).await
+            && rule.matches_conditions(asa_request)
     }
 
-    pub async fn filter_rule_by_merchant(self: Arc<Self>, rule: &Rule, asa_request: &AsaRequest, mapping: &MccMapping) -> bool {
-        let Some(merchant) = asa_request.merchant.clone() else { return false; };
+    pub async fn filter_rule_by_merchant(self: Arc<Self>, rule: &Rule, asa_request: &AsaRequest, mapping: Option<&MccMapping>, merchant_id: Option<i32>) -> bool {
+        if asa_request.merchant.is_none() { return false; }
         // TODO: this might need to be coupled with mcc
-        if rule.merchant_name.is_some() {
-            let Some(rule_merchant) = rule.merchant_name.as_ref() else { return false; };
-            let Some(descriptor) = merchant.descriptor.clone() else { return false; };
-            descriptor.to_lowercase() == *rule_merchant.to_lowercase()
+        if let Some(rule_merchant_id) = rule.merchant_id {
+            // the descriptor was resolved to a canonical merchant up front, an unresolved one matches no merchant rule
+            merchant_id == Some(rule_merchant_id)
+        } else if let Some(category_id) = rule.rule_category_id {
+            // without a category for the mcc there is nothing for a category rule to match
+            mapping.is_some_and(|mapping| category_id == mapping.category_id)
         } else {
-            //let Some(mcc) = rule.rule_category_id.as_ref() else { return false; };
-            let Some(category_id) = rule.rule_category_id else { return false; };
-            category_id == mapping.category_id
+            // an mcc set rule, the mccs are matched with the rest of the conditions
+            rule.mccs.is_some()
         }
     }
 
@@ -2,7 +2,8 @@
 mod tests {
     use std::sync::Arc;
     use crate::category::model::{CategoryModel, MccMappingModel};
-    use crate::rule::constant::DayOfMonth;
+    use crate::rule::condition::card_presence_of;
+    use crate::rule::constant::{CapPeriod, CardPresence, DayOfMonth};
     use crate::rule::request::CreateRuleRequest;
     use crate::rule::service::{
         RuleService,
@@ -21,8 +22,262 @@ mod tests {
     use crate::test_helper::user::create_mock_user;
     use crate::wallet::constant::WalletStatus;
     use crate::wallet::service::WalletService;
+    use crate::asa::request::AsaRequest;
+    use crate::credit_card_type::service::CreditCardService;
+    use crate::footprint::service::MockFootprintServiceTrait;
+    use crate::rule::constant::RoutingLossReason;
+    use crate::rule::error::RuleError;
+    use crate::rule::request::SimulateRoutingRequest;
+    use crate::test_helper::user::create_user;
+    use crate::test_helper::wallet::create_wallet;
+    use std::collections::{HashMap, HashSet};
+    use crate::rewards_program::service::RewardsProgramService;
+    use crate::rotating_category::service::RotatingCategoryService;
+    use crate::merchant::service::MerchantService;
+    use crate::rule::model::RankedCardsModel;
 
     const RULE_CATEGORY: i32 = 1;
+    const DINING_MCC: &str = "5812";
+
+    fn simulate_request(amount_cents: i32) -> SimulateRoutingRequest {
+        SimulateRoutingRequest {
+            descriptor: "test merchant".to_string(),
+            mcc: DINING_MCC.to_string(),
+            amount_cents,
+            country: Some("usa".to_string()),
+            card_present: Some(false),
+        }
+    }
+
+    fn rule_engine() -> Arc<RuleService> {
+        Arc::new(RuleService::new_with_services(
+            Arc::new(CategoryService::new()),
+            Arc::new(WalletService::new_with_services(
+                Arc::new(CreditCardService::new()),
+                Arc::new(MockFootprintServiceTrait::new())
+            )),
+            Arc::new(RewardsProgramService::new()),
+            Arc::new(RotatingCategoryService::new_with_services(Arc::new(WalletService::new_with_services(
+                Arc::new(CreditCardService::new()),
+                Arc::new(MockFootprintServiceTrait::new())
+            )))),
+            Arc::new(MerchantService::new())
+        ))
+    }
+
+    #[test]
+    async fn test_order_cards_by_point_valuation() {
+        crate::test_helper::general::init();
+        let amount_cents = 30000;
+        let mut cards: Vec<WalletModelWithRule> = vec![
+            create_mock_wallet_with_args(1, 1, 1).into(),
+            create_mock_wallet_with_args(2, 1, 2).into(),
+            create_mock_wallet_with_args(3, 1, 3).into(),
+        ];
+        let rules = vec![
+            create_mock_rule_dateless_mcc_points(1, 1, 3), // 900 points at a cent each
+            create_mock_rule_dateless_mcc_points(2, 2, 3), // 900 points at 2 cents each
+            create_mock_rule_dateless_mcc_cashback(3, 3, 400), // 1200 cents
+        ];
+        let valuations = HashMap::from([(2, 200)]);
+
+        let ordered = rule_engine().order_cards_from_rules_and_attach_rule_id_in_place(&mut cards, &rules, &valuations, &HashMap::new(), &HashSet::new(), amount_cents).await.expect("orders");
+        assert_eq!(vec![2, 3, 1], ordered.iter().map(|card| card.credit_card_id).collect::<Vec<i32>>());
+        assert_eq!(vec![1800, 1200, 900], ordered.iter().map(|card| card.reward_amount).collect::<Vec<i32>>());
+        assert_eq!(vec![Some(2), Some(3), Some(1)], ordered.iter().map(|card| card.rule_id).collect::<Vec<Option<i32>>>());
+    }
+
+    #[test]
+    async fn test_order_cards_after_cap() {
+        crate::test_helper::general::init();
+        let amount_cents = 30000;
+        let mut cards: Vec<WalletModelWithRule> = vec![
+            create_mock_wallet_with_args(1, 1, 1).into(),
+            create_mock_wallet_with_args(2, 1, 1).into(),
+            create_mock_wallet_with_args(3, 1, 3).into(),
+        ];
+        let mut capped_rule = create_mock_rule_dateless_mcc_points(1, 1, 4);
+        capped_rule.cap_amount_cents = Some(100000);
+        capped_rule.cap_period = Some(CapPeriod::Quarterly);
+        capped_rule.fallback_points_multiplier = Some(1);
+        let rules = vec![
+            capped_rule,
+            create_mock_rule_dateless_mcc_cashback(2, 3, 200), // 600 cents
+        ];
+        let cap_usage = HashMap::from([
+            ((1, 1), 100000), // 300 points, all past the cap
+            ((2, 1), 85000), // 600 points under the cap and 150 past it
+        ]);
+
+        let ordered = rule_engine().order_cards_from_rules_and_attach_rule_id_in_place(&mut cards, &rules, &HashMap::new(), &cap_usage, &HashSet::new(), amount_cents).await.expect("orders");
+        assert_eq!(vec![2, 3, 1], ordered.iter().map(|card| card.id).collect::<Vec<i32>>());
+        assert_eq!(vec![750, 600, 300], ordered.iter().map(|card| card.reward_amount).collect::<Vec<i32>>());
+
+        let ranked = RankedCardsModel {
+            cards: ordered.clone(),
+            rules,
+            valuations: HashMap::new(),
+            cap_usage
+        };
+        let explained = RuleService::explain_order(&ranked, amount_cents);
+        assert_eq!(Some(15000), explained[0].cap_remaining_cents);
+        assert_eq!(Some(750), explained[0].expected_points);
+        assert_eq!(None, explained[1].cap_remaining_cents);
+        assert_eq!(Some(RoutingLossReason::LowerReward), explained[1].loss_reason);
+        assert_eq!(Some(0), explained[2].cap_remaining_cents);
+        assert_eq!(Some(300), explained[2].expected_points);
+        assert_eq!(Some(RoutingLossReason::CapReached), explained[2].loss_reason);
+    }
+
+    #[test]
+    async fn test_order_cards_with_rotating_activation() {
+        crate::test_helper::general::init();
+        let amount_cents = 30000;
+        let schedule_id = 7;
+        let mut cards: Vec<WalletModelWithRule> = vec![
+            create_mock_wallet_with_args(1, 1, 1).into(),
+            create_mock_wallet_with_args(2, 1, 1).into(),
+        ];
+        let mut rotating_rule = create_mock_rule_dateless_mcc_points(1, 1, 5); // 1500 points once activated
+        rotating_rule.rotating_category_schedule_id = Some(schedule_id);
+        let rules = vec![
+            rotating_rule,
+            create_mock_rule_dateless_mcc_points(2, 1, 1), // 300 points
+        ];
+        // only the second card was activated this quarter
+        let activations = HashSet::from([(2, schedule_id)]);
+
+        let ordered = rule_engine().order_cards_from_rules_and_attach_rule_id_in_place(&mut cards, &rules, &HashMap::new(), &HashMap::new(), &activations, amount_cents).await.expect("orders");
+        assert_eq!(vec![2, 1], ordered.iter().map(|card| card.id).collect::<Vec<i32>>());
+        assert_eq!(vec![1500, 300], ordered.iter().map(|card| card.reward_amount).collect::<Vec<i32>>());
+        assert_eq!(vec![Some(1), Some(2)], ordered.iter().map(|card| card.rule_id).collect::<Vec<Option<i32>>>());
+        assert_eq!(vec![vec![1, 2], vec![2]], ordered.iter().map(|card| card.matched_rule_ids.clone()).collect::<Vec<Vec<i32>>>());
+
+        assert!(RuleService::filter_rule_by_activation(&rules[0], &activations));
+        assert!(!RuleService::filter_rule_by_activation(&rules[0], &HashSet::from([(2, schedule_id + 1)])));
+        assert!(RuleService::filter_rule_by_activation(&rules[1], &HashSet::new()));
+    }
+
+    #[test]
+    async fn test_filter_rule_by_merchant_id() {
+        crate::test_helper::general::init();
+        let amazon_id = 1;
+        let asa = create_example_asa(30000, DINING_MCC.to_string());
+        let mapping = MccMappingModel {
+            id: 1,
+            public_id: Default::default(),
+            mcc_code: DINING_MCC.to_string(),
+            category_id: RULE_CATEGORY,
+        };
+        let mut merchant_rule = create_mock_rule_dateless_mcc_points(1, 1, 5);
+        merchant_rule.rule_category_id = None;
+        merchant_rule.merchant_id = Some(amazon_id);
+        assert!(merchant_rule.is_valid());
+        let category_rule = create_mock_rule_dateless_mcc_points(2, 1, 2);
+
+        assert!(rule_engine().filter_rule_by_merchant(&merchant_rule, &asa, Some(&mapping), Some(amazon_id)).await);
+        assert!(!rule_engine().filter_rule_by_merchant(&merchant_rule, &asa, Some(&mapping), Some(amazon_id + 1)).await);
+        // an unresolved descriptor matches no merchant rule, category rules still match on the mcc
+        assert!(!rule_engine().filter_rule_by_merchant(&merchant_rule, &asa, Some(&mapping), None).await);
+        assert!(rule_engine().filter_rule_by_merchant(&category_rule, &asa, Some(&mapping), None).await);
+        // a failed category lookup only takes out the category rules
+        assert!(rule_engine().filter_rule_by_merchant(&merchant_rule, &asa, None, Some(amazon_id)).await);
+        assert!(!rule_engine().filter_rule_by_merchant(&category_rule, &asa, None, None).await);
+    }
+
+    #[test]
+    async fn test_explain_order() {
+        crate::test_helper::general::init();
+        let amount_cents = 30000;
+        let rules = vec![
+            create_mock_rule_dateless_mcc_points(1, 1, 2), // 600 points
+            create_mock_rule_dateless_mcc_cashback(2, 2, 250), // 750 cents
+            create_mock_rule_dateless_mcc_cashback(3, 4, 250), // 750 cents
+        ];
+        let mut cards: Vec<WalletModelWithRule> = vec![
+            create_mock_wallet_with_args(1, 1, 2).into(),
+            create_mock_wallet_with_args(2, 1, 4).into(),
+            create_mock_wallet_with_args(3, 1, 1).into(),
+            create_mock_wallet_with_args(4, 1, 3).into(),
+        ];
+        for (card, (rule_id, reward_amount)) in cards.iter_mut().zip([(Some(2), 750), (Some(3), 750), (Some(1), 600), (None, 0)]) {
+            card.rule_id = rule_id;
+            card.reward_amount = reward_amount;
+        }
+
+        let ranked = RankedCardsModel {
+            cards: cards.clone(),
+            rules,
+            valuations: HashMap::new(),
+            cap_usage: HashMap::new()
+        };
+        let explained = RuleService::explain_order(&ranked, amount_cents);
+        assert_eq!(4, explained.len());
+        assert_eq!(vec![0, 1, 2, 3], explained.iter().map(|card| card.position).collect::<Vec<usize>>());
+
+        assert_eq!(Some(ranked.rules[1].public_id), explained[0].rule_public_id);
+        assert_eq!(Some(750), explained[0].expected_cashback_cents);
+        assert_eq!(None, explained[0].cents_per_point_hundredths);
+        assert_eq!(750, explained[0].expected_value_cents);
+        assert_eq!(None, explained[0].expected_points);
+        assert_eq!(None, explained[0].loss_reason);
+        assert_eq!(None, explained[0].lost_to_wallet_card_public_id);
+
+        assert_eq!(Some(RoutingLossReason::TiedReward), explained[1].loss_reason);
+        assert_eq!(Some(cards[0].public_id), explained[1].lost_to_wallet_card_public_id);
+
+        assert_eq!(Some(600), explained[2].expected_points);
+        assert_eq!(Some(2), explained[2].points_multiplier);
+        assert_eq!(Some(100), explained[2].cents_per_point_hundredths);
+        assert_eq!(600, explained[2].expected_value_cents);
+        assert_eq!(None, explained[2].expected_cashback_cents);
+        assert_eq!(Some(RoutingLossReason::LowerReward), explained[2].loss_reason);
+
+        assert_eq!(None, explained[3].rule_public_id);
+        assert_eq!(None, explained[3].expected_points);
+        assert_eq!(Some(RoutingLossReason::NoMatchingRule), explained[3].loss_reason);
+        assert_eq!(Some(cards[0].public_id), explained[3].lost_to_wallet_card_public_id);
+
+        assert!(RuleService::explain_order(&RankedCardsModel { cards: Vec::new(), rules: Vec::new(), valuations: HashMap::new(), cap_usage: HashMap::new() }, amount_cents).is_empty());
+    }
+
+    #[test]
+    async fn test_simulate_request_to_asa() {
+        crate::test_helper::general::init();
+        let asa = AsaRequest::from(&simulate_request(1234));
+        assert_eq!(Some(1234), asa.amount);
+        assert_eq!(None, asa.cash_amount);
+        assert_eq!(Some(CardPresence::NotPresent), card_presence_of(&asa));
+        let merchant = asa.merchant.expect("has merchant");
+        assert_eq!(Some("test merchant".to_string()), merchant.descriptor);
+        assert_eq!(Some(DINING_MCC.to_string()), merchant.mcc);
+        assert_eq!(Some("USA".to_string()), merchant.country);
+    }
+
+    #[test]
+    async fn test_simulate_routing_matches_asa_order() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        create_wallet(&user).await;
+        create_wallet(&user).await;
+        let rule_engine = rule_engine();
+        let request = simulate_request(30000);
+
+        let simulated = rule_engine.clone().simulate_routing(&request, &user).await.expect("simulates");
+        let ordered = rule_engine.clone().order_user_cards_for_request(&AsaRequest::from(&request), &user).await.expect("orders");
+        assert_eq!(2, simulated.len());
+        assert_eq!(
+            ordered.iter().map(|card| (card.id, card.reward_amount)).collect::<Vec<(i32, i32)>>(),
+            simulated.iter().map(|card| (card.wallet_card_id, card.expected_value_cents)).collect::<Vec<(i32, i32)>>()
+        );
+        assert_eq!(None, simulated[0].loss_reason);
+        assert!(simulated[1].loss_reason.is_some());
+
+        assert_eq!(
+            RuleError::InvalidRequest("test".into()),
+            rule_engine.clone().simulate_routing(&simulate_request(0), &user).await.expect_err("needs an amount")
+        );
+    }
 
     /*
     #[test]
@@ -119,6 +374,7 @@ mod tests {
             credit_card_id: CreditCardTypeEnum::ChaseSapphirePreferred.into(),
             wallet_card_attempt_id: 1,
             status: WalletStatus::Active,
+            cash_advance_enabled: false,
         };
         let card_2 = WalletModel {
             id: card_2_id,
@@ -129,18 +385,29 @@ mod tests {
             credit_card_id: CreditCardTypeEnum::ChaseSapphireReserve.into(),
             wallet_card_attempt_id: 1,
             status: WalletStatus::Active,
+            cash_advance_enabled: false,
         };
 
         let should_be_filtered_rule = Rule::create(
             &CreateRuleRequest {
                 credit_card_id: 1,
                 rule_category_id: Some(RULE_CATEGORY),
-                merchant_name: None,
+                merchant_id: None,
                 points_multiplier: Some(1000),
                 cashback_percentage_bips: None,
                 recurring_day_of_month: Some(DayOfMonth::First),
                 start_date: Some(Utc::now().naive_utc().date()),
                 end_date: None,
+                cap_amount_cents: None,
+                cap_period: None,
+                fallback_points_multiplier: None,
+                fallback_cashback_percentage_bips: None,
+                rotating_category_schedule_id: None,
+                min_amount_cents: None,
+                max_amount_cents: None,
+                merchant_country: None,
+                card_presence: None,
+                mccs: None,
             }
         ).await.expect("rule should be created");
 
@@ -150,12 +417,22 @@ mod tests {
             &CreateRuleRequest {
                 credit_card_id: 1,
                 rule_category_id: Some(RULE_CATEGORY),
-                merchant_name: None,
+                merchant_id: None,
                 points_multiplier: Some(2),
                 cashback_percentage_bips: None,
                 recurring_day_of_month: None,
                 start_date: None,
                 end_date: None,
+                cap_amount_cents: None,
+                cap_period: None,
+                fallback_points_multiplier: None,
+                fallback_cashback_percentage_bips: None,
+                rotating_category_schedule_id: None,
+                min_amount_cents: None,
+                max_amount_cents: None,
+                merchant_country: None,
+                card_presence: None,
+                mccs: None,
             }
         ).await.expect("rule should be created");
 
@@ -163,12 +440,22 @@ mod tests {
             &CreateRuleRequest {
                 credit_card_id: 2,
                 rule_category_id: Some(RULE_CATEGORY),
-                merchant_name: None,
+                merchant_id: None,
                 points_multiplier: Some(5),
                 cashback_percentage_bips: None,
                 recurring_day_of_month: None,
                 start_date: None,
                 end_date: None,
+                cap_amount_cents: None,
+                cap_period: None,
+                fallback_points_multiplier: None,
+                fallback_cashback_percentage_bips: None,
+                rotating_category_schedule_id: None,
+                min_amount_cents: None,
+                max_amount_cents: None,
+                merchant_country: None,
+                card_presence: None,
+                mccs: None,
             }
         ).await.expect("rule should be created");
 
@@ -1,5 +1,24 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    authorization_adjustment (id) {
+        id -> Int4,
+        registered_transaction_id -> Int4,
+        wallet_card_charge_id -> Int4,
+        reference -> Uuid,
+        previous_amount_cents -> Int4,
+        amount_cents -> Int4,
+        #[max_length = 255]
+        psp_reference -> Nullable<Varchar>,
+        #[max_length = 30]
+        status -> Varchar,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        reversed_cents -> Int4,
+        asa_request_key -> Nullable<Uuid>,
+    }
+}
+
 diesel::table! {
     category (id) {
         id -> Int4,
@@ -23,6 +42,7 @@ diesel::table! {
         card_image_url -> Varchar,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        rewards_program_id -> Nullable<Int4>,
     }
 }
 
@@ -48,6 +68,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    end_to_end_charge_wallet_card_charge (id) {
+        id -> Int4,
+        successful_end_to_end_charge_id -> Int4,
+        wallet_card_charge_id -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     expected_wallet_charge_reference (id) {
         id -> Int4,
@@ -73,6 +103,66 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    merchant (id) {
+        id -> Int4,
+        public_id -> Uuid,
+        #[max_length = 255]
+        name -> Varchar,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    merchant_alias (id) {
+        id -> Int4,
+        merchant_id -> Int4,
+        #[max_length = 30]
+        alias_type -> Varchar,
+        #[max_length = 255]
+        pattern -> Varchar,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    merchant_control (id) {
+        id -> Int4,
+        public_id -> Uuid,
+        user_id -> Int4,
+        #[max_length = 30]
+        list_type -> Varchar,
+        #[max_length = 30]
+        match_type -> Varchar,
+        #[max_length = 255]
+        match_value -> Nullable<Varchar>,
+        #[max_length = 4]
+        mcc_range_end -> Nullable<Varchar>,
+        category_id -> Nullable<Int4>,
+        #[max_length = 255]
+        reason -> Varchar,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    merchant_control_decline (id) {
+        id -> Int4,
+        user_id -> Int4,
+        passthrough_card_id -> Int4,
+        merchant_control_id -> Nullable<Int4>,
+        #[max_length = 255]
+        transaction_token -> Nullable<Varchar>,
+        #[max_length = 255]
+        reason -> Varchar,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     passthrough_card (id) {
         id -> Int4,
@@ -121,6 +211,10 @@ diesel::table! {
         amount_cents -> Int4,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        #[max_length = 3]
+        currency -> Varchar,
+        conversion_rate -> Nullable<Float8>,
+        cash_amount_cents -> Nullable<Int4>,
     }
 }
 
@@ -137,6 +231,9 @@ diesel::table! {
         amount_cents -> Int4,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        #[max_length = 3]
+        currency -> Varchar,
+        conversion_rate -> Nullable<Float8>,
     }
 }
 
@@ -152,6 +249,20 @@ diesel::table! {
         mcc -> Varchar,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        #[max_length = 255]
+        lithic_transaction_token -> Nullable<Varchar>,
+        #[max_length = 30]
+        asa_response_result -> Nullable<Varchar>,
+        #[max_length = 3]
+        currency -> Varchar,
+        merchant_amount_cents -> Nullable<Int4>,
+        #[max_length = 3]
+        merchant_currency -> Nullable<Varchar>,
+        conversion_rate -> Nullable<Float8>,
+        #[max_length = 3]
+        charge_currency -> Varchar,
+        requested_amount_cents -> Nullable<Int4>,
+        cash_amount_cents -> Nullable<Int4>,
     }
 }
 
@@ -161,6 +272,43 @@ diesel::table! {
         body -> Nullable<Text>,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        routing_trace -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    rewards_program (id) {
+        id -> Int4,
+        public_id -> Uuid,
+        #[max_length = 255]
+        name -> Varchar,
+        cents_per_point_hundredths -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    rotating_category_activation (id) {
+        id -> Int4,
+        public_id -> Uuid,
+        user_id -> Int4,
+        wallet_card_id -> Int4,
+        rotating_category_schedule_id -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    rotating_category_schedule (id) {
+        id -> Int4,
+        public_id -> Uuid,
+        credit_card_id -> Int4,
+        year -> Int4,
+        quarter -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -170,8 +318,7 @@ diesel::table! {
         public_id -> Uuid,
         credit_card_id -> Int4,
         rule_category_id -> Nullable<Int4>,
-        #[max_length = 255]
-        merchant_name -> Nullable<Varchar>,
+        merchant_id -> Nullable<Int4>,
         points_multiplier -> Nullable<Int4>,
         cashback_percentage_bips -> Nullable<Int4>,
         #[max_length = 255]
@@ -180,6 +327,19 @@ diesel::table! {
         end_date -> Nullable<Date>,
         #[max_length = 255]
         rule_status -> Varchar,
+        cap_amount_cents -> Nullable<Int4>,
+        #[max_length = 30]
+        cap_period -> Nullable<Varchar>,
+        fallback_points_multiplier -> Nullable<Int4>,
+        fallback_cashback_percentage_bips -> Nullable<Int4>,
+        rotating_category_schedule_id -> Nullable<Int4>,
+        min_amount_cents -> Nullable<Int4>,
+        max_amount_cents -> Nullable<Int4>,
+        #[max_length = 3]
+        merchant_country -> Nullable<Varchar>,
+        #[max_length = 30]
+        card_presence -> Nullable<Varchar>,
+        mccs -> Nullable<Array<Text>>,
     }
 }
 
@@ -196,6 +356,10 @@ diesel::table! {
         amount_cents -> Int4,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        #[max_length = 3]
+        currency -> Varchar,
+        conversion_rate -> Nullable<Float8>,
+        cash_amount_cents -> Nullable<Int4>,
     }
 }
 
@@ -212,6 +376,66 @@ diesel::table! {
         amount_cents -> Int4,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        #[max_length = 3]
+        currency -> Varchar,
+        conversion_rate -> Nullable<Float8>,
+    }
+}
+
+diesel::table! {
+    spend_control (id) {
+        id -> Int4,
+        public_id -> Uuid,
+        user_id -> Int4,
+        passthrough_card_id -> Nullable<Int4>,
+        per_transaction_max_cents -> Nullable<Int4>,
+        daily_max_cents -> Nullable<Int4>,
+        weekly_max_cents -> Nullable<Int4>,
+        monthly_max_cents -> Nullable<Int4>,
+        hourly_transaction_limit -> Nullable<Int4>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    spend_control_usage (id) {
+        id -> Int4,
+        user_id -> Int4,
+        passthrough_card_id -> Int4,
+        #[max_length = 255]
+        transaction_token -> Varchar,
+        amount_cents -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        #[max_length = 255]
+        reference -> Varchar,
+    }
+}
+
+diesel::table! {
+    stand_in_transaction (id) {
+        id -> Int4,
+        public_id -> Uuid,
+        #[max_length = 255]
+        card_token -> Varchar,
+        #[max_length = 255]
+        lithic_transaction_token -> Nullable<Varchar>,
+        amount_cents -> Int4,
+        #[max_length = 255]
+        asa_response_result -> Varchar,
+        reason -> Text,
+        body -> Text,
+        #[max_length = 255]
+        status -> Varchar,
+        #[max_length = 255]
+        reconciled_result -> Nullable<Varchar>,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        user_id -> Nullable<Int4>,
+        attempts -> Int4,
+        next_attempt_at -> Timestamp,
+        claimed_until -> Nullable<Timestamp>,
     }
 }
 
@@ -225,6 +449,32 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    transaction_event (id) {
+        id -> Int4,
+        registered_transaction_id -> Int4,
+        #[max_length = 255]
+        event_token -> Varchar,
+        #[max_length = 40]
+        event_type -> Varchar,
+        amount_cents -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+        processed_at -> Nullable<Timestamp>,
+    }
+}
+
+diesel::table! {
+    user_rewards_program_valuation (id) {
+        id -> Int4,
+        user_id -> Int4,
+        rewards_program_id -> Int4,
+        cents_per_point_hundredths -> Int4,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     users (id) {
         id -> Int4,
@@ -235,6 +485,8 @@ diesel::table! {
         updated_at -> Timestamp,
         #[max_length = 255]
         footprint_vault_id -> Varchar,
+        split_tender_enabled -> Bool,
+        charge_in_merchant_currency -> Bool,
     }
 }
 
@@ -251,6 +503,7 @@ diesel::table! {
         wallet_card_attempt_id -> Int4,
         #[max_length = 20]
         status -> Varchar,
+        cash_advance_enabled -> Bool,
     }
 }
 
@@ -290,6 +543,53 @@ diesel::table! {
         created_at -> Timestamp,
         updated_at -> Timestamp,
         public_id -> Uuid,
+        #[max_length = 255]
+        refusal_reason -> Nullable<Varchar>,
+        #[max_length = 10]
+        refusal_reason_code -> Nullable<Varchar>,
+        #[max_length = 255]
+        capture_status -> Nullable<Varchar>,
+        captured_amount_cents -> Nullable<Int4>,
+        #[max_length = 255]
+        processor -> Varchar,
+        #[max_length = 255]
+        processor_charge_status -> Nullable<Varchar>,
+    }
+}
+
+diesel::table! {
+    wallet_card_charge_cancellation (id) {
+        id -> Int4,
+        registered_transaction_id -> Int4,
+        wallet_card_charge_id -> Int4,
+        #[max_length = 255]
+        psp_reference -> Nullable<Varchar>,
+        #[max_length = 255]
+        cancel_psp_reference -> Nullable<Varchar>,
+        #[max_length = 30]
+        cancel_status -> Varchar,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    wallet_card_charge_refund (id) {
+        id -> Int4,
+        registered_transaction_id -> Int4,
+        successful_end_to_end_charge_id -> Int4,
+        wallet_card_charge_id -> Int4,
+        #[max_length = 255]
+        psp_reference -> Varchar,
+        #[max_length = 255]
+        refund_psp_reference -> Nullable<Varchar>,
+        #[max_length = 255]
+        reference -> Varchar,
+        amount_cents -> Int4,
+        #[max_length = 30]
+        refund_status -> Varchar,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
     }
 }
 
@@ -307,12 +607,23 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(authorization_adjustment -> registered_transaction (registered_transaction_id));
+diesel::joinable!(authorization_adjustment -> wallet_card_charge (wallet_card_charge_id));
 diesel::joinable!(credit_card -> credit_card_issuer (credit_card_issuer_id));
 diesel::joinable!(credit_card -> credit_card_type (credit_card_type_id));
+diesel::joinable!(credit_card -> rewards_program (rewards_program_id));
+diesel::joinable!(end_to_end_charge_wallet_card_charge -> successful_end_to_end_charge (successful_end_to_end_charge_id));
+diesel::joinable!(end_to_end_charge_wallet_card_charge -> wallet_card_charge (wallet_card_charge_id));
 diesel::joinable!(expected_wallet_charge_reference -> registered_transaction (registered_transaction_id));
 diesel::joinable!(expected_wallet_charge_reference -> users (user_id));
 diesel::joinable!(expected_wallet_charge_reference -> wallet (wallet_card_id));
 diesel::joinable!(mcc_mapping -> category (category_id));
+diesel::joinable!(merchant_alias -> merchant (merchant_id));
+diesel::joinable!(merchant_control -> category (category_id));
+diesel::joinable!(merchant_control -> users (user_id));
+diesel::joinable!(merchant_control_decline -> merchant_control (merchant_control_id));
+diesel::joinable!(merchant_control_decline -> passthrough_card (passthrough_card_id));
+diesel::joinable!(merchant_control_decline -> users (user_id));
 diesel::joinable!(passthrough_card -> users (user_id));
 diesel::joinable!(passthrough_card_charge -> passthrough_card (passthrough_card_id));
 diesel::joinable!(passthrough_card_charge -> registered_transaction (registered_transaction_id));
@@ -325,17 +636,31 @@ diesel::joinable!(pending_wallet_transaction_ledger -> users (user_id));
 diesel::joinable!(pending_wallet_transaction_ledger -> wallet (wallet_id));
 diesel::joinable!(registered_transaction -> users (user_id));
 diesel::joinable!(registered_transaction_metadata -> registered_transaction (registered_transaction_id));
+diesel::joinable!(rotating_category_activation -> rotating_category_schedule (rotating_category_schedule_id));
+diesel::joinable!(rotating_category_activation -> users (user_id));
+diesel::joinable!(rotating_category_activation -> wallet (wallet_card_id));
+diesel::joinable!(rotating_category_schedule -> credit_card (credit_card_id));
 diesel::joinable!(rule -> category (rule_category_id));
 diesel::joinable!(rule -> credit_card (credit_card_id));
+diesel::joinable!(rule -> merchant (merchant_id));
+diesel::joinable!(rule -> rotating_category_schedule (rotating_category_schedule_id));
 diesel::joinable!(settled_passthrough_card_transaction_ledger -> passthrough_card (passthrough_card_id));
 diesel::joinable!(settled_passthrough_card_transaction_ledger -> registered_transaction (registered_transaction_id));
 diesel::joinable!(settled_passthrough_card_transaction_ledger -> users (user_id));
 diesel::joinable!(settled_wallet_transaction_ledger -> registered_transaction (registered_transaction_id));
 diesel::joinable!(settled_wallet_transaction_ledger -> users (user_id));
 diesel::joinable!(settled_wallet_transaction_ledger -> wallet (wallet_id));
+diesel::joinable!(spend_control -> passthrough_card (passthrough_card_id));
+diesel::joinable!(spend_control -> users (user_id));
+diesel::joinable!(spend_control_usage -> passthrough_card (passthrough_card_id));
+diesel::joinable!(spend_control_usage -> users (user_id));
+diesel::joinable!(stand_in_transaction -> users (user_id));
 diesel::joinable!(successful_end_to_end_charge -> passthrough_card_charge (passthrough_card_charge_id));
 diesel::joinable!(successful_end_to_end_charge -> registered_transaction (registered_transaction_id));
 diesel::joinable!(successful_end_to_end_charge -> wallet_card_charge (wallet_card_charge_id));
+diesel::joinable!(transaction_event -> registered_transaction (registered_transaction_id));
+diesel::joinable!(user_rewards_program_valuation -> rewards_program (rewards_program_id));
+diesel::joinable!(user_rewards_program_valuation -> users (user_id));
 diesel::joinable!(wallet -> credit_card (credit_card_id));
 diesel::joinable!(wallet -> users (user_id));
 diesel::joinable!(wallet -> wallet_card_attempt (wallet_card_attempt_id));
@@ -346,28 +671,49 @@ diesel::joinable!(wallet_card_charge -> registered_transaction (registered_trans
 diesel::joinable!(wallet_card_charge -> rule (rule_id));
 diesel::joinable!(wallet_card_charge -> users (user_id));
 diesel::joinable!(wallet_card_charge -> wallet (wallet_card_id));
+diesel::joinable!(wallet_card_charge_cancellation -> registered_transaction (registered_transaction_id));
+diesel::joinable!(wallet_card_charge_cancellation -> wallet_card_charge (wallet_card_charge_id));
+diesel::joinable!(wallet_card_charge_refund -> registered_transaction (registered_transaction_id));
+diesel::joinable!(wallet_card_charge_refund -> successful_end_to_end_charge (successful_end_to_end_charge_id));
+diesel::joinable!(wallet_card_charge_refund -> wallet_card_charge (wallet_card_charge_id));
 diesel::joinable!(wallet_status_history -> wallet (wallet_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    authorization_adjustment,
     category,
     credit_card,
     credit_card_issuer,
     credit_card_type,
+    end_to_end_charge_wallet_card_charge,
     expected_wallet_charge_reference,
     mcc_mapping,
+    merchant,
+    merchant_alias,
+    merchant_control,
+    merchant_control_decline,
     passthrough_card,
     passthrough_card_charge,
     pending_passthrough_card_transaction_ledger,
     pending_wallet_transaction_ledger,
     registered_transaction,
     registered_transaction_metadata,
+    rewards_program,
+    rotating_category_activation,
+    rotating_category_schedule,
     rule,
     settled_passthrough_card_transaction_ledger,
     settled_wallet_transaction_ledger,
+    spend_control,
+    spend_control_usage,
+    stand_in_transaction,
     successful_end_to_end_charge,
+    transaction_event,
+    user_rewards_program_valuation,
     users,
     wallet,
     wallet_card_attempt,
     wallet_card_charge,
+    wallet_card_charge_cancellation,
+    wallet_card_charge_refund,
     wallet_status_history,
 );
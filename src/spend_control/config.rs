@@ -0,0 +1,15 @@
+use actix_web::web;
+
+use super::controller;
+use crate::middleware::auth;
+
+pub fn config(cfg: &mut web::ServiceConfig) -> () {
+    cfg
+        .service(
+            web::scope("")
+                .wrap(auth::Auth)
+                .service(controller::get_controls)
+                .service(controller::update_user_control)
+                .service(controller::update_passthrough_card_control)
+        );
+}
@@ -0,0 +1,113 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpendWindow {
+    Hourly,
+    Daily,
+    Weekly,
+    Monthly
+}
+
+impl SpendWindow {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SpendWindow::Hourly => "hourly",
+            SpendWindow::Daily => "daily",
+            SpendWindow::Weekly => "weekly",
+            SpendWindow::Monthly => "monthly"
+        }
+    }
+
+    // the hourly window limits how many transactions go through, the rest limit how much is spent
+    pub fn counts_transactions(&self) -> bool {
+        *self == SpendWindow::Hourly
+    }
+
+    // windows are fixed calendar periods in utc, weeks start on monday
+    pub fn start(&self, now: NaiveDateTime) -> NaiveDateTime {
+        let midnight = now.date().and_time(NaiveTime::MIN);
+        match self {
+            SpendWindow::Hourly => midnight + Duration::hours(now.hour() as i64),
+            SpendWindow::Daily => midnight,
+            SpendWindow::Weekly => midnight - Duration::days(now.weekday().num_days_from_monday() as i64),
+            SpendWindow::Monthly => NaiveDate::from_ymd_opt(now.year(), now.month(), 1)
+                .unwrap_or(now.date())
+                .and_time(NaiveTime::MIN)
+        }
+    }
+
+    pub fn end(&self, now: NaiveDateTime) -> NaiveDateTime {
+        let start = self.start(now);
+        match self {
+            SpendWindow::Hourly => start + Duration::hours(1),
+            SpendWindow::Daily => start + Duration::days(1),
+            SpendWindow::Weekly => start + Duration::weeks(1),
+            SpendWindow::Monthly => {
+                let (year, month) = match start.month() {
+                    12 => (start.year() + 1, 1),
+                    month => (start.year(), month + 1)
+                };
+                NaiveDate::from_ymd_opt(year, month, 1)
+                    .unwrap_or(start.date())
+                    .and_time(NaiveTime::MIN)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpendScope {
+    User(i32),
+    PassthroughCard(i32)
+}
+
+impl SpendScope {
+    pub fn as_key_part(&self) -> String {
+        match self {
+            SpendScope::User(id) => format!("user_{}", id),
+            SpendScope::PassthroughCard(id) => format!("passthrough_card_{}", id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+    use crate::spend_control::constant::{SpendScope, SpendWindow};
+
+    #[test]
+    fn test_window_as_str() {
+        assert_eq!("hourly", SpendWindow::Hourly.as_str());
+        assert_eq!("daily", SpendWindow::Daily.as_str());
+        assert_eq!("weekly", SpendWindow::Weekly.as_str());
+        assert_eq!("monthly", SpendWindow::Monthly.as_str());
+    }
+
+    #[test]
+    fn test_window_counts_transactions() {
+        assert!(SpendWindow::Hourly.counts_transactions());
+        assert!(!SpendWindow::Daily.counts_transactions());
+        assert!(!SpendWindow::Weekly.counts_transactions());
+        assert!(!SpendWindow::Monthly.counts_transactions());
+    }
+
+    #[test]
+    fn test_window_bounds() {
+        // a thursday
+        let now = NaiveDate::from_ymd_opt(2024, 12, 19).unwrap().and_hms_opt(14, 35, 10).unwrap();
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 12, 19).unwrap().and_hms_opt(14, 0, 0).unwrap(), SpendWindow::Hourly.start(now));
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 12, 19).unwrap().and_hms_opt(15, 0, 0).unwrap(), SpendWindow::Hourly.end(now));
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 12, 19).unwrap().and_hms_opt(0, 0, 0).unwrap(), SpendWindow::Daily.start(now));
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 12, 20).unwrap().and_hms_opt(0, 0, 0).unwrap(), SpendWindow::Daily.end(now));
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 12, 16).unwrap().and_hms_opt(0, 0, 0).unwrap(), SpendWindow::Weekly.start(now));
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 12, 23).unwrap().and_hms_opt(0, 0, 0).unwrap(), SpendWindow::Weekly.end(now));
+        assert_eq!(NaiveDate::from_ymd_opt(2024, 12, 1).unwrap().and_hms_opt(0, 0, 0).unwrap(), SpendWindow::Monthly.start(now));
+        assert_eq!(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap(), SpendWindow::Monthly.end(now));
+    }
+
+    #[test]
+    fn test_scope_key_part() {
+        assert_eq!("user_1", SpendScope::User(1).as_key_part());
+        assert_eq!("passthrough_card_2", SpendScope::PassthroughCard(2).as_key_part());
+    }
+}
@@ -0,0 +1,58 @@
+use actix_web::{web, get, put, HttpResponse};
+use uuid::Uuid;
+use crate::middleware::services::Services;
+use crate::spend_control::model::SpendLimits;
+use crate::spend_control::request::UpdateSpendControlRequest;
+use crate::spend_control::response::SpendControlResponse;
+use crate::spend_control::service::SpendControlServiceTrait;
+use crate::user::model::UserModel as User;
+use super::error::SpendControlError;
+
+#[get("/")]
+async fn get_controls(
+    user: web::ReqData<User>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, SpendControlError> {
+    let user = user.into_inner();
+    let controls = services.spend_control_service.clone().get_controls(&user).await?;
+    Ok(HttpResponse::Ok().json(
+        controls.iter().map(SpendControlResponse::from).collect::<Vec<SpendControlResponse>>()
+    ))
+}
+
+#[put("/")]
+async fn update_user_control(
+    user: web::ReqData<User>,
+    request: web::Json<UpdateSpendControlRequest>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, SpendControlError> {
+    let user = user.into_inner();
+    let request = request.into_inner();
+    let control = services.spend_control_service.clone().set_user_control(
+        &user,
+        &SpendLimits::from(&request)
+    ).await?;
+    Ok(HttpResponse::Ok().json(
+        SpendControlResponse::from(&control)
+    ))
+}
+
+#[put("/passthrough-card/{public_id}/")]
+async fn update_passthrough_card_control(
+    user: web::ReqData<User>,
+    path: web::Path<Uuid>,
+    request: web::Json<UpdateSpendControlRequest>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, SpendControlError> {
+    let user = user.into_inner();
+    let public_id = path.into_inner();
+    let request = request.into_inner();
+    let control = services.spend_control_service.clone().set_passthrough_card_control(
+        &user,
+        &public_id,
+        &SpendLimits::from(&request)
+    ).await?;
+    Ok(HttpResponse::Ok().json(
+        SpendControlResponse::from(&control)
+    ))
+}
@@ -0,0 +1,223 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime};
+use lazy_static::lazy_static;
+use uuid::Uuid;
+use crate::error::data_error::DataError;
+use crate::spend_control::constant::SpendScope;
+use crate::spend_control::entity::{InsertableSpendControl, InsertableSpendControlUsage, SpendControl, SpendControlLimits, SpendControlUsage};
+use crate::spend_control::model::{SpendCounter, SpendCounterResult};
+#[cfg(not(feature = "no-redis"))]
+use redis::Script;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::services::{
+    RedisService,
+    RedisServiceTrait
+};
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::helper::try_redis_fallback_db;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::key::Key;
+
+#[cfg(test)]
+use mockall::{automock, predicate::*};
+
+#[cfg(not(feature = "no-redis"))]
+lazy_static! {
+    // checks every counter before touching any of them, so concurrent asas can't both squeeze under a limit.
+    // ARGV holds an (increment, limit) pair per key
+    static ref RESERVE_SCRIPT: Script = Script::new(r"
+        for i, key in ipairs(KEYS) do
+            if redis.call('EXISTS', key) == 0 then
+                return -1
+            end
+        end
+        for i, key in ipairs(KEYS) do
+            local current = tonumber(redis.call('GET', key))
+            if current + tonumber(ARGV[i * 2 - 1]) > tonumber(ARGV[i * 2]) then
+                return i
+            end
+        end
+        for i, key in ipairs(KEYS) do
+            redis.call('INCRBY', key, ARGV[i * 2 - 1])
+        end
+        return 0
+    ");
+    // a counter that already expired was rebuilt from the database without this usage, so it is left alone
+    static ref RELEASE_SCRIPT: Script = Script::new(r"
+        for i, key in ipairs(KEYS) do
+            if redis.call('EXISTS', key) == 1 then
+                redis.call('DECRBY', key, ARGV[i])
+            end
+        end
+        return 0
+    ");
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait SpendControlDaoTrait {
+    async fn insert(self: Arc<Self>, control: &InsertableSpendControl) -> Result<SpendControl, DataError>;
+    async fn get_for_user(self: Arc<Self>, user_id: i32) -> Result<Vec<SpendControl>, DataError>;
+    async fn find_for_user_and_card(self: Arc<Self>, user_id: i32, passthrough_card_id: Option<i32>) -> Result<SpendControl, DataError>;
+    async fn update_limits(self: Arc<Self>, control: &SpendControl, limits: &SpendControlLimits) -> Result<SpendControl, DataError>;
+    async fn get_passthrough_card_id_for_user(self: Arc<Self>, user_id: i32, passthrough_card_public_id: &Uuid) -> Result<i32, DataError>;
+
+    async fn insert_usage(self: Arc<Self>, usage: &InsertableSpendControlUsage) -> Result<SpendControlUsage, DataError>;
+    async fn get_usage_by_transaction_token(self: Arc<Self>, transaction_token: &str) -> Result<Vec<SpendControlUsage>, DataError>;
+    async fn delete_usage(self: Arc<Self>, id: i32) -> Result<(), DataError>;
+    async fn update_usage_amount_cents(self: Arc<Self>, id: i32, amount_cents: i32) -> Result<SpendControlUsage, DataError>;
+    async fn sum_usage_amount_since(self: Arc<Self>, scope: &SpendScope, since: NaiveDateTime, excluding_id: i32) -> Result<i64, DataError>;
+    async fn count_usage_since(self: Arc<Self>, scope: &SpendScope, since: NaiveDateTime, excluding_id: i32) -> Result<i64, DataError>;
+
+    async fn reserve_counters(self: Arc<Self>, counters: &Vec<SpendCounter>) -> Result<SpendCounterResult, DataError>;
+    async fn seed_counter(self: Arc<Self>, counter: &SpendCounter, value: i64, now: NaiveDateTime) -> Result<(), DataError>;
+    async fn release_counters(self: Arc<Self>, counters: &Vec<SpendCounter>, decrements: &Vec<i64>) -> Result<(), DataError>;
+}
+
+pub struct SpendControlDao {
+    #[cfg(not(feature = "no-redis"))]
+    redis: Arc<RedisService>
+}
+
+impl SpendControlDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub fn new() -> Self {
+        #[cfg(not(feature = "no-redis"))] {
+            Self {
+                redis: Arc::new(RedisService::new())
+            }
+        }
+        #[cfg(feature = "no-redis")] {
+            Self {}
+        }
+    }
+
+    async fn expire_controls_for_user(&self, user_id: i32) {
+        #[cfg(not(feature = "no-redis"))] {
+            tracing::info!("Expiring spend controls in redis for user_id={}", user_id);
+            self.redis.clone().expire_now::<_>(&Key::SpendControlsForUser(user_id)).await;
+        }
+    }
+}
+
+#[async_trait]
+impl SpendControlDaoTrait for SpendControlDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert(self: Arc<Self>, control: &InsertableSpendControl) -> Result<SpendControl, DataError> {
+        let control = SpendControl::insert(control).await;
+        if let Ok(control) = &control {
+            self.expire_controls_for_user(control.user_id).await;
+        }
+        control
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_for_user(self: Arc<Self>, user_id: i32) -> Result<Vec<SpendControl>, DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            Ok(try_redis_fallback_db(
+                self.redis.clone(),
+                Key::SpendControlsForUser(user_id),
+                || async {SpendControl::get_for_user(user_id).await},
+                true
+            ).await?)
+        }
+        #[cfg(feature = "no-redis")] {
+            SpendControl::get_for_user(user_id).await
+        }
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn find_for_user_and_card(self: Arc<Self>, user_id: i32, passthrough_card_id: Option<i32>) -> Result<SpendControl, DataError> {
+        SpendControl::find_for_user_and_card(user_id, passthrough_card_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_limits(self: Arc<Self>, control: &SpendControl, limits: &SpendControlLimits) -> Result<SpendControl, DataError> {
+        let updated = SpendControl::update_limits(control.id, limits).await;
+        self.expire_controls_for_user(control.user_id).await;
+        updated
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_passthrough_card_id_for_user(self: Arc<Self>, user_id: i32, passthrough_card_public_id: &Uuid) -> Result<i32, DataError> {
+        SpendControl::get_passthrough_card_id_for_user(user_id, passthrough_card_public_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert_usage(self: Arc<Self>, usage: &InsertableSpendControlUsage) -> Result<SpendControlUsage, DataError> {
+        SpendControlUsage::insert(usage).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_usage_by_transaction_token(self: Arc<Self>, transaction_token: &str) -> Result<Vec<SpendControlUsage>, DataError> {
+        SpendControlUsage::get_by_transaction_token(transaction_token).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn delete_usage(self: Arc<Self>, id: i32) -> Result<(), DataError> {
+        SpendControlUsage::delete(id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_usage_amount_cents(self: Arc<Self>, id: i32, amount_cents: i32) -> Result<SpendControlUsage, DataError> {
+        SpendControlUsage::update_amount_cents(id, amount_cents).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn sum_usage_amount_since(self: Arc<Self>, scope: &SpendScope, since: NaiveDateTime, excluding_id: i32) -> Result<i64, DataError> {
+        SpendControlUsage::sum_amount_since(scope, since, excluding_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn count_usage_since(self: Arc<Self>, scope: &SpendScope, since: NaiveDateTime, excluding_id: i32) -> Result<i64, DataError> {
+        SpendControlUsage::count_since(scope, since, excluding_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn reserve_counters(self: Arc<Self>, counters: &Vec<SpendCounter>) -> Result<SpendCounterResult, DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            let args: Vec<i64> = counters.iter()
+                .flat_map(|counter| [counter.increment, counter.limit])
+                .collect();
+            let result: i64 = self.redis.clone().invoke_script(&RESERVE_SCRIPT, counters, &args).await
+                .map_err(|e| DataError::Unexpected(e.into()))?;
+            Ok(
+                match result {
+                    0 => SpendCounterResult::Reserved,
+                    -1 => SpendCounterResult::Missing,
+                    position => SpendCounterResult::Exceeded(position as usize - 1)
+                }
+            )
+        }
+        #[cfg(feature = "no-redis")] {
+            Err(DataError::Unexpected("redis disabled".into()))
+        }
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn seed_counter(self: Arc<Self>, counter: &SpendCounter, value: i64, now: NaiveDateTime) -> Result<(), DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            // lives until its window closes, a concurrent seed that got there first wins
+            let ttl = counter.window_end - now;
+            self.redis.clone().set_primitive_if_absent(counter, value, ttl.max(Duration::seconds(1))).await
+                .map_err(|e| DataError::Unexpected(e.into()))?;
+            Ok(())
+        }
+        #[cfg(feature = "no-redis")] {
+            Err(DataError::Unexpected("redis disabled".into()))
+        }
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn release_counters(self: Arc<Self>, counters: &Vec<SpendCounter>, decrements: &Vec<i64>) -> Result<(), DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            let _: i64 = self.redis.clone().invoke_script(&RELEASE_SCRIPT, counters, decrements).await
+                .map_err(|e| DataError::Unexpected(e.into()))?;
+            Ok(())
+        }
+        #[cfg(feature = "no-redis")] {
+            Err(DataError::Unexpected("redis disabled".into()))
+        }
+    }
+}
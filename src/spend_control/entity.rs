@@ -0,0 +1,200 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::error::data_error::DataError;
+use crate::schema::{passthrough_card, spend_control, spend_control_usage};
+use crate::spend_control::constant::SpendScope;
+use crate::util::db;
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize, PartialEq)]
+#[diesel(table_name = spend_control)]
+pub struct InsertableSpendControl {
+    pub public_id: Uuid,
+    pub user_id: i32,
+    pub passthrough_card_id: Option<i32>,
+    pub per_transaction_max_cents: Option<i32>,
+    pub daily_max_cents: Option<i32>,
+    pub weekly_max_cents: Option<i32>,
+    pub monthly_max_cents: Option<i32>,
+    pub hourly_transaction_limit: Option<i32>,
+}
+
+// a limit left out of an update is removed, not kept
+#[derive(Debug, Clone, AsChangeset, Serialize, Deserialize, PartialEq)]
+#[diesel(table_name = spend_control)]
+#[diesel(treat_none_as_null = true)]
+pub struct SpendControlLimits {
+    pub per_transaction_max_cents: Option<i32>,
+    pub daily_max_cents: Option<i32>,
+    pub weekly_max_cents: Option<i32>,
+    pub monthly_max_cents: Option<i32>,
+    pub hourly_transaction_limit: Option<i32>,
+}
+
+#[derive(Debug, Clone, Identifiable, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = spend_control)]
+pub struct SpendControl {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub user_id: i32,
+    pub passthrough_card_id: Option<i32>,
+    pub per_transaction_max_cents: Option<i32>,
+    pub daily_max_cents: Option<i32>,
+    pub weekly_max_cents: Option<i32>,
+    pub monthly_max_cents: Option<i32>,
+    pub hourly_transaction_limit: Option<i32>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize, PartialEq)]
+#[diesel(table_name = spend_control_usage)]
+pub struct InsertableSpendControlUsage {
+    pub user_id: i32,
+    pub passthrough_card_id: i32,
+    pub transaction_token: String,
+    pub amount_cents: i32,
+    pub created_at: NaiveDateTime,
+    pub reference: String,
+}
+
+#[derive(Debug, Clone, Identifiable, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = spend_control_usage)]
+pub struct SpendControlUsage {
+    pub id: i32,
+    pub user_id: i32,
+    pub passthrough_card_id: i32,
+    pub transaction_token: String,
+    pub amount_cents: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub reference: String,
+}
+
+impl SpendControl {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert(control: &InsertableSpendControl) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let control = diesel::insert_into(spend_control::table)
+            .values(control)
+            .get_result(&mut conn).await?;
+        Ok(control)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_for_user(user_id: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let controls = spend_control::table
+            .filter(spend_control::user_id.eq(user_id))
+            .order(spend_control::id.asc())
+            .load::<SpendControl>(&mut conn).await?;
+        Ok(controls)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn find_for_user_and_card(user_id: i32, passthrough_card_id: Option<i32>) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let query = spend_control::table
+            .filter(spend_control::user_id.eq(user_id))
+            .into_boxed();
+        let query = match passthrough_card_id {
+            Some(card_id) => query.filter(spend_control::passthrough_card_id.eq(card_id)),
+            None => query.filter(spend_control::passthrough_card_id.is_null())
+        };
+        let control = query.first::<SpendControl>(&mut conn).await?;
+        Ok(control)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_limits(id: i32, limits: &SpendControlLimits) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let control = diesel::update(spend_control::table)
+            .filter(spend_control::id.eq(id))
+            .set(limits)
+            .get_result::<SpendControl>(&mut conn).await?;
+        Ok(control)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_passthrough_card_id_for_user(user_id: i32, passthrough_card_public_id: &Uuid) -> Result<i32, DataError> {
+        let mut conn = db::connection().await?;
+        let card_id = passthrough_card::table
+            .filter(passthrough_card::public_id.eq(passthrough_card_public_id))
+            .filter(passthrough_card::user_id.eq(user_id))
+            .select(passthrough_card::id)
+            .first::<i32>(&mut conn).await?;
+        Ok(card_id)
+    }
+}
+
+impl SpendControlUsage {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert(usage: &InsertableSpendControlUsage) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let usage = diesel::insert_into(spend_control_usage::table)
+            .values(usage)
+            .get_result(&mut conn).await?;
+        Ok(usage)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_by_transaction_token(transaction_token: &str) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let usages = spend_control_usage::table
+            .filter(spend_control_usage::transaction_token.eq(transaction_token))
+            .order(spend_control_usage::id.asc())
+            .load::<SpendControlUsage>(&mut conn).await?;
+        Ok(usages)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn delete(id: i32) -> Result<(), DataError> {
+        let mut conn = db::connection().await?;
+        diesel::delete(spend_control_usage::table)
+            .filter(spend_control_usage::id.eq(id))
+            .execute(&mut conn).await?;
+        Ok(())
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_amount_cents(id: i32, amount_cents: i32) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let usage = diesel::update(spend_control_usage::table)
+            .filter(spend_control_usage::id.eq(id))
+            .set(spend_control_usage::amount_cents.eq(amount_cents))
+            .get_result::<SpendControlUsage>(&mut conn).await?;
+        Ok(usage)
+    }
+
+    fn scoped_since<'a>(scope: &SpendScope, since: NaiveDateTime, excluding_id: i32) -> spend_control_usage::BoxedQuery<'a, diesel::pg::Pg> {
+        let query = spend_control_usage::table
+            .filter(spend_control_usage::created_at.ge(since))
+            .filter(spend_control_usage::id.ne(excluding_id))
+            .into_boxed();
+        match scope {
+            SpendScope::User(user_id) => query.filter(spend_control_usage::user_id.eq(*user_id)),
+            SpendScope::PassthroughCard(card_id) => query.filter(spend_control_usage::passthrough_card_id.eq(*card_id))
+        }
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn sum_amount_since(scope: &SpendScope, since: NaiveDateTime, excluding_id: i32) -> Result<i64, DataError> {
+        let mut conn = db::connection().await?;
+        let total = Self::scoped_since(scope, since, excluding_id)
+            .select(diesel::dsl::sum(spend_control_usage::amount_cents))
+            .first::<Option<i64>>(&mut conn).await?;
+        Ok(total.unwrap_or(0))
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn count_since(scope: &SpendScope, since: NaiveDateTime, excluding_id: i32) -> Result<i64, DataError> {
+        let mut conn = db::connection().await?;
+        // follow ups and releases share the token of the transaction they belong to
+        let count = Self::scoped_since(scope, since, excluding_id)
+            .select(diesel::dsl::count_distinct(spend_control_usage::transaction_token))
+            .get_result::<i64>(&mut conn).await?;
+        Ok(count)
+    }
+}
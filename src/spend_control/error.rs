@@ -0,0 +1,81 @@
+use actix_web::http::StatusCode;
+use actix_web::ResponseError;
+use crate::error::data_error::DataError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SpendControlError {
+    #[error("Spend control exceeded")]
+    Exceeded(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Transaction already counted")]
+    Conflict(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Not found")]
+    NotFound(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Invalid spend control")]
+    Invalid(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Unexpected error")]
+    Unexpected(#[source] Box<dyn std::error::Error + Send + Sync>)
+}
+
+impl ResponseError for SpendControlError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            SpendControlError::Exceeded(_) => StatusCode::FORBIDDEN,
+            SpendControlError::Conflict(_) => StatusCode::CONFLICT,
+            SpendControlError::NotFound(_) => StatusCode::NOT_FOUND,
+            SpendControlError::Invalid(_) => StatusCode::BAD_REQUEST,
+            SpendControlError::Unexpected(_) => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+impl From<DataError> for SpendControlError {
+    fn from(value: DataError) -> Self {
+        match value {
+            DataError::Conflict(e) => SpendControlError::Conflict(e),
+            DataError::NotFound(e) => SpendControlError::NotFound(e),
+            DataError::Format(e) => SpendControlError::Unexpected(e),
+            DataError::Unexpected(e) => SpendControlError::Unexpected(e),
+        }
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for SpendControlError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SpendControlError::Exceeded(_), SpendControlError::Exceeded(_))
+            | (SpendControlError::Conflict(_), SpendControlError::Conflict(_))
+            | (SpendControlError::NotFound(_), SpendControlError::NotFound(_))
+            | (SpendControlError::Invalid(_), SpendControlError::Invalid(_))
+            | (SpendControlError::Unexpected(_), SpendControlError::Unexpected(_)) => true,
+            _ => false
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use actix_web::http::StatusCode;
+    use actix_web::ResponseError;
+    use crate::error::data_error::DataError;
+    use crate::spend_control::error::SpendControlError;
+
+    const BASE_ERROR: &str = "test";
+
+    #[test]
+    pub fn test_from_data_error() {
+        assert_eq!(SpendControlError::Conflict(BASE_ERROR.into()), SpendControlError::from(DataError::Conflict(BASE_ERROR.into())));
+        assert_eq!(SpendControlError::NotFound(BASE_ERROR.into()), SpendControlError::from(DataError::NotFound(BASE_ERROR.into())));
+        assert_eq!(SpendControlError::Unexpected(BASE_ERROR.into()), SpendControlError::from(DataError::Format(BASE_ERROR.into())));
+        assert_eq!(SpendControlError::Unexpected(BASE_ERROR.into()), SpendControlError::from(DataError::Unexpected(BASE_ERROR.into())));
+    }
+
+    #[test]
+    pub fn test_status_codes() {
+        assert_eq!(StatusCode::FORBIDDEN, SpendControlError::Exceeded(BASE_ERROR.into()).status_code());
+        assert_eq!(StatusCode::CONFLICT, SpendControlError::Conflict(BASE_ERROR.into()).status_code());
+        assert_eq!(StatusCode::NOT_FOUND, SpendControlError::NotFound(BASE_ERROR.into()).status_code());
+        assert_eq!(StatusCode::BAD_REQUEST, SpendControlError::Invalid(BASE_ERROR.into()).status_code());
+        assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, SpendControlError::Unexpected(BASE_ERROR.into()).status_code());
+    }
+}
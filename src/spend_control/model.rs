@@ -0,0 +1,107 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+use crate::redis::key::StableRedisKey;
+use crate::spend_control::constant::{SpendScope, SpendWindow};
+use crate::spend_control::entity::{SpendControl, SpendControlLimits};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpendControlModel {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub user_id: i32,
+    pub passthrough_card_id: Option<i32>,
+    pub per_transaction_max_cents: Option<i32>,
+    pub daily_max_cents: Option<i32>,
+    pub weekly_max_cents: Option<i32>,
+    pub monthly_max_cents: Option<i32>,
+    pub hourly_transaction_limit: Option<i32>,
+}
+
+impl SpendControlModel {
+    pub fn scope(&self) -> SpendScope {
+        match self.passthrough_card_id {
+            Some(card_id) => SpendScope::PassthroughCard(card_id),
+            None => SpendScope::User(self.user_id)
+        }
+    }
+
+    pub fn limit_for(&self, window: &SpendWindow) -> Option<i32> {
+        match window {
+            SpendWindow::Hourly => self.hourly_transaction_limit,
+            SpendWindow::Daily => self.daily_max_cents,
+            SpendWindow::Weekly => self.weekly_max_cents,
+            SpendWindow::Monthly => self.monthly_max_cents
+        }
+    }
+}
+
+impl From<SpendControl> for SpendControlModel {
+    fn from(value: SpendControl) -> Self {
+        SpendControlModel {
+            id: value.id,
+            public_id: value.public_id,
+            user_id: value.user_id,
+            passthrough_card_id: value.passthrough_card_id,
+            per_transaction_max_cents: value.per_transaction_max_cents,
+            daily_max_cents: value.daily_max_cents,
+            weekly_max_cents: value.weekly_max_cents,
+            monthly_max_cents: value.monthly_max_cents,
+            hourly_transaction_limit: value.hourly_transaction_limit,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SpendLimits {
+    pub per_transaction_max_cents: Option<i32>,
+    pub daily_max_cents: Option<i32>,
+    pub weekly_max_cents: Option<i32>,
+    pub monthly_max_cents: Option<i32>,
+    pub hourly_transaction_limit: Option<i32>,
+}
+
+impl From<&SpendLimits> for SpendControlLimits {
+    fn from(value: &SpendLimits) -> Self {
+        SpendControlLimits {
+            per_transaction_max_cents: value.per_transaction_max_cents,
+            daily_max_cents: value.daily_max_cents,
+            weekly_max_cents: value.weekly_max_cents,
+            monthly_max_cents: value.monthly_max_cents,
+            hourly_transaction_limit: value.hourly_transaction_limit,
+        }
+    }
+}
+
+/// One windowed limit a transaction is counted against, keyed by the window it started in
+/// so a release after midnight still lands on the counter it was reserved on.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpendCounter {
+    pub scope: SpendScope,
+    pub window: SpendWindow,
+    pub window_start: NaiveDateTime,
+    pub window_end: NaiveDateTime,
+    pub limit: i64,
+    pub increment: i64,
+}
+
+impl StableRedisKey for SpendCounter {
+    fn to_key(&self) -> String {
+        format!("spend_{}_{}_{}", self.scope.as_key_part(), self.window.as_str(), self.window_start.and_utc().timestamp())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpendReservation {
+    pub usage_id: i32,
+    pub amount_cents: i32,
+    pub counters: Vec<SpendCounter>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpendCounterResult {
+    Reserved,
+    // position of the first counter the transaction would push over its limit
+    Exceeded(usize),
+    // one or more counters aren't in redis yet and need seeding from the database
+    Missing
+}
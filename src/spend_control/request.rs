@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+use crate::spend_control::model::SpendLimits;
+
+// every limit is optional, leaving one out removes it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UpdateSpendControlRequest {
+    pub per_transaction_max_cents: Option<i32>,
+    pub daily_max_cents: Option<i32>,
+    pub weekly_max_cents: Option<i32>,
+    pub monthly_max_cents: Option<i32>,
+    pub hourly_transaction_limit: Option<i32>,
+}
+
+impl From<&UpdateSpendControlRequest> for SpendLimits {
+    fn from(value: &UpdateSpendControlRequest) -> Self {
+        SpendLimits {
+            per_transaction_max_cents: value.per_transaction_max_cents,
+            daily_max_cents: value.daily_max_cents,
+            weekly_max_cents: value.weekly_max_cents,
+            monthly_max_cents: value.monthly_max_cents,
+            hourly_transaction_limit: value.hourly_transaction_limit,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::spend_control::constant::SpendScope;
+use crate::spend_control::model::SpendControlModel;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SpendControlResponse {
+    pub public_id: Uuid,
+    pub scope: String,
+    pub per_transaction_max_cents: Option<i32>,
+    pub daily_max_cents: Option<i32>,
+    pub weekly_max_cents: Option<i32>,
+    pub monthly_max_cents: Option<i32>,
+    pub hourly_transaction_limit: Option<i32>,
+}
+
+impl From<&SpendControlModel> for SpendControlResponse {
+    fn from(value: &SpendControlModel) -> Self {
+        SpendControlResponse {
+            public_id: value.public_id,
+            scope: match value.scope() {
+                SpendScope::User(_) => "user".to_string(),
+                SpendScope::PassthroughCard(_) => "passthrough_card".to_string()
+            },
+            per_transaction_max_cents: value.per_transaction_max_cents,
+            daily_max_cents: value.daily_max_cents,
+            weekly_max_cents: value.weekly_max_cents,
+            monthly_max_cents: value.monthly_max_cents,
+            hourly_transaction_limit: value.hourly_transaction_limit,
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{NaiveDateTime, Utc};
+use uuid::Uuid;
+use crate::asa::request::AsaRequest;
+use crate::error::data_error::DataError;
+use crate::passthrough_card::model::PassthroughCardModel as PassthroughCard;
+use crate::spend_control::constant::SpendWindow;
+use crate::spend_control::dao::{SpendControlDao, SpendControlDaoTrait};
+use crate::spend_control::entity::{InsertableSpendControl, InsertableSpendControlUsage, SpendControlLimits};
+use crate::spend_control::error::SpendControlError;
+use crate::spend_control::model::{SpendControlModel, SpendCounter, SpendCounterResult, SpendLimits, SpendReservation};
+use crate::user::model::UserModel as User;
+
+const WINDOWS: [SpendWindow; 4] = [SpendWindow::Hourly, SpendWindow::Daily, SpendWindow::Weekly, SpendWindow::Monthly];
+
+#[async_trait(?Send)]
+pub trait SpendControlServiceTrait {
+    async fn get_controls(self: Arc<Self>, user: &User) -> Result<Vec<SpendControlModel>, SpendControlError>;
+    async fn set_user_control(self: Arc<Self>, user: &User, limits: &SpendLimits) -> Result<SpendControlModel, SpendControlError>;
+    async fn set_passthrough_card_control(self: Arc<Self>, user: &User, passthrough_card_public_id: &Uuid, limits: &SpendLimits) -> Result<SpendControlModel, SpendControlError>;
+    async fn reserve(self: Arc<Self>, request: &AsaRequest, passthrough_card: &PassthroughCard) -> Result<Option<SpendReservation>, SpendControlError>;
+    async fn release(self: Arc<Self>, reservation: &SpendReservation, approved_amount_cents: i32) -> Result<(), SpendControlError>;
+    async fn release_transaction(self: Arc<Self>, transaction_token: &str, event_token: &str, amount_cents: Option<i32>) -> Result<(), SpendControlError>;
+}
+
+pub struct SpendControlService {
+    dao: Arc<dyn SpendControlDaoTrait>,
+}
+
+impl SpendControlService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub fn new() -> Self {
+        Self {
+            dao: Arc::new(SpendControlDao::new())
+        }
+    }
+
+    #[cfg(test)]
+    pub fn new_with_mocks(dao: Arc<dyn SpendControlDaoTrait>) -> Self {
+        Self {
+            dao
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl SpendControlServiceTrait for SpendControlService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_controls(self: Arc<Self>, user: &User) -> Result<Vec<SpendControlModel>, SpendControlError> {
+        Ok(
+            self.dao.clone().get_for_user(user.id).await?
+                .into_iter()
+                .map(SpendControlModel::from)
+                .collect()
+        )
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn set_user_control(self: Arc<Self>, user: &User, limits: &SpendLimits) -> Result<SpendControlModel, SpendControlError> {
+        self.clone().upsert_control(user, None, limits).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn set_passthrough_card_control(self: Arc<Self>, user: &User, passthrough_card_public_id: &Uuid, limits: &SpendLimits) -> Result<SpendControlModel, SpendControlError> {
+        let passthrough_card_id = self.dao.clone().get_passthrough_card_id_for_user(user.id, passthrough_card_public_id).await?;
+        self.clone().upsert_control(user, Some(passthrough_card_id), limits).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn reserve(self: Arc<Self>, request: &AsaRequest, passthrough_card: &PassthroughCard) -> Result<Option<SpendReservation>, SpendControlError> {
+        let amount_cents = request.amount.unwrap_or(0);
+        if amount_cents <= 0 {
+            // nothing is being spent on a zero dollar verification
+            return Ok(None)
+        }
+        let controls = self.clone().controls_for_card(passthrough_card.user_id, passthrough_card.id).await?;
+        if controls.is_empty() {
+            return Ok(None)
+        }
+        if let Some(control) = controls.iter().find(|control| control.per_transaction_max_cents.is_some_and(|max| amount_cents > max)) {
+            tracing::warn!("Amount {} over per transaction max for spend control={}", amount_cents, &control.public_id);
+            return Err(SpendControlError::Exceeded("per transaction max".into()))
+        }
+        let transaction_token = request.token.clone().ok_or(
+            SpendControlError::Unexpected("expect token on request".into())
+        )?;
+        // a follow up asa reuses the token with the new total, only what it adds on top is new spend
+        let previous = self.dao.clone().get_usage_by_transaction_token(&transaction_token).await?;
+        let reference = amount_cents.to_string();
+        if previous.iter().any(|usage| usage.reference == reference) {
+            return Err(SpendControlError::Conflict("transaction already counted".into()))
+        }
+        let is_follow_up = !previous.is_empty();
+        let counted_cents: i32 = previous.iter().map(|usage| usage.amount_cents).sum();
+        let delta_cents = amount_cents - counted_cents;
+        if is_follow_up && delta_cents <= 0 {
+            // a lower total comes back through the reversal lithic sends for it
+            return Ok(None)
+        }
+        let now = Utc::now().naive_utc();
+        // the usage goes in before the check so the database fallback sees concurrent transactions,
+        // keying it on the token and amount also stops a retried asa from being counted twice
+        let usage = self.dao.clone().insert_usage(
+            &InsertableSpendControlUsage {
+                user_id: passthrough_card.user_id,
+                passthrough_card_id: passthrough_card.id,
+                transaction_token,
+                amount_cents: delta_cents,
+                created_at: now,
+                reference,
+            }
+        ).await?;
+        let counters: Vec<SpendCounter> = counters_for(&controls, delta_cents, now)
+            .into_iter()
+            .filter(|counter| !(is_follow_up && counter.window.counts_transactions()))
+            .collect();
+        match self.clone().reserve_counters(&counters, usage.id, now).await {
+            Ok(None) => Ok(Some(
+                SpendReservation {
+                    usage_id: usage.id,
+                    amount_cents: delta_cents,
+                    counters
+                }
+            )),
+            Ok(Some(counter)) => {
+                tracing::warn!("Amount {} exceeds {} limit of {} for {}", delta_cents, counter.window.as_str(), counter.limit, counter.scope.as_key_part());
+                self.dao.clone().delete_usage(usage.id).await?;
+                Err(SpendControlError::Exceeded(format!("{} limit", counter.window.as_str()).into()))
+            },
+            Err(e) => {
+                self.dao.clone().delete_usage(usage.id).await?;
+                Err(e)
+            }
+        }
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn release(self: Arc<Self>, reservation: &SpendReservation, approved_amount_cents: i32) -> Result<(), SpendControlError> {
+        let decrements: Vec<i64> = if approved_amount_cents <= 0 {
+            self.dao.clone().delete_usage(reservation.usage_id).await?;
+            reservation.counters.iter().map(|counter| counter.increment).collect()
+        } else if approved_amount_cents < reservation.amount_cents {
+            // a partial approval is still one transaction, only the amount comes back
+            self.dao.clone().update_usage_amount_cents(reservation.usage_id, approved_amount_cents).await?;
+            let returned = (reservation.amount_cents - approved_amount_cents) as i64;
+            reservation.counters.iter()
+                .map(|counter| if counter.window.counts_transactions() { 0 } else { returned })
+                .collect()
+        } else {
+            return Ok(())
+        };
+        if let Err(e) = self.dao.clone().release_counters(&reservation.counters, &decrements).await {
+            // the counters only overstate spend until their window rolls over
+            tracing::warn!("Unable to release spend counters for usage={} error={:?}", reservation.usage_id, &e);
+        }
+        Ok(())
+    }
+
+    // gives back spend lithic voided, reversed or returned, all of what's left when there's no amount.
+    // the release is its own usage row keyed on the event, so a redelivered event doesn't give it back twice
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn release_transaction(self: Arc<Self>, transaction_token: &str, event_token: &str, amount_cents: Option<i32>) -> Result<(), SpendControlError> {
+        let usages = self.dao.clone().get_usage_by_transaction_token(transaction_token).await?;
+        let first = match usages.first() {
+            Some(first) => first,
+            // nothing was counted for it
+            None => return Ok(())
+        };
+        let counted_cents: i32 = usages.iter().map(|usage| usage.amount_cents).sum::<i32>().max(0);
+        let released_cents = amount_cents.map_or(counted_cents, |amount_cents| amount_cents.min(counted_cents));
+        if released_cents <= 0 {
+            return Ok(())
+        }
+        // dated with the transaction so it comes off the windows the spend was counted in
+        let release = match self.dao.clone().insert_usage(
+            &InsertableSpendControlUsage {
+                user_id: first.user_id,
+                passthrough_card_id: first.passthrough_card_id,
+                transaction_token: transaction_token.to_string(),
+                amount_cents: -released_cents,
+                created_at: first.created_at,
+                reference: event_token.to_string(),
+            }
+        ).await {
+            Ok(release) => release,
+            Err(DataError::Conflict(_)) => return Ok(()),
+            Err(e) => return Err(e.into())
+        };
+        let controls = self.clone().controls_for_card(first.user_id, first.passthrough_card_id).await?;
+        // the transaction still happened, only its amount comes back
+        let counters: Vec<SpendCounter> = counters_for(&controls, released_cents, first.created_at)
+            .into_iter()
+            .filter(|counter| !counter.window.counts_transactions())
+            .collect();
+        let decrements: Vec<i64> = counters.iter().map(|counter| counter.increment).collect();
+        if let Err(e) = self.dao.clone().release_counters(&counters, &decrements).await {
+            tracing::warn!("Unable to release spend counters for usage={} error={:?}", release.id, &e);
+        }
+        Ok(())
+    }
+}
+
+impl SpendControlService {
+    // the user wide control and the one on this card
+    async fn controls_for_card(self: Arc<Self>, user_id: i32, passthrough_card_id: i32) -> Result<Vec<SpendControlModel>, SpendControlError> {
+        Ok(
+            self.dao.clone().get_for_user(user_id).await?
+                .into_iter()
+                .map(SpendControlModel::from)
+                .filter(|control| control.passthrough_card_id.map_or(true, |id| id == passthrough_card_id))
+                .collect()
+        )
+    }
+
+    async fn upsert_control(self: Arc<Self>, user: &User, passthrough_card_id: Option<i32>, limits: &SpendLimits) -> Result<SpendControlModel, SpendControlError> {
+        if [limits.per_transaction_max_cents, limits.daily_max_cents, limits.weekly_max_cents, limits.monthly_max_cents, limits.hourly_transaction_limit]
+            .iter()
+            .any(|limit| limit.is_some_and(|limit| limit < 0)) {
+            return Err(SpendControlError::Invalid("limits can't be negative".into()))
+        }
+        let control = match self.dao.clone().find_for_user_and_card(user.id, passthrough_card_id).await {
+            Ok(control) => self.dao.clone().update_limits(&control, &SpendControlLimits::from(limits)).await?,
+            Err(DataError::NotFound(_)) => self.dao.clone().insert(
+                &InsertableSpendControl {
+                    public_id: Uuid::new_v4(),
+                    user_id: user.id,
+                    passthrough_card_id,
+                    per_transaction_max_cents: limits.per_transaction_max_cents,
+                    daily_max_cents: limits.daily_max_cents,
+                    weekly_max_cents: limits.weekly_max_cents,
+                    monthly_max_cents: limits.monthly_max_cents,
+                    hourly_transaction_limit: limits.hourly_transaction_limit,
+                }
+            ).await?,
+            Err(e) => return Err(e.into())
+        };
+        Ok(SpendControlModel::from(control))
+    }
+
+    // returns the first counter the transaction doesn't fit under, redis first and the database when redis can't answer
+    async fn reserve_counters(self: Arc<Self>, counters: &Vec<SpendCounter>, usage_id: i32, now: NaiveDateTime) -> Result<Option<SpendCounter>, SpendControlError> {
+        if counters.is_empty() {
+            return Ok(None)
+        }
+        match self.dao.clone().reserve_counters(counters).await {
+            Ok(SpendCounterResult::Reserved) => return Ok(None),
+            Ok(SpendCounterResult::Exceeded(position)) => return Ok(counters.get(position).cloned()),
+            Ok(SpendCounterResult::Missing) => {
+                let mut seeded = true;
+                for counter in counters.iter() {
+                    let used = self.clone().used_in_window(counter, usage_id).await?;
+                    if let Err(e) = self.dao.clone().seed_counter(counter, used, now).await {
+                        tracing::warn!("Unable to seed spend counter error={:?}", &e);
+                        seeded = false;
+                        break;
+                    }
+                }
+                if seeded {
+                    match self.dao.clone().reserve_counters(counters).await {
+                        Ok(SpendCounterResult::Reserved) => return Ok(None),
+                        Ok(SpendCounterResult::Exceeded(position)) => return Ok(counters.get(position).cloned()),
+                        result => tracing::warn!("Spend counters unavailable after seeding result={:?}", &result)
+                    }
+                }
+            },
+            Err(e) => tracing::warn!("Unable to reserve spend counters in redis, falling back to database error={:?}", &e)
+        }
+        for counter in counters.iter() {
+            let used = self.clone().used_in_window(counter, usage_id).await?;
+            if used + counter.increment > counter.limit {
+                return Ok(Some(counter.clone()))
+            }
+        }
+        Ok(None)
+    }
+
+    async fn used_in_window(self: Arc<Self>, counter: &SpendCounter, excluding_usage_id: i32) -> Result<i64, SpendControlError> {
+        Ok(
+            if counter.window.counts_transactions() {
+                self.dao.clone().count_usage_since(&counter.scope, counter.window_start, excluding_usage_id).await?
+            } else {
+                self.dao.clone().sum_usage_amount_since(&counter.scope, counter.window_start, excluding_usage_id).await?
+            }
+        )
+    }
+}
+
+pub fn counters_for(controls: &Vec<SpendControlModel>, amount_cents: i32, now: NaiveDateTime) -> Vec<SpendCounter> {
+    controls.iter()
+        .flat_map(|control| WINDOWS.iter().filter_map(move |window| {
+            let limit = control.limit_for(window)?;
+            Some(
+                SpendCounter {
+                    scope: control.scope(),
+                    window: *window,
+                    window_start: window.start(now),
+                    window_end: window.end(now),
+                    limit: limit as i64,
+                    increment: if window.counts_transactions() { 1 } else { amount_cents as i64 },
+                }
+            )
+        }))
+        .collect()
+}
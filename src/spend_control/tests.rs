@@ -0,0 +1,283 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::test;
+    use chrono::Utc;
+    use uuid::Uuid;
+    use crate::asa::request::{create_example_asa, AsaRequest};
+    use crate::error::data_error::DataError;
+    use crate::spend_control::constant::SpendWindow;
+    use crate::spend_control::dao::MockSpendControlDaoTrait;
+    use crate::spend_control::entity::{SpendControl, SpendControlUsage};
+    use crate::spend_control::error::SpendControlError;
+    use crate::spend_control::model::{SpendControlModel, SpendLimits};
+    use crate::spend_control::service::{counters_for, SpendControlService, SpendControlServiceTrait};
+    use crate::test_helper::passthrough_card::create_passthrough_card;
+    use crate::test_helper::user::create_user;
+
+    fn asa_for(amount_cents: i32) -> AsaRequest {
+        let mut asa = create_example_asa(amount_cents, "7184".to_string());
+        asa.token = Some(Uuid::new_v4().to_string());
+        asa
+    }
+
+    #[test]
+    async fn test_no_controls_reserves_nothing() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let service = Arc::new(SpendControlService::new());
+        assert!(service.clone().reserve(&asa_for(1000), &card).await.expect("reserves").is_none());
+    }
+
+    #[test]
+    async fn test_per_transaction_max() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let service = Arc::new(SpendControlService::new());
+        service.clone().set_user_control(&user, &SpendLimits {
+            per_transaction_max_cents: Some(5000),
+            ..Default::default()
+        }).await.expect("sets control");
+        assert!(service.clone().reserve(&asa_for(5000), &card).await.is_ok());
+        assert_eq!(
+            SpendControlError::Exceeded("test".into()),
+            service.clone().reserve(&asa_for(5001), &card).await.expect_err("over max")
+        );
+    }
+
+    #[test]
+    async fn test_daily_max_and_release_on_decline() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let service = Arc::new(SpendControlService::new());
+        service.clone().set_user_control(&user, &SpendLimits {
+            daily_max_cents: Some(10000),
+            ..Default::default()
+        }).await.expect("sets control");
+        let first = service.clone().reserve(&asa_for(6000), &card).await.expect("reserves").expect("has reservation");
+        assert_eq!(
+            SpendControlError::Exceeded("test".into()),
+            service.clone().reserve(&asa_for(6000), &card).await.expect_err("over daily max")
+        );
+        // the declined charge gives its room back
+        service.clone().release(&first, 0).await.expect("releases");
+        assert!(service.clone().reserve(&asa_for(6000), &card).await.is_ok());
+    }
+
+    #[test]
+    async fn test_partial_approval_releases_difference() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let service = Arc::new(SpendControlService::new());
+        service.clone().set_user_control(&user, &SpendLimits {
+            daily_max_cents: Some(10000),
+            ..Default::default()
+        }).await.expect("sets control");
+        let reservation = service.clone().reserve(&asa_for(10000), &card).await.expect("reserves").expect("has reservation");
+        service.clone().release(&reservation, 4000).await.expect("releases");
+        assert!(service.clone().reserve(&asa_for(6000), &card).await.is_ok());
+        assert!(service.clone().reserve(&asa_for(1), &card).await.is_err());
+    }
+
+    #[test]
+    async fn test_hourly_transaction_limit_on_card() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let service = Arc::new(SpendControlService::new());
+        service.clone().set_passthrough_card_control(&user, &card.public_id, &SpendLimits {
+            hourly_transaction_limit: Some(2),
+            ..Default::default()
+        }).await.expect("sets control");
+        assert!(service.clone().reserve(&asa_for(100), &card).await.is_ok());
+        assert!(service.clone().reserve(&asa_for(100), &card).await.is_ok());
+        assert_eq!(
+            SpendControlError::Exceeded("test".into()),
+            service.clone().reserve(&asa_for(100), &card).await.expect_err("over hourly limit")
+        );
+    }
+
+    #[test]
+    async fn test_duplicate_transaction_conflicts() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let service = Arc::new(SpendControlService::new());
+        service.clone().set_user_control(&user, &SpendLimits {
+            daily_max_cents: Some(10000),
+            ..Default::default()
+        }).await.expect("sets control");
+        let asa = asa_for(100);
+        assert!(service.clone().reserve(&asa, &card).await.is_ok());
+        assert_eq!(
+            SpendControlError::Conflict("test".into()),
+            service.clone().reserve(&asa, &card).await.expect_err("already counted")
+        );
+    }
+
+    #[test]
+    async fn test_follow_up_reserves_only_the_increment() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let service = Arc::new(SpendControlService::new());
+        service.clone().set_passthrough_card_control(&user, &card.public_id, &SpendLimits {
+            daily_max_cents: Some(10000),
+            hourly_transaction_limit: Some(1),
+            ..Default::default()
+        }).await.expect("sets control");
+        let mut asa = asa_for(6000);
+        service.clone().reserve(&asa, &card).await.expect("reserves").expect("has reservation");
+        // same token with a higher total, only the 2000 on top counts and it isn't another transaction
+        asa.amount = Some(8000);
+        let increment = service.clone().reserve(&asa, &card).await.expect("reserves increment").expect("has reservation");
+        assert_eq!(2000, increment.amount_cents);
+        assert_eq!(
+            SpendControlError::Conflict("test".into()),
+            service.clone().reserve(&asa, &card).await.expect_err("increment already counted")
+        );
+        // a lower total isn't new spend
+        asa.amount = Some(7000);
+        assert!(service.clone().reserve(&asa, &card).await.expect("reserves").is_none());
+    }
+
+    #[test]
+    async fn test_released_transaction_gives_back_spend() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let service = Arc::new(SpendControlService::new());
+        service.clone().set_user_control(&user, &SpendLimits {
+            daily_max_cents: Some(10000),
+            ..Default::default()
+        }).await.expect("sets control");
+        let asa = asa_for(8000);
+        let token = asa.token.clone().expect("has token");
+        service.clone().reserve(&asa, &card).await.expect("reserves").expect("has reservation");
+        assert!(service.clone().reserve(&asa_for(3000), &card).await.is_err());
+
+        // a redelivered reversal only gives the 3000 back once
+        service.clone().release_transaction(&token, "reversal", Some(3000)).await.expect("releases");
+        service.clone().release_transaction(&token, "reversal", Some(3000)).await.expect("releases again");
+        let usages = crate::spend_control::entity::SpendControlUsage::get_by_transaction_token(&token).await.expect("loads usage");
+        assert_eq!(5000, usages.iter().map(|usage| usage.amount_cents).sum::<i32>());
+        assert!(service.clone().reserve(&asa_for(5000), &card).await.is_ok());
+        assert!(service.clone().reserve(&asa_for(1), &card).await.is_err());
+
+        // the void gives back everything that's left
+        service.clone().release_transaction(&token, "void", None).await.expect("releases rest");
+        assert!(service.clone().reserve(&asa_for(5000), &card).await.is_ok());
+    }
+
+    #[test]
+    async fn test_set_control_upserts() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let card = create_passthrough_card(&user).await;
+        let service = Arc::new(SpendControlService::new());
+        let created = service.clone().set_user_control(&user, &SpendLimits {
+            daily_max_cents: Some(10000),
+            ..Default::default()
+        }).await.expect("sets control");
+        let updated = service.clone().set_user_control(&user, &SpendLimits {
+            weekly_max_cents: Some(20000),
+            ..Default::default()
+        }).await.expect("updates control");
+        assert_eq!(created.public_id, updated.public_id);
+        assert_eq!(None, updated.daily_max_cents);
+        assert_eq!(Some(20000), updated.weekly_max_cents);
+        service.clone().set_passthrough_card_control(&user, &card.public_id, &SpendLimits::default()).await.expect("sets card control");
+        let controls = service.clone().get_controls(&user).await.expect("gets controls");
+        assert_eq!(2, controls.len());
+        assert_eq!(
+            SpendControlError::NotFound("test".into()),
+            service.clone().set_passthrough_card_control(&user, &Uuid::new_v4(), &SpendLimits::default()).await.expect_err("unknown card")
+        );
+        assert_eq!(
+            SpendControlError::Invalid("test".into()),
+            service.clone().set_user_control(&user, &SpendLimits { daily_max_cents: Some(-1), ..Default::default() }).await.expect_err("negative")
+        );
+    }
+
+    #[test]
+    async fn test_falls_back_to_database_when_redis_unavailable() {
+        crate::test_helper::general::init();
+        let now = Utc::now().naive_utc();
+        let mut dao = MockSpendControlDaoTrait::new();
+        dao.expect_get_for_user()
+            .times(1)
+            .return_once(move |user_id| Ok(vec![SpendControl {
+                id: 1,
+                public_id: Uuid::new_v4(),
+                user_id,
+                passthrough_card_id: None,
+                per_transaction_max_cents: None,
+                daily_max_cents: Some(10000),
+                weekly_max_cents: None,
+                monthly_max_cents: None,
+                hourly_transaction_limit: None,
+                created_at: now,
+                updated_at: now,
+            }]));
+        dao.expect_get_usage_by_transaction_token()
+            .times(1)
+            .return_once(|_| Ok(vec![]));
+        dao.expect_insert_usage()
+            .times(1)
+            .return_once(move |usage| Ok(SpendControlUsage {
+                id: 7,
+                user_id: usage.user_id,
+                passthrough_card_id: usage.passthrough_card_id,
+                transaction_token: usage.transaction_token.clone(),
+                amount_cents: usage.amount_cents,
+                created_at: now,
+                updated_at: now,
+                reference: usage.reference.clone(),
+            }));
+        dao.expect_reserve_counters()
+            .times(1)
+            .return_once(|_| Err(DataError::Unexpected("redis unavailable".into())));
+        dao.expect_sum_usage_amount_since()
+            .withf(|_, _, excluding_id| *excluding_id == 7)
+            .times(1)
+            .return_once(|_, _, _| Ok(9000));
+        dao.expect_delete_usage()
+            .withf(|id| *id == 7)
+            .times(1)
+            .return_once(|_| Ok(()));
+        let service = Arc::new(SpendControlService::new_with_mocks(Arc::new(dao)));
+        let card = crate::test_helper::passthrough_card::create_mock_passthrough_card();
+        assert_eq!(
+            SpendControlError::Exceeded("test".into()),
+            service.clone().reserve(&asa_for(1001), &card).await.expect_err("over daily max")
+        );
+    }
+
+    #[test]
+    async fn test_counters_for_controls() {
+        let now = Utc::now().naive_utc();
+        let control = SpendControlModel {
+            id: 1,
+            public_id: Uuid::new_v4(),
+            user_id: 1,
+            passthrough_card_id: Some(2),
+            per_transaction_max_cents: Some(100),
+            daily_max_cents: Some(1000),
+            weekly_max_cents: None,
+            monthly_max_cents: None,
+            hourly_transaction_limit: Some(3),
+        };
+        let counters = counters_for(&vec![control], 500, now);
+        assert_eq!(2, counters.len());
+        assert_eq!(SpendWindow::Hourly, counters[0].window);
+        assert_eq!(1, counters[0].increment);
+        assert_eq!(3, counters[0].limit);
+        assert_eq!(SpendWindow::Daily, counters[1].window);
+        assert_eq!(500, counters[1].increment);
+        assert_eq!(1000, counters[1].limit);
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt;
+use std::io::Write;
+use diesel::backend::Backend;
+use diesel::deserialize::{FromSql, FromSqlRow};
+use diesel::expression::AsExpression;
+use diesel::pg::Pg;
+use diesel::serialize::{IsNull, Output, ToSql};
+use diesel::sql_types::Text;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Eq, PartialEq, Clone, AsExpression, FromSqlRow)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[diesel(sql_type = Text)]
+pub enum StandInStatus {
+    Pending,
+    Reconciled,
+    Failed
+}
+
+impl ToSql<Text, Pg> for StandInStatus {
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, Pg>) -> diesel::serialize::Result {
+        out.write_all(self.to_string().as_bytes())?;
+        Ok(IsNull::No)
+    }
+}
+
+impl FromSql<Text, Pg> for StandInStatus {
+    fn from_sql(bytes: <Pg as Backend>::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        match bytes.as_bytes() {
+            b"PENDING" => Ok(StandInStatus::Pending),
+            b"RECONCILED" => Ok(StandInStatus::Reconciled),
+            b"FAILED" => Ok(StandInStatus::Failed),
+            v => Err(format!("Unknown value for StandInStatus found").into()),
+        }
+    }
+}
+
+impl fmt::Display for StandInStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", match *self {
+            StandInStatus::Pending => "PENDING",
+            StandInStatus::Reconciled => "RECONCILED",
+            StandInStatus::Failed => "FAILED"
+        })
+    }
+}
@@ -0,0 +1,179 @@
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDateTime};
+use lazy_static::lazy_static;
+use uuid::Uuid;
+use crate::asa::response::AsaResponseResult;
+use crate::error::data_error::DataError;
+use crate::stand_in::constant::StandInStatus;
+use crate::stand_in::entity::{InsertableStandInTransaction, StandInTransaction};
+use crate::stand_in::model::StandInExposureResult;
+#[cfg(not(feature = "no-redis"))]
+use redis::Script;
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::services::{
+    RedisService,
+    RedisServiceTrait
+};
+#[cfg(not(feature = "no-redis"))]
+use crate::redis::key::Key;
+
+#[cfg(test)]
+use mockall::{automock, predicate::*};
+
+// a counter nobody has touched in this long is dropped and rebuilt from the database on the next stand in
+const EXPOSURE_TTL_SECONDS: i64 = 7 * 24 * 60 * 60;
+
+#[cfg(not(feature = "no-redis"))]
+lazy_static! {
+    // check and increment in one step, so concurrent stand ins for a user can't both squeeze under the threshold.
+    // ARGV holds the increment, the threshold and the ttl
+    static ref RESERVE_EXPOSURE_SCRIPT: Script = Script::new(r"
+        if redis.call('EXISTS', KEYS[1]) == 0 then
+            return -1
+        end
+        if tonumber(redis.call('GET', KEYS[1])) + tonumber(ARGV[1]) > tonumber(ARGV[2]) then
+            return 1
+        end
+        redis.call('INCRBY', KEYS[1], ARGV[1])
+        redis.call('EXPIRE', KEYS[1], ARGV[3])
+        return 0
+    ");
+    // an expired counter gets rebuilt from the database without this stand in, so it is left alone
+    static ref RELEASE_EXPOSURE_SCRIPT: Script = Script::new(r"
+        if redis.call('EXISTS', KEYS[1]) == 1 then
+            local remaining = tonumber(redis.call('DECRBY', KEYS[1], ARGV[1]))
+            if remaining < 0 then
+                redis.call('INCRBY', KEYS[1], -remaining)
+            end
+        end
+        return 0
+    ");
+}
+
+#[cfg_attr(test, automock)]
+#[async_trait]
+pub trait StandInDaoTrait {
+    async fn insert(self: Arc<Self>, stand_in: &InsertableStandInTransaction) -> Result<StandInTransaction, DataError>;
+    async fn get_by_public_id(self: Arc<Self>, public_id: &Uuid) -> Result<StandInTransaction, DataError>;
+    async fn get_by_status(self: Arc<Self>, status: &StandInStatus) -> Result<Vec<StandInTransaction>, DataError>;
+    async fn get_due(self: Arc<Self>, now: NaiveDateTime, max_attempts: i32) -> Result<Vec<StandInTransaction>, DataError>;
+    async fn claim(self: Arc<Self>, id: i32, now: NaiveDateTime, claimed_until: NaiveDateTime) -> Result<StandInTransaction, DataError>;
+    async fn update_status(self: Arc<Self>, id: i32, status: &StandInStatus, reconciled_result: Option<&str>) -> Result<StandInTransaction, DataError>;
+    async fn schedule_retry(self: Arc<Self>, id: i32, reconciled_result: Option<&str>, next_attempt_at: NaiveDateTime) -> Result<StandInTransaction, DataError>;
+    async fn sum_outstanding_approved_for_user(self: Arc<Self>, user_id: i32) -> Result<i64, DataError>;
+
+    async fn reserve_exposure(self: Arc<Self>, user_id: i32, amount_cents: i64, threshold_cents: i64) -> Result<StandInExposureResult, DataError>;
+    async fn seed_exposure(self: Arc<Self>, user_id: i32, value: i64) -> Result<(), DataError>;
+    async fn release_exposure(self: Arc<Self>, user_id: i32, amount_cents: i64) -> Result<(), DataError>;
+}
+
+pub struct StandInDao {
+    #[cfg(not(feature = "no-redis"))]
+    redis: Arc<RedisService>
+}
+
+impl StandInDao {
+    pub fn new() -> Self {
+        #[cfg(not(feature = "no-redis"))] {
+            Self {
+                redis: Arc::new(RedisService::new())
+            }
+        }
+        #[cfg(feature = "no-redis")] {
+            Self {}
+        }
+    }
+}
+
+#[async_trait]
+impl StandInDaoTrait for StandInDao {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn insert(self: Arc<Self>, stand_in: &InsertableStandInTransaction) -> Result<StandInTransaction, DataError> {
+        StandInTransaction::insert(stand_in).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_by_public_id(self: Arc<Self>, public_id: &Uuid) -> Result<StandInTransaction, DataError> {
+        StandInTransaction::get_by_public_id(public_id).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_by_status(self: Arc<Self>, status: &StandInStatus) -> Result<Vec<StandInTransaction>, DataError> {
+        StandInTransaction::get_by_status(status).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn get_due(self: Arc<Self>, now: NaiveDateTime, max_attempts: i32) -> Result<Vec<StandInTransaction>, DataError> {
+        StandInTransaction::get_due(now, max_attempts).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn claim(self: Arc<Self>, id: i32, now: NaiveDateTime, claimed_until: NaiveDateTime) -> Result<StandInTransaction, DataError> {
+        StandInTransaction::claim(id, now, claimed_until).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_status(self: Arc<Self>, id: i32, status: &StandInStatus, reconciled_result: Option<&str>) -> Result<StandInTransaction, DataError> {
+        StandInTransaction::update_status(id, status, reconciled_result).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn schedule_retry(self: Arc<Self>, id: i32, reconciled_result: Option<&str>, next_attempt_at: NaiveDateTime) -> Result<StandInTransaction, DataError> {
+        StandInTransaction::schedule_retry(id, reconciled_result, next_attempt_at).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn sum_outstanding_approved_for_user(self: Arc<Self>, user_id: i32) -> Result<i64, DataError> {
+        StandInTransaction::sum_outstanding_approved_for_user(user_id, &String::from(&AsaResponseResult::Approved)).await
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn reserve_exposure(self: Arc<Self>, user_id: i32, amount_cents: i64, threshold_cents: i64) -> Result<StandInExposureResult, DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            let result: i64 = self.redis.clone().invoke_script(
+                &RESERVE_EXPOSURE_SCRIPT,
+                &[Key::StandInExposureForUser(user_id)],
+                &[amount_cents, threshold_cents, EXPOSURE_TTL_SECONDS]
+            ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+            Ok(
+                match result {
+                    0 => StandInExposureResult::Reserved,
+                    -1 => StandInExposureResult::Missing,
+                    _ => StandInExposureResult::Exceeded
+                }
+            )
+        }
+        #[cfg(feature = "no-redis")] {
+            Err(DataError::Unexpected("redis disabled".into()))
+        }
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn seed_exposure(self: Arc<Self>, user_id: i32, value: i64) -> Result<(), DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            // a concurrent seed that got there first wins
+            self.redis.clone().set_primitive_if_absent(&Key::StandInExposureForUser(user_id), value, Duration::seconds(EXPOSURE_TTL_SECONDS)).await
+                .map_err(|e| DataError::Unexpected(e.into()))?;
+            Ok(())
+        }
+        #[cfg(feature = "no-redis")] {
+            Err(DataError::Unexpected("redis disabled".into()))
+        }
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn release_exposure(self: Arc<Self>, user_id: i32, amount_cents: i64) -> Result<(), DataError> {
+        #[cfg(not(feature = "no-redis"))] {
+            let _: i64 = self.redis.clone().invoke_script(
+                &RELEASE_EXPOSURE_SCRIPT,
+                &[Key::StandInExposureForUser(user_id)],
+                &[amount_cents]
+            ).await.map_err(|e| DataError::Unexpected(e.into()))?;
+            Ok(())
+        }
+        #[cfg(feature = "no-redis")] {
+            Err(DataError::Unexpected("redis disabled".into()))
+        }
+    }
+}
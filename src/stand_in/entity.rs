@@ -0,0 +1,151 @@
+use chrono::NaiveDateTime;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::error::data_error::DataError;
+use crate::schema::stand_in_transaction;
+use crate::stand_in::constant::StandInStatus;
+use crate::util::db;
+
+#[derive(Debug, Clone, Insertable, Serialize, Deserialize, PartialEq)]
+#[diesel(table_name = stand_in_transaction)]
+pub struct InsertableStandInTransaction {
+    pub public_id: Uuid,
+    pub card_token: String,
+    pub lithic_transaction_token: Option<String>,
+    pub amount_cents: i32,
+    pub asa_response_result: String,
+    pub reason: String,
+    pub body: String,
+    pub status: StandInStatus,
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Clone, Identifiable, Serialize, Deserialize, Queryable, Selectable)]
+#[diesel(table_name = stand_in_transaction)]
+pub struct StandInTransaction {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub card_token: String,
+    pub lithic_transaction_token: Option<String>,
+    pub amount_cents: i32,
+    pub asa_response_result: String,
+    pub reason: String,
+    pub body: String,
+    pub status: StandInStatus,
+    pub reconciled_result: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub user_id: Option<i32>,
+    pub attempts: i32,
+    pub next_attempt_at: NaiveDateTime,
+    pub claimed_until: Option<NaiveDateTime>,
+}
+
+impl StandInTransaction {
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn insert(stand_in: &InsertableStandInTransaction) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let stand_in = diesel::insert_into(stand_in_transaction::table)
+            .values(stand_in)
+            .get_result(&mut conn).await?;
+        Ok(stand_in)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_by_public_id(public_id: &Uuid) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let stand_in = stand_in_transaction::table
+            .filter(stand_in_transaction::public_id.eq(public_id))
+            .first::<StandInTransaction>(&mut conn).await?;
+        Ok(stand_in)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_by_status(status: &StandInStatus) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let stand_ins = stand_in_transaction::table
+            .filter(stand_in_transaction::status.eq(status))
+            .order(stand_in_transaction::id.asc())
+            .load::<StandInTransaction>(&mut conn).await?;
+        Ok(stand_ins)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn get_due(now: NaiveDateTime, max_attempts: i32) -> Result<Vec<Self>, DataError> {
+        let mut conn = db::connection().await?;
+        let stand_ins = stand_in_transaction::table
+            .filter(stand_in_transaction::status.eq_any(vec![StandInStatus::Pending, StandInStatus::Failed]))
+            .filter(stand_in_transaction::attempts.lt(max_attempts))
+            .filter(stand_in_transaction::next_attempt_at.le(now))
+            .filter(
+                stand_in_transaction::claimed_until.is_null()
+                    .or(stand_in_transaction::claimed_until.lt(now))
+            )
+            .order(stand_in_transaction::id.asc())
+            .load::<StandInTransaction>(&mut conn).await?;
+        Ok(stand_ins)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn claim(id: i32, now: NaiveDateTime, claimed_until: NaiveDateTime) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        // only one reconciler gets the row back, everyone else finds nothing to update
+        let stand_in = diesel::update(stand_in_transaction::table)
+            .filter(
+                stand_in_transaction::id.eq(id)
+                    .and(stand_in_transaction::status.ne(StandInStatus::Reconciled))
+                    .and(stand_in_transaction::next_attempt_at.le(now))
+                    .and(
+                        stand_in_transaction::claimed_until.is_null()
+                            .or(stand_in_transaction::claimed_until.lt(now))
+                    )
+            )
+            .set(stand_in_transaction::claimed_until.eq(claimed_until))
+            .get_result::<StandInTransaction>(&mut conn).await?;
+        Ok(stand_in)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_status(id: i32, status: &StandInStatus, reconciled_result: Option<&str>) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let stand_in = diesel::update(stand_in_transaction::table)
+            .filter(stand_in_transaction::id.eq(id))
+            .set((
+                stand_in_transaction::status.eq(status),
+                stand_in_transaction::reconciled_result.eq(reconciled_result),
+                stand_in_transaction::claimed_until.eq(None::<NaiveDateTime>)
+            ))
+            .get_result::<StandInTransaction>(&mut conn).await?;
+        Ok(stand_in)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn schedule_retry(id: i32, reconciled_result: Option<&str>, next_attempt_at: NaiveDateTime) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let stand_in = diesel::update(stand_in_transaction::table)
+            .filter(stand_in_transaction::id.eq(id))
+            .set((
+                stand_in_transaction::status.eq(StandInStatus::Failed),
+                stand_in_transaction::reconciled_result.eq(reconciled_result),
+                stand_in_transaction::attempts.eq(stand_in_transaction::attempts + 1),
+                stand_in_transaction::next_attempt_at.eq(next_attempt_at),
+                stand_in_transaction::claimed_until.eq(None::<NaiveDateTime>)
+            ))
+            .get_result::<StandInTransaction>(&mut conn).await?;
+        Ok(stand_in)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn sum_outstanding_approved_for_user(user_id: i32, approved: &str) -> Result<i64, DataError> {
+        let mut conn = db::connection().await?;
+        let total = stand_in_transaction::table
+            .filter(stand_in_transaction::user_id.eq(user_id))
+            .filter(stand_in_transaction::asa_response_result.eq(approved))
+            .filter(stand_in_transaction::status.ne(StandInStatus::Reconciled))
+            .select(diesel::dsl::sum(stand_in_transaction::amount_cents))
+            .first::<Option<i64>>(&mut conn).await?;
+        Ok(total.unwrap_or(0))
+    }
+}
@@ -0,0 +1,26 @@
+use crate::error::data_error::DataError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum StandInError {
+    #[error("Unable to record stand in decision")]
+    Record(#[source] Box<dyn std::error::Error + Send + Sync>),
+    #[error("Unexpected stand in error")]
+    Unexpected(#[source] Box<dyn std::error::Error + Send + Sync>)
+}
+
+impl From<DataError> for StandInError {
+    fn from(value: DataError) -> Self {
+        StandInError::Unexpected(Box::new(value))
+    }
+}
+
+#[cfg(test)]
+impl PartialEq for StandInError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (StandInError::Record(_), StandInError::Record(_))
+            | (StandInError::Unexpected(_), StandInError::Unexpected(_)) => true,
+            _ => false
+        }
+    }
+}
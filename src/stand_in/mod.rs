@@ -0,0 +1,7 @@
+pub mod service;
+pub mod constant;
+pub mod error;
+pub mod model;
+mod entity;
+mod dao;
+mod tests;
@@ -0,0 +1,61 @@
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+use crate::asa::response::AsaResponseResult;
+use crate::stand_in::constant::StandInStatus;
+use crate::stand_in::entity::StandInTransaction;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StandInTransactionModel {
+    pub id: i32,
+    pub public_id: Uuid,
+    pub card_token: String,
+    pub lithic_transaction_token: Option<String>,
+    pub amount_cents: i32,
+    pub asa_response_result: AsaResponseResult,
+    pub reason: String,
+    pub body: String,
+    pub status: StandInStatus,
+    pub reconciled_result: Option<AsaResponseResult>,
+    pub created_at: NaiveDateTime,
+    pub user_id: Option<i32>,
+    pub attempts: i32,
+}
+
+impl From<StandInTransaction> for StandInTransactionModel {
+    fn from(value: StandInTransaction) -> Self {
+        StandInTransactionModel {
+            id: value.id,
+            public_id: value.public_id,
+            card_token: value.card_token,
+            lithic_transaction_token: value.lithic_transaction_token,
+            amount_cents: value.amount_cents,
+            asa_response_result: AsaResponseResult::from(value.asa_response_result),
+            reason: value.reason,
+            body: value.body,
+            status: value.status,
+            reconciled_result: value.reconciled_result.map(AsaResponseResult::from),
+            created_at: value.created_at,
+            user_id: value.user_id,
+            attempts: value.attempts,
+        }
+    }
+}
+
+impl StandInTransactionModel {
+    pub fn collection_key(&self) -> Uuid {
+        // one per reconcile attempt, an attempt that errored after authorizing has already cancelled under its key
+        Uuid::new_v5(
+            &Uuid::NAMESPACE_OID,
+            format!("{}:{}", self.public_id, self.attempts).as_bytes()
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StandInExposureResult {
+    Reserved,
+    // the amount would push the user's outstanding stand in approvals over the threshold
+    Exceeded,
+    // not in redis yet and needs seeding from the database
+    Missing
+}
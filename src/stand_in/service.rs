@@ -0,0 +1,269 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::Path;
+use std::sync::Arc;
+use async_trait::async_trait;
+use chrono::Duration;
+use uuid::Uuid;
+use crate::asa::request::AsaRequest;
+use crate::asa::response::AsaResponseResult;
+use crate::configuration::stand_in::{StandInConfiguration, StandInPolicy};
+use crate::error::data_error::DataError;
+use crate::stand_in::constant::StandInStatus;
+use crate::stand_in::dao::{StandInDao, StandInDaoTrait};
+use crate::stand_in::entity::InsertableStandInTransaction;
+use crate::stand_in::error::StandInError;
+use crate::stand_in::model::{StandInExposureResult, StandInTransactionModel};
+
+// a claim older than this belongs to a reconciler that died, the next run takes it over
+const RECONCILE_CLAIM_LEASE_SECONDS: i64 = 300;
+const RECONCILE_BACKOFF_SECONDS: i64 = 60;
+const MAX_RECONCILE_BACKOFF_SECONDS: i64 = 6 * 60 * 60;
+// past this a failed stand in is left for someone to collect by hand
+pub const MAX_RECONCILE_ATTEMPTS: i32 = 10;
+
+/// How long a stand in that has failed `attempts` times waits before the reconciler tries it again.
+pub fn reconcile_backoff(attempts: i32) -> Duration {
+    let exponent = attempts.clamp(0, 16) as u32;
+    Duration::seconds((RECONCILE_BACKOFF_SECONDS * 2_i64.pow(exponent)).min(MAX_RECONCILE_BACKOFF_SECONDS))
+}
+
+#[async_trait(?Send)]
+pub trait StandInServiceTrait {
+    async fn stand_in(self: Arc<Self>, request: &AsaRequest, card_token: &str, user_id: Option<i32>, reason: &str) -> AsaResponseResult;
+    async fn import_journal(self: Arc<Self>) -> Result<usize, StandInError>;
+    async fn get_pending(self: Arc<Self>) -> Result<Vec<StandInTransactionModel>, StandInError>;
+    async fn claim_due(self: Arc<Self>) -> Result<Vec<StandInTransactionModel>, StandInError>;
+    async fn resolve(self: Arc<Self>, stand_in: &StandInTransactionModel, status: &StandInStatus, reconciled_result: Option<&AsaResponseResult>) -> Result<StandInTransactionModel, StandInError>;
+}
+
+pub struct StandInService {
+    dao: Arc<dyn StandInDaoTrait>,
+    configuration: StandInConfiguration,
+}
+
+impl StandInService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
+    pub fn new(configuration: &StandInConfiguration) -> Self {
+        Self {
+            dao: Arc::new(StandInDao::new()),
+            configuration: configuration.clone(),
+        }
+    }
+
+}
+
+#[cfg(test)]
+impl StandInService {
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
+    pub fn new_with_mocks(
+        dao: Arc<dyn StandInDaoTrait>,
+        configuration: &StandInConfiguration
+    ) -> Self {
+        Self {
+            dao,
+            configuration: configuration.clone(),
+        }
+    }
+}
+
+impl StandInService {
+    pub async fn decide(self: Arc<Self>, user_id: Option<i32>, amount_cents: i32, cash_amount_cents: Option<i32>) -> AsaResponseResult {
+        let decline = AsaResponseResult::from(self.configuration.decline_result.as_str());
+        // every cash policy needs a card that can take cash, and there's no wallet to check while standing in
+        if let Some(cash_amount_cents) = cash_amount_cents.filter(|cash| *cash > 0) {
+            tracing::warn!("Declining stand in with {} cents of cash", cash_amount_cents);
+            return decline
+        }
+        match self.configuration.policy {
+            StandInPolicy::Decline => decline,
+            StandInPolicy::ApproveUnderThreshold => {
+                let user_id = match user_id {
+                    Some(user_id) => user_id,
+                    None => {
+                        tracing::warn!("No user to hold stand in exposure against, declining {} cents", amount_cents);
+                        return decline
+                    }
+                };
+                if amount_cents <= 0 {
+                    return decline
+                }
+                match self.clone().reserve_exposure(user_id, amount_cents).await {
+                    Ok(true) => AsaResponseResult::Approved,
+                    Ok(false) => {
+                        tracing::warn!("Stand in exposure for user_id={} leaves no room for {} cents", user_id, amount_cents);
+                        decline
+                    },
+                    Err(e) => {
+                        tracing::error!("Unable to hold stand in exposure for user_id={}, declining error={:?}", user_id, &e);
+                        decline
+                    }
+                }
+            }
+        }
+    }
+
+    async fn reserve_exposure(self: Arc<Self>, user_id: i32, amount_cents: i32) -> Result<bool, StandInError> {
+        let threshold_cents = self.configuration.approve_threshold_cents as i64;
+        match self.dao.clone().reserve_exposure(user_id, amount_cents as i64, threshold_cents).await? {
+            StandInExposureResult::Reserved => return Ok(true),
+            StandInExposureResult::Exceeded => return Ok(false),
+            StandInExposureResult::Missing => {}
+        }
+        // the database is often why we're standing in, without it the count starts over from zero
+        let outstanding = match self.dao.clone().sum_outstanding_approved_for_user(user_id).await {
+            Ok(outstanding) => outstanding,
+            Err(e) => {
+                tracing::warn!("Unable to rebuild stand in exposure for user_id={}, starting from zero error={:?}", user_id, &e);
+                0
+            }
+        };
+        self.dao.clone().seed_exposure(user_id, outstanding).await?;
+        Ok(self.dao.clone().reserve_exposure(user_id, amount_cents as i64, threshold_cents).await? == StandInExposureResult::Reserved)
+    }
+
+    pub async fn release_exposure(self: Arc<Self>, user_id: i32, amount_cents: i32) {
+        if let Err(e) = self.dao.clone().release_exposure(user_id, amount_cents as i64).await {
+            tracing::error!("Unable to release {} cents of stand in exposure for user_id={} error={:?}", amount_cents, user_id, &e);
+        }
+    }
+
+    fn importing_journal_path(&self) -> String {
+        format!("{}.importing", &self.configuration.journal_path)
+    }
+
+    fn append_to_journal(&self, stand_in: &InsertableStandInTransaction) -> Result<(), StandInError> {
+        let line = serde_json::to_string(stand_in).map_err(|e| StandInError::Record(e.into()))?;
+        let mut journal = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.configuration.journal_path)
+            .map_err(|e| StandInError::Record(e.into()))?;
+        // one write per line so concurrent appends never interleave
+        journal.write_all(format!("{}\n", line).as_bytes()).map_err(|e| StandInError::Record(e.into()))?;
+        journal.sync_data().map_err(|e| StandInError::Record(e.into()))
+    }
+
+    pub async fn record(self: Arc<Self>, stand_in: &InsertableStandInTransaction) -> Result<(), StandInError> {
+        match self.dao.clone().insert(stand_in).await {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                tracing::warn!("Unable to record stand in={} in the database, journaling locally error={:?}", &stand_in.public_id, &e);
+                self.append_to_journal(stand_in)
+            }
+        }
+    }
+}
+
+#[async_trait(?Send)]
+impl StandInServiceTrait for StandInService {
+    #[tracing::instrument(skip(self, request))]
+    async fn stand_in(self: Arc<Self>, request: &AsaRequest, card_token: &str, user_id: Option<i32>, reason: &str) -> AsaResponseResult {
+        let amount_cents = request.amount.unwrap_or(0);
+        let decision = self.clone().decide(user_id, amount_cents, request.cash_amount).await;
+        tracing::warn!("Standing in for card={} with result={:?} policy={:?}", card_token, &decision, &self.configuration.policy);
+        let stand_in = InsertableStandInTransaction {
+            public_id: Uuid::new_v4(),
+            card_token: card_token.to_string(),
+            lithic_transaction_token: request.token.clone(),
+            amount_cents,
+            asa_response_result: String::from(&decision),
+            reason: reason.to_string(),
+            body: serde_json::to_string(request).unwrap_or_default(),
+            status: StandInStatus::Pending,
+            user_id,
+        };
+        match self.clone().record(&stand_in).await {
+            Ok(()) => decision,
+            Err(e) => {
+                tracing::error!("Unable to record stand in={} anywhere error={:?}", &stand_in.public_id, &e);
+                // an approval nobody can reconcile is money we never collect, so it becomes a decline
+                if let (AsaResponseResult::Approved, Some(user_id)) = (&decision, user_id) {
+                    self.release_exposure(user_id, amount_cents).await;
+                    return AsaResponseResult::from(self.configuration.decline_result.as_str())
+                }
+                decision
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn import_journal(self: Arc<Self>) -> Result<usize, StandInError> {
+        // the journal is moved aside first so stand ins made while importing start a fresh one
+        let importing_path = self.importing_journal_path();
+        if !Path::new(&importing_path).exists() {
+            match fs::rename(&self.configuration.journal_path, &importing_path) {
+                Ok(_) => {},
+                Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+                Err(e) => return Err(StandInError::Unexpected(e.into()))
+            }
+        }
+        let journal = fs::read_to_string(&importing_path).map_err(|e| StandInError::Unexpected(e.into()))?;
+        let mut imported = 0;
+        for line in journal.lines().filter(|line| !line.trim().is_empty()) {
+            let stand_in: InsertableStandInTransaction = match serde_json::from_str(line) {
+                Ok(stand_in) => stand_in,
+                Err(e) => {
+                    tracing::error!("Skipping unreadable stand in journal line error={:?}", &e);
+                    continue;
+                }
+            };
+            match self.dao.clone().insert(&stand_in).await {
+                Ok(_) => imported += 1,
+                // already made it in on an earlier import that didn't finish
+                Err(DataError::Conflict(_)) => {},
+                // the importing journal stays where it is and is picked back up next run
+                Err(e) => return Err(e.into())
+            }
+        }
+        fs::remove_file(&importing_path).map_err(|e| StandInError::Unexpected(e.into()))?;
+        tracing::info!("Imported {} stand ins from the local journal", imported);
+        Ok(imported)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn get_pending(self: Arc<Self>) -> Result<Vec<StandInTransactionModel>, StandInError> {
+        Ok(
+            self.dao.clone().get_by_status(&StandInStatus::Pending).await?
+                .into_iter()
+                .map(StandInTransactionModel::from)
+                .collect()
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn claim_due(self: Arc<Self>) -> Result<Vec<StandInTransactionModel>, StandInError> {
+        let now = chrono::Utc::now().naive_utc();
+        let claimed_until = now + Duration::seconds(RECONCILE_CLAIM_LEASE_SECONDS);
+        let mut claimed = vec![];
+        for stand_in in self.dao.clone().get_due(now, MAX_RECONCILE_ATTEMPTS).await? {
+            match self.dao.clone().claim(stand_in.id, now, claimed_until).await {
+                Ok(stand_in) => claimed.push(StandInTransactionModel::from(stand_in)),
+                // another reconciler got to it first
+                Err(DataError::NotFound(_)) => {},
+                Err(e) => return Err(e.into())
+            }
+        }
+        Ok(claimed)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn resolve(self: Arc<Self>, stand_in: &StandInTransactionModel, status: &StandInStatus, reconciled_result: Option<&AsaResponseResult>) -> Result<StandInTransactionModel, StandInError> {
+        let reconciled_result = reconciled_result.map(String::from);
+        let resolved = match status {
+            StandInStatus::Failed => {
+                if stand_in.attempts + 1 >= MAX_RECONCILE_ATTEMPTS {
+                    tracing::error!("Giving up on stand in={} after {} attempts", &stand_in.public_id, stand_in.attempts + 1);
+                }
+                let next_attempt_at = chrono::Utc::now().naive_utc() + reconcile_backoff(stand_in.attempts);
+                self.dao.clone().schedule_retry(stand_in.id, reconciled_result.as_deref(), next_attempt_at).await?
+            },
+            status => self.dao.clone().update_status(stand_in.id, status, reconciled_result.as_deref()).await?
+        };
+        // uncollected approvals keep counting against the user, the rebuild from the database counts them too
+        if let (StandInStatus::Reconciled, AsaResponseResult::Approved, Some(user_id)) = (status, &stand_in.asa_response_result, stand_in.user_id) {
+            self.release_exposure(user_id, stand_in.amount_cents).await;
+        }
+        Ok(resolved.into())
+    }
+}
@@ -0,0 +1,197 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use actix_web::test;
+    use uuid::Uuid;
+    use crate::asa::request::create_example_asa;
+    use crate::asa::response::AsaResponseResult;
+    use crate::configuration::stand_in::{StandInConfiguration, StandInPolicy};
+    use crate::error::data_error::DataError;
+    use crate::stand_in::constant::StandInStatus;
+    use crate::stand_in::dao::{MockStandInDaoTrait, StandInDao, StandInDaoTrait};
+    use crate::stand_in::model::StandInExposureResult;
+    use crate::stand_in::service::{reconcile_backoff, StandInService, StandInServiceTrait};
+    use crate::test_helper::user::create_user;
+
+    fn stand_in_configuration(policy: StandInPolicy) -> StandInConfiguration {
+        StandInConfiguration {
+            policy,
+            decline_result: "ACCOUNT_INACTIVE".to_string(),
+            approve_threshold_cents: 5000,
+            journal_path: format!("{}/stand_in_journal_{}.jsonl", std::env::temp_dir().display(), Uuid::new_v4()),
+            reconcile_interval_seconds: 60,
+        }
+    }
+
+    #[test]
+    async fn test_decline_policy_declines() {
+        crate::test_helper::general::init();
+        let service = Arc::new(StandInService::new(&stand_in_configuration(StandInPolicy::Decline)));
+        let user = create_user().await;
+        assert_eq!(AsaResponseResult::AccountInactive, service.clone().decide(Some(user.id), 100, None).await);
+    }
+
+    #[test]
+    async fn test_approve_under_threshold_tracks_exposure() {
+        crate::test_helper::general::init();
+        let service = Arc::new(StandInService::new(&stand_in_configuration(StandInPolicy::ApproveUnderThreshold)));
+        let user = create_user().await;
+        assert_eq!(AsaResponseResult::Approved, service.clone().decide(Some(user.id), 3000, None).await);
+        // 3000 already outstanding, another 3000 puts the user over the 5000 threshold
+        assert_eq!(AsaResponseResult::AccountInactive, service.clone().decide(Some(user.id), 3000, None).await);
+        assert_eq!(AsaResponseResult::Approved, service.clone().decide(Some(user.id), 2000, None).await);
+        service.clone().release_exposure(user.id, 3000).await;
+        assert_eq!(AsaResponseResult::Approved, service.clone().decide(Some(user.id), 3000, None).await);
+    }
+
+    #[test]
+    async fn test_exposure_is_shared_across_instances() {
+        crate::test_helper::general::init();
+        let configuration = stand_in_configuration(StandInPolicy::ApproveUnderThreshold);
+        let service = Arc::new(StandInService::new(&configuration));
+        let other_instance = Arc::new(StandInService::new(&configuration));
+        let user = create_user().await;
+        assert_eq!(AsaResponseResult::Approved, service.clone().decide(Some(user.id), 3000, None).await);
+        assert_eq!(AsaResponseResult::AccountInactive, other_instance.clone().decide(Some(user.id), 3000, None).await);
+    }
+
+    #[test]
+    async fn test_approve_under_threshold_declines_over_threshold() {
+        crate::test_helper::general::init();
+        let service = Arc::new(StandInService::new(&stand_in_configuration(StandInPolicy::ApproveUnderThreshold)));
+        let user = create_user().await;
+        assert_eq!(AsaResponseResult::AccountInactive, service.clone().decide(Some(user.id), 5001, None).await);
+        assert_eq!(AsaResponseResult::AccountInactive, service.clone().decide(Some(user.id), 0, None).await);
+        // nobody to hold the exposure against
+        assert_eq!(AsaResponseResult::AccountInactive, service.clone().decide(None, 1000, None).await);
+    }
+
+    #[test]
+    async fn test_approve_under_threshold_declines_cash() {
+        crate::test_helper::general::init();
+        let service = Arc::new(StandInService::new(&stand_in_configuration(StandInPolicy::ApproveUnderThreshold)));
+        let user = create_user().await;
+        // no wallet to route the cash to a card that takes it
+        assert_eq!(AsaResponseResult::AccountInactive, service.clone().decide(Some(user.id), 1000, Some(500)).await);
+        assert_eq!(AsaResponseResult::Approved, service.clone().decide(Some(user.id), 1000, Some(0)).await);
+    }
+
+    #[test]
+    async fn test_stand_in_records_pending_and_resolves() {
+        crate::test_helper::general::init();
+        let service = Arc::new(StandInService::new(&stand_in_configuration(StandInPolicy::ApproveUnderThreshold)));
+        let user = create_user().await;
+        let card_token = Uuid::new_v4().to_string();
+        let asa = create_example_asa(1000, "7184".to_string());
+        let result = service.clone().stand_in(&asa, &card_token, Some(user.id), "database unavailable").await;
+        assert_eq!(AsaResponseResult::Approved, result);
+
+        let pending = service.clone().get_pending().await.expect("gets pending");
+        let stand_in = pending.into_iter().find(|stand_in| stand_in.card_token == card_token).expect("records stand in");
+        assert_eq!(StandInStatus::Pending, stand_in.status);
+        assert_eq!(AsaResponseResult::Approved, stand_in.asa_response_result);
+        assert_eq!(1000, stand_in.amount_cents);
+        assert_eq!(asa.token, stand_in.lithic_transaction_token);
+        assert_eq!("database unavailable", &stand_in.reason);
+        assert_eq!(Some(user.id), stand_in.user_id);
+
+        let resolved = service.clone().resolve(&stand_in, &StandInStatus::Reconciled, Some(&AsaResponseResult::Approved)).await.expect("resolves");
+        assert_eq!(StandInStatus::Reconciled, resolved.status);
+        assert_eq!(Some(AsaResponseResult::Approved), resolved.reconciled_result);
+        let pending = service.clone().get_pending().await.expect("gets pending");
+        assert!(pending.iter().all(|stand_in| stand_in.card_token != card_token));
+        // resolving gives the exposure back
+        assert_eq!(AsaResponseResult::Approved, service.clone().decide(Some(user.id), 5000, None).await);
+    }
+
+    #[test]
+    async fn test_claimed_stand_in_is_leased_and_failures_back_off() {
+        crate::test_helper::general::init();
+        let configuration = stand_in_configuration(StandInPolicy::ApproveUnderThreshold);
+        let service = Arc::new(StandInService::new(&configuration));
+        let other_instance = Arc::new(StandInService::new(&configuration));
+        let user = create_user().await;
+        let card_token = Uuid::new_v4().to_string();
+        let asa = create_example_asa(1000, "7184".to_string());
+        service.clone().stand_in(&asa, &card_token, Some(user.id), "database unavailable").await;
+
+        let claimed = service.clone().claim_due().await.expect("claims");
+        let stand_in = claimed.into_iter().find(|stand_in| stand_in.card_token == card_token).expect("claims stand in");
+        // leased to the first reconciler
+        let claimed = other_instance.clone().claim_due().await.expect("claims");
+        assert!(claimed.iter().all(|stand_in| stand_in.card_token != card_token));
+
+        let failed = service.clone().resolve(&stand_in, &StandInStatus::Failed, None).await.expect("resolves");
+        assert_eq!(StandInStatus::Failed, failed.status);
+        assert_eq!(1, failed.attempts);
+        // not due again until its backoff runs out
+        let claimed = other_instance.clone().claim_due().await.expect("claims");
+        assert!(claimed.iter().all(|stand_in| stand_in.card_token != card_token));
+        // still uncollected, so the exposure is still held
+        assert_eq!(AsaResponseResult::AccountInactive, service.clone().decide(Some(user.id), 5000, None).await);
+    }
+
+    #[test]
+    async fn test_reconcile_backoff_grows_and_caps() {
+        assert_eq!(chrono::Duration::seconds(60), reconcile_backoff(0));
+        assert_eq!(chrono::Duration::seconds(120), reconcile_backoff(1));
+        assert_eq!(chrono::Duration::seconds(480), reconcile_backoff(3));
+        assert_eq!(chrono::Duration::hours(6), reconcile_backoff(30));
+    }
+
+    #[test]
+    async fn test_stand_in_journals_when_database_unavailable() {
+        crate::test_helper::general::init();
+        let configuration = stand_in_configuration(StandInPolicy::ApproveUnderThreshold);
+        let mut dao = MockStandInDaoTrait::new();
+        dao.expect_reserve_exposure()
+            .times(1)
+            .return_once(|_, _, _| Ok(StandInExposureResult::Reserved));
+        dao.expect_insert()
+            .times(1)
+            .return_once(|_| Err(DataError::Unexpected("database unavailable".into())));
+        let service = Arc::new(StandInService::new_with_mocks(Arc::new(dao), &configuration));
+        let user = create_user().await;
+        let card_token = Uuid::new_v4().to_string();
+        let asa = create_example_asa(1000, "7184".to_string());
+        let result = service.clone().stand_in(&asa, &card_token, Some(user.id), "database unavailable").await;
+        assert_eq!(AsaResponseResult::Approved, result);
+
+        let importing_service = Arc::new(StandInService::new(&configuration));
+        let imported = importing_service.clone().import_journal().await.expect("imports journal");
+        assert_eq!(1, imported);
+        assert!(!std::path::Path::new(&configuration.journal_path).exists());
+        let pending = importing_service.clone().get_pending().await.expect("gets pending");
+        let stand_in = pending.into_iter().find(|stand_in| stand_in.card_token == card_token).expect("imports stand in");
+        assert_eq!(1000, stand_in.amount_cents);
+        assert_eq!(Some(user.id), stand_in.user_id);
+        // importing again is a no-op
+        assert_eq!(0, importing_service.clone().import_journal().await.expect("imports journal"));
+        let found = Arc::new(StandInDao::new()).get_by_public_id(&stand_in.public_id).await.expect("finds stand in");
+        assert_eq!(card_token, found.card_token);
+    }
+
+    #[test]
+    async fn test_stand_in_declines_when_approval_cannot_be_recorded() {
+        crate::test_helper::general::init();
+        let mut configuration = stand_in_configuration(StandInPolicy::ApproveUnderThreshold);
+        configuration.journal_path = format!("{}/missing_{}/stand_in_journal.jsonl", std::env::temp_dir().display(), Uuid::new_v4());
+        let mut dao = MockStandInDaoTrait::new();
+        dao.expect_reserve_exposure()
+            .times(1)
+            .return_once(|_, _, _| Ok(StandInExposureResult::Reserved));
+        dao.expect_insert()
+            .times(1)
+            .return_once(|_| Err(DataError::Unexpected("database unavailable".into())));
+        // the approval is given back
+        dao.expect_release_exposure()
+            .times(1)
+            .withf(|user_id, amount_cents| *user_id == 1 && *amount_cents == 1000)
+            .return_once(|_, _| Ok(()));
+        let service = Arc::new(StandInService::new_with_mocks(Arc::new(dao), &configuration));
+        let card_token = Uuid::new_v4().to_string();
+        let asa = create_example_asa(1000, "7184".to_string());
+        let result = service.clone().stand_in(&asa, &card_token, Some(1), "database unavailable").await;
+        assert_eq!(AsaResponseResult::AccountInactive, result);
+    }
+}
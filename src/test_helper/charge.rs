@@ -1,11 +1,13 @@
+use std::time::Duration;
 use chrono::Utc;
 use crate::common::model::TransactionMetadata;
-use crate::charge::constant::ChargeStatus;
+use crate::charge::constant::{CaptureStatus, ChargeStatus};
 use crate::charge::model::{
     WalletCardChargeModel,
     PassthroughCardChargeModel,
     RegisteredTransactionModel as RegisteredTransaction,
-    SuccessfulEndToEndChargeModel
+    SuccessfulEndToEndChargeModel,
+    ChargeBudget
 };
 
 pub fn create_mock_registered_transaction(
@@ -17,7 +19,16 @@ pub fn create_mock_registered_transaction(
         transaction_id: Default::default(),
         memo: metadata.memo.clone(),
         amount_cents: metadata.amount_cents,
-        mcc: metadata.mcc.clone()
+        mcc: metadata.mcc.clone(),
+        lithic_transaction_token: None,
+        asa_response_result: None,
+        currency: metadata.currency.clone(),
+        merchant_amount_cents: metadata.merchant_amount_cents,
+        merchant_currency: metadata.merchant_currency.clone(),
+        conversion_rate: metadata.conversion_rate,
+        charge_currency: metadata.currency.clone(),
+        requested_amount_cents: None,
+        cash_amount_cents: metadata.cash_amount_cents
     }
 }
 
@@ -25,10 +36,27 @@ pub fn default_transaction_metadata() -> TransactionMetadata {
     TransactionMetadata {
         amount_cents: 0,
         memo: "".to_string(),
-        mcc: "7184".to_string()
+        mcc: "7184".to_string(),
+        currency: "USD".to_string(),
+        merchant_amount_cents: None,
+        merchant_currency: None,
+        conversion_rate: None,
+        partial_approval_capable: false,
+        avs_address: None,
+        avs_zipcode: None,
+        cash_amount_cents: None
     }
 }
 
+pub fn default_charge_budget() -> ChargeBudget {
+    ChargeBudget::new(
+        Duration::from_secs(30),
+        Duration::from_millis(500),
+        Duration::from_secs(10),
+        Duration::from_secs(1)
+    )
+}
+
 pub fn create_mock_failed_wallet_charge() -> WalletCardChargeModel {
     WalletCardChargeModel {
         id: 1,
@@ -39,7 +67,11 @@ pub fn create_mock_failed_wallet_charge() -> WalletCardChargeModel {
         status: ChargeStatus::Fail,
         is_success: None,
         created_at: Utc::now().naive_utc(),
-        rule_id: None
+        rule_id: None,
+        refusal_reason: None,
+        refusal_reason_code: None,
+        capture_status: None,
+        captured_amount_cents: None,
     }
 }
 
@@ -54,6 +86,10 @@ pub fn create_mock_success_wallet_charge() -> WalletCardChargeModel {
         is_success: Some(true),
         created_at: Utc::now().naive_utc(),
         rule_id: None,
+        refusal_reason: None,
+        refusal_reason_code: None,
+        capture_status: Some(CaptureStatus::Authorized),
+        captured_amount_cents: None,
     }
 }
 
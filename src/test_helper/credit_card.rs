@@ -14,6 +14,7 @@ pub fn create_mock_credit_card(
         credit_card_type_id: 1,
         credit_card_issuer_id: 1,
         card_image_url: "".to_string(),
+        rewards_program_id: None,
     }
 }
 
@@ -30,6 +31,7 @@ pub fn create_mock_credit_card_with_args(
         credit_card_type_id: credit_card_type_id,
         credit_card_issuer_id: credit_card_issuer_id,
         card_image_url: "".to_string(),
+        rewards_program_id: None,
     }
 }
 
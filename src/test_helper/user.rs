@@ -16,7 +16,9 @@ pub fn create_mock_user() -> User {
     User {
         id: 1,
         public_id: Default::default(),
-        footprint_vault_id: USER_FOOTPRINT_VAULT_ID.to_string()
+        footprint_vault_id: USER_FOOTPRINT_VAULT_ID.to_string(),
+        split_tender_enabled: false,
+        charge_in_merchant_currency: false
     }
 }
 
@@ -23,6 +23,7 @@ pub fn create_mock_wallet() -> Wallet {
         credit_card_id: 0,
         wallet_card_attempt_id: 0,
         status: WalletStatus::Active,
+        cash_advance_enabled: false,
     }
 }
 
@@ -36,7 +37,11 @@ pub fn create_mock_wallet_with_rule() -> WalletModelWithRule {
         credit_card_id: 0,
         wallet_card_attempt_id: 0,
         status: WalletStatus::Active,
+        cash_advance_enabled: false,
         rule_id: Some(1),
+        reward_amount: 0,
+        matched_rule_ids: vec![1],
+        health: None,
     }
 }
 
@@ -54,6 +59,7 @@ pub fn create_mock_wallet_with_args(
         credit_card_id: credit_card_id,
         wallet_card_attempt_id: 0,
         status: WalletStatus::Active,
+        cash_advance_enabled: false,
     }
 }
 
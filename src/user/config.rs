@@ -7,6 +7,8 @@ pub fn config(cfg: &mut web::ServiceConfig) -> () {
         .service(
             web::scope("")
                 .wrap(crate::middleware::auth::Auth)
+                .service(controller::update_split_tender)
+                .service(controller::update_merchant_currency)
         );
 
 }
@@ -1,7 +1,8 @@
-use actix_web::{web, get, post, HttpResponse, services};
+use actix_web::{web, get, post, put, HttpResponse, services};
 use crate::auth::entity::Claims;
 use crate::middleware::services::Services;
-use crate::user::request::CreateUserRequest;
+use crate::user::model::UserModel as User;
+use crate::user::request::{CreateUserRequest, UpdateMerchantCurrencyRequest, UpdateSplitTenderRequest};
 use crate::user::response::UserResponse;
 use crate::user::service::UserServiceTrait;
 use super::error::UserError;
@@ -24,4 +25,38 @@ async fn create(
     Ok(HttpResponse::Ok().json(
         UserResponse::from(&user)
     ))
-}
\ No newline at end of file
+}
+
+#[put("/split-tender/")]
+async fn update_split_tender(
+    user: web::ReqData<User>,
+    request: web::Json<UpdateSplitTenderRequest>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, UserError> {
+    let user = user.into_inner();
+    let request = request.into_inner();
+    let user = services.user_service.clone().set_split_tender_enabled(
+        &user,
+        request.enabled
+    ).await?;
+    Ok(HttpResponse::Ok().json(
+        UserResponse::from(&user)
+    ))
+}
+
+#[put("/merchant-currency/")]
+async fn update_merchant_currency(
+    user: web::ReqData<User>,
+    request: web::Json<UpdateMerchantCurrencyRequest>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, UserError> {
+    let user = user.into_inner();
+    let request = request.into_inner();
+    let user = services.user_service.clone().set_charge_in_merchant_currency(
+        &user,
+        request.enabled
+    ).await?;
+    Ok(HttpResponse::Ok().json(
+        UserResponse::from(&user)
+    ))
+}
@@ -28,6 +28,8 @@ pub trait UserDaoTrait {
     async fn find_by_auth0_id(&self, auth0_id: &str) -> Result<User, DataError>;
     async fn create<'a>(&self, user: &UserMessage<'a>) -> Result<User, DataError>;
     async fn update<'a>(&self, id: &Uuid, user: &UserMessage<'a>) -> Result<User, DataError>;
+    async fn update_split_tender_enabled(&self, id: i32, enabled: bool) -> Result<User, DataError>;
+    async fn update_charge_in_merchant_currency(&self, id: i32, enabled: bool) -> Result<User, DataError>;
 }
 
 pub struct UserDao {
@@ -93,4 +95,24 @@ impl UserDaoTrait for UserDao {
     async fn update<'a>(&self, id: &Uuid, user: &UserMessage<'a>) -> Result<User, DataError> {
         User::update(id, user).await
     }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_split_tender_enabled(&self, id: i32, enabled: bool) -> Result<User, DataError> {
+        let user = User::update_split_tender_enabled(id, enabled).await;
+        #[cfg(not(feature = "no-redis"))] {
+            tracing::info!("Expiring user in redis for user_id={}", id);
+            self.redis.clone().expire_now::<_>(&Key::User(id)).await;
+        }
+        user
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_charge_in_merchant_currency(&self, id: i32, enabled: bool) -> Result<User, DataError> {
+        let user = User::update_charge_in_merchant_currency(id, enabled).await;
+        #[cfg(not(feature = "no-redis"))] {
+            tracing::info!("Expiring user in redis for user_id={}", id);
+            self.redis.clone().expire_now::<_>(&Key::User(id)).await;
+        }
+        user
+    }
 }
\ No newline at end of file
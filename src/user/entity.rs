@@ -24,7 +24,9 @@ pub struct User {
     pub auth0_user_id: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
-    pub footprint_vault_id: String
+    pub footprint_vault_id: String,
+    pub split_tender_enabled: bool,
+    pub charge_in_merchant_currency: bool
 }
 
 #[derive(Insertable)]
@@ -112,6 +114,36 @@ impl User {
         Ok(user)
     }
 
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_split_tender_enabled(id: i32, enabled: bool) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+
+        let user = diesel::update(users::table)
+            .filter(users::id.eq(id))
+            .set((
+                users::split_tender_enabled.eq(enabled),
+                users::updated_at.eq(Utc::now().naive_utc())
+            ))
+            .get_result(&mut conn).await?;
+
+        Ok(user)
+    }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_charge_in_merchant_currency(id: i32, enabled: bool) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+
+        let user = diesel::update(users::table)
+            .filter(users::id.eq(id))
+            .set((
+                users::charge_in_merchant_currency.eq(enabled),
+                users::updated_at.eq(Utc::now().naive_utc())
+            ))
+            .get_result(&mut conn).await?;
+
+        Ok(user)
+    }
+
     #[cfg(test)]
     #[cfg_attr(feature="trace-detail", tracing::instrument(skip_all))]
     pub async fn delete(id: i32) -> Result<usize, DataError> {
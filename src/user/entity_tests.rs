@@ -245,4 +245,34 @@ mod test {
         }).await.expect_err("error");
         assert_eq!(DataError::NotFound("test".into()), error);
     }
-}
\ No newline at end of file
+
+    #[test]
+    async fn test_update_split_tender_enabled() {
+        crate::test_helper::general::init();
+        let dao = UserDao::new();
+        let user = create_user().await;
+        assert!(!user.split_tender_enabled);
+
+        let updated = dao.update_split_tender_enabled(user.id, true).await.expect("ok");
+        assert!(updated.split_tender_enabled);
+        let found = dao.find_by_internal_id(user.id).await.expect("ok");
+        assert!(found.split_tender_enabled);
+
+        let updated = dao.update_split_tender_enabled(user.id, false).await.expect("ok");
+        assert!(!updated.split_tender_enabled);
+    }
+
+    #[test]
+    async fn test_update_charge_in_merchant_currency() {
+        crate::test_helper::general::init();
+        let dao = UserDao::new();
+        let user = create_user().await;
+        assert!(!user.charge_in_merchant_currency);
+
+        let updated = dao.update_charge_in_merchant_currency(user.id, true).await.expect("ok");
+        assert!(updated.charge_in_merchant_currency);
+        let found = dao.find_by_internal_id(user.id).await.expect("ok");
+        assert!(found.charge_in_merchant_currency);
+        assert!(!found.split_tender_enabled);
+    }
+}
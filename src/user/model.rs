@@ -9,6 +9,8 @@ pub struct UserModel {
     pub id: i32,
     pub public_id: Uuid,
     pub footprint_vault_id: String,
+    pub split_tender_enabled: bool,
+    pub charge_in_merchant_currency: bool,
 }
 
 
@@ -17,7 +19,9 @@ impl From<User> for UserModel {
         UserModel {
             id: user.id,
             public_id: user.public_id,
-            footprint_vault_id: user.footprint_vault_id
+            footprint_vault_id: user.footprint_vault_id,
+            split_tender_enabled: user.split_tender_enabled,
+            charge_in_merchant_currency: user.charge_in_merchant_currency
         }
     }
 }
\ No newline at end of file
@@ -3,4 +3,14 @@ use serde::{Deserialize, Serialize};
 #[derive(Clone, Serialize, Deserialize)]
 pub struct CreateUserRequest {
     pub email: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UpdateSplitTenderRequest {
+    pub enabled: bool,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct UpdateMerchantCurrencyRequest {
+    pub enabled: bool,
 }
\ No newline at end of file
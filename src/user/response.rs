@@ -3,13 +3,17 @@ use crate::user::model::UserModel as User;
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct UserResponse {
-    pub public_id: String
+    pub public_id: String,
+    pub split_tender_enabled: bool,
+    pub charge_in_merchant_currency: bool
 }
 
 impl From<&User> for UserResponse {
     fn from(user: &User) -> Self {
         UserResponse {
-            public_id: user.public_id.to_string()
+            public_id: user.public_id.to_string(),
+            split_tender_enabled: user.split_tender_enabled,
+            charge_in_merchant_currency: user.charge_in_merchant_currency
         }
     }
 }
@@ -23,5 +27,7 @@ mod test {
         let model = crate::test_helper::user::create_mock_user();
         let resp = UserResponse::from(&model);
         assert_eq!(resp.public_id, model.public_id.to_string());
+        assert_eq!(resp.split_tender_enabled, model.split_tender_enabled);
+        assert_eq!(resp.charge_in_merchant_currency, model.charge_in_merchant_currency);
     }
 }
\ No newline at end of file
@@ -11,6 +11,8 @@ use super::error::UserError;
 pub trait UserServiceTrait {
     async fn get_or_create(self: Arc<Self>, auth0_user_id: &str, email: &str) -> Result<UserModel, UserError>;
     async fn find_by_internal_id(&self, id: i32) -> Result<UserModel, UserError>;
+    async fn set_split_tender_enabled(&self, user: &UserModel, enabled: bool) -> Result<UserModel, UserError>;
+    async fn set_charge_in_merchant_currency(&self, user: &UserModel, enabled: bool) -> Result<UserModel, UserError>;
 }
 
 pub struct UserService {
@@ -105,4 +107,16 @@ impl UserServiceTrait for UserService {
             }
         })?.into())
     }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_split_tender_enabled(&self, user: &UserModel, enabled: bool) -> Result<UserModel, UserError> {
+        tracing::info!("Setting split tender enabled={} for user={}", enabled, user.id);
+        Ok(self.user_dao.clone().update_split_tender_enabled(user.id, enabled).await?.into())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn set_charge_in_merchant_currency(&self, user: &UserModel, enabled: bool) -> Result<UserModel, UserError> {
+        tracing::info!("Setting charge in merchant currency={} for user={}", enabled, user.id);
+        Ok(self.user_dao.clone().update_charge_in_merchant_currency(user.id, enabled).await?.into())
+    }
 }
\ No newline at end of file
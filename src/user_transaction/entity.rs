@@ -38,6 +38,7 @@ pub struct TransactionWithDetail {
     pub wallet_card_charge_user_id: i32,
     pub registered_transaction_memo: String,
     pub registered_transaction_amount_cents: i32,
+    pub registered_transaction_cash_amount_cents: Option<i32>,
     pub category_name: Option<String>, // we need better modeling than string
     pub credit_card_issuer_name: String,
     pub credit_card_type_name: String,
@@ -146,7 +147,7 @@ impl TransactionWithDetail {
             )
             .select((
                 successful_end_to_end_charge::id, wallet_card_charge::user_id, registered_transaction::memo, registered_transaction::amount_cents,
-                category::name.nullable(), credit_card_issuer::name, credit_card_type::name, credit_card::name,
+                registered_transaction::cash_amount_cents, category::name.nullable(), credit_card_issuer::name, credit_card_type::name, credit_card::name,
                 rule::points_multiplier.nullable(), rule::cashback_percentage_bips.nullable(),
                 wallet_card_charge::created_at, successful_end_to_end_charge::public_id
             ))
@@ -194,7 +195,7 @@ impl TransactionWithDetail {
         let txns = query
             .select((
                 successful_end_to_end_charge::id, wallet_card_charge::user_id, registered_transaction::memo, registered_transaction::amount_cents,
-                category::name.nullable(), credit_card_issuer::name, credit_card_type::name, credit_card::name,
+                registered_transaction::cash_amount_cents, category::name.nullable(), credit_card_issuer::name, credit_card_type::name, credit_card::name,
                 rule::points_multiplier.nullable(), rule::cashback_percentage_bips.nullable(),
                 wallet_card_charge::created_at, successful_end_to_end_charge::public_id
             ))
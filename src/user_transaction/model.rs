@@ -16,6 +16,7 @@ pub struct InnerCardChargeWithDetailModel {
 pub struct TransactionWithDetailModel {
     pub memo: String,
     pub amount_cents: i32,
+    pub cash_amount_cents: Option<i32>,
     pub category: Option<String>,
     pub credit_card_issuer: String,
     pub credit_card_type: String,
@@ -42,6 +43,7 @@ impl From<TransactionWithDetail> for TransactionWithDetailModel {
         TransactionWithDetailModel {
             memo: value.registered_transaction_memo,
             amount_cents: value.registered_transaction_amount_cents,
+            cash_amount_cents: value.registered_transaction_cash_amount_cents,
             category: value.category_name,
             credit_card_issuer: value.credit_card_issuer_name,
             credit_card_type: value.credit_card_type_name,
@@ -19,6 +19,35 @@ pub fn last_of_month(date: NaiveDate) -> Result<NaiveDate, UtilityError> {
     }
 }
 
+pub fn first_of_quarter(date: NaiveDate) -> Result<NaiveDate, UtilityError> {
+    NaiveDate::from_ymd_opt(date.year(), date.month0() / 3 * 3 + 1, 1).ok_or(UtilityError::DateError(
+        format!("Cannot construct first of quarter: {:?}", &date).into()
+    ))
+}
+
+pub fn quarter_of(date: NaiveDate) -> i32 {
+    (date.month0() / 3 + 1) as i32
+}
+
+pub fn first_of_year(date: NaiveDate) -> Result<NaiveDate, UtilityError> {
+    NaiveDate::from_ymd_opt(date.year(), 1, 1).ok_or(UtilityError::DateError(
+        format!("Cannot construct first of year: {:?}", &date).into()
+    ))
+}
+
+pub fn last_anniversary(date: NaiveDate, anchor: NaiveDate) -> Result<NaiveDate, UtilityError> {
+    // the most recent day on or before date with the anchor's month and day, feb 29 falls back to feb 28
+    let anniversary_in = |year: i32| NaiveDate::from_ymd_opt(year, anchor.month(), anchor.day())
+        .or_else(|| NaiveDate::from_ymd_opt(year, anchor.month(), anchor.day() - 1))
+        .ok_or(UtilityError::DateError(format!("Cannot construct anniversary of {:?} in {}", &anchor, year).into()));
+    let this_year = anniversary_in(date.year())?;
+    if this_year <= date {
+        Ok(this_year)
+    } else {
+        anniversary_in(date.year() - 1)
+    }
+}
+
 pub fn adjust_recurring_to_date(date: NaiveDate, day_of_month: &DayOfMonth) -> Result<NaiveDate, UtilityError> {
     //given a date and a day_of_month enum, use the month from date and day of month from day of month
     match day_of_month {
@@ -49,7 +78,7 @@ mod test {
     use std::ops::Add;
     use chrono::NaiveDate;
     use crate::rule::constant::DayOfMonth;
-    use crate::util::date::{adjust_recurring_to_date, expiration_date_from_str_parts, first_of_month, last_of_month};
+    use crate::util::date::{adjust_recurring_to_date, expiration_date_from_str_parts, first_of_month, first_of_quarter, first_of_year, last_anniversary, last_of_month, quarter_of};
     use crate::util::error::UtilityError;
 
     const DAYS_OF_MONTHS: &'static [u32; 12] = &[31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
@@ -230,4 +259,64 @@ mod test {
             }
         }
     }
+
+    #[test]
+    fn test_first_of_quarter() {
+        for month in 1..=MONTHS {
+            let quarter_start = ((month - 1) / 3 * 3 + 1) as u32;
+            for day in 1..=DAYS_OF_MONTHS[month-1] {
+                assert_eq!(
+                    NaiveDate::from_ymd_opt(REGULAR_YEAR, quarter_start, 1).expect("gets date"),
+                    first_of_quarter(
+                        NaiveDate::from_ymd_opt(REGULAR_YEAR, month as u32, day).expect("gets date")
+                    ).expect("gets date")
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_quarter_of() {
+        for month in 1..=MONTHS {
+            assert_eq!(
+                ((month - 1) / 3 + 1) as i32,
+                quarter_of(NaiveDate::from_ymd_opt(REGULAR_YEAR, month as u32, 1).expect("gets date"))
+            );
+        }
+        assert_eq!(4, quarter_of(NaiveDate::from_ymd_opt(LEAP_YEAR, 12, 31).expect("gets date")));
+    }
+
+    #[test]
+    fn test_first_of_year() {
+        assert_eq!(
+            NaiveDate::from_ymd_opt(LEAP_YEAR, 1, 1).expect("gets date"),
+            first_of_year(NaiveDate::from_ymd_opt(LEAP_YEAR, 12, 31).expect("gets date")).expect("gets date")
+        );
+        assert_eq!(
+            NaiveDate::from_ymd_opt(LEAP_YEAR, 1, 1).expect("gets date"),
+            first_of_year(NaiveDate::from_ymd_opt(LEAP_YEAR, 1, 1).expect("gets date")).expect("gets date")
+        );
+    }
+
+    #[test]
+    fn test_last_anniversary() {
+        let anchor = NaiveDate::from_ymd_opt(2022, 6, 15).expect("gets date");
+        assert_eq!(
+            NaiveDate::from_ymd_opt(REGULAR_YEAR, 6, 15).expect("gets date"),
+            last_anniversary(NaiveDate::from_ymd_opt(REGULAR_YEAR, 6, 15).expect("gets date"), anchor).expect("gets date")
+        );
+        assert_eq!(
+            NaiveDate::from_ymd_opt(REGULAR_YEAR, 6, 15).expect("gets date"),
+            last_anniversary(NaiveDate::from_ymd_opt(REGULAR_YEAR, 12, 1).expect("gets date"), anchor).expect("gets date")
+        );
+        assert_eq!(
+            NaiveDate::from_ymd_opt(REGULAR_YEAR - 1, 6, 15).expect("gets date"),
+            last_anniversary(NaiveDate::from_ymd_opt(REGULAR_YEAR, 6, 14).expect("gets date"), anchor).expect("gets date")
+        );
+        let leap_anchor = NaiveDate::from_ymd_opt(LEAP_YEAR, 2, 29).expect("gets date");
+        assert_eq!(
+            NaiveDate::from_ymd_opt(REGULAR_YEAR, 2, 28).expect("gets date"),
+            last_anniversary(NaiveDate::from_ymd_opt(REGULAR_YEAR, 3, 1).expect("gets date"), leap_anchor).expect("gets date")
+        );
+    }
 }
\ No newline at end of file
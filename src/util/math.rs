@@ -8,4 +8,8 @@ pub fn get_cents_of_cashback(amount_cents: i32, cashback_percentage_bips: i32) -
 
 pub fn get_number_of_points(amount_cents: i32, points_multiplier: i32) -> i32 {
     (amount_cents as f64 * points_multiplier as f64 / CENTS_TO_DOLLAR as f64) as i32
-}
\ No newline at end of file
+}
+
+pub fn get_cents_of_points(points: i32, cents_per_point_hundredths: i32) -> i32 {
+    (points as f64 * cents_per_point_hundredths as f64 / CENTS_TO_DOLLAR as f64) as i32
+}
@@ -13,5 +13,7 @@ pub fn config(cfg: &mut web::ServiceConfig) -> () {
                 .service(controller::register_new_card_attempt)
                 .service(controller::match_card)
                 .service(controller::update_status)
+                .service(controller::update_cash_advance)
+                .service(controller::list_card_health)
         );
 }
\ No newline at end of file
@@ -9,7 +9,8 @@ use super::error::WalletError;
 use crate::middleware::services::Services;
 use crate::user::model::UserModel as User;
 use crate::wallet::service::{WalletService, WalletServiceTrait};
-use crate::wallet::response::{DisplayableCardInfo, UpdateStatusResponse};
+use crate::card_health::service::CardHealthServiceTrait;
+use crate::wallet::response::{CardHealthResponse, DisplayableCardInfo, UpdateCashAdvanceResponse, UpdateStatusResponse};
 use crate::wallet::response::WalletAddCardSuccessResponse;
 use super::{
     request, 
@@ -104,3 +105,41 @@ async fn update_status(
         }
     ))
 }
+
+#[post("/update-cash-advance/")]
+async fn update_cash_advance(
+    user: web::ReqData<User>,
+    info: web::Json<request::UpdateCashAdvanceRequest>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, WalletError> {
+    let user = user.into_inner();
+    let info = info.into_inner();
+    let card = services.wallet_service.clone().update_cash_advance(
+        &user,
+        &info.wallet_card_public_id,
+        info.cash_advance_enabled
+    ).await?;
+
+    Ok(HttpResponse::Ok().json(
+        UpdateCashAdvanceResponse {
+            public_id: card.public_id,
+            cash_advance_enabled: card.cash_advance_enabled
+        }
+    ))
+}
+
+#[get("/card-health/")]
+async fn list_card_health(
+    user: web::ReqData<User>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, WalletError> {
+    let user = user.into_inner();
+    let cards = services.wallet_service.clone().find_all_active_for_user(&user).await?;
+    let wallet_card_ids: Vec<i32> = cards.iter().map(|card| card.id).collect();
+    let health: Vec<CardHealthResponse> = services.card_health_service.clone().get_health(&wallet_card_ids).await?
+        .into_iter()
+        .zip(cards.iter())
+        .map(|(health, card)| CardHealthResponse::new(card.public_id, health))
+        .collect();
+    Ok(HttpResponse::Ok().json(health))
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 use crate::error::data_error::DataError;
 use crate::user::model::UserModel as User;
-use crate::wallet::entity::{InsertableCardAttempt, Wallet, WalletCardAttempt, UpdateCardAttempt, WalletDetail, InsertableCard, WalletWithExtraInfo, UpdateWalletStatus, WalletStatusHistory, InsertableWalletStatusHistory};
+use crate::wallet::entity::{InsertableCardAttempt, Wallet, WalletCardAttempt, UpdateCardAttempt, WalletDetail, InsertableCard, WalletWithExtraInfo, UpdateWalletStatus, UpdateWalletCashAdvance, WalletStatusHistory, InsertableWalletStatusHistory};
 use async_trait::async_trait;
 use tracing;
 use parking_lot::Mutex;
@@ -24,6 +24,7 @@ pub trait WalletDaoTrait {
     async fn find_all_for_user_with_card_info(self: Arc<Self>, user: &User) -> Result<Vec<WalletWithExtraInfo>, DataError>;
     async fn insert_card<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, card: &InsertableCard<'a>) -> Result<Wallet, DataError>;
     async fn update_card_status<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, id: i32, status_update: &UpdateWalletStatus) -> Result<Wallet, DataError>;
+    async fn update_cash_advance(self: Arc<Self>, id: i32, cash_advance_update: &UpdateWalletCashAdvance) -> Result<Wallet, DataError>;
 }
 
 
@@ -103,6 +104,17 @@ impl WalletDaoTrait for WalletDao {
     async fn update_card_status<'a>(self: Arc<Self>, transaction: &mut Transaction<'_, '_>, id: i32, status_update: &UpdateWalletStatus) -> Result<Wallet, DataError> {
         Wallet::update_card_status(transaction, id, status_update).await
     }
+
+    #[cfg_attr(feature="trace-detail", tracing::instrument(skip(self)))]
+    async fn update_cash_advance(self: Arc<Self>, id: i32, cash_advance_update: &UpdateWalletCashAdvance) -> Result<Wallet, DataError> {
+        let card = Wallet::update_cash_advance(id, cash_advance_update).await?;
+        // routing reads the cached wallet, so it has to see the flag right away
+        #[cfg(not(feature = "no-redis"))] {
+            tracing::info!("Expiring user's wallet in redis for user_id={}", card.user_id);
+            self.redis.clone().expire_now::<_>(&Key::CardsForUser(card.user_id)).await;
+        }
+        Ok(card)
+    }
 }
 
 impl WalletCardAttemptDao {
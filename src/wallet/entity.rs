@@ -75,6 +75,9 @@ pub struct Wallet {
     pub credit_card_id: i32,
     pub wallet_card_attempt_id: i32,
     pub status: WalletStatus,
+    // wallets cached before the column existed don't have it
+    #[serde(default)]
+    pub cash_advance_enabled: bool,
 }
 
 #[derive(Insertable, Debug)]
@@ -94,11 +97,18 @@ pub struct UpdateWalletStatus {
     pub status: WalletStatus
 }
 
+#[derive(Serialize, Deserialize, AsChangeset, Clone, Debug)]
+#[diesel(table_name = wallet)]
+pub struct UpdateWalletCashAdvance {
+    pub cash_advance_enabled: bool
+}
+
 #[derive(Queryable, Debug)]
 pub struct WalletWithExtraInfo {
     pub id: i32,
     pub public_id: Uuid,
     pub status: WalletStatus,
+    pub cash_advance_enabled: bool,
     pub created_at: NaiveDateTime,
     pub card_name: String,
     pub issuer_name: String,
@@ -167,6 +177,7 @@ impl Wallet {
                 wallet::id,
                 wallet::public_id,
                 wallet::status,
+                wallet::cash_advance_enabled,
                 wallet::created_at,
                 credit_card::name,
                 credit_card_issuer::name,
@@ -195,6 +206,16 @@ impl Wallet {
         Ok(wallet)
     }
 
+    #[cfg_attr(feature="trace-detail", tracing::instrument)]
+    pub async fn update_cash_advance(id: i32, cash_advance_update: &UpdateWalletCashAdvance) -> Result<Self, DataError> {
+        let mut conn = db::connection().await?;
+        let wallet = diesel::update(wallet::table)
+            .filter(wallet::id.eq(id))
+            .set(cash_advance_update)
+            .get_result::<Wallet>(&mut conn).await?;
+        Ok(wallet)
+    }
+
     #[cfg(test)]
     #[cfg_attr(feature="trace-detail", tracing::instrument)]
     pub async fn delete(id: i32) -> Result<usize, DataError> {
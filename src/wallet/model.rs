@@ -1,5 +1,6 @@
 use chrono::NaiveDateTime;
 use uuid::Uuid;
+use crate::card_health::model::CardHealthModel;
 use crate::wallet::constant::{WalletCardAttemptStatus, WalletStatus};
 use crate::wallet::entity::{Wallet, WalletCardAttempt, WalletWithExtraInfo};
 
@@ -12,7 +13,8 @@ pub struct WalletModel {
     pub created_at: NaiveDateTime,
     pub credit_card_id: i32,
     pub wallet_card_attempt_id: i32,
-    pub status: WalletStatus
+    pub status: WalletStatus,
+    pub cash_advance_enabled: bool
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -25,7 +27,12 @@ pub struct WalletModelWithRule {
     pub credit_card_id: i32,
     pub wallet_card_attempt_id: i32,
     pub status: WalletStatus,
-    pub rule_id: Option<i32>
+    pub cash_advance_enabled: bool,
+    pub rule_id: Option<i32>,
+    // expected value in cents of the best rule, at the user's point valuations
+    pub reward_amount: i32,
+    pub matched_rule_ids: Vec<i32>,
+    pub health: Option<CardHealthModel>
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -45,6 +52,7 @@ pub struct WalletWithExtraInfoModel {
     pub id: i32,
     pub public_id: Uuid,
     pub status: WalletStatus,
+    pub cash_advance_enabled: bool,
     pub created_at: NaiveDateTime,
     pub card_name: String,
     pub issuer_name: String,
@@ -63,7 +71,8 @@ impl From<Wallet> for WalletModel {
             created_at: value.created_at,
             credit_card_id: value.credit_card_id,
             wallet_card_attempt_id: value.wallet_card_attempt_id,
-            status: value.status
+            status: value.status,
+            cash_advance_enabled: value.cash_advance_enabled
         }
     }
 }
@@ -90,6 +99,7 @@ impl From<WalletWithExtraInfo> for WalletWithExtraInfoModel {
             id: value.id,
             public_id: value.public_id,
             status: value.status,
+            cash_advance_enabled: value.cash_advance_enabled,
             created_at: value.created_at,
             card_name: value.card_name,
             issuer_name: value.issuer_name,
@@ -99,6 +109,13 @@ impl From<WalletWithExtraInfo> for WalletWithExtraInfoModel {
     }
 }
 
+impl WalletModelWithRule {
+    // set by the card health check when routing an asa, never skipped otherwise
+    pub fn is_skipped(&self) -> bool {
+        self.health.as_ref().map_or(false, |health| health.skipped)
+    }
+}
+
 impl From<WalletModel> for WalletModelWithRule {
     fn from(value: WalletModel) -> Self {
         WalletModelWithRule {
@@ -110,7 +127,11 @@ impl From<WalletModel> for WalletModelWithRule {
             credit_card_id: value.credit_card_id,
             wallet_card_attempt_id: value.wallet_card_attempt_id,
             status: value.status,
+            cash_advance_enabled: value.cash_advance_enabled,
             rule_id: None,
+            reward_amount: 0,
+            matched_rule_ids: vec![],
+            health: None,
         }
     }
 }
@@ -126,6 +147,7 @@ impl From<WalletModelWithRule> for WalletModel {
             credit_card_id: value.credit_card_id,
             wallet_card_attempt_id: value.wallet_card_attempt_id,
             status: value.status,
+            cash_advance_enabled: value.cash_advance_enabled,
         }
     }
 }
\ No newline at end of file
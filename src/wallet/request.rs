@@ -32,6 +32,12 @@ pub struct UpdateStatusRequest {
     pub status: WalletStatus
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateCashAdvanceRequest {
+    pub wallet_card_public_id: Uuid,
+    pub cash_advance_enabled: bool
+}
+
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct PaymentMethod {
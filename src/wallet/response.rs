@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::NaiveDateTime;
+use crate::card_health::constant::CardHealthStatus;
+use crate::card_health::model::CardHealthModel;
 use crate::wallet::constant::WalletStatus;
 use crate::wallet::model::WalletWithExtraInfoModel;
 
@@ -23,6 +25,12 @@ pub struct UpdateStatusResponse {
     pub status: WalletStatus,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UpdateCashAdvanceResponse {
+    pub public_id: Uuid,
+    pub cash_advance_enabled: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DisplayableCardInfo {
     pub public_id: Uuid,
@@ -31,7 +39,8 @@ pub struct DisplayableCardInfo {
     pub issuer_name: String,
     pub card_type: String,
     pub card_image_url: String,
-    pub status: WalletStatus
+    pub status: WalletStatus,
+    pub cash_advance_enabled: bool
 }
 
 impl From<WalletWithExtraInfoModel> for DisplayableCardInfo {
@@ -43,7 +52,35 @@ impl From<WalletWithExtraInfoModel> for DisplayableCardInfo {
             issuer_name: value.issuer_name,
             card_type: value.card_type,
             card_image_url: value.card_image_url,
-            status: value.status
+            status: value.status,
+            cash_advance_enabled: value.cash_advance_enabled
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CardHealthResponse {
+    pub public_id: Uuid,
+    pub status: CardHealthStatus,
+    pub attempts: i32,
+    pub failures: i32,
+    pub consecutive_failures: i32,
+    pub last_failure_at: Option<NaiveDateTime>,
+    pub retry_at: Option<NaiveDateTime>,
+    pub skipped: bool
+}
+
+impl CardHealthResponse {
+    pub fn new(public_id: Uuid, health: CardHealthModel) -> Self {
+        CardHealthResponse {
+            public_id,
+            status: health.status,
+            attempts: health.attempts,
+            failures: health.failures,
+            consecutive_failures: health.consecutive_failures,
+            last_failure_at: health.last_failure_at,
+            retry_at: health.retry_at,
+            skipped: health.skipped
         }
     }
 }
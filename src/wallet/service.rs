@@ -8,7 +8,7 @@ use crate::error::data_error::DataError;
 use crate::user::model::UserModel as User;
 use crate::wallet::constant::{WalletCardAttemptStatus, WalletStatus};
 use crate::wallet::dao::{WalletCardAttemptDao, WalletCardAttemtDaoTrait, WalletDao, WalletDaoTrait, WalletStatusHistoryDao, WalletStatusHistoryDaoTrait};
-use crate::wallet::entity::{InsertableCardAttempt, InsertableCard, UpdateCardAttempt, Wallet, WalletCardAttempt, WalletDetail, UpdateWalletStatus, InsertableWalletStatusHistory};
+use crate::wallet::entity::{InsertableCardAttempt, InsertableCard, UpdateCardAttempt, Wallet, WalletCardAttempt, WalletDetail, UpdateWalletStatus, UpdateWalletCashAdvance, InsertableWalletStatusHistory};
 use crate::wallet::request::{MatchRequest, RegisterAttemptRequest};
 use crate::footprint::service::{FootprintService, FootprintServiceTrait};
 use crate::util::transaction::transactional;
@@ -44,6 +44,13 @@ pub trait WalletServiceTrait {
         public_id: &Uuid,
         new_status: WalletStatus
     ) -> Result<WalletModel, WalletError>;
+
+    async fn update_cash_advance(
+        self: Arc<Self>,
+        user: &User,
+        public_id: &Uuid,
+        enabled: bool
+    ) -> Result<WalletModel, WalletError>;
 }
 
 // TODO: now that we make the api calls from the backend, we can consolidate the wallet card attempt creation
@@ -256,6 +263,30 @@ impl WalletServiceTrait for WalletService {
 
     }
 
+    #[tracing::instrument(skip(self))]
+    async fn update_cash_advance(
+        self: Arc<Self>,
+        user: &User,
+        public_id: &Uuid,
+        enabled: bool
+    ) -> Result<WalletModel, WalletError> {
+        let card = self.wallet_dao.clone()
+            .find_by_public_id(public_id).await?;
+
+        if card.user_id != user.id {
+            return Err(WalletError::Unauthorized("User is not owner of card".into()))
+        }
+
+        let card = self.wallet_dao.clone().update_cash_advance(
+            card.id,
+            &UpdateWalletCashAdvance {
+                cash_advance_enabled: enabled
+            }
+        ).await?;
+        tracing::info!("Updated card={} to cash_advance_enabled={}", card.id, enabled);
+        Ok(card.into())
+    }
+
     #[tracing::instrument(skip(self))]
     async fn find_by_public_id(self: Arc<Self>, public_id: &Uuid) -> Result<WalletModel, WalletError> {
         Ok(self.wallet_dao.clone().find_by_public_id(public_id).await?.into())
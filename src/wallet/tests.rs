@@ -19,7 +19,7 @@ mod tests {
     };
     use crate::test_helper::credit_card::get_card_from_database;
     use crate::test_helper::user::create_user;
-    use crate::test_helper::wallet::create_mock_wallet_attempt;
+    use crate::test_helper::wallet::{create_mock_wallet_attempt, create_wallet};
     use crate::util::db;
     use crate::util::transaction::transactional;
     use crate::wallet::constant::{WalletCardAttemptStatus, WalletStatus};
@@ -714,4 +714,36 @@ mod tests {
         let history = WalletStatusHistory::get_by_wallet_id(created_card.id).await.expect("history");
         assert_eq!(2, history.len());
     }
-}
\ No newline at end of file
+
+    #[test]
+    async fn test_update_cash_advance() {
+        crate::test_helper::general::init();
+        let user = create_user().await;
+        let other_user = create_user().await;
+        let created_card = create_wallet(&user).await;
+        assert!(!created_card.cash_advance_enabled);
+
+        let wallet_engine = Arc::new(WalletService::new_with_services(
+            Arc::new(CreditCardService::new()),
+            Arc::new(MockFootprintServiceTrait::new())
+        ));
+
+        let card = wallet_engine.clone().update_cash_advance(
+            &user,
+            &created_card.public_id,
+            true
+        ).await.expect("updates");
+        assert!(card.cash_advance_enabled);
+        let cards = wallet_engine.clone().find_all_for_user(&user).await.expect("finds cards");
+        assert!(cards.iter().any(|card| card.id == created_card.id && card.cash_advance_enabled));
+
+        let err = wallet_engine.clone().update_cash_advance(
+            &other_user,
+            &created_card.public_id,
+            false
+        ).await.expect_err("not the owner");
+        assert_eq!(WalletError::Unauthorized("test".into()), err);
+        let card = Wallet::find_by_public_id(&created_card.public_id).await.expect("gets card");
+        assert!(card.cash_advance_enabled);
+    }
+}
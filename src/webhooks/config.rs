@@ -3,5 +3,6 @@ use super::controller;
 
 pub fn config(cfg: &mut web::ServiceConfig) -> () {
     cfg
-        .service(controller::lithic_asa_webhook);
+        .service(controller::lithic_asa_webhook)
+        .service(controller::lithic_transaction_webhook);
 }
@@ -6,6 +6,7 @@ use actix_web::{
 use crate::asa::request::AsaRequest;
 use crate::middleware::services::Services;
 use crate::webhooks::error::LithicHandlerError;
+use crate::webhooks::request::LithicTransactionRequest;
 
 #[post("/lithic-asa-webhook/")]
 async fn lithic_asa_webhook(
@@ -18,4 +19,15 @@ async fn lithic_asa_webhook(
             resp
         )
     )
-}
\ No newline at end of file
+}
+
+#[post("/lithic-transaction-webhook/")]
+async fn lithic_transaction_webhook(
+    transaction: web::Json<LithicTransactionRequest>,
+    services: web::Data<Services>
+) -> Result<HttpResponse, LithicHandlerError> {
+    services.lithic_handler.clone().handle_transaction_event(transaction.into_inner()).await?;
+    Ok(
+        HttpResponse::Ok().finish()
+    )
+}
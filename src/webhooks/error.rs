@@ -1,10 +1,16 @@
 use actix_web::http::StatusCode;
 use actix_web::ResponseError;
+use crate::charge::error::ChargeError;
+use crate::merchant_control::error::MerchantControlError;
 use crate::passthrough_card::error::PassthroughCardError;
+use crate::spend_control::error::SpendControlError;
+use crate::stand_in::error::StandInError;
 use crate::wallet::error::WalletError;
 
 #[derive(thiserror::Error, Debug)]
 pub enum LithicHandlerError {
+    #[error("Transaction is already being processed")]
+    Conflict(#[source] Box<dyn std::error::Error + Send + Sync>),
     #[error("Unexpected error")]
     Unexpected(#[source] Box<dyn std::error::Error + Send + Sync>)
 }
@@ -12,6 +18,7 @@ pub enum LithicHandlerError {
 impl ResponseError for LithicHandlerError {
     fn status_code(&self) -> StatusCode {
         match self {
+            LithicHandlerError::Conflict(_) => StatusCode::CONFLICT,
             LithicHandlerError::Unexpected(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
@@ -30,11 +37,42 @@ impl From<WalletError> for LithicHandlerError {
     }
 }
 
+impl From<StandInError> for LithicHandlerError {
+    fn from(value: StandInError) -> Self {
+        LithicHandlerError::Unexpected(Box::new(value))
+    }
+}
+
+impl From<MerchantControlError> for LithicHandlerError {
+    fn from(value: MerchantControlError) -> Self {
+        LithicHandlerError::Unexpected(Box::new(value))
+    }
+}
+
+impl From<SpendControlError> for LithicHandlerError {
+    fn from(value: SpendControlError) -> Self {
+        match value {
+            SpendControlError::Conflict(e) => LithicHandlerError::Conflict(e),
+            e => LithicHandlerError::Unexpected(Box::new(e))
+        }
+    }
+}
+
+impl From<ChargeError> for LithicHandlerError {
+    fn from(value: ChargeError) -> Self {
+        match value {
+            ChargeError::DuplicateTransaction(e) => LithicHandlerError::Conflict(e),
+            e => LithicHandlerError::Unexpected(Box::new(e))
+        }
+    }
+}
+
 #[cfg(test)]
 impl PartialEq for LithicHandlerError {
     fn eq(&self, other: &Self) -> bool {
         match(self, other) {
-            (LithicHandlerError::Unexpected(_), LithicHandlerError::Unexpected(_)) => true,
+            (LithicHandlerError::Unexpected(_), LithicHandlerError::Unexpected(_))
+            | (LithicHandlerError::Conflict(_), LithicHandlerError::Conflict(_)) => true,
             _ => false
         }
     }
@@ -44,7 +82,11 @@ impl PartialEq for LithicHandlerError {
 mod test {
     use actix_web::http::StatusCode;
     use actix_web::ResponseError;
+    use crate::charge::error::ChargeError;
+    use crate::merchant_control::error::MerchantControlError;
     use crate::passthrough_card::error::PassthroughCardError;
+    use crate::spend_control::error::SpendControlError;
+    use crate::stand_in::error::StandInError;
     use crate::wallet::error::WalletError;
     use crate::webhooks::error::LithicHandlerError;
 
@@ -67,8 +109,29 @@ mod test {
         assert_eq!(LithicHandlerError::Unexpected(BASE_ERROR.into()), LithicHandlerError::from(PassthroughCardError::CardNotFound(BASE_ERROR.into())));
     }
 
+    #[test]
+    pub fn test_from_charge() {
+        assert_eq!(LithicHandlerError::Conflict(BASE_ERROR.into()), LithicHandlerError::from(ChargeError::DuplicateTransaction(BASE_ERROR.into())));
+        assert_eq!(LithicHandlerError::Unexpected(BASE_ERROR.into()), LithicHandlerError::from(ChargeError::NoCardInRequest));
+        assert_eq!(LithicHandlerError::Unexpected(BASE_ERROR.into()), LithicHandlerError::from(ChargeError::Unexpected(BASE_ERROR.into())));
+    }
+
+    #[test]
+    pub fn test_from_merchant_control() {
+        assert_eq!(LithicHandlerError::Unexpected(BASE_ERROR.into()), LithicHandlerError::from(MerchantControlError::NotFound(BASE_ERROR.into())));
+        assert_eq!(LithicHandlerError::Unexpected(BASE_ERROR.into()), LithicHandlerError::from(MerchantControlError::Unexpected(BASE_ERROR.into())));
+    }
+
+    #[test]
+    pub fn test_from_spend_control() {
+        assert_eq!(LithicHandlerError::Conflict(BASE_ERROR.into()), LithicHandlerError::from(SpendControlError::Conflict(BASE_ERROR.into())));
+        assert_eq!(LithicHandlerError::Unexpected(BASE_ERROR.into()), LithicHandlerError::from(SpendControlError::Exceeded(BASE_ERROR.into())));
+        assert_eq!(LithicHandlerError::Unexpected(BASE_ERROR.into()), LithicHandlerError::from(SpendControlError::Unexpected(BASE_ERROR.into())));
+    }
+
     #[test]
     pub fn test_status_code() {
+        assert_eq!(StatusCode::CONFLICT, LithicHandlerError::Conflict(BASE_ERROR.into()).status_code());
         assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, LithicHandlerError::Unexpected(BASE_ERROR.into()).status_code());
     }
 }
\ No newline at end of file
@@ -1,18 +1,32 @@
 use std::sync::Arc;
 use std::time::Instant;
+use tokio::time::timeout;
 use crate::adyen::checkout::service::AdyenChargeServiceTrait;
+use crate::avs::service::AvsServiceTrait;
+use crate::card_health::service::CardHealthServiceTrait;
+use crate::cash::service::CashServiceTrait;
 
+use crate::charge::constant::TransactionEventType;
+use crate::charge::model::{AsaChargeResult, ChargeBudget};
 use crate::charge::service::{ChargeService, ChargeServiceTrait};
 use crate::asa::request::AsaRequest;
+use crate::merchant_control::service::MerchantControlServiceTrait;
 use crate::rule::service::RuleService;
 use crate::rule::service::RuleServiceTrait;
+use crate::spend_control::error::SpendControlError;
+use crate::spend_control::service::SpendControlServiceTrait;
 use crate::asa::response::{AsaResponse, AsaResponseResult, AvsResponseResult};
+use crate::configuration::asa::AsaConfiguration;
 
 use crate::footprint::service::{FootprintService, FootprintServiceTrait};
 use crate::ledger::service::LedgerServiceTrait;
 use crate::passthrough_card::constant::PassthroughCardStatus;
 use crate::passthrough_card::service::PassthroughCardServiceTrait;
+use crate::stand_in::constant::StandInStatus;
+use crate::stand_in::model::StandInTransactionModel;
+use crate::stand_in::service::StandInServiceTrait;
 use crate::user::service::UserServiceTrait;
+use crate::webhooks::request::LithicTransactionRequest;
 use super::error::LithicHandlerError;
 
 pub struct LithicHandler {
@@ -20,6 +34,13 @@ pub struct LithicHandler {
     rule_service: Arc<dyn RuleServiceTrait>,
     passthrough_card_service: Arc<dyn PassthroughCardServiceTrait>,
     user_service: Arc<dyn UserServiceTrait>,
+    stand_in_service: Arc<dyn StandInServiceTrait>,
+    avs_service: Arc<dyn AvsServiceTrait>,
+    spend_control_service: Arc<dyn SpendControlServiceTrait>,
+    merchant_control_service: Arc<dyn MerchantControlServiceTrait>,
+    card_health_service: Arc<dyn CardHealthServiceTrait>,
+    cash_service: Arc<dyn CashServiceTrait>,
+    asa_configuration: AsaConfiguration,
 }
 
 impl LithicHandler {
@@ -30,16 +51,32 @@ impl LithicHandler {
         rule_service: Arc<RuleService>,
         passthrough_card_service: Arc<dyn PassthroughCardServiceTrait>,
         user_service: Arc<dyn UserServiceTrait>,
+        stand_in_service: Arc<dyn StandInServiceTrait>,
+        avs_service: Arc<dyn AvsServiceTrait>,
+        spend_control_service: Arc<dyn SpendControlServiceTrait>,
+        merchant_control_service: Arc<dyn MerchantControlServiceTrait>,
+        card_health_service: Arc<dyn CardHealthServiceTrait>,
+        cash_service: Arc<dyn CashServiceTrait>,
+        asa_configuration: &AsaConfiguration,
     ) -> Self {
         Self {
             charge_service,
             rule_service,
             passthrough_card_service,
-            user_service
+            user_service,
+            stand_in_service,
+            avs_service,
+            spend_control_service,
+            merchant_control_service,
+            card_health_service,
+            cash_service,
+            asa_configuration: asa_configuration.clone()
         }
     }
     #[tracing::instrument(skip(self))]
     pub async fn handle(self: Arc<Self>, request: AsaRequest) -> Result<AsaResponse, LithicHandlerError>{
+        // lithic stops waiting on us after a fixed window, so the clock starts as soon as the request lands
+        let budget = ChargeBudget::from_configuration(&self.asa_configuration);
         // TODO: do a reverse lookup based on the card token to get the user
         tracing::info!("Identifying user by card");
         let card = request.card.clone().ok_or(
@@ -48,7 +85,44 @@ impl LithicHandler {
         let token = card.token.clone().ok_or(
             LithicHandlerError::Unexpected("expect token on card".into())
         )?;
-        let passthrough_card = self.passthrough_card_service.clone().get_by_token(&token).await
+
+        // an answer after the window is as good as none, so running out of budget stands in like any other failure
+        let authorized = match timeout(budget.remaining(), self.clone().authorize(&request, &token, &budget)).await {
+            Ok(authorized) => authorized,
+            Err(_) => Err(LithicHandlerError::Unexpected("authorization ran past the asa budget".into()))
+        };
+        let (result, avs_result) = match authorized {
+            Ok(authorized) => authorized,
+            Err(LithicHandlerError::Unexpected(e)) => {
+                // lithic falls back to its own default if we don't answer, so we answer with our own
+                tracing::error!("Unable to authorize card={}, standing in error={:?}", &token, &e);
+                // the card lookup is cached in redis, so it usually still answers with the database gone
+                let user_id = self.passthrough_card_service.clone().get_by_token(&token).await
+                    .ok()
+                    .map(|passthrough_card| passthrough_card.user_id);
+                (AsaChargeResult::from(self.stand_in_service.clone().stand_in(&request, &token, user_id, &format!("{:?}", e)).await), None)
+            },
+            Err(e) => return Err(e)
+        };
+
+        Ok(
+            AsaResponse {
+                token,
+                result: result.result,
+                avs_result: avs_result.map(String::from),
+                balance: None,
+                approved_amount: result.approved_amount_cents,
+            }
+        )
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn authorize(self: Arc<Self>, request: &AsaRequest, token: &str, budget: &ChargeBudget) -> Result<(AsaChargeResult, Option<AvsResponseResult>), LithicHandlerError> {
+        if let Some(result) = self.charge_service.clone().get_previous_result_for_request(request).await? {
+            tracing::info!("Replayed request, returning previous result={:?}", &result);
+            return Ok((result, None))
+        }
+        let passthrough_card = self.passthrough_card_service.clone().get_by_token(token).await
             .map_err(|e| LithicHandlerError::Unexpected(e.into()))?;
 
 
@@ -61,42 +135,186 @@ impl LithicHandler {
                 PassthroughCardStatus::PendingFulfillment => AsaResponseResult::AccountInactive,
                 _ => AsaResponseResult::AccountInactive
             };
-            return Ok(
-                AsaResponse {
-                    token,
-                    result: result,
-                    avs_result: None,
-                    balance: None,
-                }
-            )
+            return Ok((AsaChargeResult::from(result), None))
+        }
+        match self.merchant_control_service.clone().check(request, &passthrough_card).await {
+            Ok(Some(decision)) => {
+                tracing::warn!("Declining for merchant control={:?} reason={} for card={}", decision.merchant_control_id, &decision.reason, passthrough_card.id);
+                return Ok((AsaChargeResult::from(AsaResponseResult::UnauthorizedMerchant), None))
+            },
+            Ok(None) => {},
+            // an error here would stand in and approve past a block the user set, so decline instead
+            Err(e) => {
+                tracing::error!("Declining, unable to check merchant controls for card={} error={:?}", passthrough_card.id, &e);
+                return Ok((AsaChargeResult::from(AsaResponseResult::UnauthorizedMerchant), None))
+            }
         }
         let user = self.user_service.clone().find_by_internal_id(passthrough_card.user_id).await
             .map_err(|e| LithicHandlerError::Unexpected(e.into()))?;
 
+        let avs_result = self.avs_service.clone().verify(request, &user, budget).await;
+        if avs_result.as_ref().is_some_and(|result| self.avs_service.clone().should_decline(result)) {
+            tracing::warn!("Declining for avs result={:?} for userId={}", &avs_result, user.id);
+            return Ok((AsaChargeResult::from(AsaResponseResult::AvsInvalid), avs_result))
+        }
+
+        let reservation = match self.spend_control_service.clone().reserve(request, &passthrough_card).await {
+            Ok(reservation) => reservation,
+            Err(SpendControlError::Exceeded(e)) => {
+                tracing::warn!("Declining for spend control={:?} for userId={}", &e, user.id);
+                return Ok((AsaChargeResult::from(AsaResponseResult::VelocityExceeded), avs_result))
+            },
+            Err(e) => return Err(e.into())
+        };
+
         tracing::info!("Getting user cards for userId={}", user.id);
+        let charged = match self.rule_service.clone().order_user_cards_for_request(
+            request,
+            &user
+        ).await.map_err(|e| LithicHandlerError::Unexpected(e.into())) {
+            Ok(cards) => match self.cash_service.clone().route(request, cards) {
+                Some(cards) => {
+                    tracing::info!("Got {} cards for userId={}", cards.len(), user.id);
+                    let cards = self.card_health_service.clone().route(cards).await;
+                    tracing::info!("Attempting to charge userId={}", user.id);
+                    let charged = self.charge_service.clone().charge_from_asa_request(
+                        request,
+                        &cards,
+                        &passthrough_card,
+                        &user,
+                        budget
+                    ).await.map_err(LithicHandlerError::from);
+                    self.card_health_service.clone().invalidate(&cards).await;
+                    charged
+                },
+                None => {
+                    tracing::warn!("Declining cash of {:?} cents for userId={}", request.cash_amount, user.id);
+                    Ok(AsaChargeResult::from(AsaResponseResult::UnauthorizedMerchant))
+                }
+            },
+            Err(e) => Err(e)
+        };
+
+        if let Some(reservation) = reservation.as_ref() {
+            // whatever wasn't approved shouldn't count against the user's limits
+            let approved_amount_cents = match &charged {
+                Ok(AsaChargeResult { result: AsaResponseResult::Approved, approved_amount_cents }) => approved_amount_cents.unwrap_or(reservation.amount_cents),
+                _ => 0
+            };
+            if let Err(e) = self.spend_control_service.clone().release(reservation, approved_amount_cents).await {
+                tracing::error!("Unable to release spend reservation for usage={} error={:?}", reservation.usage_id, &e);
+            }
+        }
+        let result = charged?;
+
+        tracing::info!("Charged with result={:?} with {:?} of budget left", &result, budget.remaining());
+        Ok((result, avs_result))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn reconcile_stand_in_transactions(self: Arc<Self>) -> Result<(), LithicHandlerError> {
+        self.stand_in_service.clone().import_journal().await?;
+        // claimed rows are leased to this run, another instance's reconciler skips them
+        let due = self.stand_in_service.clone().claim_due().await?;
+        tracing::info!("Reconciling {} stand in transactions", due.len());
+        for stand_in in due.iter() {
+            if stand_in.asa_response_result != AsaResponseResult::Approved {
+                // nothing was promised to the merchant, so there is nothing to collect
+                self.stand_in_service.clone().resolve(stand_in, &StandInStatus::Reconciled, None).await?;
+                continue;
+            }
+            let (status, result) = match self.clone().reconcile_approved_stand_in(stand_in).await {
+                Ok(AsaChargeResult { result: AsaResponseResult::Approved, approved_amount_cents: None }) => (StandInStatus::Reconciled, Some(AsaResponseResult::Approved)),
+                Ok(result) => {
+                    // a partial approval still leaves part of what we promised uncollected
+                    tracing::error!("Unable to collect stand in={} for {} cents result={:?}", &stand_in.public_id, stand_in.amount_cents, &result);
+                    (StandInStatus::Failed, Some(result.result))
+                },
+                Err(e) => {
+                    tracing::error!("Unable to collect stand in={} for {} cents error={:?}", &stand_in.public_id, stand_in.amount_cents, &e);
+                    (StandInStatus::Failed, None)
+                }
+            };
+            self.stand_in_service.clone().resolve(stand_in, &status, result.as_ref()).await?;
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn reconcile_approved_stand_in(self: Arc<Self>, stand_in: &StandInTransactionModel) -> Result<AsaChargeResult, LithicHandlerError> {
+        let request: AsaRequest = serde_json::from_str(&stand_in.body)
+            .map_err(|e| LithicHandlerError::Unexpected(e.into()))?;
+        let budget = ChargeBudget::from_configuration(&self.asa_configuration);
+        let passthrough_card = self.passthrough_card_service.clone().get_by_token(&stand_in.card_token).await
+            .map_err(|e| LithicHandlerError::Unexpected(e.into()))?;
+        let user = self.user_service.clone().find_by_internal_id(passthrough_card.user_id).await
+            .map_err(|e| LithicHandlerError::Unexpected(e.into()))?;
         let cards = self.rule_service.clone().order_user_cards_for_request(
             &request,
             &user
         ).await.map_err(|e| LithicHandlerError::Unexpected(e.into()))?;
-        tracing::info!("Got {} cards for userId={}", cards.len(), user.id);
-        tracing::info!("Attempting to charge userId={}", user.id);
-
-        let result = self.charge_service.clone().charge_from_asa_request(
+        // the merchant was already promised this money, so every card gets a try regardless of health, stand in never approves cash
+        let charged = self.charge_service.clone().collect_stand_in(
             &request,
             &cards,
             &passthrough_card,
-            &user
-        ).await.map_err(|e| LithicHandlerError::Unexpected(e.into()))?;
+            &user,
+            &stand_in.collection_key(),
+            &budget
+        ).await;
+        self.card_health_service.clone().invalidate(&cards).await;
+        Ok(charged?)
+    }
 
-        tracing::info!("Charged with result={:?}", &result);
+    #[tracing::instrument(skip(self))]
+    pub async fn handle_transaction_event(self: Arc<Self>, request: LithicTransactionRequest) -> Result<(), LithicHandlerError> {
+        let transaction_token = request.token.clone().ok_or(
+            LithicHandlerError::Unexpected("expect token on transaction".into())
+        )?;
+        let card_token = request.card_token.clone().ok_or(
+            LithicHandlerError::Unexpected("expect card token on transaction".into())
+        )?;
 
-        Ok(
-            AsaResponse {
-                token,
-                result,
-                avs_result: None,
-                balance: None,
+        // lithic sends the full event history, the newest event we act on is the one that triggered this call
+        let event = request.events.clone().unwrap_or_default().into_iter().rev().find_map(|event| {
+            let event_type = event.type_.as_deref().and_then(TransactionEventType::from_lithic_type)?;
+            Some((event_type, event))
+        });
+        let (event_type, event) = match event {
+            Some(event) => event,
+            None => {
+                tracing::info!("No actionable event for transaction={}", &transaction_token);
+                return Ok(())
             }
-        )
+        };
+        let event_token = event.token.clone().ok_or(
+            LithicHandlerError::Unexpected("expect token on event".into())
+        )?;
+        let amount_cents = event.amount.ok_or(
+            LithicHandlerError::Unexpected("expect amount on event".into())
+        )?.abs();
+
+        let passthrough_card = self.passthrough_card_service.clone().get_by_token(&card_token).await
+            .map_err(|e| LithicHandlerError::Unexpected(e.into()))?;
+
+        self.charge_service.clone().process_transaction_event(
+            &transaction_token,
+            &event_type,
+            &event_token,
+            amount_cents,
+            &passthrough_card
+        ).await?;
+        // spend that didn't end up leaving stops counting against the user's limits
+        match event_type {
+            TransactionEventType::Clearing => {},
+            TransactionEventType::Void | TransactionEventType::AuthorizationExpiry => {
+                self.spend_control_service.clone().release_transaction(&transaction_token, &event_token, None).await?;
+            },
+            TransactionEventType::AuthorizationReversal | TransactionEventType::Return => {
+                self.spend_control_service.clone().release_transaction(&transaction_token, &event_token, Some(amount_cents)).await?;
+            }
+        }
+        tracing::info!("Processed event={} type={} for transaction={}", &event_token, &event_type, &transaction_token);
+        Ok(())
     }
-}
\ No newline at end of file
+}
@@ -3,3 +3,4 @@ pub mod controller;
 pub mod lithic_handler;
 //pub mod lithic_handler_tests;
 pub mod error;
+pub mod request;
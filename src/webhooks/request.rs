@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use crate::asa::request::Event;
+
+// lithic transaction object, sent on every lifecycle update after the initial authorization
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LithicTransactionRequest {
+    pub amount: Option<i32>,
+    pub card_token: Option<String>,
+    pub created: Option<String>,
+    pub events: Option<Vec<Event>>,
+    pub settled_amount: Option<i32>,
+    pub status: Option<String>,
+    pub token: Option<String>,
+}